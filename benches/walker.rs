@@ -0,0 +1,111 @@
+//! Criterion benchmarks of the graph walk and of single rule matching.
+//!
+//! Run with `cargo bench --features bench --bench walker`, and use
+//! `scripts/bench-baseline.sh` to record the median times of a run on this
+//! machine. Add the `ahash` or `std-hasher` feature to compare hashers.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tree_magic::TypeDatabase;
+use tree_magic_mini as tree_magic;
+
+const SAMPLES: &[(&str, &[u8])] = &[
+    ("image/gif", include_bytes!("image/gif")),
+    ("image/png", include_bytes!("image/png")),
+    ("application/zip", include_bytes!("application/zip")),
+    (
+        "application/x-7z-compressed",
+        include_bytes!("application/x-7z-compressed"),
+    ),
+    ("application/x-tar", include_bytes!("application/x-tar")),
+    ("application/msword", include_bytes!("application/msword")),
+    ("audio/flac", include_bytes!("audio/flac")),
+    ("text/plain", include_bytes!("text/plain")),
+];
+
+fn from_u8(c: &mut Criterion) {
+    let mut group = c.benchmark_group("from_u8");
+    for &(mime, bytes) in SAMPLES {
+        group.bench_function(mime, |b| b.iter(|| tree_magic::from_u8(black_box(bytes))));
+    }
+    group.finish();
+}
+
+fn single_rule(c: &mut Criterion) {
+    let mut group = c.benchmark_group("match_magic");
+    for &(mime, bytes) in SAMPLES {
+        if tree_magic::bench::rule_count(mime) == 0 {
+            continue;
+        }
+        group.bench_function(mime, |b| {
+            b.iter(|| tree_magic::bench::match_magic(black_box(mime), black_box(bytes)))
+        });
+    }
+    group.finish();
+}
+
+/// Loading the system types and building the graph
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("database");
+    group.sample_size(20);
+    group.bench_function("build", |b| b.iter(|| TypeDatabase::builder().build()));
+    group.finish();
+}
+
+/// Resolving names to types and their checkers, with matching kept cheap
+fn lookup(c: &mut Criterion) {
+    let db = TypeDatabase::default();
+    let names = [
+        "image/gif",
+        "application/x-zip-compressed",
+        "application/msword",
+        "text/plain",
+        "application/x-unknown",
+    ];
+    c.bench_function("database/lookup", |b| {
+        b.iter(|| {
+            for name in names {
+                black_box(db.match_u8(black_box(name), b""));
+            }
+        })
+    });
+}
+
+/// `match_u8` against checks with the type looked up once beforehand
+fn matcher(c: &mut Criterion) {
+    let mut group = c.benchmark_group("matcher");
+    for &(mime, bytes) in SAMPLES {
+        let m = tree_magic::matcher(mime).unwrap();
+        group.bench_function(format!("{}/match_u8", mime), |b| {
+            b.iter(|| tree_magic::match_u8(black_box(mime), black_box(bytes)))
+        });
+        group.bench_function(format!("{}/matches_u8", mime), |b| {
+            b.iter(|| m.matches_u8(black_box(bytes)))
+        });
+    }
+    group.finish();
+}
+
+fn report_counts(_: &mut Criterion) {
+    println!(
+        "{} types, {} magic rules",
+        tree_magic::bench::type_count(),
+        tree_magic::bench::total_rule_count()
+    );
+    for &(mime, bytes) in SAMPLES {
+        println!(
+            "{}: {} nodes visited",
+            mime,
+            tree_magic::bench::nodes_visited(bytes)
+        );
+    }
+}
+
+criterion_group!(
+    benches,
+    report_counts,
+    from_u8,
+    single_rule,
+    build,
+    lookup,
+    matcher
+);
+criterion_main!(benches);
@@ -0,0 +1,177 @@
+//! Turns the embedded magic database into static tables, so it isn't parsed
+//! when the program runs. Only used with the `with-gpl-data` or
+//! `builtin-minimal` features; otherwise the database is read from the
+//! system at run time.
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// A rule of a magic file, as in `src/fdo_magic/mod.rs`
+struct Rule<'a> {
+    negate: bool,
+    indent: u32,
+    offset: u32,
+    value: &'a [u8],
+    mask: Option<&'a [u8]>,
+    word_len: u32,
+    range: u32,
+}
+
+fn number(b: &[u8], pos: &mut usize) -> Option<u32> {
+    let start = *pos;
+    while b.get(*pos).is_some_and(u8::is_ascii_digit) {
+        *pos += 1;
+    }
+    std::str::from_utf8(&b[start..*pos]).ok()?.parse().ok()
+}
+
+fn take<'a>(b: &'a [u8], pos: &mut usize, len: usize) -> &'a [u8] {
+    let out = &b[*pos..*pos + len];
+    *pos += len;
+    out
+}
+
+/// Parses a magic file into its types, their priorities and rules, in order
+fn parse(b: &[u8]) -> Vec<(&str, u32, Vec<Rule<'_>>)> {
+    let mut pos = b"MIME-Magic\0\n".len();
+    assert!(b.starts_with(b"MIME-Magic\0\n"), "not a magic file");
+    let mut types: Vec<(&str, u32, Vec<Rule>)> = Vec::new();
+    while pos < b.len() {
+        if b[pos] == b'[' {
+            let end = pos + b[pos..].iter().position(|&c| c == b'\n').unwrap();
+            let header = std::str::from_utf8(&b[pos + 1..end - 1]).unwrap();
+            let (priority, mime) = header.split_once(':').unwrap();
+            types.push((mime, priority.parse().unwrap(), Vec::new()));
+            pos = end + 1;
+            continue;
+        }
+        let negate = b[pos] == b'!';
+        if negate {
+            pos += 1;
+        }
+        let indent = number(b, &mut pos).unwrap_or(0);
+        assert_eq!(b[pos], b'>');
+        pos += 1;
+        let offset = number(b, &mut pos).unwrap_or(0);
+        assert_eq!(b[pos], b'=');
+        pos += 1;
+        let len = u16::from_be_bytes([b[pos], b[pos + 1]]) as usize;
+        pos += 2;
+        let value = take(b, &mut pos, len);
+        let mut rule = Rule {
+            negate,
+            indent,
+            offset,
+            value,
+            mask: None,
+            word_len: 1,
+            range: 0,
+        };
+        if b[pos] == b'&' {
+            pos += 1;
+            rule.mask = Some(take(b, &mut pos, len));
+        }
+        if b[pos] == b'~' {
+            pos += 1;
+            rule.word_len = number(b, &mut pos).unwrap_or(1);
+        }
+        if b[pos] == b'+' {
+            pos += 1;
+            rule.range = number(b, &mut pos).unwrap_or(0);
+        }
+        assert_eq!(b[pos], b'\n');
+        pos += 1;
+        types.last_mut().expect("rule before any type").2.push(rule);
+    }
+    types
+}
+
+/// Writes bytes as a byte string literal
+fn bytes_literal(b: &[u8]) -> String {
+    let mut out = String::from("b\"");
+    for &c in b {
+        match c {
+            b'"' | b'\\' => write!(out, "\\{}", c as char).unwrap(),
+            0x20..=0x7e => out.push(c as char),
+            _ => write!(out, "\\x{:02x}", c).unwrap(),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Pairs of types from the lines of an `aliases` or `subclasses` file
+fn pairs(text: &str) -> Vec<(&str, &str)> {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?, parts.next()?))
+        })
+        .collect()
+}
+
+fn tables(magic: &[u8], aliases: &str, subclasses: &str) -> String {
+    let mut out = String::new();
+    out.push_str("pub(crate) static MAGIC: &[(&str, u32, &[MagicRule<'static>])] = &[\n");
+    for (mime, priority, rules) in parse(magic) {
+        writeln!(out, "    ({:?}, {}, &[", mime, priority).unwrap();
+        for r in rules {
+            let mask = match r.mask {
+                Some(m) => format!("Some({})", bytes_literal(m)),
+                None => String::from("None"),
+            };
+            writeln!(
+                out,
+                "        MagicRule {{ indent_level: {}, start_off: {}, val: {}, \
+                 mask: {}, word_len: {}, region_len: {}, negate: {} }},",
+                r.indent,
+                r.offset,
+                bytes_literal(r.value),
+                mask,
+                r.word_len,
+                r.range,
+                r.negate
+            )
+            .unwrap();
+        }
+        out.push_str("    ]),\n");
+    }
+    out.push_str("];\n\n");
+    for (name, text) in [("ALIASES", aliases), ("SUBCLASSES", subclasses)] {
+        writeln!(out, "pub(crate) static {}: &[(&str, &str)] = &[", name).unwrap();
+        for (a, b) in pairs(text) {
+            writeln!(out, "    ({:?}, {:?}),", a, b).unwrap();
+        }
+        out.push_str("];\n\n");
+    }
+    out
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "with-gpl-data")]
+    let data = Some((
+        tree_magic_db::magic().to_vec(),
+        tree_magic_db::aliases().to_string(),
+        tree_magic_db::subclasses().to_string(),
+    ));
+    #[cfg(all(feature = "builtin-minimal", not(feature = "with-gpl-data")))]
+    let data = {
+        let dir = Path::new("src/fdo_magic/builtin/minimal");
+        println!("cargo:rerun-if-changed={}", dir.display());
+        Some((
+            fs::read(dir.join("magic")).unwrap(),
+            fs::read_to_string(dir.join("aliases")).unwrap(),
+            fs::read_to_string(dir.join("subclasses")).unwrap(),
+        ))
+    };
+    #[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
+    let data: Option<(Vec<u8>, String, String)> = None;
+
+    if let Some((magic, aliases, subclasses)) = data {
+        let out = Path::new(&env::var("OUT_DIR").unwrap()).join("magic_tables.rs");
+        fs::write(out, tables(&magic, &aliases, &subclasses)).unwrap();
+    }
+}
@@ -10,6 +10,8 @@
 // MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
 // GNU General Public License for more details.
 
+#![no_std]
+
 /// Returns a static reference to the MIME database 'aliases'
 pub fn aliases() -> &'static str {
     include_str!("aliases")
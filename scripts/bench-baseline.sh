@@ -0,0 +1,26 @@
+#!/bin/sh
+# Runs the walker benchmarks and writes the median time of each one, in
+# nanoseconds, to target/bench-baseline.json for comparison across changes.
+# The times depend on the machine and toolchain, so they aren't committed;
+# only compare runs made on the same machine.
+#
+# Usage: scripts/bench-baseline.sh [extra criterion args]
+set -eu
+
+cargo bench --features bench --bench walker -- "$@"
+
+out=target/bench-baseline.json
+{
+    echo "{"
+    first=1
+    for f in target/criterion/*/*/new/estimates.json; do
+        name=$(echo "$f" | cut -d/ -f3,4)
+        median=$(sed -e 's/.*"median":{[^}]*}[^}]*"point_estimate":\([0-9.e+-]*\).*/\1/' "$f")
+        if [ $first -eq 0 ]; then echo ","; fi
+        first=0
+        printf '  "%s": %s' "$name" "$median"
+    done
+    echo
+    echo "}"
+} > "$out"
+echo "wrote $out"
@@ -0,0 +1,20 @@
+#!/bin/sh
+# Downloads and unpacks a tarball of sample files for tests/differential.rs.
+#
+# Usage: scripts/fetch-corpus.sh <tarball-url> [destination]
+#
+# Then run:
+#   TREE_MAGIC_CORPUS=<destination> cargo test --features testing --test differential -- --nocapture
+set -eu
+
+if [ $# -lt 1 ]; then
+    echo "usage: $0 <tarball-url> [destination]" >&2
+    exit 1
+fi
+
+url=$1
+dest=${2:-target/corpus}
+
+mkdir -p "$dest"
+curl -fL "$url" | tar -xz -C "$dest"
+echo "corpus unpacked to $dest"
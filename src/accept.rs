@@ -0,0 +1,135 @@
+//! Pick the type to send for a detected type from an HTTP `Accept` header
+use crate::database::normalize;
+use crate::TypeDatabase;
+use alloc::borrow::Cow;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use petgraph::prelude::*;
+
+/// A media range of the header, with its quality in thousandths
+struct MediaRange<'h> {
+    name: Cow<'h, str>,
+    quality: u16,
+}
+
+/// How closely a range names a type, from `*/*` to the type itself
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Specificity {
+    Any,
+    TopLevel,
+    Exact,
+}
+
+/// A quality value, `0` to `1` with up to three decimals
+fn quality(value: &str) -> Option<u16> {
+    let (int, frac) = value.split_once('.').unwrap_or((value, ""));
+    if frac.len() > 3 || !frac.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let frac = frac
+        .bytes()
+        .chain(core::iter::repeat(b'0'))
+        .take(3)
+        .fold(0, |q, c| q * 10 + u16::from(c - b'0'));
+    match (int, frac) {
+        ("0", _) => Some(frac),
+        ("1", 0) => Some(1000),
+        _ => None,
+    }
+}
+
+/// A media range such as `text/*;q=0.5`. Malformed ranges are left out, as
+/// are the parameters other than the quality.
+fn media_range(range: &str) -> Option<MediaRange<'_>> {
+    let mut parts = range.split(';');
+    let name = normalize(parts.next()?);
+    let (top, sub) = name.split_once('/')?;
+    if top.is_empty() || sub.is_empty() || (top == "*" && sub != "*") {
+        return None;
+    }
+    let mut out = MediaRange {
+        name,
+        quality: 1000,
+    };
+    for param in parts {
+        if let Some((key, value)) = param.split_once('=') {
+            if key.trim().eq_ignore_ascii_case("q") {
+                out.quality = quality(value.trim())?;
+            }
+        }
+    }
+    Some(out)
+}
+
+/// The type followed by the types it's a subclass of, nearest first and
+/// parents by name, leaving out the `all/*` base types
+fn ancestors<'a>(db: &'a TypeDatabase, mimetype: &str) -> Vec<&'a str> {
+    let graph = db.graph();
+    let mut out = Vec::new();
+    let mut seen = Vec::new();
+    let mut queue: VecDeque<NodeIndex> = db.node(db.get_alias(mimetype)).into_iter().collect();
+    while let Some(n) = queue.pop_front() {
+        if graph[n].starts_with("all/") {
+            continue;
+        }
+        out.push(graph[n]);
+        let mut parents: Vec<NodeIndex> = graph
+            .neighbors_directed(n, Incoming)
+            .filter(|p| !seen.contains(p))
+            .collect();
+        parents.sort_by_key(|&p| graph[p]);
+        seen.extend(&parents);
+        queue.extend(parents);
+    }
+    out
+}
+
+pub(crate) fn negotiate<'a>(
+    db: &'a TypeDatabase,
+    accept: &str,
+    detected: &'a str,
+) -> Option<&'a str> {
+    if accept.trim().is_empty() {
+        return Some(
+            db.node(db.get_alias(detected))
+                .map_or(detected, |n| db.graph()[n]),
+        );
+    }
+    let ranges: Vec<MediaRange> = accept.split(',').filter_map(media_range).collect();
+    let mut candidates = ancestors(db, detected);
+    if candidates.is_empty() {
+        candidates.push(detected);
+    }
+
+    let mut best: Option<(u16, &str)> = None;
+    for candidate in candidates {
+        // Of the ranges naming the type, the most specific one decides
+        let mut found: Option<(Specificity, u16, &str)> = None;
+        for range in &ranges {
+            let (specificity, name) = if range.name == "*/*" {
+                (Specificity::Any, candidate)
+            } else if let Some(top) = range.name.strip_suffix("/*") {
+                if candidate.split('/').next() != Some(top) {
+                    continue;
+                }
+                (Specificity::TopLevel, candidate)
+            } else if db.get_alias(&range.name) == candidate {
+                // Sent as the client names it
+                let name = db.alias_key(&range.name).unwrap_or(candidate);
+                (Specificity::Exact, name)
+            } else {
+                continue;
+            };
+            let key = (specificity, range.quality);
+            if found.map_or(true, |(s, q, _)| key > (s, q)) {
+                found = Some((specificity, range.quality, name));
+            }
+        }
+        if let Some((_, quality, name)) = found {
+            if quality > 0 && best.map_or(true, |(q, _)| quality > q) {
+                best = Some((quality, name));
+            }
+        }
+    }
+    best.map(|(_, name)| name)
+}
@@ -0,0 +1,191 @@
+//! Classify single members of ZIP and TAR archives without extracting the
+//! rest of the archive
+use crate::tar::check::{is_valid_header, parse_octal, BLOCK_LEN, TYPEFLAG};
+use alloc::borrow::Cow;
+use core::str;
+
+/// Most of a compressed member that's unpacked for detection
+const MEMBER_PREFIX_LEN: usize = 64 * 1024;
+
+/// Start of the data of the named member, or `None` if the archive isn't a
+/// ZIP or TAR archive or has no such member
+pub(crate) fn member_prefix<'a>(archive: &'a [u8], member: &str) -> Option<Cow<'a, [u8]>> {
+    if archive.starts_with(b"PK\x03\x04") || archive.starts_with(b"PK\x05\x06") {
+        zip_member(archive, member)
+    } else if is_valid_header(archive) {
+        tar_member(archive, member).map(Cow::Borrowed)
+    } else {
+        None
+    }
+}
+
+/// Whether a stored member name is the one asked for, ignoring a leading `./`
+fn same_name(stored: &[u8], member: &str) -> bool {
+    let stored = stored.strip_prefix(b"./").unwrap_or(stored);
+    let member = member.strip_prefix("./").unwrap_or(member);
+    stored == member.as_bytes()
+}
+
+fn le_u16(b: &[u8], off: usize) -> Option<usize> {
+    let x = b.get(off..off + 2)?;
+    Some(u16::from_le_bytes([x[0], x[1]]) as usize)
+}
+
+fn le_u32(b: &[u8], off: usize) -> Option<usize> {
+    let x = b.get(off..off + 4)?;
+    Some(u32::from_le_bytes([x[0], x[1], x[2], x[3]]) as usize)
+}
+
+/// Where a ZIP member's data starts, and how it's stored
+struct ZipEntry {
+    header_offset: usize,
+    method: usize,
+    compressed_len: usize,
+}
+
+/// Looks the member up in the central directory at the end of the archive,
+/// or failing that, in the local headers from the start
+fn zip_member<'a>(archive: &'a [u8], member: &str) -> Option<Cow<'a, [u8]>> {
+    let entry = zip_central_entry(archive, member).or_else(|| zip_local_entry(archive, member))?;
+
+    let header = archive.get(entry.header_offset..)?;
+    if !header.starts_with(b"PK\x03\x04") {
+        return None;
+    }
+    let start = 30 + le_u16(header, 26)? + le_u16(header, 28)?;
+    let data = header.get(start..)?;
+    let data = &data[..entry.compressed_len.min(data.len())];
+
+    match entry.method {
+        0 => Some(Cow::Borrowed(data)),
+        8 => match miniz_oxide::inflate::decompress_to_vec_with_limit(data, MEMBER_PREFIX_LEN) {
+            Ok(x) => Some(Cow::Owned(x)),
+            // Only the start is needed, which is what's there when the
+            // limit is hit or the member is cut off
+            Err(e) if !e.output.is_empty() => Some(Cow::Owned(e.output)),
+            Err(_) => None,
+        },
+        _ => None,
+    }
+}
+
+fn zip_central_entry(archive: &[u8], member: &str) -> Option<ZipEntry> {
+    // The end record is 22 bytes, followed by a comment of up to 64 KiB
+    let search_from = archive.len().saturating_sub(22 + 0xFFFF);
+    let end = (search_from..archive.len().saturating_sub(21))
+        .rev()
+        .find(|&i| archive[i..].starts_with(b"PK\x05\x06"))?;
+    let count = le_u16(archive, end + 10)?;
+    let mut offset = le_u32(archive, end + 16)?;
+
+    for _ in 0..count {
+        let header = archive.get(offset..)?;
+        if !header.starts_with(b"PK\x01\x02") {
+            return None;
+        }
+        let name_len = le_u16(header, 28)?;
+        let name = header.get(46..46 + name_len)?;
+        if same_name(name, member) {
+            return Some(ZipEntry {
+                header_offset: le_u32(header, 42)?,
+                method: le_u16(header, 10)?,
+                compressed_len: le_u32(header, 20)?,
+            });
+        }
+        offset += 46 + name_len + le_u16(header, 30)? + le_u16(header, 32)?;
+    }
+    None
+}
+
+fn zip_local_entry(archive: &[u8], member: &str) -> Option<ZipEntry> {
+    let mut offset = 0;
+    loop {
+        let header = archive.get(offset..)?;
+        if !header.starts_with(b"PK\x03\x04") {
+            return None;
+        }
+        let flags = le_u16(header, 6)?;
+        let compressed_len = le_u32(header, 18)?;
+        let name_len = le_u16(header, 26)?;
+        let name = header.get(30..30 + name_len)?;
+        if same_name(name, member) {
+            return Some(ZipEntry {
+                header_offset: offset,
+                method: le_u16(header, 8)?,
+                // Sizes that follow the data can't be known here
+                compressed_len: if flags & 0x08 != 0 {
+                    usize::MAX
+                } else {
+                    compressed_len
+                },
+            });
+        }
+        // Without sizes up front, the next header can't be found
+        if flags & 0x08 != 0 {
+            return None;
+        }
+        offset += 30 + name_len + le_u16(header, 28)? + compressed_len;
+    }
+}
+
+/// Walks the TAR headers, following GNU long names and ustar prefixes
+fn tar_member<'a>(archive: &'a [u8], member: &str) -> Option<&'a [u8]> {
+    let mut offset = 0;
+    let mut long_name: Option<&[u8]> = None;
+    loop {
+        let header = archive.get(offset..offset + BLOCK_LEN)?;
+        if !is_valid_header(header) {
+            return None;
+        }
+        let size = parse_octal(&header[124..136]).unwrap_or(0) as usize;
+        let data_start = offset + BLOCK_LEN;
+        let data = archive.get(data_start..)?;
+        let data = &data[..size.min(data.len())];
+
+        match header[TYPEFLAG] {
+            b'L' => long_name = Some(trim_nul(data)),
+            b'0' | 0 | b'7' => {
+                let matched = match long_name.take() {
+                    Some(name) => same_name(name, member),
+                    None => ustar_name_matches(header, member),
+                };
+                if matched {
+                    return Some(data);
+                }
+            }
+            _ => long_name = None,
+        }
+        offset = data_start + size.div_ceil(BLOCK_LEN) * BLOCK_LEN;
+    }
+}
+
+fn trim_nul(b: &[u8]) -> &[u8] {
+    match b.iter().position(|&c| c == 0) {
+        Some(end) => &b[..end],
+        None => b,
+    }
+}
+
+/// Compares against the name field, joined to the prefix field of ustar
+/// headers
+fn ustar_name_matches(header: &[u8], member: &str) -> bool {
+    let name = trim_nul(&header[..100]);
+    let prefix = match &header[257..263] {
+        b"ustar\0" => trim_nul(&header[345..500]),
+        _ => &[],
+    };
+    if prefix.is_empty() {
+        return same_name(name, member);
+    }
+    match (str::from_utf8(prefix), str::from_utf8(name)) {
+        (Ok(p), Ok(n)) => {
+            let member = member.strip_prefix("./").unwrap_or(member);
+            let p = p.strip_prefix("./").unwrap_or(p);
+            member.len() == p.len() + 1 + n.len()
+                && member.starts_with(p)
+                && member[p.len()..].starts_with('/')
+                && member.ends_with(n)
+        }
+        _ => false,
+    }
+}
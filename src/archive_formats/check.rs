@@ -0,0 +1,163 @@
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct ArchiveFormats;
+
+impl crate::Checker for ArchiveFormats {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        let b = match source.read_at(0, PROBE_LEN) {
+            Ok(b) => b,
+            Err(_) => return false,
+        };
+        match mimetype {
+            // The two bytes of magic are too few to go by alone
+            "application/x-arj" => is_arj(&b),
+            "application/zstd" => is_zstd(source, &b),
+            "application/x-cpio" | "application/x-archive" => {
+                variant(&b, mimetype).is_some()
+                    || fdo_magic::builtin::check::from_source(db, source, mimetype)
+            }
+            "application/vnd.debian.binary-package" => is_deb(&b),
+            _ => fdo_magic::builtin::check::from_source(db, source, mimetype),
+        }
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            // A skippable frame may come before the first frame
+            "application/zstd" => None,
+            _ => Some(PROBE_LEN),
+        }
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        variant(&source.read_at(0, PROBE_LEN).ok()?, mimetype)
+    }
+}
+
+/// Enough for the headers of each format, and the first member of a
+/// Debian package
+const PROBE_LEN: usize = 72;
+
+const SEVEN_ZIP_MAGIC: &[u8; 6] = b"7z\xbc\xaf\x27\x1c";
+
+const RAR4_MAGIC: &[u8; 7] = b"Rar!\x1a\x07\x00";
+const RAR5_MAGIC: &[u8; 8] = b"Rar!\x1a\x07\x01\x00";
+
+const ZSTD_MAGIC: u32 = 0xfd2f_b528;
+/// Skippable frames, such as the seek table of the seekable format, have
+/// any of 16 magic numbers, then the length of their data
+const SKIPPABLE_MAGIC: u32 = 0x184d_2a50;
+const SKIPPABLE_MASK: u32 = 0xffff_fff0;
+
+const LZ4_FRAME_MAGIC: u32 = 0x184d_2204;
+const LZ4_LEGACY_MAGIC: u32 = 0x184c_2102;
+
+/// ARJ headers start with their magic and size, then a header of their own
+/// with the file type, which is 2 for the main header
+const ARJ_MAGIC: &[u8; 2] = b"\x60\xea";
+const ARJ_MAX_HEADER_LEN: usize = 2600;
+const ARJ_FIRST_HEADER_MIN_LEN: u8 = 30;
+const ARJ_MAIN_HEADER: u8 = 2;
+
+/// The global header of ar archives, and of thin ones, which refer to
+/// their members by path rather than holding them
+const AR_MAGIC: &[u8; 8] = b"!<arch>\n";
+const AR_THIN_MAGIC: &[u8; 8] = b"!<thin>\n";
+/// Name, dates, owner, mode and size, then two bytes ending the header
+const AR_HEADER_LEN: usize = 60;
+
+fn le_u32(b: &[u8], at: usize) -> Option<u32> {
+    let b = b.get(at..at + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn is_zstd_frame(b: &[u8]) -> bool {
+    // The frame header descriptor has a reserved bit, which must be unset
+    le_u32(b, 0) == Some(ZSTD_MAGIC) && b.get(4).is_some_and(|d| d & 0x08 == 0)
+}
+
+/// A frame, or a skippable frame followed by one
+fn is_zstd(source: &dyn DataSource, b: &[u8]) -> bool {
+    if is_zstd_frame(b) {
+        return true;
+    }
+    match (le_u32(b, 0), le_u32(b, 4)) {
+        (Some(magic), Some(len)) if magic & SKIPPABLE_MASK == SKIPPABLE_MAGIC => {
+            match source.read_at(8 + len as u64, 5) {
+                Ok(next) => is_zstd_frame(&next),
+                Err(_) => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+fn is_arj(b: &[u8]) -> bool {
+    if !b.starts_with(ARJ_MAGIC) || b.len() < 11 {
+        return false;
+    }
+    let len = u16::from_le_bytes([b[2], b[3]]) as usize;
+    (1..=ARJ_MAX_HEADER_LEN).contains(&len)
+        && b[4] >= ARJ_FIRST_HEADER_MIN_LEN
+        && b[10] == ARJ_MAIN_HEADER
+}
+
+/// The first member of a Debian package is `debian-binary`, holding the
+/// version of the format
+fn is_deb(b: &[u8]) -> bool {
+    let header = match b.strip_prefix(AR_MAGIC) {
+        Some(x) if x.len() >= AR_HEADER_LEN + 2 => x,
+        _ => return false,
+    };
+    let name = &header[..16];
+    (name.starts_with(b"debian-binary ") || name.starts_with(b"debian-binary/"))
+        && &header[58..60] == b"`\n"
+        && header[AR_HEADER_LEN..].starts_with(b"2.")
+}
+
+/// The cpio header: `bin-le` or `bin-be` for the old binary one, `odc` for
+/// the old portable one, and `newc` or `crc` for the new portable one
+fn cpio(b: &[u8]) -> Option<&'static str> {
+    match b.get(..6)? {
+        b"070707" => Some("odc"),
+        b"070701" => Some("newc"),
+        b"070702" => Some("crc"),
+        [0xc7, 0x71, ..] => Some("bin-le"),
+        [0x71, 0xc7, ..] => Some("bin-be"),
+        _ => None,
+    }
+}
+
+fn variant(b: &[u8], mimetype: &str) -> Option<&'static str> {
+    match mimetype {
+        "application/x-7z-compressed" => match b.strip_prefix(SEVEN_ZIP_MAGIC)? {
+            [0, 2, ..] => Some("0.2"),
+            [0, 3, ..] => Some("0.3"),
+            [0, 4, ..] => Some("0.4"),
+            _ => None,
+        },
+        "application/vnd.rar" if b.starts_with(RAR5_MAGIC) => Some("rar5"),
+        "application/vnd.rar" if b.starts_with(RAR4_MAGIC) => Some("rar4"),
+        "application/x-lz4" => match le_u32(b, 0)? {
+            LZ4_FRAME_MAGIC => Some("frame"),
+            LZ4_LEGACY_MAGIC => Some("legacy"),
+            _ => None,
+        },
+        "application/x-cpio" => cpio(b),
+        "application/x-archive" if b.starts_with(AR_THIN_MAGIC) => Some("thin"),
+        _ => None,
+    }
+}
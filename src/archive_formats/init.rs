@@ -0,0 +1,26 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    vec![(
+        "application/x-archive",
+        "application/vnd.debian.binary-package",
+    )]
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/x-zstd", "application/zstd"),
+        ("application/x-rar", "application/vnd.rar"),
+        ("application/x-rar-compressed", "application/vnd.rar"),
+        ("application/x-deb", "application/vnd.debian.binary-package"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
@@ -0,0 +1,15 @@
+//! Handles archive and compression formats whose magic leaves gaps in the
+//! databases, and reports which variant of a format the data is
+const TYPES: [&str; 8] = [
+    "application/x-7z-compressed",
+    "application/vnd.rar",
+    "application/zstd",
+    "application/x-lz4",
+    "application/x-arj",
+    "application/x-cpio",
+    "application/x-archive",
+    "application/vnd.debian.binary-package",
+];
+
+pub mod check;
+pub mod init;
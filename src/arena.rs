@@ -0,0 +1,58 @@
+//! Storage for names and magic only known at runtime, such as the types and
+//! patterns of custom magic files
+use crate::MIME;
+use alloc::{boxed::Box, vec::Vec};
+use core::ptr::NonNull;
+
+/// Owns the names a [`TypeDatabase`](crate::TypeDatabase) or
+/// [`Detector`](crate::Detector) adds at runtime, and the magic files whose
+/// patterns the rules of a database borrow.
+///
+/// Internally every name is a [`MIME`], so names stored here are handed out
+/// as `&'static str` too. They're only valid while the arena lives, so
+/// public methods must tie them to the lifetime of their owner instead.
+#[derive(Debug, Default)]
+pub(crate) struct Arena {
+    blocks: Vec<NonNull<[u8]>>,
+}
+
+// The blocks are never changed once stored
+unsafe impl Send for Arena {}
+unsafe impl Sync for Arena {}
+
+impl Arena {
+    /// Stores a name.
+    ///
+    /// # Safety
+    /// The returned name must not be used once the arena is dropped.
+    pub(crate) unsafe fn alloc(&mut self, name: Box<str>) -> MIME {
+        // Safety: the bytes came from a `str`
+        core::str::from_utf8_unchecked(self.alloc_bytes(name.into_boxed_bytes()))
+    }
+
+    /// Stores a block of bytes, such as a whole magic file, so every
+    /// pattern in it can borrow from a single allocation.
+    ///
+    /// # Safety
+    /// The returned bytes must not be used once the arena is dropped.
+    pub(crate) unsafe fn alloc_bytes(&mut self, bytes: Box<[u8]>) -> &'static [u8] {
+        let ptr = NonNull::from(Box::leak(bytes));
+        self.blocks.push(ptr);
+        ptr.as_ref()
+    }
+
+    /// Bytes held by the stored blocks
+    pub(crate) fn size(&self) -> usize {
+        self.blocks.capacity() * core::mem::size_of::<NonNull<[u8]>>()
+            + self.blocks.iter().map(|b| b.len()).sum::<usize>()
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        for ptr in self.blocks.drain(..) {
+            // Safety: allocated by `alloc_bytes` and only freed here
+            drop(unsafe { Box::from_raw(ptr.as_ptr()) });
+        }
+    }
+}
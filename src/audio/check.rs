@@ -0,0 +1,205 @@
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Audio;
+
+impl crate::Checker for Audio {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        match stream(source) {
+            Some(found) => found == mimetype,
+            // Also ADIF, and an ID3 tag before anything else
+            None => fdo_magic::builtin::check::from_source(db, source, mimetype),
+        }
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        match mimetype {
+            "audio/mpeg" => layer(source),
+            _ => None,
+        }
+    }
+}
+
+const ID3_MAGIC: &[u8; 3] = b"ID3";
+/// Magic, version, flags and the size as a syncsafe integer
+const ID3_HEADER_LEN: usize = 10;
+/// Tags are sometimes written more than once
+const MAX_ID3_TAGS: usize = 4;
+
+/// Encoders may pad the start of the stream with zeros
+const MAX_PADDING: usize = 4096;
+
+const FLAC_MAGIC: &[u8; 4] = b"fLaC";
+
+/// Frames followed before the stream is taken for one. Each frame header
+/// says where the next one starts, which random data rarely gets right.
+const FRAMES: usize = 3;
+/// Longest frame header, that of ADTS
+const FRAME_HEADER_LEN: usize = 7;
+
+/// Bitrates in kbit/s, by MPEG version and layer, leaving out the free and
+/// bad indexes at either end
+const MPEG_BITRATES: [[[u16; 14]; 3]; 2] = [
+    [
+        [
+            32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+        ],
+        [
+            32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+        ],
+        [
+            32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ],
+    ],
+    [
+        [
+            32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+        ],
+        [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+        [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+    ],
+];
+const MPEG1_SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+/// Sample rates ADTS can give by index
+const ADTS_SAMPLE_RATES: usize = 13;
+
+/// Where the audio starts, after any ID3v2 tags and padding
+fn stream_start(source: &dyn DataSource) -> u64 {
+    let mut start = 0;
+    for _ in 0..MAX_ID3_TAGS {
+        let b = match source.read_at(start, ID3_HEADER_LEN) {
+            Ok(b) if b.len() == ID3_HEADER_LEN && b.starts_with(ID3_MAGIC) => b,
+            _ => break,
+        };
+        if b[6..].iter().any(|&c| c & 0x80 != 0) {
+            break;
+        }
+        let size = b[6..].iter().fold(0u64, |n, &c| n << 7 | c as u64);
+        // A footer repeats the header at the end
+        let footer = if b[5] & 0x10 != 0 { ID3_HEADER_LEN } else { 0 };
+        start += (ID3_HEADER_LEN + footer) as u64 + size;
+    }
+    match source.read_at(start, MAX_PADDING) {
+        Ok(b) => start + b.iter().take_while(|&&c| c == 0).count() as u64,
+        Err(_) => start,
+    }
+}
+
+/// Length of an MPEG audio frame, and the fields every frame of the stream
+/// shares: version, layer and sample rate
+fn mpeg_header(b: &[u8]) -> Option<(u64, [u8; 3])> {
+    if b.len() < 4 || b[0] != 0xff || b[1] & 0xe0 != 0xe0 {
+        return None;
+    }
+    let version = (b[1] >> 3) & 3;
+    let layer = (b[1] >> 1) & 3;
+    let bitrate = (b[2] >> 4) as usize;
+    let rate = ((b[2] >> 2) & 3) as usize;
+    // Reserved version and layer, free and bad bitrates, reserved rate
+    if version == 1 || layer == 0 || bitrate == 0 || bitrate == 15 || rate == 3 {
+        return None;
+    }
+    let mpeg1 = version == 3;
+    let layer_index = 3 - layer as usize;
+    let bitrate = MPEG_BITRATES[!mpeg1 as usize][layer_index][bitrate - 1] as u64 * 1000;
+    // Halved for MPEG 2, and again for MPEG 2.5
+    let halvings = match version {
+        3 => 0,
+        2 => 1,
+        _ => 2,
+    };
+    let sample_rate = (MPEG1_SAMPLE_RATES[rate] >> halvings) as u64;
+    let padding = ((b[2] >> 1) & 1) as u64;
+    let len = match layer_index {
+        0 => (12 * bitrate / sample_rate + padding) * 4,
+        2 if !mpeg1 => 72 * bitrate / sample_rate + padding,
+        _ => 144 * bitrate / sample_rate + padding,
+    };
+    Some((len, [version, layer, rate as u8]))
+}
+
+/// Length of an ADTS frame, and the fields every frame of the stream
+/// shares: version, profile, sample rate and channels
+fn adts_header(b: &[u8]) -> Option<(u64, [u8; 3])> {
+    if b.len() < FRAME_HEADER_LEN || b[0] != 0xff || b[1] & 0xf6 != 0xf0 {
+        return None;
+    }
+    let rate = (b[2] >> 2) & 0xf;
+    if rate as usize >= ADTS_SAMPLE_RATES {
+        return None;
+    }
+    let len = ((b[3] & 3) as u64) << 11 | (b[4] as u64) << 3 | (b[5] >> 5) as u64;
+    if len < FRAME_HEADER_LEN as u64 {
+        return None;
+    }
+    Some((len, [b[1] & 0x08, b[2] & 0xfc, (b[2] & 1) << 2 | b[3] >> 6]))
+}
+
+/// Reads a frame header, giving the length of the frame and the fields the
+/// frames of a stream share
+type FrameHeader = fn(&[u8]) -> Option<(u64, [u8; 3])>;
+
+/// Follows frames from `start`, each of which must agree with the first.
+/// The data may end after any frame but the first.
+fn frames(source: &dyn DataSource, start: u64, header: FrameHeader) -> bool {
+    let mut at = start;
+    let mut first = None;
+    for i in 0..FRAMES {
+        let b = match source.read_at(at, FRAME_HEADER_LEN) {
+            Ok(b) => b,
+            Err(_) => return false,
+        };
+        if b.is_empty() && i > 0 {
+            return true;
+        }
+        let (len, fields) = match header(&b) {
+            Some(x) => x,
+            None => return false,
+        };
+        if *first.get_or_insert(fields) != fields {
+            return false;
+        }
+        at += len;
+    }
+    true
+}
+
+/// The kind of stream after any tags
+fn stream(source: &dyn DataSource) -> Option<MIME> {
+    let start = stream_start(source);
+    if source
+        .read_at(start, FLAC_MAGIC.len())
+        .is_ok_and(|b| b[..] == FLAC_MAGIC[..])
+    {
+        Some("audio/flac")
+    } else if frames(source, start, mpeg_header) {
+        Some("audio/mpeg")
+    } else if frames(source, start, adts_header) {
+        Some("audio/aac")
+    } else {
+        None
+    }
+}
+
+/// Reports the layer as `mp1`, `mp2` or `mp3`
+fn layer(source: &dyn DataSource) -> Option<&'static str> {
+    let b = source.read_at(stream_start(source), 4).ok()?;
+    match mpeg_header(&b)?.1[1] {
+        3 => Some("mp1"),
+        2 => Some("mp2"),
+        _ => Some("mp3"),
+    }
+}
@@ -0,0 +1,15 @@
+use crate::{FnvHashMap, MIME};
+use alloc::vec::Vec;
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    Vec::new()
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    FnvHashMap::default()
+}
@@ -0,0 +1,6 @@
+//! Handles bare audio streams (MP3, AAC and FLAC) by following their frames,
+//! after any ID3 tags
+const TYPES: [&str; 3] = ["audio/mpeg", "audio/aac", "audio/flac"];
+
+pub mod check;
+pub mod init;
@@ -1,16 +1,11 @@
-use crate::{read_bytes, MIME};
-use fnv::FnvHashMap;
-use std::path::Path;
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
 
 pub(crate) struct BaseType;
 
 impl crate::Checker for BaseType {
-    fn from_u8(&self, file: &[u8], mimetype: &str) -> bool {
-        from_u8(file, mimetype)
-    }
-
-    fn from_filepath(&self, filepath: &Path, mimetype: &str) -> bool {
-        from_filepath(filepath, mimetype)
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
     }
 
     fn get_supported(&self) -> Vec<MIME> {
@@ -24,53 +19,129 @@ impl crate::Checker for BaseType {
     fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
         super::init::get_aliaslist()
     }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "text/plain" => Some(TEXT_SCAN_LEN),
+            _ => Some(0),
+        }
+    }
 }
 
-/// If there are any null bytes, return False. Otherwise return True.
-fn is_text_plain_from_u8(b: &[u8]) -> bool {
-    bytecount::count(b, 0) == 0
+/// How much of the data to look at when deciding if it's text
+const TEXT_SCAN_LEN: usize = 2048;
+
+/// If there are any null bytes, return False, unless the data is blank text.
+/// Otherwise return True.
+fn is_text_plain(source: &dyn DataSource) -> bool {
+    match source.read_at(0, TEXT_SCAN_LEN) {
+        Ok(b) => bytecount::count(&b, 0) == 0 || (b.len() < TEXT_SCAN_LEN && is_blank(&b)),
+        Err(_) => false,
+    }
 }
 
-// TODO: Hoist the main logic here somewhere else. This'll get redundant fast!
-fn is_text_plain_from_filepath(filepath: &Path) -> bool {
-    let b = match read_bytes(filepath, 512) {
-        Ok(x) => x,
-        Err(_) => return false,
-    };
-    is_text_plain_from_u8(b.as_slice())
+/// Byte order marks, with the size of a code unit and whether it's big
+/// endian. `FF FE 00 00` is read as UTF-32 before UTF-16, as a UTF-16 text
+/// can't start with a null character.
+const BOMS: [(&[u8], usize, bool); 5] = [
+    (b"\x00\x00\xfe\xff", 4, true),
+    (b"\xff\xfe\x00\x00", 4, false),
+    (b"\xef\xbb\xbf", 1, true),
+    (b"\xfe\xff", 2, true),
+    (b"\xff\xfe", 2, false),
+];
+
+/// Non-empty data holding nothing but a byte order mark and whitespace,
+/// short enough to have been read whole
+pub(crate) fn is_blank_text(source: &dyn DataSource) -> bool {
+    match source.read_at(0, TEXT_SCAN_LEN) {
+        Ok(b) => b.len() < TEXT_SCAN_LEN && is_blank(&b),
+        Err(_) => false,
+    }
 }
 
-#[allow(unused_variables)]
-pub fn from_u8(b: &[u8], mimetype: &str) -> bool {
-    if mimetype == "application/octet-stream" || mimetype == "all/allfiles" {
-        // Both of these are the case if we have a bytestream at all
-        return true;
+/// An optional byte order mark followed by nothing but whitespace, in the
+/// encoding of the mark
+fn is_blank(bytes: &[u8]) -> bool {
+    if bytes.is_empty() {
+        return false;
     }
-    if mimetype == "text/plain" {
-        is_text_plain_from_u8(b)
-    } else {
-        // ...how did we get bytes for this?
-        false
+    let whitespace = |units: &[u8], size: usize, big_endian: bool| {
+        let mut units = units.chunks_exact(size);
+        units.remainder().is_empty()
+            && units.all(|unit| {
+                let c = match big_endian {
+                    true => unit.iter().fold(0u32, |c, &b| c << 8 | b as u32),
+                    false => unit.iter().rev().fold(0u32, |c, &b| c << 8 | b as u32),
+                };
+                matches!(c, 0x09..=0x0d | 0x20)
+            })
+    };
+    BOMS.iter()
+        .filter_map(|&(bom, size, be)| Some((bytes.strip_prefix(bom)?, size, be)))
+        .chain(core::iter::once((bytes, 1, true)))
+        .any(|(rest, size, be)| whitespace(rest, size, be))
+}
+
+/// An empty file, known from its size where there is one, so nothing's read
+#[cfg(feature = "std")]
+pub(crate) fn is_empty(source: &dyn DataSource) -> bool {
+    match source.metadata() {
+        Some(m) => m.is_file() && m.len() == 0,
+        None => source.read_at(0, 1).is_ok_and(|b| b.is_empty()),
     }
 }
 
-pub fn from_filepath(filepath: &Path, mimetype: &str) -> bool {
-    use std::fs;
+#[cfg(not(feature = "std"))]
+pub(crate) fn is_empty(source: &dyn DataSource) -> bool {
+    source.read_at(0, 1).is_ok_and(|b| b.is_empty())
+}
 
-    // Being bad with error handling here,
-    // but if you can't open it it's probably not a file.
-    let meta = match fs::metadata(filepath) {
-        Ok(x) => x,
-        Err(_) => {
-            return false;
-        }
-    };
+/// Without metadata we were handed a bytestream, which is as good as a file
+#[cfg(feature = "std")]
+fn is_file(source: &dyn DataSource) -> bool {
+    source.metadata().map_or(true, |m| m.is_file())
+}
+
+#[cfg(not(feature = "std"))]
+fn is_file(_source: &dyn DataSource) -> bool {
+    true
+}
+
+#[cfg(feature = "std")]
+fn is_dir(source: &dyn DataSource) -> bool {
+    source.metadata().is_some_and(|m| m.is_dir())
+}
+
+#[cfg(not(feature = "std"))]
+fn is_dir(_source: &dyn DataSource) -> bool {
+    false
+}
+
+/// A directory on another device than its parent, or the root directory
+#[cfg(all(feature = "std", unix))]
+fn is_mount_point(source: &dyn DataSource) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (source.metadata(), source.parent_metadata()) {
+        (Some(m), Some(p)) => m.is_dir() && (m.dev() != p.dev() || m.ino() == p.ino()),
+        _ => false,
+    }
+}
+
+#[cfg(not(all(feature = "std", unix)))]
+fn is_mount_point(_source: &dyn DataSource) -> bool {
+    false
+}
 
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
     match mimetype {
         "all/all" => true,
-        "all/allfiles" | "application/octet-stream" => meta.is_file(),
-        "inode/directory" => meta.is_dir(),
-        "text/plain" => is_text_plain_from_filepath(filepath),
+        "all/allfiles" | "application/octet-stream" => is_file(source),
+        "inode/directory" => is_dir(source),
+        "inode/mount-point" => is_mount_point(source),
+        "application/x-zerosize" => is_empty(source),
+        "text/plain" => is_file(source) && is_text_plain(source),
         _ => false,
     }
 }
@@ -1,5 +1,5 @@
-use crate::MIME;
-use fnv::FnvHashMap;
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
 
 pub fn get_supported() -> Vec<MIME> {
     super::TYPES.to_vec()
@@ -10,7 +10,9 @@ pub fn get_subclasses() -> Vec<(MIME, MIME)> {
     vec![
         ("all/all", "all/allfiles"),
         ("all/all", "inode/directory"),
+        ("inode/directory", "inode/mount-point"),
         ("all/allfiles", "application/octet-stream"),
+        ("all/allfiles", "application/x-zerosize"),
         ("application/octet-stream", "text/plain"),
     ]
 }
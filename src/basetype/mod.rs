@@ -1,10 +1,12 @@
 //! Handles "base types" such as inode/* and text/plain
-const TYPES: [&str; 5] = [
+const TYPES: [&str; 7] = [
     "all/all",
     "all/allfiles",
     "inode/directory",
+    "inode/mount-point",
     "text/plain",
     "application/octet-stream",
+    "application/x-zerosize",
 ];
 
 pub mod check;
@@ -0,0 +1,51 @@
+//! Internal hooks for evaluating performance work, such as changes to the
+//! walk order. Enabled by the `bench` feature; not covered by semver.
+use crate::{database, fdo_magic};
+use alloc::vec::Vec;
+
+/// Number of types in the type graph
+pub fn type_count() -> usize {
+    database().graph().node_count()
+}
+
+/// Number of magic rules loaded for the given type
+pub fn rule_count(mimetype: &str) -> usize {
+    database().rule_count(mimetype)
+}
+
+/// Number of magic rules loaded for all types
+pub fn total_rule_count() -> usize {
+    database()
+        .graph()
+        .node_weights()
+        .map(|m| rule_count(m))
+        .sum()
+}
+
+/// Number of graph nodes checked while detecting the type of `bytes`
+pub fn nodes_visited(bytes: &[u8]) -> usize {
+    let mut visited = 0;
+    database().typegraph_walker(database().root_node(), &bytes, &mut visited);
+    visited
+}
+
+/// Subclasses of the given type, in the order the walk checks them
+pub fn walk_order(mimetype: &str) -> Vec<&'static str> {
+    match database().node(mimetype) {
+        Some(node) => database()
+            .walk_order(node)
+            .into_iter()
+            .map(|n| database().graph()[n])
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Checks `bytes` against the magic rules of one type, bypassing alias
+/// resolution and checker lookup
+pub fn match_magic(mimetype: &str, bytes: &[u8]) -> bool {
+    match database().magic(mimetype) {
+        Some(rules) => fdo_magic::builtin::check::from_u8(bytes, &rules),
+        None => false,
+    }
+}
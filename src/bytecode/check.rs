@@ -0,0 +1,149 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Bytecode;
+
+impl crate::Checker for Bytecode {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        Some(HEADER_LEN)
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        variant(source, mimetype)
+    }
+}
+
+const HEADER_LEN: usize = 16;
+
+/// Shared by Java classes and fat Mach-O binaries
+const CAFEBABE: [u8; 4] = [0xCA, 0xFE, 0xBA, 0xBE];
+/// Fat Mach-O binaries with 64-bit offsets
+const CAFEBABF: [u8; 4] = [0xCA, 0xFE, 0xBA, 0xBF];
+
+/// Major version of the first Java class file format. Fat Mach-O binaries
+/// store their slice count where classes store their version, and never
+/// have anywhere near this many slices.
+const JAVA_MIN_VERSION: u32 = 45;
+
+/// Size of a `fat_arch` entry, after the 8 byte fat header
+const FAT_ARCH_LEN: usize = 20;
+const FAT_ARCH64_LEN: usize = 32;
+
+const CPU_ARCH_ABI64: u32 = 0x0100_0000;
+const CPU_TYPE_X86: u32 = 7;
+const CPU_TYPE_ARM: u32 = 12;
+const CPU_TYPE_POWERPC: u32 = 18;
+
+fn be_u32(b: &[u8], off: usize) -> Option<u32> {
+    let x = b.get(off..off + 4)?;
+    Some(u32::from_be_bytes([x[0], x[1], x[2], x[3]]))
+}
+
+fn is_wasm(b: &[u8]) -> bool {
+    b.starts_with(b"\0asm") && b.get(4..8) == Some(&[1, 0, 0, 0])
+}
+
+/// `dex\n` followed by a three digit version and a NUL
+fn is_dex(b: &[u8]) -> bool {
+    b.starts_with(b"dex\n")
+        && b.get(4..7)
+            .is_some_and(|v| v.iter().all(u8::is_ascii_digit))
+        && b.get(7) == Some(&0)
+}
+
+fn is_java_class(b: &[u8]) -> bool {
+    b.starts_with(&CAFEBABE) && be_u32(b, 4).is_some_and(|v| v >= JAVA_MIN_VERSION)
+}
+
+/// Number of slices in a fat Mach-O binary
+fn fat_slices(b: &[u8]) -> Option<u32> {
+    if !(b.starts_with(&CAFEBABE) || b.starts_with(&CAFEBABF)) {
+        return None;
+    }
+    match be_u32(b, 4)? {
+        n if n > 0 && n < JAVA_MIN_VERSION => Some(n),
+        _ => None,
+    }
+}
+
+/// CPU type of a thin Mach-O binary, in either byte order
+fn thin_cputype(b: &[u8]) -> Option<u32> {
+    let magic = be_u32(b, 0)?;
+    let cputype = be_u32(b, 4)?;
+    match magic {
+        0xFEED_FACE | 0xFEED_FACF => Some(cputype),
+        0xCEFA_EDFE | 0xCFFA_EDFE => Some(cputype.swap_bytes()),
+        _ => None,
+    }
+}
+
+fn arch_name(cputype: u32) -> Option<&'static str> {
+    match cputype {
+        CPU_TYPE_X86 => Some("i386"),
+        x if x == CPU_TYPE_X86 | CPU_ARCH_ABI64 => Some("x86_64"),
+        CPU_TYPE_ARM => Some("arm"),
+        x if x == CPU_TYPE_ARM | CPU_ARCH_ABI64 => Some("arm64"),
+        CPU_TYPE_POWERPC => Some("ppc"),
+        x if x == CPU_TYPE_POWERPC | CPU_ARCH_ABI64 => Some("ppc64"),
+        _ => None,
+    }
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    let b = match source.read_at(0, HEADER_LEN) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+
+    match mimetype {
+        "application/wasm" => is_wasm(&b),
+        "application/vnd.android.dex" => is_dex(&b),
+        "application/x-java" => is_java_class(&b),
+        "application/x-mach-binary" => fat_slices(&b).is_some() || thin_cputype(&b).is_some(),
+        _ => false,
+    }
+}
+
+/// Reports the architecture of Mach-O binaries. Fat binaries report
+/// `universal`, or `universal-arm64` if one of their slices is for arm64.
+pub fn variant(source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+    if mimetype != "application/x-mach-binary" {
+        return None;
+    }
+    let b = source.read_at(0, HEADER_LEN).ok()?;
+    if let Some(cputype) = thin_cputype(&b) {
+        return arch_name(cputype);
+    }
+
+    let slices = fat_slices(&b)? as usize;
+    let arch_len = if b.starts_with(&CAFEBABF) {
+        FAT_ARCH64_LEN
+    } else {
+        FAT_ARCH_LEN
+    };
+    let archs = source.read_at(8, slices * arch_len).ok()?;
+    let has_arm64 = archs
+        .chunks_exact(arch_len)
+        .any(|a| be_u32(a, 0) == Some(CPU_TYPE_ARM | CPU_ARCH_ABI64));
+    if has_arm64 {
+        Some("universal-arm64")
+    } else {
+        Some("universal")
+    }
+}
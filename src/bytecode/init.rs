@@ -0,0 +1,25 @@
+use crate::{FnvHashMap, MIME};
+use alloc::vec::Vec;
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    Vec::new()
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/x-dex", "application/vnd.android.dex"),
+        ("application/x-mach-o", "application/x-mach-binary"),
+        (
+            "application/x-mach-o-executable",
+            "application/x-mach-binary",
+        ),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
@@ -0,0 +1,11 @@
+//! Handles bytecode formats (WebAssembly, Dalvik, Java) and Mach-O binaries,
+//! which share Java's magic number
+const TYPES: [&str; 4] = [
+    "application/wasm",
+    "application/vnd.android.dex",
+    "application/x-java",
+    "application/x-mach-binary",
+];
+
+pub mod check;
+pub mod init;
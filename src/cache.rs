@@ -0,0 +1,180 @@
+//! Remember the types of files between scans, for as long as they're left
+//! unchanged
+use crate::{FnvHashMap, TypeDatabase};
+use alloc::string::{String, ToString};
+use core::str::{FromStr, Split};
+use std::fs::Metadata;
+use std::io::{self, BufRead, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+/// First line of a saved cache, changed along with the format
+const HEADER: &str = "tree_magic_mini cache 1";
+
+/// Device and inode of a file
+type Key = (u64, u64);
+
+/// A file as it was when its type was found
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Seconds and nanoseconds
+    mtime: (i64, i64),
+    size: u64,
+    mime: String,
+}
+
+impl Entry {
+    fn is_current(&self, meta: &Metadata) -> bool {
+        self.mtime == (meta.mtime(), meta.mtime_nsec()) && self.size == meta.len()
+    }
+}
+
+/// Types of files found before, kept by device and inode, so scanning a tree
+/// again only reads the files changed since. An entry is used as long as
+/// the modification time and size of its file stay the same.
+///
+/// Only regular files are kept; the types of directories and other special
+/// files come from their metadata alone. Entries of files that went away
+/// stay until [`clear`](Self::clear). A cache can be shared between threads,
+/// and [saved](Self::save) for the next run of the program. Needs the `std`
+/// feature, on Unix.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+/// use tree_magic_mini::{DetectionCache, TypeDatabase};
+///
+/// let db = TypeDatabase::builder().build();
+/// let cache = DetectionCache::new();
+/// let gif = Path::new("tests/image/gif");
+///
+/// assert_eq!(cache.detect_filepath(&db, gif), Some("image/gif"));
+/// // Not read again
+/// assert_eq!(cache.detect_filepath(&db, gif), Some("image/gif"));
+/// assert_eq!(cache.len(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct DetectionCache {
+    entries: Mutex<FnvHashMap<Key, Entry>>,
+}
+
+impl DetectionCache {
+    /// An empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FnvHashMap<Key, Entry>> {
+        // Entries are only ever replaced whole, so none can be left half
+        // written by a panic
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Gets the type of a file like
+    /// [`TypeDatabase::from_filepath`], without reading the file if the
+    /// cache has its type from before and it hasn't changed since.
+    ///
+    /// Types are kept by name, and the database is asked for each again, so
+    /// a cached type it doesn't know is detected anew. A cache is meant to
+    /// be used with a single database, as different databases may find
+    /// different types for the same file.
+    pub fn detect_filepath<'a>(&self, db: &'a TypeDatabase, filepath: &Path) -> Option<&'a str> {
+        let meta = std::fs::metadata(filepath).ok()?;
+        if !meta.is_file() {
+            return db.from_filepath_with_meta(filepath, &meta);
+        }
+        let key = (meta.dev(), meta.ino());
+        let cached = self
+            .lock()
+            .get(&key)
+            .filter(|e| e.is_current(&meta))
+            .and_then(|e| db.node(&e.mime));
+        if let Some(node) = cached {
+            return Some(db.graph()[node]);
+        }
+
+        let mime = db.from_filepath_with_meta(filepath, &meta)?;
+        let entry = Entry {
+            mtime: (meta.mtime(), meta.mtime_nsec()),
+            size: meta.len(),
+            mime: mime.to_string(),
+        };
+        self.lock().insert(key, entry);
+        Some(mime)
+    }
+
+    /// Number of files kept.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether no file is kept.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Forgets every file.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Writes the cache out as text, one file per line, to be read back with
+    /// [`load`](Self::load). Device and inode numbers only hold for the same
+    /// machine, with the same file systems mounted.
+    pub fn save(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "{}", HEADER)?;
+        for ((dev, ino), e) in self.lock().iter() {
+            writeln!(
+                writer,
+                "{} {} {} {} {} {}",
+                dev, ino, e.mtime.0, e.mtime.1, e.size, e.mime
+            )?;
+        }
+        writer.flush()
+    }
+
+    /// Reads a cache written by [`save`](Self::save).
+    ///
+    /// Fails with [`InvalidData`](io::ErrorKind::InvalidData) if it isn't
+    /// one, or was written by a version of this crate using another format.
+    pub fn load(reader: impl BufRead) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "not a detection cache");
+        let mut lines = reader.lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid());
+        }
+        let mut entries = FnvHashMap::default();
+        for line in lines {
+            let line = line?;
+            let (key, entry) = parse_line(&line).ok_or_else(invalid)?;
+            entries.insert(key, entry);
+        }
+        Ok(DetectionCache {
+            entries: Mutex::new(entries),
+        })
+    }
+}
+
+/// Reads a field of a saved entry
+fn field<T: FromStr>(fields: &mut Split<'_, char>) -> Option<T> {
+    fields.next()?.parse().ok()
+}
+
+/// An entry as [`DetectionCache::save`] writes it
+fn parse_line(line: &str) -> Option<(Key, Entry)> {
+    let mut fields = line.split(' ');
+    let key = (field(&mut fields)?, field(&mut fields)?);
+    let mtime = (field(&mut fields)?, field(&mut fields)?);
+    let size = field(&mut fields)?;
+    let mime = fields.next().filter(|m| !m.is_empty())?;
+    if fields.next().is_some() {
+        return None;
+    }
+    let entry = Entry {
+        mtime,
+        size,
+        mime: mime.to_string(),
+    };
+    Some((key, entry))
+}
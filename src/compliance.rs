@@ -0,0 +1,51 @@
+//! Which parts of the shared MIME-info spec this build implements
+use self::SpecFeature::*;
+
+/// A part of the
+/// [shared MIME-info spec](https://specifications.freedesktop.org/shared-mime-info-spec/shared-mime-info-spec-latest.html),
+/// as listed by [`compliance`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SpecFeature {
+    /// Content detection with the rules of `magic` files, including masks,
+    /// word sizes and ranges.
+    Magic,
+    /// File names matched against the patterns of `globs2` files.
+    Globs,
+    /// Directories told apart by the files they hold, from `treemagic`
+    /// files.
+    TreeMagic,
+    /// XML documents told apart by their root element and namespace.
+    XmlRoot,
+    /// Other names for types, from `aliases` files.
+    Aliases,
+    /// Types that are a kind of another, from `subclasses` files.
+    Subclassing,
+    /// Icon names of types, from `icons` and `generic-icons` files.
+    Icons,
+    /// Localized comments and acronyms of types, from the XML files of the
+    /// database.
+    Descriptions,
+}
+
+#[cfg(feature = "std")]
+const SUPPORTED: &[SpecFeature] = &[Magic, Globs, TreeMagic, Aliases, Subclassing, Descriptions];
+
+#[cfg(not(feature = "std"))]
+const SUPPORTED: &[SpecFeature] = &[Magic, Aliases, Subclassing];
+
+/// Lists the parts of the shared MIME-info spec this build of the crate
+/// implements, so callers can check for them at runtime instead of by
+/// version. Globs, treemagic and descriptions need the `std` feature, as
+/// they're read from the system.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::{compliance, SpecFeature};
+///
+/// assert!(compliance().contains(&SpecFeature::Magic));
+/// assert!(!compliance().contains(&SpecFeature::Icons));
+/// ```
+pub fn compliance() -> &'static [SpecFeature] {
+    SUPPORTED
+}
@@ -0,0 +1,269 @@
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct ConfigFormats;
+
+impl crate::Checker for ConfigFormats {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(db, source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        Some(PROBE_LEN)
+    }
+}
+
+/// How much of the text is looked at
+const PROBE_LEN: usize = 4096;
+
+/// Lines of the text, without the last one if the text was cut off within
+/// it, or any line endings
+fn lines(b: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let b = if b.len() < PROBE_LEN {
+        b
+    } else {
+        match b.iter().rposition(|&c| c == b'\n') {
+            Some(n) => &b[..n],
+            None => &[],
+        }
+    };
+    b.split(|&c| c == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+}
+
+fn trim(b: &[u8]) -> &[u8] {
+    let start = b.iter().position(|c| !c.is_ascii_whitespace());
+    let end = b.iter().rposition(|c| !c.is_ascii_whitespace());
+    match (start, end) {
+        (Some(s), Some(e)) => &b[s..=e],
+        _ => &[],
+    }
+}
+
+fn is_blank_or_comment(line: &[u8]) -> bool {
+    matches!(trim(line).first(), None | Some(b'#'))
+}
+
+/// Where the key of a YAML mapping entry ends, at a colon followed by a
+/// space or the end of the line
+fn yaml_key_end(content: &[u8]) -> Option<usize> {
+    let n = (0..content.len())
+        .find(|&i| content[i] == b':' && matches!(content.get(i + 1), None | Some(b' ')))?;
+    let key = &content[..n];
+    let ok = match key.first()? {
+        b'"' | b'\'' => true,
+        c => c.is_ascii_alphanumeric() || *c == b'_',
+    };
+    (ok && !key.windows(2).any(|w| w == b" #")).then_some(n)
+}
+
+/// Every line a mapping entry, list item, document marker, comment, or
+/// part of a block scalar, with at least two entries, or a marker and an
+/// entry or item. Items alone could as well be a Markdown list.
+fn is_yaml(b: &[u8]) -> bool {
+    let mut entries = 0;
+    let mut items = 0;
+    let mut markers = 0;
+    // Indent of the line starting a block scalar, which more indented lines
+    // belong to
+    let mut block: Option<usize> = None;
+
+    for line in lines(b) {
+        let indent = line.iter().take_while(|&&c| c == b' ').count();
+        if let Some(n) = block {
+            if indent > n || trim(line).is_empty() {
+                continue;
+            }
+            block = None;
+        }
+        if is_blank_or_comment(line) {
+            continue;
+        }
+        if line.get(indent) == Some(&b'\t') {
+            return false;
+        }
+        if line == b"---" || line.starts_with(b"--- ") || line == b"..." {
+            markers += 1;
+            continue;
+        }
+
+        let mut content = trim(line);
+        let mut item = false;
+        while content == b"-" || content.starts_with(b"- ") {
+            content = trim(&content[1..]);
+            item = true;
+        }
+        match yaml_key_end(content) {
+            Some(n) => {
+                let value = trim(&content[n + 1..]);
+                if matches!(value.first(), Some(b'|' | b'>')) {
+                    block = Some(indent);
+                }
+                entries += 1;
+            }
+            None if item => items += 1,
+            None => return false,
+        }
+    }
+    entries >= 2 || (markers >= 1 && entries + items >= 1)
+}
+
+/// A bare or quoted key, or dotted keys made of them
+fn is_toml_key(key: &[u8]) -> bool {
+    let key = trim(key);
+    !key.is_empty()
+        && key.split(|&c| c == b'.').all(|part| {
+            let part = trim(part);
+            match part {
+                [b'"', .., b'"'] | [b'\'', .., b'\''] => part.len() >= 2,
+                _ => {
+                    !part.is_empty()
+                        && part
+                            .iter()
+                            .all(|&c| c.is_ascii_alphanumeric() || c == b'_' || c == b'-')
+                }
+            }
+        })
+}
+
+/// Change in the depth of arrays over a line, ignoring brackets in strings
+fn bracket_depth(line: &[u8]) -> isize {
+    let mut depth = 0;
+    let mut quote = None;
+    for &c in line {
+        match (quote, c) {
+            (None, b'#') => break,
+            (None, b'"' | b'\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, b'[') => depth += 1,
+            (None, b']') => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// What follows a value on its line, which must be nothing or a comment
+fn toml_after(rest: &[u8]) -> bool {
+    is_blank_or_comment(rest)
+}
+
+/// Every line a table header, a key with a value, a comment or part of a
+/// multi-line value, with a key and either a header or another key
+fn is_toml(b: &[u8]) -> bool {
+    let mut keys = 0;
+    let mut tables = 0;
+    let mut array_depth = 0;
+    let mut multiline: Option<&[u8]> = None;
+
+    for line in lines(b) {
+        if let Some(delim) = multiline {
+            if line.windows(3).any(|w| w == delim) {
+                multiline = None;
+            }
+            continue;
+        }
+        if array_depth > 0 {
+            array_depth += bracket_depth(line);
+            continue;
+        }
+        if is_blank_or_comment(line) {
+            continue;
+        }
+
+        let content = trim(line);
+        if content[0] == b'[' {
+            let inner = content
+                .strip_prefix(b"[[")
+                .and_then(|x| Some(x.split_at(x.windows(2).position(|w| w == b"]]")?)))
+                .map(|(name, rest)| (name, &rest[2..]))
+                .or_else(|| {
+                    let x = &content[1..];
+                    let n = x.iter().position(|&c| c == b']')?;
+                    Some((&x[..n], &x[n + 1..]))
+                });
+            match inner {
+                Some((name, rest)) if is_toml_key(name) && toml_after(rest) => tables += 1,
+                _ => return false,
+            }
+            continue;
+        }
+
+        let n = match content.iter().position(|&c| c == b'=') {
+            Some(n) if is_toml_key(&content[..n]) => n,
+            _ => return false,
+        };
+        let value = trim(&content[n + 1..]);
+        let ok = match value {
+            [] => false,
+            [b'"', b'"', b'"', rest @ ..] | [b'\'', b'\'', b'\'', rest @ ..] => {
+                let delim = &value[..3];
+                if !rest.windows(3).any(|w| w == delim) {
+                    multiline = Some(delim);
+                }
+                true
+            }
+            [q @ (b'"' | b'\''), rest @ ..] => match rest.iter().position(|c| c == q) {
+                Some(end) => toml_after(&rest[end + 1..]),
+                None => false,
+            },
+            [b'[', ..] => {
+                array_depth = bracket_depth(value);
+                array_depth >= 0
+            }
+            [b'{', ..] => value.contains(&b'}'),
+            _ => {
+                let token_len = value
+                    .iter()
+                    .take_while(|&&c| {
+                        c.is_ascii_alphanumeric() || matches!(c, b'_' | b'.' | b':' | b'+' | b'-')
+                    })
+                    .count();
+                let token = &value[..token_len];
+                let scalar = matches!(token, b"true" | b"false" | b"inf" | b"nan")
+                    || matches!(token.first(), Some(c) if c.is_ascii_digit())
+                    || matches!(token, [b'+' | b'-', c, ..] if c.is_ascii_digit() || *c == b'i' || *c == b'n');
+                scalar && toml_after(&value[token_len..])
+            }
+        };
+        if !ok {
+            return false;
+        }
+        keys += 1;
+    }
+    keys >= 1 && (tables >= 1 || keys >= 2)
+}
+
+pub fn from_source(db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+    // Magic such as the `%YAML` directive holds whether or not the
+    // structure is looked at
+    if fdo_magic::builtin::check::from_source(db, source, mimetype) {
+        return true;
+    }
+    if !db.text_heuristics() {
+        return false;
+    }
+    let b = match source.read_at(0, PROBE_LEN) {
+        Ok(b) => b,
+        Err(_) => return false,
+    };
+    let b = b.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&b);
+
+    match mimetype {
+        "application/x-yaml" => is_yaml(b),
+        "application/toml" => is_toml(b),
+        _ => false,
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    vec![
+        ("text/plain", "application/x-yaml"),
+        ("text/plain", "application/toml"),
+    ]
+}
+
+/// The registered name of YAML is newer than the one the shared MIME-info
+/// database uses
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/yaml", "application/x-yaml"),
+        ("application/x-toml", "application/toml"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
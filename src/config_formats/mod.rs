@@ -0,0 +1,8 @@
+//! Tells YAML and TOML apart from other text by the structure of its lines.
+//!
+//! Being guesses, these can be turned off with
+//! [`text_heuristics`](crate::TypeDatabaseBuilder::text_heuristics).
+const TYPES: [&str; 2] = ["application/x-yaml", "application/toml"];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,234 @@
+use crate::source::source_len;
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct DataFormats;
+
+impl crate::Checker for DataFormats {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "application/vnd.apache.parquet" => Some(PARQUET_MAGIC.len()),
+            "application/vnd.apache.avro" => Some(AVRO_PROBE_LEN),
+            "application/cbor" => Some(CBOR_MAGIC.len()),
+            "application/vnd.msgpack" => Some(MSGPACK_MAX_LEN + 1),
+            // These look at the end of the data
+            _ => None,
+        }
+    }
+}
+
+/// `PAR1`, followed by the first field of the Thrift header of the first
+/// page: its type, as a 32-bit integer
+const PARQUET_MAGIC: &[u8; 5] = b"PAR1\x15";
+
+const AVRO_MAGIC: &[u8; 4] = b"Obj\x01";
+/// Magic, count of metadata entries, and the start of the first key
+const AVRO_PROBE_LEN: usize = 32;
+
+const ORC_MAGIC: &[u8; 3] = b"ORC";
+/// Magic as the last field of the postscript, then the postscript length
+const ORC_TAIL_LEN: usize = 5;
+
+/// Tag 55799, which marks data as CBOR without changing its meaning
+const CBOR_MAGIC: &[u8; 3] = b"\xd9\xd9\xf7";
+
+/// Length, type of the first element, and its name
+const BSON_PROBE_LEN: usize = 64;
+
+/// MessagePack has no magic, so the data is only taken for it when it
+/// parses as a whole, which it must be short enough for
+const MSGPACK_MAX_LEN: usize = 64 * 1024;
+/// Deepest nesting followed, to keep the parser off the end of the stack
+const MSGPACK_MAX_DEPTH: usize = 64;
+
+/// A zigzag-encoded variable-length integer, and the bytes after it
+fn avro_long(b: &[u8]) -> Option<(i64, &[u8])> {
+    let mut n = 0u64;
+    for (i, &c) in b.iter().enumerate().take(10) {
+        n |= ((c & 0x7f) as u64) << (7 * i);
+        if c & 0x80 == 0 {
+            return Some(((n >> 1) as i64 ^ -((n & 1) as i64), &b[i + 1..]));
+        }
+    }
+    None
+}
+
+/// The header of an object container file is a map of metadata, whose keys
+/// all start with `avro.`
+fn is_avro(b: &[u8]) -> bool {
+    let b = match b.strip_prefix(AVRO_MAGIC) {
+        Some(b) => b,
+        None => return false,
+    };
+    let (count, b) = match avro_long(b) {
+        Some(x) => x,
+        None => return false,
+    };
+    // A negative count is followed by the size of the block in bytes
+    let b = match count {
+        0 => return false,
+        c if c < 0 => match avro_long(b) {
+            Some((_, b)) => b,
+            None => return false,
+        },
+        _ => b,
+    };
+    match avro_long(b) {
+        Some((len, key)) => len >= 5 && key.starts_with(b"avro."),
+        None => false,
+    }
+}
+
+/// The postscript at the end says how long it is in its last byte, and ends
+/// with the magic again
+fn is_orc(source: &dyn DataSource) -> bool {
+    match source.read_at(0, ORC_MAGIC.len()) {
+        Ok(b) if b[..] == ORC_MAGIC[..] => {}
+        _ => return false,
+    }
+    let len = match source_len(source) {
+        Some(len) if len >= (ORC_MAGIC.len() + ORC_TAIL_LEN) as u64 => len,
+        _ => return false,
+    };
+    match source.read_at(len - ORC_TAIL_LEN as u64, ORC_TAIL_LEN) {
+        Ok(b) => b.len() == ORC_TAIL_LEN && b[..4] == b"\x03ORC"[..] && b[4] >= 5,
+        Err(_) => false,
+    }
+}
+
+/// Element types of BSON, including deprecated ones, and min and max key
+fn is_bson_type(t: u8) -> bool {
+    matches!(t, 0x01..=0x13 | 0x7f | 0xff)
+}
+
+/// A document holding at least one element, whose length says where the
+/// data ends
+fn is_bson(source: &dyn DataSource) -> bool {
+    let b = match source.read_at(0, BSON_PROBE_LEN) {
+        Ok(b) => b,
+        Err(_) => return false,
+    };
+    if b.len() < 7 || !is_bson_type(b[4]) {
+        return false;
+    }
+    // The name of the first element
+    match b[5..].iter().position(|&c| c == 0) {
+        Some(n) if b[5..5 + n].iter().all(|c| (0x20..0x7f).contains(c)) => {}
+        _ => return false,
+    }
+    // The document ends with a null byte, and so does the data
+    let len = u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as u64;
+    if len < 7 {
+        return false;
+    }
+    match source.read_at(len - 1, 2) {
+        Ok(end) => end[..] == [0],
+        Err(_) => false,
+    }
+}
+
+/// Reads a big-endian length of `n` bytes at `pos`
+fn msgpack_len(b: &[u8], pos: usize, n: usize) -> Option<usize> {
+    let bytes = b.get(pos..pos + n)?;
+    Some(bytes.iter().fold(0usize, |len, &c| len << 8 | c as usize))
+}
+
+/// Skips over `count` values starting at `pos`, returning where they end
+fn msgpack_skip(b: &[u8], mut pos: usize, count: usize, depth: usize) -> Option<usize> {
+    if depth > MSGPACK_MAX_DEPTH {
+        return None;
+    }
+    for _ in 0..count {
+        let c = *b.get(pos)?;
+        pos += 1;
+        pos = match c {
+            0x00..=0x7f | 0xc0 | 0xc2 | 0xc3 | 0xe0..=0xff => pos,
+            0x80..=0x8f => msgpack_skip(b, pos, 2 * (c & 0x0f) as usize, depth + 1)?,
+            0x90..=0x9f => msgpack_skip(b, pos, (c & 0x0f) as usize, depth + 1)?,
+            0xa0..=0xbf => pos + (c & 0x1f) as usize,
+            0xc1 => return None,
+            // bin and str of 8, 16 and 32-bit lengths
+            0xc4 | 0xd9 => (pos + 1).checked_add(msgpack_len(b, pos, 1)?)?,
+            0xc5 | 0xda => (pos + 2).checked_add(msgpack_len(b, pos, 2)?)?,
+            0xc6 | 0xdb => (pos + 4).checked_add(msgpack_len(b, pos, 4)?)?,
+            // ext, with its type after the length
+            0xc7 => (pos + 2).checked_add(msgpack_len(b, pos, 1)?)?,
+            0xc8 => (pos + 3).checked_add(msgpack_len(b, pos, 2)?)?,
+            0xc9 => (pos + 5).checked_add(msgpack_len(b, pos, 4)?)?,
+            0xca => pos + 4,
+            0xcb => pos + 8,
+            0xcc | 0xd0 => pos + 1,
+            0xcd | 0xd1 => pos + 2,
+            0xce | 0xd2 => pos + 4,
+            0xcf | 0xd3 => pos + 8,
+            // fixext of 1 to 16 bytes, with its type
+            0xd4..=0xd8 => pos + 1 + (1 << (c - 0xd4)),
+            0xdc => msgpack_skip(b, pos + 2, msgpack_len(b, pos, 2)?, depth + 1)?,
+            0xdd => msgpack_skip(b, pos + 4, msgpack_len(b, pos, 4)?, depth + 1)?,
+            0xde => msgpack_skip(b, pos + 2, 2 * msgpack_len(b, pos, 2)?, depth + 1)?,
+            0xdf => {
+                let len = msgpack_len(b, pos, 4)?.checked_mul(2)?;
+                msgpack_skip(b, pos + 4, len, depth + 1)?
+            }
+        };
+        if pos > b.len() {
+            return None;
+        }
+    }
+    Some(pos)
+}
+
+/// A non-empty map with string keys, making up all of the data
+fn is_msgpack(b: &[u8]) -> bool {
+    let (count, mut pos) = match b.first() {
+        Some(&c @ 0x81..=0x8f) => ((c & 0x0f) as usize, 1),
+        Some(0xde) => (msgpack_len(b, 1, 2).unwrap_or(0), 3),
+        Some(0xdf) => (msgpack_len(b, 1, 4).unwrap_or(0), 5),
+        _ => return false,
+    };
+    if count == 0 || b.len() > MSGPACK_MAX_LEN {
+        return false;
+    }
+    for _ in 0..count {
+        if !matches!(b.get(pos), Some(0xa0..=0xbf | 0xd9..=0xdb)) {
+            return false;
+        }
+        pos = match msgpack_skip(b, pos, 2, 1) {
+            Some(x) => x,
+            None => return false,
+        };
+    }
+    pos == b.len()
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    let (len, check): (usize, fn(&[u8]) -> bool) = match mimetype {
+        "application/vnd.apache.parquet" => (PARQUET_MAGIC.len(), |b| b == PARQUET_MAGIC),
+        "application/vnd.apache.avro" => (AVRO_PROBE_LEN, is_avro),
+        "application/vnd.apache.orc" => return is_orc(source),
+        "application/cbor" => (CBOR_MAGIC.len(), |b| b == CBOR_MAGIC),
+        "application/bson" => return is_bson(source),
+        "application/vnd.msgpack" => (MSGPACK_MAX_LEN + 1, is_msgpack),
+        _ => return false,
+    };
+    match source.read_at(0, len) {
+        Ok(b) => check(&b),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{FnvHashMap, MIME};
+use alloc::vec::Vec;
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    Vec::new()
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/x-parquet", "application/vnd.apache.parquet"),
+        ("application/avro", "application/vnd.apache.avro"),
+        ("application/x-msgpack", "application/vnd.msgpack"),
+        ("application/msgpack", "application/vnd.msgpack"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
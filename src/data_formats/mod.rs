@@ -0,0 +1,13 @@
+//! Handles data serialization formats missing from older shared MIME-info
+//! databases, such as Parquet and Avro
+const TYPES: [&str; 6] = [
+    "application/vnd.apache.parquet",
+    "application/vnd.apache.avro",
+    "application/vnd.apache.orc",
+    "application/cbor",
+    "application/bson",
+    "application/vnd.msgpack",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,1834 @@
+//! Loaded MIME types, their relations and their magic rules
+use crate::arena::Arena;
+use crate::basetype;
+use crate::family::Family;
+use crate::fdo_magic::lazy::{LazyRuleset, Rules, Section};
+use crate::fdo_magic::{self, MagicRule, MagicRuleset};
+use crate::footprint::{graph_size, map_size};
+use crate::options::WalkLimits;
+use crate::perfect::PerfectMap;
+use crate::source::BudgetedSource;
+use crate::stats::CountedSource;
+#[cfg(feature = "std")]
+use crate::stream::StreamSource;
+use crate::{
+    accept, diff, export, lint, scan, sensitivity, AnyChecker, Checker, DataSource, DatabaseDiff,
+    DetectOptions, FnvHashMap, FnvHashSet, GraphFormat, Lint, ScanOptions, StatsHook, TypeMatcher,
+    CHECKERS, MIME, TYPEORDER,
+};
+#[cfg(feature = "std")]
+use crate::{info, source, symlink, FileSource, FollowedPath};
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+use core::mem::size_of;
+use core::ops::Range;
+use core::sync::atomic::AtomicUsize;
+use petgraph::prelude::*;
+#[cfg(feature = "std")]
+use std::fs::{File, Metadata};
+#[cfg(feature = "std")]
+use std::io::{Seek, SeekFrom};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// Error returned when building a [`TypeDatabase`] from invalid input.
+///
+/// Problems the database can work around are kept as warnings instead, see
+/// [`TypeDatabase::warnings`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DatabaseError {
+    /// A magic file could not be parsed.
+    Magic(String),
+    /// A subclass relation closed a cycle, and was dropped so detection
+    /// can't walk in circles.
+    SubclassCycle {
+        /// The parent type.
+        parent: String,
+        /// The type that was given as its subclass.
+        child: String,
+    },
+    /// Aliases lead back to themselves without reaching a type, starting
+    /// from the lowest.
+    AliasCycle(Vec<String>),
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatabaseError::Magic(e) => write!(f, "invalid magic file: {}", e),
+            DatabaseError::SubclassCycle { parent, child } => write!(
+                f,
+                "subclass cycle: dropped {} as a subclass of {}",
+                child, parent
+            ),
+            DatabaseError::AliasCycle(aliases) => {
+                write!(f, "alias cycle: {} -> {}", aliases.join(" -> "), aliases[0])
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DatabaseError {}
+
+/// A set of MIME types to detect, with everything needed to detect them.
+///
+/// The free functions of this crate use a database holding the system (or
+/// embedded) shared MIME-info data. Build your own with
+/// [`TypeDatabase::builder`] to add or exclude magic rules.
+///
+/// Types are returned as `&str` borrowed from the database, since the names
+/// of types added at runtime are owned by it. The free functions return
+/// `&'static str`, as their database is never dropped.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::TypeDatabase;
+///
+/// // Don't report files starting with "PK\x03\x04PLAIN" as ZIP archives
+/// let db = TypeDatabase::builder()
+///     .exclude("application/zip", 4, b"PLAIN")
+///     .build();
+///
+/// assert!(!db.match_u8("application/zip", b"PK\x03\x04PLAIN"));
+/// ```
+pub struct TypeDatabase {
+    /// Subclass relations between all types, pointing from parent to child.
+    /// The root is "all/all".
+    graph: DiGraph<MIME, u32>,
+    nodes: FnvHashMap<MIME, NodeIndex>,
+    /// Every type and alias, looked up for each check of a type by name,
+    /// with a single probe of a perfect hash map
+    types: PerfectMap<TypeEntry>,
+    /// Topmost types of each family, the only ones a family check tests
+    families: FnvHashMap<Family, Vec<NodeIndex>>,
+    /// Problems worked around while building
+    warnings: Vec<DatabaseError>,
+    /// Bytes left for keeping rule trees, with a memory limit
+    magic_budget: Option<AtomicUsize>,
+    /// Whether PDF documents must start with their header
+    strict_pdf: bool,
+    /// Whether YAML, TOML and OBJ are told by the structure of text
+    text_heuristics: bool,
+    /// Whether the language of source code is told from its contents
+    source_heuristics: bool,
+    /// Whether CSV and TSV are told by the number of fields on each line
+    delimited_heuristics: bool,
+    /// Whether game ROMs and asset containers are told by their structure
+    game_formats: bool,
+    /// Names of types added by the builder and the magic files given to it,
+    /// only held to be freed along with the database
+    #[allow(dead_code)]
+    names: Arena,
+}
+
+/// What the database knows of a name
+#[derive(Default)]
+struct TypeEntry {
+    /// The type the name is an alias of
+    alias: Option<MIME>,
+    checker: Option<AnyChecker>,
+    /// Parsed the first time the type is checked
+    magic: Option<LazyRuleset>,
+}
+
+/// Fewest children of a node worth checking in parallel
+#[cfg(feature = "rayon")]
+const PARALLEL_MIN_CHILDREN: usize = 16;
+
+/// Smallest input worth checking in parallel. Below this, the rules of a
+/// node finish faster than threads can be handed work.
+#[cfg(feature = "rayon")]
+const PARALLEL_MIN_LEN: usize = 64 * 1024;
+
+/// Builder for a [`TypeDatabase`], returned by [`TypeDatabase::builder`].
+#[derive(Default)]
+pub struct TypeDatabaseBuilder {
+    /// Rules borrowing their patterns from `files`
+    magic: Vec<(String, u32, MagicRuleset, Layer)>,
+    /// The magic files given, each kept whole so their patterns need no
+    /// allocations of their own. Handed on to the database.
+    files: Arena,
+    aliases: Vec<(String, String)>,
+    subclasses: Vec<(String, String)>,
+    disabled_types: Vec<String>,
+    disabled_rules: Vec<(String, usize)>,
+    retain: Option<Vec<String>>,
+    memory_limit: Option<usize>,
+    strict_pdf: bool,
+    skip_text_heuristics: bool,
+    source_heuristics: bool,
+    delimited_heuristics: bool,
+    game_formats: bool,
+}
+
+/// How rules given to the builder combine with the rules already loaded for
+/// their type
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layer {
+    /// Added to the existing rules
+    Merge,
+    /// Replace the existing rules, as a per-user definition does
+    Override,
+}
+
+impl TypeDatabaseBuilder {
+    /// Adds the rules of a magic file, in the binary format used by the
+    /// shared MIME-info database (`/usr/share/mime/magic`).
+    ///
+    /// Rules for already known types are added to their existing rules; new
+    /// types are placed under `text/plain` or `application/octet-stream`.
+    ///
+    /// As an extension, a rule line may start with `!`. A negated top-level
+    /// rule excludes the type whenever it matches, whichever checker handles
+    /// the type. A negated nested rule holds when its pattern does *not*
+    /// match.
+    ///
+    /// ```text
+    /// MIME-Magic\0
+    /// [50:application/x-foo]
+    /// >0=\0\x03FOO
+    /// !1>3=\0\x01X
+    /// [50:application/zip]
+    /// !>30=\0\x08mimetype
+    /// ```
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let db = TypeDatabase::builder()
+    ///     .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n!1>3=\0\x01X\n")
+    ///     .unwrap()
+    ///     .build();
+    ///
+    /// assert!(db.match_u8("application/x-foo", b"FOObar"));
+    /// // The negated nested rule matches, so the type doesn't
+    /// assert!(!db.match_u8("application/x-foo", b"FOOXbar"));
+    /// ```
+    pub fn magic(self, data: &[u8]) -> Result<Self, DatabaseError> {
+        self.add_magic(data, Layer::Merge)
+    }
+
+    /// Adds the rules of a magic file that take precedence over everything
+    /// loaded before.
+    ///
+    /// Unlike [`magic`](Self::magic), the rules of a type defined here replace
+    /// its existing rules instead of being added to them. This is how the
+    /// shared MIME-info spec layers its directories: a type defined in
+    /// `~/.local/share/mime` overrides the system definition as a whole.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let db = TypeDatabase::builder()
+    ///     .override_magic(b"MIME-Magic\0\n[50:image/gif]\n>0=\0\x03FOO\n")
+    ///     .unwrap()
+    ///     .build();
+    ///
+    /// let gif: &[u8] = include_bytes!("../tests/image/gif");
+    /// assert!(!db.match_u8("image/gif", gif));
+    /// assert!(db.match_u8("image/gif", b"FOObar"));
+    /// ```
+    pub fn override_magic(self, data: &[u8]) -> Result<Self, DatabaseError> {
+        self.add_magic(data, Layer::Override)
+    }
+
+    /// Adds the magic file of a MIME directory laid out like
+    /// `/usr/share/mime`, overriding the types it defines. A directory
+    /// without a magic file adds nothing.
+    ///
+    /// Directories added later take precedence, so add them from least to
+    /// most important.
+    #[cfg(feature = "std")]
+    pub fn mime_dir(self, dir: &Path) -> Result<Self, DatabaseError> {
+        let path = dir.join("magic");
+        match std::fs::read(&path) {
+            Ok(data) => self.override_magic(&data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(self),
+            Err(e) => Err(DatabaseError::Magic(alloc::format!(
+                "{}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Adds the user's own magic file, from `$XDG_DATA_HOME/mime` or
+    /// `~/.local/share/mime`, overriding the types it defines.
+    ///
+    /// Databases loaded from the system already include the user's file.
+    /// With embedded data (the `with-gpl-data` or `builtin-minimal`
+    /// feature), this is how to honour local definitions.
+    #[cfg(feature = "std")]
+    pub fn user_overrides(self) -> Result<Self, DatabaseError> {
+        match crate::xdg::user_mime_dir() {
+            Some(dir) => self.mime_dir(&dir),
+            None => Ok(self),
+        }
+    }
+
+    fn add_magic(mut self, data: &[u8], layer: Layer) -> Result<Self, DatabaseError> {
+        // Safety: the arena goes to the database along with the rules, and
+        // is only dropped with them
+        let data = unsafe { self.files.alloc_bytes(data.into()) };
+        let rules = fdo_magic::ruleset::from_u8(data).map_err(DatabaseError::Magic)?;
+        for (mime, (priority, graph)) in rules {
+            self.magic.push((mime.to_string(), priority, graph, layer));
+        }
+        Ok(self)
+    }
+
+    /// Excludes `mimetype` for any data containing `pattern` at `offset`.
+    ///
+    /// Useful to tell apart formats that share a prefix with more specific
+    /// formats, such as plain ZIP archives and ZIP-based documents.
+    pub fn exclude(mut self, mimetype: &str, offset: u32, pattern: &[u8]) -> Self {
+        let mut graph = MagicRuleset::new();
+        graph.add_node(MagicRule {
+            indent_level: 0,
+            start_off: offset,
+            // Safety: as for the patterns of magic files
+            val: unsafe { self.files.alloc_bytes(pattern.into()) },
+            mask: None,
+            word_len: 1,
+            region_len: 0,
+            negate: true,
+        });
+        // Lowest, so it doesn't raise the priority of the type's rules
+        self.magic
+            .push((mimetype.to_string(), 0, graph, Layer::Merge));
+        self
+    }
+
+    /// Adds `alias` as another name for `mimetype`, so legacy or in-house
+    /// names can be used wherever a type is asked for.
+    ///
+    /// Detection still reports `mimetype`. An alias that's already the name
+    /// of a known type is ignored, as is an alias of itself.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let db = TypeDatabase::builder()
+    ///     .alias("application/x-mycorp-doc", "application/zip")
+    ///     .build();
+    ///
+    /// let zip: &[u8] = include_bytes!("../tests/application/zip");
+    /// assert!(db.match_u8("application/x-mycorp-doc", zip));
+    /// ```
+    pub fn alias(mut self, alias: &str, mimetype: &str) -> Self {
+        self.aliases
+            .push((alias.to_ascii_lowercase(), mimetype.to_ascii_lowercase()));
+        self
+    }
+
+    /// Makes `mimetype` a subclass of `parent`, so it's only checked for
+    /// once the data matched `parent`.
+    ///
+    /// Both types must be known once everything else is loaded, or the
+    /// relation is ignored. A relation that would close a cycle is dropped
+    /// with a warning, see [`TypeDatabase::warnings`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let db = TypeDatabase::builder()
+    ///     .magic(b"MIME-Magic\0\n[50:image/x-foo]\n>0=\0\x06GIF89a\n")
+    ///     .unwrap()
+    ///     .subclass("image/x-foo", "image/gif")
+    ///     .build();
+    ///
+    /// let gif: &[u8] = include_bytes!("../tests/image/gif");
+    /// assert_eq!(db.from_u8(gif), "image/x-foo");
+    /// ```
+    pub fn subclass(mut self, mimetype: &str, parent: &str) -> Self {
+        self.subclasses
+            .push((parent.to_ascii_lowercase(), mimetype.to_ascii_lowercase()));
+        self
+    }
+
+    /// Drops a type, such as one whose system rules match far too much.
+    ///
+    /// Data of the type is reported as its parent instead, and its subclasses
+    /// are detected on their own. Aliases of the type are dropped with it.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let db = TypeDatabase::builder().disable_type("image/gif").build();
+    ///
+    /// let gif: &[u8] = include_bytes!("../tests/image/gif");
+    /// assert_eq!(db.from_u8(gif), "application/octet-stream");
+    /// ```
+    pub fn disable_type(mut self, mimetype: &str) -> Self {
+        self.disabled_types.push(mimetype.to_ascii_lowercase());
+        self
+    }
+
+    /// Drops a single magic rule of a type, along with its nested rules,
+    /// leaving its other rules in place.
+    ///
+    /// `rule_index` counts the top-level rules of the type (lines without an
+    /// indent level, including exclusions) from 0, in the order they're
+    /// loaded: the system database first, then everything given to the
+    /// builder. Indexes past the last rule are ignored.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let db = TypeDatabase::builder()
+    ///     .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n>0=\0\x03BAR\n")
+    ///     .unwrap()
+    ///     .disable_rule("application/x-foo", 0)
+    ///     .build();
+    ///
+    /// assert!(!db.match_u8("application/x-foo", b"FOO"));
+    /// assert!(db.match_u8("application/x-foo", b"BAR"));
+    /// ```
+    pub fn disable_rule(mut self, mimetype: &str, rule_index: usize) -> Self {
+        self.disabled_rules
+            .push((mimetype.to_ascii_lowercase(), rule_index));
+        self
+    }
+
+    /// Only keeps the given types, and what's needed to detect them.
+    ///
+    /// Patterns are either a type or a top-level type followed by `/*`. The
+    /// parents of kept types are kept too, as detection walks through them.
+    /// Everything else is dropped after loading, which saves memory and
+    /// makes detection faster when only a handful of types matter. Data of
+    /// other types is reported as one of the kept parents, such as
+    /// `application/octet-stream`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let db = TypeDatabase::builder()
+    ///     .retain_types(&["image/*", "application/pdf"])
+    ///     .build();
+    ///
+    /// let gif: &[u8] = include_bytes!("../tests/image/gif");
+    /// assert_eq!(db.from_u8(gif), "image/gif");
+    ///
+    /// let sevenzip: &[u8] = include_bytes!("../tests/application/x-7z-compressed");
+    /// assert_eq!(db.from_u8(sevenzip), "application/octet-stream");
+    /// ```
+    pub fn retain_types(mut self, patterns: &[&str]) -> Self {
+        self.retain
+            .get_or_insert_with(Vec::new)
+            .extend(patterns.iter().map(|p| p.to_ascii_lowercase()));
+        self
+    }
+
+    /// Caps the memory the database holds, as estimated by
+    /// [`TypeDatabase::memory_footprint`], for devices short on memory.
+    ///
+    /// Magic rules are only turned into rule trees when a type is first
+    /// checked, and the trees are kept for later checks. Once keeping
+    /// another tree would go over the limit, trees are built for a single
+    /// check and dropped again. Detection gives the same results, but gets
+    /// slower the more types don't fit. Use
+    /// [`retain_types`](Self::retain_types) too if only some types matter.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let unlimited = TypeDatabase::default();
+    /// let limit = unlimited.memory_footprint();
+    /// let db = TypeDatabase::builder().memory_limit(limit).build();
+    ///
+    /// let gif: &[u8] = include_bytes!("../tests/image/gif");
+    /// assert_eq!(db.from_u8(gif), "image/gif");
+    /// assert!(db.memory_footprint() <= limit);
+    /// ```
+    pub fn memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Only takes data for `application/pdf` when its `%PDF-` header comes
+    /// first, instead of anywhere in the first 1024 bytes as readers allow.
+    ///
+    /// Leading junk lets a file pass for another type to one tool and open
+    /// as a PDF in another, so security checks may want to refuse it.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let input = b"Content-Type: text/plain\r\n\r\n%PDF-1.7\n";
+    /// assert!(TypeDatabase::default().match_u8("application/pdf", input));
+    ///
+    /// let db = TypeDatabase::builder().strict_pdf(true).build();
+    /// assert!(!db.match_u8("application/pdf", input));
+    /// ```
+    pub fn strict_pdf(mut self, enabled: bool) -> Self {
+        self.strict_pdf = enabled;
+        self
+    }
+
+    /// Whether YAML, TOML and Wavefront OBJ are told by the structure of
+    /// their lines, such as `key: value`, `[table]` or `v 0.0 1.0 0.0`, on by
+    /// default.
+    ///
+    /// Other text can have the same structure, such as a list of mail
+    /// headers, which is then taken for YAML. With this off, YAML is only
+    /// found by its `%YAML` directive, OBJ by the magic of the database, and
+    /// TOML not at all.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let input = b"[package]\nname = \"demo\"\nversion = \"0.1.0\"\n";
+    /// assert_eq!(TypeDatabase::default().from_u8(input), "application/toml");
+    ///
+    /// let db = TypeDatabase::builder().text_heuristics(false).build();
+    /// assert_eq!(db.from_u8(input), "text/plain");
+    /// ```
+    pub fn text_heuristics(mut self, enabled: bool) -> Self {
+        self.skip_text_heuristics = !enabled;
+        self
+    }
+
+    /// Whether the language of source code is told from keywords and syntax
+    /// typical of it, off by default.
+    ///
+    /// Covers Python, Rust, Go, Java, C and C++, for code found without a
+    /// file name, such as in repositories being indexed. Code mixing
+    /// languages, or with few lines, may be told wrongly or not at all.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let input = b"use std::io;\n\nfn main() {\n    let mut line = String::new();\n}\n";
+    /// assert_eq!(TypeDatabase::default().from_u8(input), "text/plain");
+    ///
+    /// let db = TypeDatabase::builder().source_heuristics(true).build();
+    /// assert_eq!(db.from_u8(input), "text/rust");
+    /// ```
+    pub fn source_heuristics(mut self, enabled: bool) -> Self {
+        self.source_heuristics = enabled;
+        self
+    }
+
+    /// Whether CSV and TSV are told by the same number of tab, comma,
+    /// semicolon or pipe separated fields on each line, off by default.
+    ///
+    /// Takes at least four lines, or eight with only two fields each, below
+    /// a header of short, non-empty fields. Letters and lists have commas on
+    /// most lines too, so some may still be taken for CSV. With this off,
+    /// CSV and TSV aren't found at all.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let input = b"name,qty,price\napple,3,0.50\npear,5,0.80\nplum,8,0.30\n";
+    /// assert_eq!(TypeDatabase::default().from_u8(input), "text/plain");
+    ///
+    /// let db = TypeDatabase::builder().delimited_heuristics(true).build();
+    /// assert_eq!(db.from_u8(input), "text/csv");
+    /// ```
+    pub fn delimited_heuristics(mut self, enabled: bool) -> Self {
+        self.delimited_heuristics = enabled;
+        self
+    }
+
+    /// Whether game cartridge ROMs, console disc images and game engine
+    /// asset containers are told by their headers, off by default.
+    ///
+    /// Covers NES, SNES, Game Boy, Game Boy Advance and Nintendo 64 ROMs,
+    /// GameCube, Wii and PlayStation discs, Unity asset bundles and Unreal
+    /// pak files, for collections being archived or organized. Most of these
+    /// have no magic of their own, so other data can happen to match. With
+    /// this off, only the magic rules of the database are checked.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let mut input = b"NES\x1a\x02\x01".to_vec();
+    /// input.resize(16 + 2 * 16384 + 8192, 0);
+    /// assert_eq!(TypeDatabase::default().from_u8(&input), "application/octet-stream");
+    ///
+    /// let db = TypeDatabase::builder().game_formats(true).build();
+    /// assert_eq!(db.from_u8(&input), "application/x-nes-rom");
+    /// ```
+    pub fn game_formats(mut self, enabled: bool) -> Self {
+        self.game_formats = enabled;
+        self
+    }
+
+    /// Loads the system types and adds everything given to the builder.
+    pub fn build(self) -> TypeDatabase {
+        let mut checkers = FnvHashMap::<MIME, AnyChecker>::default();
+        let mut aliases = FnvHashMap::<MIME, MIME>::default();
+        let mut subclasses = Vec::<(MIME, MIME)>::new();
+        for &c in CHECKERS {
+            for m in c.get_supported() {
+                // A type with its own checker is never an alias
+                aliases.remove(m);
+                checkers.insert(m, c);
+            }
+            aliases.extend(c.get_aliaslist());
+            subclasses.extend(c.get_subclasses());
+        }
+        // Keeps the patterns of the custom magic alive as long as the rules
+        let mut names = self.files;
+        let resolve = |m: MIME| aliases.get(m).copied().unwrap_or(m);
+
+        // Older databases may use names that are aliases by now
+        let mut magic = FnvHashMap::<MIME, LazyRuleset>::default();
+        for (m, (priority, section)) in fdo_magic::builtin::rules() {
+            magic.entry(resolve(m)).or_default().push(section, priority);
+        }
+        for &m in magic.keys() {
+            checkers.entry(m).or_insert(AnyChecker::FdoMagic);
+        }
+        for x in subclasses.iter_mut() {
+            *x = (resolve(x.0), resolve(x.1));
+        }
+
+        // Added first, so magic given for an alias goes to its type
+        for (alias, mime) in self.aliases {
+            if alias == mime || checkers.contains_key(alias.as_str()) {
+                continue;
+            }
+            let mime = match aliases.get(mime.as_str()) {
+                Some(&x) => x,
+                None => match checkers.get_key_value(mime.as_str()) {
+                    Some((&x, _)) => x,
+                    // Safety: as for the names of custom magic below
+                    None => unsafe { names.alloc(mime.into_boxed_str()) },
+                },
+            };
+            // Safety: as for the names of custom magic below
+            let alias = unsafe { names.alloc(alias.into_boxed_str()) };
+            aliases.insert(alias, mime);
+        }
+
+        for (mime, priority, graph, layer) in self.magic {
+            let mime = match aliases.get(mime.as_str()) {
+                Some(&x) => x,
+                None => match checkers.get_key_value(mime.as_str()) {
+                    Some((&x, _)) => x,
+                    // Safety: the database keeps the arena, and never
+                    // hands out names for longer than it's borrowed
+                    None => unsafe { names.alloc(mime.into_boxed_str()) },
+                },
+            };
+            checkers.entry(mime).or_insert(AnyChecker::FdoMagic);
+            match layer {
+                Layer::Merge => magic
+                    .entry(mime)
+                    .or_default()
+                    .push(Section::Parsed(graph), priority),
+                Layer::Override => {
+                    magic.insert(mime, LazyRuleset::new(Section::Parsed(graph), priority));
+                }
+            }
+        }
+
+        for (parent, child) in &self.subclasses {
+            let known = |m: &str| {
+                let m = aliases.get(m).copied().unwrap_or(m);
+                checkers.get_key_value(m).map(|(&m, _)| m)
+            };
+            if let (Some(parent), Some(child)) = (known(parent), known(child)) {
+                subclasses.push((parent, child));
+            }
+        }
+
+        for mime in &self.disabled_types {
+            let mime = aliases.get(mime.as_str()).copied().unwrap_or(mime);
+            checkers.remove(mime);
+            magic.remove(mime);
+            aliases.retain(|_, m| *m != mime);
+            subclasses.retain(|(a, b)| *a != mime && *b != mime);
+        }
+        // Highest index first, so earlier indexes of a type stay valid
+        let mut disabled_rules = self.disabled_rules;
+        disabled_rules.sort_by_key(|r| core::cmp::Reverse(r.1));
+        for (mime, index) in &disabled_rules {
+            let mime = aliases.get(mime.as_str()).copied().unwrap_or(mime);
+            if let Some(rules) = magic.get_mut(mime) {
+                remove_rule(rules.get_mut(), *index);
+            }
+        }
+
+        if let Some(patterns) = self.retain {
+            let (graph, _, _) = graph_init(&checkers, subclasses.clone());
+            let keep = retained(&graph, &patterns);
+            checkers.retain(|m, _| keep.contains(m));
+            magic.retain(|m, _| keep.contains(m));
+            aliases.retain(|_, m| keep.contains(m));
+            subclasses.retain(|(a, b)| keep.contains(a) && keep.contains(b));
+        }
+
+        let (graph, nodes, broken) = graph_init(&checkers, subclasses);
+        let families = family_roots(&graph);
+
+        let mut warnings: Vec<DatabaseError> = broken
+            .into_iter()
+            .map(|(parent, child)| DatabaseError::SubclassCycle {
+                parent: parent.into(),
+                child: child.into(),
+            })
+            .collect();
+        // Aliases resolve a single step, so a cycle can't hang a lookup
+        warnings.extend(
+            alias_cycles(&aliases)
+                .into_iter()
+                .map(|c| DatabaseError::AliasCycle(c.into_iter().map(String::from).collect())),
+        );
+
+        let mut types = FnvHashMap::<MIME, TypeEntry>::default();
+        for (m, c) in checkers {
+            types.entry(m).or_default().checker = Some(c);
+        }
+        for (a, m) in aliases {
+            types.entry(a).or_default().alias = Some(m);
+        }
+        for (m, r) in magic {
+            types.entry(m).or_default().magic = Some(r);
+        }
+
+        let mut db = TypeDatabase {
+            graph,
+            nodes,
+            types: PerfectMap::new(types),
+            families,
+            warnings,
+            magic_budget: None,
+            strict_pdf: self.strict_pdf,
+            text_heuristics: !self.skip_text_heuristics,
+            source_heuristics: self.source_heuristics,
+            delimited_heuristics: self.delimited_heuristics,
+            game_formats: self.game_formats,
+            names,
+        };
+        if let Some(limit) = self.memory_limit {
+            let left = limit.saturating_sub(db.memory_footprint());
+            db.magic_budget = Some(AtomicUsize::new(left));
+        }
+        db
+    }
+}
+
+/// Lowercases a type and strips any parameters, as in `IMAGE/GIF; q=0.8`,
+/// since names often come straight from HTTP headers
+pub(crate) fn normalize(mimetype: &str) -> Cow<'_, str> {
+    let mimetype = match mimetype.find(';') {
+        Some(i) => &mimetype[..i],
+        None => mimetype,
+    }
+    .trim();
+    if mimetype.bytes().any(|c| c.is_ascii_uppercase()) {
+        Cow::Owned(mimetype.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(mimetype)
+    }
+}
+
+/// Whether a `retain_types` pattern covers the type
+fn pattern_matches(pattern: &str, mimetype: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some("*") => true,
+        Some(top) => mimetype.split('/').next() == Some(top),
+        None => pattern == mimetype,
+    }
+}
+
+/// Types matching the patterns, their parents, and the base types every
+/// walk starts from
+fn retained(graph: &DiGraph<MIME, u32>, patterns: &[String]) -> FnvHashSet<MIME> {
+    let mut keep = FnvHashSet::<MIME>::default();
+    keep.extend([
+        "all/all",
+        "all/allfiles",
+        "application/octet-stream",
+        "text/plain",
+    ]);
+
+    let mut stack: Vec<NodeIndex> = graph
+        .node_indices()
+        .filter(|&n| patterns.iter().any(|p| pattern_matches(p, graph[n])))
+        .collect();
+    while let Some(n) = stack.pop() {
+        if keep.insert(graph[n]) {
+            stack.extend(graph.neighbors_directed(n, Incoming));
+        }
+    }
+    keep
+}
+
+/// Sort key pulling the most common types towards the start of the walk
+fn walk_priority(mimetype: &str) -> usize {
+    TYPEORDER
+        .iter()
+        .position(|&t| t == mimetype)
+        .unwrap_or(TYPEORDER.len())
+}
+
+/// Removes the `index`th top-level rule and the rules nested in it
+fn remove_rule(rules: &mut MagicRuleset, index: usize) {
+    let root = match rules.externals(Incoming).nth(index) {
+        Some(x) => x,
+        None => return,
+    };
+    let mut drop = FnvHashSet::<NodeIndex>::default();
+    let mut stack = vec![root];
+    while let Some(n) = stack.pop() {
+        if drop.insert(n) {
+            stack.extend(rules.neighbors_directed(n, Outgoing));
+        }
+    }
+    *rules = rules.filter_map(
+        |n, r| match drop.contains(&n) {
+            true => None,
+            false => Some(r.clone()),
+        },
+        |_, &e| Some(e),
+    );
+}
+
+/// `(parent, child)` subclass relations
+type Subclasses = Vec<(MIME, MIME)>;
+
+// Initialize filetype graph, along with the relations dropped to break
+// cycles
+fn graph_init(
+    checkers: &FnvHashMap<MIME, AnyChecker>,
+    edgelist_raw: Subclasses,
+) -> (DiGraph<MIME, u32>, FnvHashMap<MIME, NodeIndex>, Subclasses) {
+    let mut graph = DiGraph::<MIME, u32>::new();
+    let mut added_mimes = FnvHashMap::<MIME, NodeIndex>::default();
+
+    // Get list of MIME types
+    let mut mimelist: Vec<MIME> = checkers.keys().copied().collect();
+    mimelist.sort_unstable();
+    let mimelist = mimelist;
+
+    // Create all nodes
+    for mimetype in mimelist.iter() {
+        let node = graph.add_node(*mimetype);
+        added_mimes.insert(mimetype, node);
+    }
+
+    let mut edge_list = FnvHashSet::<(NodeIndex, NodeIndex)>::with_capacity_and_hasher(
+        edgelist_raw.len(),
+        Default::default(),
+    );
+    for x in edgelist_raw {
+        let child_raw = x.0;
+        let parent_raw = x.1;
+
+        let parent = match added_mimes.get(&parent_raw) {
+            Some(node) => *node,
+            None => {
+                continue;
+            }
+        };
+
+        let child = match added_mimes.get(&child_raw) {
+            Some(node) => *node,
+            None => {
+                continue;
+            }
+        };
+
+        edge_list.insert((child, parent));
+    }
+
+    graph.extend_with_edges(&edge_list);
+
+    // Before the base types are linked in, so types left without a parent
+    // end up under one of them
+    let broken = break_cycles(&mut graph);
+    for &(parent, child) in &broken {
+        edge_list.remove(&(added_mimes[parent], added_mimes[child]));
+    }
+
+    //Add to applicaton/octet-stream, all/all, or text/plain, depending on top-level
+    //(We'll just do it here because having the graph makes it really nice)
+    let added_mimes_tmp = added_mimes.clone();
+    let node_text = match added_mimes_tmp.get("text/plain") {
+        Some(x) => *x,
+        None => {
+            let node = graph.add_node("text/plain");
+            added_mimes.insert("text/plain", node);
+            node
+        }
+    };
+    let node_octet = match added_mimes_tmp.get("application/octet-stream") {
+        Some(x) => *x,
+        None => {
+            let node = graph.add_node("application/octet-stream");
+            added_mimes.insert("application/octet-stream", node);
+            node
+        }
+    };
+    let node_allall = match added_mimes_tmp.get("all/all") {
+        Some(x) => *x,
+        None => {
+            let node = graph.add_node("all/all");
+            added_mimes.insert("all/all", node);
+            node
+        }
+    };
+    let node_allfiles = match added_mimes_tmp.get("all/allfiles") {
+        Some(x) => *x,
+        None => {
+            let node = graph.add_node("all/allfiles");
+            added_mimes.insert("all/allfiles", node);
+            node
+        }
+    };
+
+    let mut edge_list_2 = FnvHashSet::<(NodeIndex, NodeIndex)>::default();
+    for mimenode in graph.externals(Incoming) {
+        let mimetype = &graph[mimenode];
+        let toplevel = mimetype.split('/').next().unwrap_or("");
+
+        if mimenode == node_text
+            || mimenode == node_octet
+            || mimenode == node_allfiles
+            || mimenode == node_allall
+        {
+            continue;
+        }
+
+        if toplevel == "text" {
+            edge_list_2.insert((node_text, mimenode));
+        } else if toplevel == "inode" {
+            edge_list_2.insert((node_allall, mimenode));
+        } else {
+            edge_list_2.insert((node_octet, mimenode));
+        }
+    }
+    // Don't add duplicate entries
+    graph.extend_with_edges(edge_list_2.difference(&edge_list));
+
+    (graph, added_mimes, broken)
+}
+
+/// Drops the relations closing a cycle, so every walk down the graph ends.
+///
+/// Searches depth first from the types without a parent, then from the rest
+/// in name order, dropping every relation that leads back to a type on the
+/// current path. Returns the `(parent, child)` pairs dropped.
+fn break_cycles(graph: &mut DiGraph<MIME, u32>) -> Subclasses {
+    // Children in name order, last first, to pop off
+    let children = |graph: &DiGraph<MIME, u32>, n: NodeIndex| {
+        let mut out: Vec<NodeIndex> = graph.neighbors_directed(n, Outgoing).collect();
+        out.sort_by_key(|&c| core::cmp::Reverse(graph[c]));
+        out
+    };
+
+    let mut starts: Vec<NodeIndex> = graph.externals(Incoming).collect();
+    starts.extend(graph.node_indices());
+    let mut on_path = vec![false; graph.node_count()];
+    let mut done = vec![false; graph.node_count()];
+    let mut back = Vec::<(NodeIndex, NodeIndex)>::new();
+    for start in starts {
+        if done[start.index()] {
+            continue;
+        }
+        on_path[start.index()] = true;
+        let mut stack = vec![(start, children(graph, start))];
+        while let Some((node, next)) = stack.last_mut().map(|(n, c)| (*n, c.pop())) {
+            match next {
+                Some(c) if on_path[c.index()] => back.push((node, c)),
+                Some(c) if !done[c.index()] => {
+                    on_path[c.index()] = true;
+                    stack.push((c, children(graph, c)));
+                }
+                Some(_) => {}
+                None => {
+                    on_path[node.index()] = false;
+                    done[node.index()] = true;
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    back.into_iter()
+        .filter_map(|(p, c)| {
+            let e = graph.find_edge(p, c)?;
+            graph.remove_edge(e);
+            Some((graph[p], graph[c]))
+        })
+        .collect()
+}
+
+/// Aliases leading back to themselves, each cycle once, starting from its
+/// lowest alias
+pub(crate) fn alias_cycles<'a>(aliases: &FnvHashMap<&'a str, &'a str>) -> Vec<Vec<&'a str>> {
+    let mut starts: Vec<&str> = aliases.keys().copied().collect();
+    starts.sort_unstable();
+    let mut cycles = Vec::new();
+    for start in starts {
+        let mut cycle = vec![start];
+        let mut next = aliases[start];
+        while let Some(&target) = aliases.get(next) {
+            if next == start || cycle.contains(&next) {
+                break;
+            }
+            cycle.push(next);
+            next = target;
+        }
+        // Only kept from its lowest alias, so each cycle shows up once
+        if next == start && cycle.iter().all(|&a| a >= start) {
+            cycles.push(cycle);
+        }
+    }
+    cycles
+}
+
+/// Finds the family members none of whose parents are in the same family
+fn family_roots(graph: &DiGraph<MIME, u32>) -> FnvHashMap<Family, Vec<NodeIndex>> {
+    Family::ALL
+        .iter()
+        .map(|&family| {
+            let roots = graph
+                .node_indices()
+                .filter(|&n| family.contains(graph[n]))
+                .filter(|&n| {
+                    !graph
+                        .neighbors_directed(n, Incoming)
+                        .any(|p| family.contains(graph[p]))
+                })
+                .collect();
+            (family, roots)
+        })
+        .collect()
+}
+
+impl Default for TypeDatabase {
+    fn default() -> Self {
+        TypeDatabase::builder().build()
+    }
+}
+
+impl TypeDatabase {
+    /// Starts building a database from the system types.
+    pub fn builder() -> TypeDatabaseBuilder {
+        TypeDatabaseBuilder::default()
+    }
+
+    /// Subclass graph of all loaded types
+    pub(crate) fn graph(&self) -> &DiGraph<MIME, u32> {
+        &self.graph
+    }
+
+    /// All aliases, mapped to the type they stand for
+    pub(crate) fn aliases(&self) -> impl Iterator<Item = (&MIME, &MIME)> {
+        self.types
+            .iter()
+            .filter_map(|(a, e)| Some((a, e.alias.as_ref()?)))
+    }
+
+    /// Node of the given type in the graph
+    pub(crate) fn node(&self, mimetype: &str) -> Option<NodeIndex> {
+        self.nodes.get(mimetype).copied()
+    }
+
+    /// All loaded magic rules
+    pub(crate) fn all_magic(&self) -> impl Iterator<Item = (MIME, Rules<'_>)> {
+        self.types
+            .iter()
+            .filter_map(move |(&m, e)| Some((m, self.rules(e.magic.as_ref()?))))
+    }
+
+    /// Magic rules loaded for the given type, if any
+    pub(crate) fn magic(&self, mimetype: &str) -> Option<Rules<'_>> {
+        self.magic_entry(mimetype).map(|r| self.rules(r))
+    }
+
+    /// Magic rules of the given type as kept, to look at with [`rules`](Self::rules)
+    pub(crate) fn magic_entry(&self, mimetype: &str) -> Option<&LazyRuleset> {
+        self.types.get(mimetype)?.magic.as_ref()
+    }
+
+    /// Priority of the magic rules of the given type, or the default one for
+    /// types without magic
+    pub(crate) fn magic_priority(&self, mimetype: &str) -> u32 {
+        self.magic_entry(mimetype)
+            .map_or(fdo_magic::DEFAULT_PRIORITY, LazyRuleset::priority)
+    }
+
+    /// The rule trees of a type, kept for later if the memory limit allows
+    pub(crate) fn rules<'a>(&'a self, entry: &'a LazyRuleset) -> Rules<'a> {
+        entry.get(self.magic_budget.as_ref())
+    }
+
+    /// Where the magic rules of the given type matched in `bytes`
+    pub(crate) fn evidence(&self, bytes: &[u8], mimetype: &str) -> Vec<Range<usize>> {
+        self.magic(mimetype)
+            .and_then(|rules| fdo_magic::builtin::check::evidence(bytes, &rules))
+            .unwrap_or_default()
+    }
+
+    /// Whether PDF documents must start with their header
+    pub(crate) fn strict_pdf(&self) -> bool {
+        self.strict_pdf
+    }
+
+    /// Whether YAML, TOML and OBJ are told by the structure of text
+    pub(crate) fn text_heuristics(&self) -> bool {
+        self.text_heuristics
+    }
+
+    /// Whether the language of source code is told from its contents
+    pub(crate) fn source_heuristics(&self) -> bool {
+        self.source_heuristics
+    }
+
+    /// Whether CSV and TSV are told by the number of fields on each line
+    pub(crate) fn delimited_heuristics(&self) -> bool {
+        self.delimited_heuristics
+    }
+
+    /// Whether game ROMs and asset containers are told by their structure
+    pub(crate) fn game_formats(&self) -> bool {
+        self.game_formats
+    }
+
+    /// Checker responsible for the given type
+    pub(crate) fn checker(&self, mimetype: &str) -> Option<AnyChecker> {
+        self.types.get(mimetype)?.checker
+    }
+
+    /// Number of magic rules loaded for the given type
+    pub(crate) fn rule_count(&self, mimetype: &str) -> usize {
+        self.magic(mimetype).map_or(0, |g| g.node_count())
+    }
+
+    /// Finds the root of the type graph
+    pub(crate) fn root_node(&self) -> NodeIndex {
+        match self.graph.externals(Incoming).next() {
+            Some(foundnode) => foundnode,
+            None => panic!("No filetype definitions are loaded."),
+        }
+    }
+
+    /// The alias as the database spells it, if it's one
+    pub(crate) fn alias_key(&self, alias: &str) -> Option<&str> {
+        match self.types.get_key_value(alias) {
+            Some((&k, e)) if e.alias.is_some() => Some(k),
+            _ => None,
+        }
+    }
+
+    /// Transforms an alias into it's real type
+    pub(crate) fn get_alias<'a>(&'a self, mimetype: &'a str) -> &'a str {
+        match self.types.get(mimetype).and_then(|e| e.alias) {
+            Some(x) => x,
+            None => mimetype,
+        }
+    }
+
+    /// Whether the type is `parent` or one of its subclasses, following
+    /// aliases
+    pub(crate) fn is_a(&self, mimetype: &str, parent: &str) -> bool {
+        let (child, parent) = match (
+            self.node(self.get_alias(mimetype)),
+            self.node(self.get_alias(parent)),
+        ) {
+            (Some(c), Some(p)) => (c, p),
+            _ => return self.get_alias(mimetype) == self.get_alias(parent),
+        };
+        let mut stack = vec![child];
+        let mut seen = FnvHashSet::<NodeIndex>::default();
+        while let Some(n) = stack.pop() {
+            if n == parent {
+                return true;
+            }
+            if seen.insert(n) {
+                stack.extend(self.graph.neighbors_directed(n, Incoming));
+            }
+        }
+        false
+    }
+
+    /// Children of the node in the order the walk checks them: the types of
+    /// `TYPEORDER` first, in that order, then the rest by name, so the order
+    /// doesn't depend on the hasher the graph was built with
+    pub(crate) fn walk_order(&self, parentnode: NodeIndex) -> Vec<NodeIndex> {
+        let mut children: Vec<NodeIndex> = self
+            .graph
+            .neighbors_directed(parentnode, Outgoing)
+            .collect();
+        children.sort_by_key(|&n| (walk_priority(self.graph[n]), self.graph[n]));
+        children
+    }
+
+    /// Just the part of from_*_node that walks the graph
+    ///
+    /// `visited` is incremented for every node checked along the way.
+    pub(crate) fn typegraph_walker(
+        &self,
+        parentnode: NodeIndex,
+        source: &dyn DataSource,
+        visited: &mut usize,
+    ) -> Option<MIME> {
+        self.limited_walker(parentnode, source, visited, &WalkLimits::default(), 0)
+    }
+
+    /// The graph walk, stopping early once `limits` are exceeded. `depth` is
+    /// how far `parentnode` is below the root.
+    fn limited_walker(
+        &self,
+        parentnode: NodeIndex,
+        source: &dyn DataSource,
+        visited: &mut usize,
+        limits: &WalkLimits,
+        depth: usize,
+    ) -> Option<MIME> {
+        if depth >= limits.max_depth {
+            return None;
+        }
+
+        // Blank text is plain text, whatever loose rule of a text subtype or
+        // binary type would match it first
+        match self.graph[parentnode] {
+            "application/octet-stream" if basetype::check::is_blank_text(source) => {
+                return Some("text/plain")
+            }
+            "text/plain" if basetype::check::is_blank_text(source) => return None,
+            _ => {}
+        }
+
+        let mut children = self.walk_order(parentnode);
+        if limits.by_priority {
+            let priority = |n: NodeIndex| self.magic_priority(self.graph[n]);
+            children.sort_by_key(|&n| (core::cmp::Reverse(priority(n)), self.graph[n]));
+        }
+        if !limits.family_preference.is_empty() {
+            // Stable, so the walk order holds within each media type
+            children.sort_by_key(|&n| limits.preference(self.graph[n]));
+        }
+
+        #[cfg(feature = "rayon")]
+        if let Some(bytes) = source.as_slice() {
+            if children.len() >= PARALLEL_MIN_CHILDREN && bytes.len() >= PARALLEL_MIN_LEN {
+                return self.parallel_walker(&children, bytes, visited, limits, depth);
+            }
+        }
+
+        // Walk graph
+        for childnode in children {
+            let mimetype = self.graph[childnode];
+            if limits.expired() {
+                return None;
+            }
+
+            *visited += 1;
+            let result = self.match_source_noalias(mimetype, source);
+            if let Some(stats) = limits.stats {
+                stats.type_checked(mimetype, result);
+            }
+            match result {
+                true => match self.limited_walker(childnode, source, visited, limits, depth + 1) {
+                    Some(foundtype) => return Some(foundtype),
+                    None => return Some(mimetype),
+                },
+                false => continue,
+            }
+        }
+
+        None
+    }
+
+    /// Checks the children in parallel, then walks on from the first one in
+    /// walk order that matched, like the sequential walk would
+    #[cfg(feature = "rayon")]
+    fn parallel_walker(
+        &self,
+        children: &[NodeIndex],
+        bytes: &[u8],
+        visited: &mut usize,
+        limits: &WalkLimits,
+        depth: usize,
+    ) -> Option<MIME> {
+        use rayon::prelude::*;
+
+        if limits.expired() {
+            return None;
+        }
+
+        let found = children
+            .par_iter()
+            .position_first(|&c| self.match_source_noalias(self.graph[c], &bytes));
+        *visited += found.map_or(children.len(), |i| i + 1);
+
+        let childnode = children[found?];
+        match self.limited_walker(childnode, &bytes, visited, limits, depth + 1) {
+            Some(foundtype) => Some(foundtype),
+            None => Some(self.graph[childnode]),
+        }
+    }
+
+    /// Checks whether the source is of any type in the family, testing only
+    /// the topmost types of the family instead of walking the whole graph
+    pub(crate) fn match_family(&self, family: Family, source: &dyn DataSource) -> bool {
+        match self.families.get(&family) {
+            Some(roots) => roots
+                .iter()
+                .any(|&n| self.match_source_noalias(self.graph[n], source)),
+            None => false,
+        }
+    }
+
+    /// Internal function. Runs the checker for the type, unless the data
+    /// matches one of its exclusion rules.
+    pub(crate) fn match_source_noalias(&self, mimetype: &str, source: &dyn DataSource) -> bool {
+        let c = match self.checker(mimetype) {
+            Some(c) => c,
+            None => return false,
+        };
+        if fdo_magic::builtin::check::is_excluded(self, source, mimetype) {
+            return false;
+        }
+        c.from_source(self, source, mimetype)
+    }
+
+    /// Checks if the given data source matches the given MIME type.
+    ///
+    /// See [`match_source`](crate::match_source).
+    pub fn match_source(&self, mimetype: &str, source: &dyn DataSource) -> bool {
+        self.matcher(mimetype)
+            .is_some_and(|m| m.matches_source(source))
+    }
+
+    /// Looks up a type once, to check many inputs against it.
+    ///
+    /// See [`matcher`](crate::matcher).
+    pub fn matcher(&self, mimetype: &str) -> Option<TypeMatcher<'_>> {
+        let (mut name, mut entry) = self.types.get_key_value(&normalize(mimetype))?;
+        if let Some(alias) = entry.alias {
+            (name, entry) = self.types.get_key_value(alias)?;
+        }
+        Some(TypeMatcher::new(
+            self,
+            name,
+            entry.checker?,
+            entry.magic.as_ref(),
+        ))
+    }
+
+    /// Checks if the given bytestream matches the given MIME type.
+    ///
+    /// See [`match_u8`](crate::match_u8).
+    pub fn match_u8(&self, mimetype: &str, bytes: &[u8]) -> bool {
+        self.match_source(mimetype, &bytes)
+    }
+
+    /// Short name of the type, such as `PDF`.
+    ///
+    /// See [`acronym`](crate::acronym).
+    #[cfg(feature = "std")]
+    pub fn acronym(&self, mimetype: &str) -> Option<String> {
+        let mimetype = normalize(mimetype);
+        info::element(self.get_alias(&mimetype), "acronym")
+    }
+
+    /// What the acronym of the type stands for, such as
+    /// `Portable Document Format`.
+    ///
+    /// See [`expanded_acronym`](crate::expanded_acronym).
+    #[cfg(feature = "std")]
+    pub fn expanded_acronym(&self, mimetype: &str) -> Option<String> {
+        let mimetype = normalize(mimetype);
+        info::element(self.get_alias(&mimetype), "expanded-acronym")
+    }
+
+    /// Description of the type, such as `PDF document`, in the given
+    /// locale.
+    ///
+    /// See [`description`](crate::description).
+    #[cfg(feature = "std")]
+    pub fn description(&self, mimetype: &str, locale: &str) -> Option<String> {
+        let mimetype = normalize(mimetype);
+        info::localized_element(self.get_alias(&mimetype), "comment", locale)
+    }
+
+    /// Locales the description of the type is translated into.
+    ///
+    /// See [`description_locales`](crate::description_locales).
+    #[cfg(feature = "std")]
+    pub fn description_locales(&self, mimetype: &str) -> Vec<String> {
+        let mimetype = normalize(mimetype);
+        info::element_locales(self.get_alias(&mimetype), "comment")
+    }
+
+    /// How many bytes from the start of the data checking for the type
+    /// reads, so ranged or streamed input can fetch just that much.
+    ///
+    /// Returns `None` for unknown types, and for types whose checks follow
+    /// offsets found in the data, such as TIFF-based raw images.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let db = TypeDatabase::default();
+    /// assert_eq!(db.needed_len("application/x-tar"), Some(512));
+    /// assert_eq!(db.needed_len("image/x-canon-cr2"), None);
+    /// ```
+    pub fn needed_len(&self, mimetype: &str) -> Option<usize> {
+        let mimetype = normalize(mimetype);
+        let mimetype = self.get_alias(&mimetype);
+        let len = self.checker(mimetype)?.needed_len(self, mimetype)?;
+        // Exclusion rules are read whichever checker handles the type
+        let excluded_len = self
+            .magic(mimetype)
+            .map_or(0, |r| fdo_magic::builtin::check::scan_len(&r));
+        Some(len.max(excluded_len))
+    }
+
+    /// Gets the type of a data source.
+    ///
+    /// See [`from_source`](crate::from_source).
+    pub fn from_source(&self, source: &dyn DataSource) -> Option<&str> {
+        self.typegraph_walker(self.root_node(), source, &mut 0)
+    }
+
+    /// Gets the type of a data source, with the given options.
+    ///
+    /// See [`detect_source`](crate::detect_source).
+    pub fn detect(&self, source: &dyn DataSource, options: &DetectOptions) -> Option<&str> {
+        self.detect_limited(source, options, &options.walk_limits())
+    }
+
+    /// Gets the type of a data source, with the given options, telling
+    /// `stats` about the work done along the way.
+    ///
+    /// See [`detect_with_stats`](crate::detect_with_stats).
+    pub fn detect_with_stats(
+        &self,
+        source: &dyn DataSource,
+        options: &DetectOptions,
+        stats: &dyn StatsHook,
+    ) -> Option<&str> {
+        let source = CountedSource {
+            inner: source,
+            hook: stats,
+        };
+        let mut limits = options.walk_limits();
+        limits.stats = Some(stats);
+        let out = self.detect_limited(&source, options, &limits);
+        stats.finished(out);
+        out
+    }
+
+    fn detect_limited(
+        &self,
+        source: &dyn DataSource,
+        options: &DetectOptions,
+        limits: &WalkLimits,
+    ) -> Option<&str> {
+        if options.has_io_budget() {
+            let budgeted = BudgetedSource::new(source, options.max_io_bytes, options.max_reads);
+            return self.detect_unbudgeted(&budgeted, options, limits);
+        }
+        self.detect_unbudgeted(source, options, limits)
+    }
+
+    fn detect_unbudgeted(
+        &self,
+        source: &dyn DataSource,
+        options: &DetectOptions,
+        limits: &WalkLimits,
+    ) -> Option<&str> {
+        if !self.nodes.contains_key("application/x-zerosize") && basetype::check::is_empty(source) {
+            return options.empty_fallback;
+        }
+        self.limited_walker(self.root_node(), source, &mut 0, limits, 0)
+            .and_then(|mime| options.apply_fallback(mime))
+    }
+
+    /// Gets the type of a single member of a ZIP or TAR archive.
+    ///
+    /// See [`from_archive_member`](crate::from_archive_member).
+    #[cfg(feature = "archive")]
+    pub fn from_archive_member(&self, archive: &[u8], member: &str) -> Option<&str> {
+        let prefix = crate::archive::member_prefix(archive, member)?;
+        Some(self.from_u8(&prefix))
+    }
+
+    /// Gets the type of a file from a byte stream.
+    ///
+    /// See [`from_u8`](crate::from_u8).
+    pub fn from_u8(&self, bytes: &[u8]) -> &str {
+        self.from_source(&bytes).unwrap()
+    }
+
+    /// Gets the type of a byte stream, followed by the types it's a subclass
+    /// of.
+    ///
+    /// See [`from_u8_with_lineage`](crate::from_u8_with_lineage).
+    pub fn from_u8_with_lineage(&self, bytes: &[u8]) -> Vec<&str> {
+        self.lineage(self.from_u8(bytes), &bytes)
+    }
+
+    /// The type followed by its parents, up to the `all/*` base types. Of
+    /// several parents, the first in walk order that the data matches is
+    /// followed, as the walk that found the type did.
+    fn lineage(&self, mimetype: &str, source: &dyn DataSource) -> Vec<&str> {
+        let mut out = Vec::new();
+        let mut node = self.node(mimetype);
+        while let Some(n) = node {
+            let mimetype = self.graph[n];
+            if mimetype.starts_with("all/") {
+                break;
+            }
+            out.push(mimetype);
+            let mut parents: Vec<NodeIndex> = self.graph.neighbors_directed(n, Incoming).collect();
+            parents.sort_by_key(|&p| (walk_priority(self.graph[p]), self.graph[p]));
+            node = parents
+                .into_iter()
+                .find(|&p| self.match_source_noalias(self.graph[p], source));
+        }
+        out
+    }
+
+    /// Gets the type of a data source, as a string that can outlive the
+    /// database.
+    ///
+    /// Types added by the builder are only borrowed from the database by
+    /// [`from_source`](Self::from_source); this copies the result out, for
+    /// callers that keep results around while databases are rebuilt.
+    pub fn from_source_owned(&self, source: &dyn DataSource) -> Option<String> {
+        self.from_source(source).map(String::from)
+    }
+
+    /// Gets the type of a file from a byte stream, as a string that can
+    /// outlive the database.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let mime = {
+    ///     let db = TypeDatabase::builder()
+    ///         .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+    ///         .unwrap()
+    ///         .build();
+    ///     db.from_u8_owned(b"FOObar")
+    /// };
+    /// assert_eq!(mime, "application/x-foo");
+    /// ```
+    pub fn from_u8_owned(&self, bytes: &[u8]) -> String {
+        String::from(self.from_u8(bytes))
+    }
+
+    /// Gets the type of a file from a filepath, as a string that can outlive
+    /// the database.
+    #[cfg(feature = "std")]
+    pub fn from_filepath_owned(&self, filepath: &Path) -> Option<String> {
+        self.from_filepath(filepath).map(String::from)
+    }
+
+    /// Check if the given filepath matches the given MIME type.
+    ///
+    /// See [`match_filepath`](crate::match_filepath).
+    #[cfg(feature = "std")]
+    pub fn match_filepath(&self, mimetype: &str, filepath: &Path) -> bool {
+        self.try_match_filepath(mimetype, filepath).unwrap_or(false)
+    }
+
+    /// Check if the given filepath matches the given MIME type, reporting
+    /// errors opening or reading the file.
+    ///
+    /// See [`try_match_filepath`](crate::try_match_filepath).
+    #[cfg(feature = "std")]
+    pub fn try_match_filepath(&self, mimetype: &str, filepath: &Path) -> std::io::Result<bool> {
+        let prefix_len = self
+            .needed_len(mimetype)
+            .map_or(source::PREFIX_LEN, |n| n.min(source::PREFIX_LEN));
+        source::with_buffer(|buf| {
+            let source = FileSource::open_with_prefix(filepath, buf, prefix_len)?;
+            let out = self.match_source(mimetype, &source);
+            let error = source.take_error();
+            *buf = source.into_buffer();
+            match error {
+                Some(e) => Err(e),
+                None => Ok(out),
+            }
+        })
+    }
+
+    /// Gets the type of a file from a filepath.
+    ///
+    /// See [`from_filepath`](crate::from_filepath).
+    #[cfg(feature = "std")]
+    pub fn from_filepath(&self, filepath: &Path) -> Option<&str> {
+        source::with_buffer(|buf| self.from_filepath_buf(filepath, buf))
+    }
+
+    /// Gets the type of a file from a filepath, reading into `buf`.
+    ///
+    /// See [`from_filepath_buf`](crate::from_filepath_buf).
+    #[cfg(feature = "std")]
+    pub fn from_filepath_buf(&self, filepath: &Path, buf: &mut Vec<u8>) -> Option<&str> {
+        let source = FileSource::open_with_buffer(filepath, buf).ok()?;
+        let out = self.from_source(&source);
+        *buf = source.into_buffer();
+        out
+    }
+
+    /// Gets the type of a file from a filepath and its metadata.
+    ///
+    /// See [`from_filepath_with_meta`](crate::from_filepath_with_meta).
+    #[cfg(feature = "std")]
+    pub fn from_filepath_with_meta(&self, filepath: &Path, meta: &Metadata) -> Option<&str> {
+        source::with_buffer(|buf| {
+            let source = FileSource::open_with_metadata(filepath, meta, buf).ok()?;
+            let out = self.from_source(&source);
+            *buf = source.into_buffer();
+            out
+        })
+    }
+
+    /// Gets the type of a stream while writing all of it to a hasher.
+    ///
+    /// See [`detect_and_hash`](crate::detect_and_hash).
+    #[cfg(feature = "std")]
+    pub fn detect_and_hash(
+        &self,
+        reader: impl std::io::Read,
+        hasher: &mut impl std::io::Write,
+    ) -> std::io::Result<Option<&str>> {
+        let source = StreamSource::read(reader, hasher)?;
+        Ok(self.from_source(&source))
+    }
+
+    /// Gets the type of a file from a handle that's already open.
+    ///
+    /// See [`from_file`](crate::from_file).
+    #[cfg(feature = "std")]
+    pub fn from_file(&self, file: &File) -> Option<&str> {
+        let mut handle = file;
+        let pos = handle.stream_position().ok()?;
+        let meta = file.metadata().ok()?;
+
+        let out = source::with_buffer(|buf| {
+            let source = match meta.is_file() && meta.len() > 0 {
+                true => {
+                    handle.seek(SeekFrom::Start(0)).ok()?;
+                    let file = file.try_clone().ok()?;
+                    FileSource::from_file(file, meta, buf, source::PREFIX_LEN).ok()?
+                }
+                false => FileSource::unread(meta, None, buf, source::PREFIX_LEN),
+            };
+            let out = self.from_source(&source);
+            *buf = source.into_buffer();
+            out
+        });
+        // The clone shares the position with the caller's handle
+        handle.seek(SeekFrom::Start(pos)).ok()?;
+        out
+    }
+
+    /// Gets the type of a file in a directory, opened relative to the
+    /// directory's descriptor.
+    ///
+    /// See [`from_dirfd`](crate::from_dirfd).
+    #[cfg(all(feature = "unix-io", unix))]
+    pub fn from_dirfd(
+        &self,
+        dirfd: std::os::fd::BorrowedFd<'_>,
+        name: &std::ffi::OsStr,
+    ) -> Option<&str> {
+        crate::dirfd::from_dirfd(self, dirfd, name)
+    }
+
+    /// Detects the type of a file and tags it with it.
+    ///
+    /// See [`tag_filepath`](crate::tag_filepath).
+    #[cfg(all(feature = "unix-io", any(target_os = "linux", target_os = "macos")))]
+    pub fn tag_filepath(&self, filepath: &Path) -> std::io::Result<&str> {
+        crate::xattr::tag_filepath(self, filepath)
+    }
+
+    /// Gets the type of a symbolic link and of the file it points to.
+    ///
+    /// See [`from_filepath_following`](crate::from_filepath_following).
+    #[cfg(feature = "std")]
+    pub fn from_filepath_following(
+        &self,
+        filepath: &Path,
+        max_depth: usize,
+    ) -> std::io::Result<FollowedPath<'_>> {
+        symlink::from_filepath_following(self, filepath, max_depth)
+    }
+
+    /// Gets every top-level type the source matches.
+    ///
+    /// See [`detect_polyglot`](crate::detect_polyglot).
+    pub fn detect_polyglot(&self, source: &dyn DataSource) -> Vec<&str> {
+        let mut out: Vec<MIME> = ["application/octet-stream", "text/plain"]
+            .iter()
+            .filter_map(|&base| self.node(base))
+            .flat_map(|base| self.graph.neighbors_directed(base, Outgoing))
+            .map(|n| self.graph[n])
+            .filter(|mime| self.match_source_noalias(mime, source))
+            .collect();
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+
+    /// Finds files embedded in `bytes` past its start, by their magic
+    /// signatures.
+    ///
+    /// See [`scan_embedded`](crate::scan_embedded).
+    pub fn scan_embedded(&self, bytes: &[u8], options: &ScanOptions) -> Vec<(usize, &str)> {
+        scan::scan_embedded(self, bytes, options)
+    }
+
+    /// Finds the byte ranges of a byte stream that the detected type hinges
+    /// on.
+    ///
+    /// See [`sensitivity`](crate::sensitivity).
+    pub fn sensitivity(&self, bytes: &[u8]) -> Vec<(Range<usize>, &str)> {
+        sensitivity::sensitivity(self, bytes)
+    }
+
+    /// Picks the type to send for a detected type, from the value of an HTTP
+    /// `Accept` header.
+    ///
+    /// See [`negotiate`](crate::negotiate).
+    pub fn negotiate<'a>(&'a self, accept_header: &str, detected: &'a str) -> Option<&'a str> {
+        accept::negotiate(self, accept_header, detected)
+    }
+
+    /// Lists the types, aliases and magic rules present in only one of the
+    /// two databases.
+    ///
+    /// Useful to find out why detection differs between systems, or how
+    /// custom rules change a database.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let system = TypeDatabase::default();
+    /// let custom = TypeDatabase::builder()
+    ///     .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+    ///     .unwrap()
+    ///     .build();
+    ///
+    /// let diff = system.diff(&custom);
+    /// assert!(diff.only_in_self.is_empty());
+    /// assert_eq!(diff.only_in_other.types, vec!["application/x-foo"]);
+    /// assert_eq!(
+    ///     diff.only_in_other.rules,
+    ///     vec![("application/x-foo", ">0=FOO".to_string())]
+    /// );
+    /// ```
+    pub fn diff<'a>(&'a self, other: &'a TypeDatabase) -> DatabaseDiff<'a> {
+        diff::diff(self, other)
+    }
+
+    /// Checks this database for magic rules and aliases that can't work.
+    ///
+    /// See [`validate_database`](crate::validate_database).
+    pub fn validate(&self) -> Vec<Lint<'_>> {
+        lint::validate(self)
+    }
+
+    /// Estimated bytes of memory held by this database.
+    ///
+    /// This covers the types, their relations and the magic rules, along
+    /// with the rule trees built so far, which grow as more types are
+    /// checked. Data shared by all databases, like the system magic files
+    /// loaded at run time or the embedded tables, isn't counted. See
+    /// [`TypeDatabaseBuilder::memory_limit`] to cap it.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let db = TypeDatabase::default();
+    /// let before = db.memory_footprint();
+    /// db.from_u8(b"GIF89a");
+    /// assert!(db.memory_footprint() >= before);
+    /// ```
+    pub fn memory_footprint(&self) -> usize {
+        let families: usize = self
+            .families
+            .values()
+            .map(|v| v.capacity() * size_of::<NodeIndex>())
+            .sum();
+        let magic: usize = self
+            .types
+            .values()
+            .filter_map(|e| e.magic.as_ref())
+            .map(LazyRuleset::size)
+            .sum();
+        size_of::<Self>()
+            + graph_size(&self.graph)
+            + map_size(&self.nodes)
+            + self.types.size()
+            + magic
+            + map_size(&self.families)
+            + families
+            + self.warnings.capacity() * size_of::<DatabaseError>()
+            + self.names.size()
+    }
+
+    /// Problems worked around while building the database, such as
+    /// subclass relations dropped to break a cycle.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::{DatabaseError, TypeDatabase};
+    ///
+    /// let db = TypeDatabase::builder()
+    ///     .subclass("image/png", "image/gif")
+    ///     .subclass("image/gif", "image/png")
+    ///     .build();
+    ///
+    /// assert!(db
+    ///     .warnings()
+    ///     .iter()
+    ///     .any(|w| matches!(w, DatabaseError::SubclassCycle { .. })));
+    /// ```
+    pub fn warnings(&self) -> &[DatabaseError] {
+        &self.warnings
+    }
+
+    /// Exports the subclass graph of this database.
+    ///
+    /// See [`export_graph`](crate::export_graph).
+    pub fn export_graph(&self, format: GraphFormat) -> String {
+        export::export(self, format)
+    }
+}
+
+impl fmt::Debug for TypeDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeDatabase")
+            .field("types", &self.graph.node_count())
+            .field("aliases", &self.aliases().count())
+            .finish()
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::{vec, vec::Vec};
+
+pub(crate) struct Delimited;
+
+impl crate::Checker for Delimited {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        db.delimited_heuristics() && from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        Some(PROBE_LEN)
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        variant(source, mimetype)
+    }
+}
+
+/// How much of the text is looked at
+const PROBE_LEN: usize = 4096;
+
+/// Records compared, from the start
+const MAX_RECORDS: usize = 20;
+
+/// Fewest records to go by, header included, as a few lines with a comma
+/// each are as likely to be prose
+const MIN_RECORDS: usize = 4;
+
+/// Fewest records to go by when each has only two fields, as a single
+/// comma per line is common in prose and verse
+const MIN_PAIR_RECORDS: usize = 8;
+
+/// Longest the fields of the header may be on average, not counting
+/// whitespace. Column names are short, unlike the clauses of a sentence.
+const MAX_HEADER_FIELD_LEN: usize = 16;
+
+/// Tried in this order, so tabs win over commas within fields
+const DELIMITERS: [(u8, &str); 4] = [(b'\t', "\t"), (b',', ","), (b';', ";"), (b'|', "|")];
+
+/// Number of fields in each record that isn't blank. Delimiters and line
+/// breaks within double quotes belong to a field.
+fn field_counts(b: &[u8], delimiter: u8) -> Option<Vec<usize>> {
+    // Without the last record if the text was cut off within it
+    let b = if b.len() < PROBE_LEN {
+        b
+    } else {
+        &b[..b.iter().rposition(|&c| c == b'\n')?]
+    };
+
+    let mut counts = Vec::new();
+    let mut quoted = false;
+    let mut fields = 1;
+    let mut blank = true;
+    for &c in b {
+        match c {
+            b'"' => quoted = !quoted,
+            b'\n' if !quoted => {
+                if !blank {
+                    counts.push(fields);
+                    if counts.len() == MAX_RECORDS {
+                        return Some(counts);
+                    }
+                }
+                fields = 1;
+                blank = true;
+                continue;
+            }
+            c if c == delimiter && !quoted => fields += 1,
+            _ => {}
+        }
+        if !c.is_ascii_whitespace() {
+            blank = false;
+        }
+    }
+    if quoted {
+        return None;
+    }
+    if !blank {
+        counts.push(fields);
+    }
+    Some(counts)
+}
+
+/// Whether the first record that isn't blank reads like a header: no empty
+/// fields, and short ones
+fn dense_header(b: &[u8], delimiter: u8) -> bool {
+    let header = match b
+        .split(|&c| c == b'\n')
+        .find(|line| !line.iter().all(u8::is_ascii_whitespace))
+    {
+        Some(header) => header,
+        None => return false,
+    };
+
+    // Bytes in each field that aren't whitespace or quotes
+    let mut fields = vec![0];
+    let mut quoted = false;
+    for &c in header {
+        match c {
+            b'"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(0),
+            c if c.is_ascii_whitespace() => {}
+            _ => *fields.last_mut().unwrap() += 1,
+        }
+    }
+    fields.iter().all(|&n| n > 0)
+        && fields.iter().sum::<usize>() <= MAX_HEADER_FIELD_LEN * fields.len()
+}
+
+/// The first delimiter splitting enough records into the same number of
+/// fields, more than one, below a header of short fields
+fn delimiter(b: &[u8]) -> Option<(u8, &'static str)> {
+    DELIMITERS.iter().copied().find(|&(d, _)| {
+        field_counts(b, d).is_some_and(|counts| match counts.first() {
+            Some(&fields) if fields > 1 => {
+                let min_records = if fields > 2 {
+                    MIN_RECORDS
+                } else {
+                    MIN_PAIR_RECORDS
+                };
+                counts.len() >= min_records && counts.iter().all(|&n| n == fields)
+            }
+            _ => false,
+        }) && dense_header(b, d)
+    })
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    let b = match source.read_at(0, PROBE_LEN) {
+        Ok(b) => b,
+        Err(_) => return false,
+    };
+
+    match (mimetype, delimiter(&b)) {
+        ("text/tab-separated-values", Some((d, _))) => d == b'\t',
+        ("text/csv", Some((d, _))) => d != b'\t',
+        _ => false,
+    }
+}
+
+/// Reports the delimiter of the fields, such as `,` or `;`
+pub fn variant(source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+    if mimetype != "text/csv" && mimetype != "text/tab-separated-values" {
+        return None;
+    }
+    let b = source.read_at(0, PROBE_LEN).ok()?;
+    delimiter(&b).map(|(_, name)| name)
+}
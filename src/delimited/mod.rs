@@ -0,0 +1,10 @@
+//! Tells CSV and TSV apart from other text by the number of fields on each
+//! line.
+//!
+//! Only looked for while
+//! [`delimited_heuristics`](crate::TypeDatabaseBuilder::delimited_heuristics)
+//! is on.
+const TYPES: [&str; 2] = ["text/csv", "text/tab-separated-values"];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,475 @@
+//! Combine content magic, file name globs and directory treemagic
+use crate::arena::Arena;
+use crate::glob::{self, GlobSet};
+#[cfg(feature = "std")]
+use crate::treemagic::{self, TreeMagic};
+use crate::{database, DataSource, DetectOptions, TypeDatabase};
+#[cfg(feature = "std")]
+use crate::{source, FileSource};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// How a [`Detector`] weighs the file name against the content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// The checking order recommended by the shared MIME-info spec. A file
+    /// name matching the globs of a single type decides the type without
+    /// looking at the content. When several types match, the content picks
+    /// one of them; when none do, the content decides alone.
+    XdgRecommended,
+    /// Always look at the content. The file name only narrows it down to a
+    /// subclass, such as an OpenDocument file over `application/zip`, or
+    /// names data the content alone can't tell apart from plain text or
+    /// binary.
+    ContentFirst,
+    /// The preferences of Qt's `QMimeDatabase`, for the same answers as KDE
+    /// and other Qt applications. A file name matching the globs of a single
+    /// type decides it, counting globs of lower weight too. Otherwise the
+    /// content decides: it picks the type of the best globs it found, or
+    /// the first of any glob that is its subclass, and where no glob
+    /// matched it stands alone. Globs the content doesn't settle are taken
+    /// in alphabetical order. Where the data matches sibling types, the one
+    /// whose magic rules have the highest priority wins, then the first by
+    /// name.
+    QtCompatible,
+}
+
+/// Refines a detected type, given the start of the data and the type found so
+/// far. Returns the type to report instead, or the one it was given.
+///
+/// See [`DetectorBuilder::on_result`].
+pub type ResultHook = for<'a> fn(&[u8], &'a str) -> &'a str;
+
+/// Most of a file or other source read for result hooks. Byte streams are
+/// handed over whole.
+const HOOK_PREFIX_LEN: usize = 64 * 1024;
+
+/// Lowest magic priority letting content decide over conflicting globs in
+/// [`guess_content_type`](Detector::guess_content_type), as in GIO
+const GIO_DECISIVE_PRIORITY: u32 = 80;
+
+/// Builder for a [`Detector`], returned by [`Detector::builder`].
+///
+/// By default content and globs are used, with the
+/// [`XdgRecommended`](Strategy::XdgRecommended) order.
+#[derive(Debug)]
+pub struct DetectorBuilder {
+    content: bool,
+    glob: bool,
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    treemagic: bool,
+    #[cfg_attr(
+        not(all(feature = "unix-io", any(target_os = "linux", target_os = "macos"))),
+        allow(dead_code)
+    )]
+    xattr: bool,
+    order: Strategy,
+    database: Option<TypeDatabase>,
+    globs: Vec<String>,
+    hooks: Vec<ResultHook>,
+}
+
+impl Default for DetectorBuilder {
+    fn default() -> Self {
+        DetectorBuilder {
+            content: true,
+            glob: true,
+            treemagic: false,
+            xattr: false,
+            order: Strategy::XdgRecommended,
+            database: None,
+            globs: Vec::new(),
+            hooks: Vec::new(),
+        }
+    }
+}
+
+impl DetectorBuilder {
+    /// Whether to look at the content, using the magic rules and checkers.
+    pub fn content(mut self, enabled: bool) -> Self {
+        self.content = enabled;
+        self
+    }
+
+    /// Whether to match file names against the glob patterns of the system
+    /// (`/usr/share/mime/globs2`) and those added with
+    /// [`globs`](Self::globs).
+    pub fn glob(mut self, enabled: bool) -> Self {
+        self.glob = enabled;
+        self
+    }
+
+    /// Whether to detect the content type of directories, such as
+    /// `x-content/image-dcf` for a camera's memory card, using the system
+    /// treemagic rules. Needs the `std` feature.
+    pub fn treemagic(mut self, enabled: bool) -> Self {
+        self.treemagic = enabled;
+        self
+    }
+
+    /// Whether [`detect_filepath`](Detector::detect_filepath) takes the type
+    /// a file is tagged with in its `user.mime_type` extended attribute, as
+    /// file servers may set when storing uploads. The tag is only used if it
+    /// names a known type, and decides over the file name and the content.
+    /// Result hooks are given no data for it. Needs the `unix-io` feature on
+    /// Linux or macOS.
+    pub fn xattr(mut self, enabled: bool) -> Self {
+        self.xattr = enabled;
+        self
+    }
+
+    /// How to weigh the file name against the content.
+    pub fn order(mut self, order: Strategy) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Detect with the given database instead of the one the free functions
+    /// use, which is the system database unless
+    /// [`set_global_database`](crate::set_global_database) changed it.
+    pub fn database(mut self, db: TypeDatabase) -> Self {
+        self.database = Some(db);
+        self
+    }
+
+    /// Adds glob patterns in the format of a `globs2` file, one
+    /// `weight:type:pattern[:cs]` per line. They're loaded after the system
+    /// globs, so `__NOGLOBS__` drops the system globs of a type.
+    pub fn globs(mut self, text: &str) -> Self {
+        self.globs.push(text.to_string());
+        self
+    }
+
+    /// Adds a hook refining every type found, such as to report an
+    /// application's own type for ZIP archives it recognizes. Hooks run in
+    /// the order they're added, each given the type the one before returned.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::Detector;
+    ///
+    /// fn epub<'a>(bytes: &[u8], mime: &'a str) -> &'a str {
+    ///     match mime {
+    ///         "application/zip" if bytes.get(30..58) == Some(b"mimetypeapplication/epub+zip") => {
+    ///             "application/epub+zip"
+    ///         }
+    ///         _ => mime,
+    ///     }
+    /// }
+    ///
+    /// let detector = Detector::builder().glob(false).on_result(epub).build();
+    ///
+    /// let mut book = b"PK\x03\x04".to_vec();
+    /// book.resize(30, 0);
+    /// book.extend_from_slice(b"mimetypeapplication/epub+zip");
+    /// assert_eq!(detector.detect_u8(None, &book), Some("application/epub+zip"));
+    /// ```
+    pub fn on_result(mut self, hook: ResultHook) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Loads the system globs and treemagic rules as needed.
+    pub fn build(self) -> Detector {
+        let mut names = Arena::default();
+        let extra = self.globs;
+        let globs = self.glob.then(|| {
+            let mut set = glob::system();
+            for text in extra {
+                // Safety: the detector keeps the arena, and never hands out
+                // names for longer than it's borrowed
+                set.extend(unsafe { names.alloc(text.into_boxed_str()) });
+            }
+            set
+        });
+        Detector {
+            names,
+            database: self.database,
+            content: self.content,
+            globs,
+            #[cfg(feature = "std")]
+            treemagic: self.treemagic.then(treemagic::system),
+            #[cfg(all(feature = "unix-io", any(target_os = "linux", target_os = "macos")))]
+            xattr: self.xattr,
+            order: self.order,
+            hooks: self.hooks,
+        }
+    }
+}
+
+/// Detects types from the content, the file name, or both.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::{Detector, Strategy};
+///
+/// let detector = Detector::builder()
+///     .content(true)
+///     .glob(true)
+///     .treemagic(false)
+///     .order(Strategy::XdgRecommended)
+///     .globs("50:image/gif:*.gif")
+///     .build();
+///
+/// let gif: &[u8] = include_bytes!("../tests/image/gif");
+/// assert_eq!(detector.detect_u8(Some("cat.gif"), gif), Some("image/gif"));
+/// assert_eq!(detector.detect_u8(None, gif), Some("image/gif"));
+/// ```
+#[derive(Debug)]
+pub struct Detector {
+    /// Text of the globs added to the builder, only held to be freed along
+    /// with the detector
+    #[allow(dead_code)]
+    names: Arena,
+    database: Option<TypeDatabase>,
+    content: bool,
+    globs: Option<GlobSet>,
+    #[cfg(feature = "std")]
+    treemagic: Option<TreeMagic>,
+    #[cfg(all(feature = "unix-io", any(target_os = "linux", target_os = "macos")))]
+    xattr: bool,
+    order: Strategy,
+    hooks: Vec<ResultHook>,
+}
+
+impl Detector {
+    /// Starts building a detector.
+    pub fn builder() -> DetectorBuilder {
+        DetectorBuilder::default()
+    }
+
+    /// Database used for the content and for relations between types
+    fn database(&self) -> &TypeDatabase {
+        self.database.as_ref().unwrap_or_else(|| database())
+    }
+
+    /// Types whose globs match the file name, best match first. Empty if
+    /// globs are disabled.
+    pub fn from_filename(&self, filename: &str) -> Vec<&str> {
+        let db = self.database();
+        let mut out: Vec<&str> = match &self.globs {
+            Some(globs) => globs.matches(filename),
+            None => return Vec::new(),
+        };
+        for mime in out.iter_mut() {
+            *mime = db.get_alias(mime);
+        }
+        out.dedup();
+        out
+    }
+
+    /// Gets the type of a data source, going by its file name too if given.
+    ///
+    /// Returns `None` if neither the enabled methods find a type.
+    pub fn detect(&self, filename: Option<&str>, source: &dyn DataSource) -> Option<&str> {
+        let found = self.detect_unhooked(filename, source)?;
+        Some(self.run_hooks(source, found))
+    }
+
+    /// Passes a type found for the source through the result hooks
+    fn run_hooks<'a>(&self, source: &dyn DataSource, mime: &'a str) -> &'a str {
+        if self.hooks.is_empty() {
+            return mime;
+        }
+        let prefix;
+        let bytes = match source.as_slice() {
+            Some(b) => b,
+            None => {
+                prefix = source.read_at(0, HOOK_PREFIX_LEN).unwrap_or_default();
+                &prefix[..]
+            }
+        };
+        self.hooks.iter().fold(mime, |mime, hook| hook(bytes, mime))
+    }
+
+    /// [`detect`](Self::detect) without the result hooks
+    fn detect_unhooked(&self, filename: Option<&str>, source: &dyn DataSource) -> Option<&str> {
+        if self.order == Strategy::QtCompatible {
+            return self.detect_qt(filename, source);
+        }
+        let globs = filename.map_or_else(Vec::new, |n| self.from_filename(n));
+        if !self.content {
+            return globs.first().copied();
+        }
+        if self.order == Strategy::XdgRecommended && globs.len() == 1 {
+            return Some(globs[0]);
+        }
+
+        let db = self.database();
+        let sniffed = match db.from_source(source) {
+            Some(x) => x,
+            None => return globs.first().copied(),
+        };
+        // A glob naming the sniffed type or one of its subclasses is more
+        // precise than the content alone
+        if let Some(&mime) = globs.iter().find(|g| db.is_a(g, sniffed)) {
+            return Some(mime);
+        }
+        match (globs.first(), sniffed) {
+            (Some(&mime), "application/octet-stream" | "text/plain" | "all/allfiles") => Some(mime),
+            _ => Some(sniffed),
+        }
+    }
+
+    /// The known type a file is tagged with, under its canonical name
+    #[cfg(all(feature = "unix-io", any(target_os = "linux", target_os = "macos")))]
+    fn tagged_type(&self, filepath: &Path) -> Option<&str> {
+        let tagged = crate::xattr::mime_type(filepath)?;
+        let db = self.database();
+        let node = db.node(db.get_alias(&tagged))?;
+        Some(db.graph()[node])
+    }
+
+    /// Guesses a type the way GIO's `g_content_type_guess` does, from a
+    /// file name, the start of the data, or both. Also returns whether the
+    /// guess is uncertain.
+    ///
+    /// A name ending in `/` is a directory. A name whose globs match a
+    /// single type decides it. Otherwise the data decides, unless several
+    /// types match the name, when it's only used to pick one of them that
+    /// is a subclass of its type. Failing that, the type of the data wins if
+    /// its magic has a priority of 80 or more, and otherwise the first is
+    /// guessed. Data only known as `application/octet-stream` doesn't count,
+    /// and data looking like a desktop entry under a name that doesn't is
+    /// taken for `text/plain`, as desktop entries can run any program.
+    pub fn guess_content_type(&self, filename: Option<&str>, data: Option<&[u8]>) -> (&str, bool) {
+        if filename.is_some_and(|n| n.ends_with('/')) {
+            return ("inode/directory", false);
+        }
+        let (mime, uncertain) = self.guess_unhooked(filename, data);
+        (self.run_hooks(&data.unwrap_or_default(), mime), uncertain)
+    }
+
+    /// [`guess_content_type`](Self::guess_content_type) without the result
+    /// hooks
+    fn guess_unhooked(&self, filename: Option<&str>, data: Option<&[u8]>) -> (&str, bool) {
+        // Only the last component, like `g_path_get_basename`
+        let basename = filename.map(|n| n.rsplit('/').next().unwrap_or(n));
+        let globs = basename.map_or_else(Vec::new, |n| self.from_filename(n));
+        if globs.len() == 1 {
+            return (globs[0], false);
+        }
+
+        let db = self.database();
+        let sniffed = match data {
+            Some(data) if self.content => db.from_source(&data),
+            _ => None,
+        };
+        let sniffed = match sniffed {
+            Some("application/octet-stream" | "all/allfiles") | None => None,
+            Some("application/x-desktop") if filename.is_some() => Some("text/plain"),
+            Some(mime) => Some(mime),
+        };
+
+        let first = match globs.first() {
+            Some(&mime) => mime,
+            None => return sniffed.map_or(("application/octet-stream", true), |m| (m, false)),
+        };
+        match sniffed.and_then(|s| globs.iter().find(|g| db.is_a(g, s))) {
+            Some(&mime) => (mime, false),
+            None => match sniffed {
+                Some(s) if db.magic_priority(s) >= GIO_DECISIVE_PRIORITY => (s, false),
+                _ => (first, true),
+            },
+        }
+    }
+
+    /// Types whose globs match the file name, as the best globs and those
+    /// of lower weight give them
+    fn ranked_globs(&self, filename: &str) -> (Vec<&str>, Vec<&str>) {
+        let db = self.database();
+        let (best, weaker) = match &self.globs {
+            Some(globs) => globs.ranked_matches(filename),
+            None => return (Vec::new(), Vec::new()),
+        };
+        let mut best: Vec<&str> = best.into_iter().map(|m| db.get_alias(m)).collect();
+        best.dedup();
+        let mut weaker: Vec<&str> = weaker.into_iter().map(|m| db.get_alias(m)).collect();
+        weaker.retain(|m| !best.contains(m));
+        weaker.dedup();
+        (best, weaker)
+    }
+
+    /// [`detect_unhooked`](Self::detect_unhooked) with the preferences of
+    /// [`QtCompatible`](Strategy::QtCompatible)
+    fn detect_qt(&self, filename: Option<&str>, source: &dyn DataSource) -> Option<&str> {
+        let (mut best, weaker) = filename.map_or_else(Default::default, |n| self.ranked_globs(n));
+        let all: Vec<&str> = best.iter().chain(&weaker).copied().collect();
+        if all.len() == 1 {
+            return Some(all[0]);
+        }
+
+        let db = self.database();
+        let sniffed = match self.content {
+            true => db.detect(source, &DetectOptions::new().by_priority()),
+            false => None,
+        };
+        // Only data no rule matched at all is left to the globs
+        if let Some(s) =
+            sniffed.filter(|s| !matches!(*s, "application/octet-stream" | "all/allfiles"))
+        {
+            if best.contains(&s) {
+                return Some(s);
+            }
+            if let Some(&mime) = all.iter().find(|m| db.is_a(m, s)) {
+                return Some(mime);
+            }
+            if all.is_empty() {
+                return Some(s);
+            }
+        }
+        best.sort_unstable();
+        best.first().copied().or(sniffed)
+    }
+
+    /// Gets the type of a byte stream, going by its file name too if given.
+    ///
+    /// See [`detect`](Self::detect).
+    pub fn detect_u8(&self, filename: Option<&str>, bytes: &[u8]) -> Option<&str> {
+        self.detect(filename, &bytes)
+    }
+
+    /// Gets the type of a file or directory.
+    ///
+    /// Directories are `inode/directory` or `inode/mount-point`, unless
+    /// treemagic is enabled and finds a more specific type. Files are only
+    /// opened if content detection is enabled, and a type tagged with
+    /// [`xattr`](DetectorBuilder::xattr) comes before all else.
+    #[cfg(feature = "std")]
+    pub fn detect_filepath(&self, filepath: &Path) -> Option<&str> {
+        let meta = std::fs::metadata(filepath).ok()?;
+        if meta.is_dir() {
+            let found = self
+                .treemagic
+                .as_ref()
+                .and_then(|t| t.detect(self.database(), filepath))
+                .or_else(|| self.database().from_filepath(filepath))?;
+            let empty: &[u8] = &[];
+            return Some(self.run_hooks(&empty, found));
+        }
+
+        #[cfg(all(feature = "unix-io", any(target_os = "linux", target_os = "macos")))]
+        if self.xattr {
+            if let Some(found) = self.tagged_type(filepath) {
+                let empty: &[u8] = &[];
+                return Some(self.run_hooks(&empty, found));
+            }
+        }
+
+        let filename = filepath.file_name().and_then(|n| n.to_str());
+        if !self.content {
+            let empty: &[u8] = &[];
+            return self.detect(filename, &empty);
+        }
+        source::with_buffer(|buf| {
+            let source = FileSource::open_with_buffer(filepath, buf).ok()?;
+            let out = self.detect(filename, &source);
+            *buf = source.into_buffer();
+            out
+        })
+    }
+}
@@ -0,0 +1,90 @@
+//! Compare two type databases
+use crate::{FnvHashSet, TypeDatabase};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Entries present in only one of two databases, as part of a
+/// [`DatabaseDiff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatabaseEntries<'a> {
+    /// Known types, sorted.
+    pub types: Vec<&'a str>,
+    /// `(alias, type)` pairs, sorted.
+    pub aliases: Vec<(&'a str, &'a str)>,
+    /// `(type, rule)` pairs, sorted. Rules are written like lines of a magic
+    /// file, with printable bytes left as they are.
+    /// (EX: `("image/gif", ">0=GIF8")`)
+    pub rules: Vec<(&'a str, String)>,
+}
+
+impl DatabaseEntries<'_> {
+    /// Whether there are no entries at all
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.aliases.is_empty() && self.rules.is_empty()
+    }
+}
+
+/// Differences between two databases, as returned by [`TypeDatabase::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatabaseDiff<'a> {
+    /// What the database `diff` was called on has, and the other lacks.
+    pub only_in_self: DatabaseEntries<'a>,
+    /// What the other database has, and the one `diff` was called on lacks.
+    pub only_in_other: DatabaseEntries<'a>,
+}
+
+impl DatabaseDiff<'_> {
+    /// Whether both databases hold the same types, aliases and rules
+    pub fn is_empty(&self) -> bool {
+        self.only_in_self.is_empty() && self.only_in_other.is_empty()
+    }
+}
+
+/// Everything in a database that's compared
+struct Entries<'a> {
+    types: FnvHashSet<&'a str>,
+    aliases: FnvHashSet<(&'a str, &'a str)>,
+    rules: FnvHashSet<(&'a str, String)>,
+}
+
+impl<'a> Entries<'a> {
+    fn new(db: &'a TypeDatabase) -> Self {
+        Entries {
+            types: db.graph().node_weights().copied().collect(),
+            aliases: db.aliases().map(|(&a, &b)| (a, b)).collect(),
+            rules: db
+                .all_magic()
+                .flat_map(|(mime, rules)| {
+                    rules
+                        .node_weights()
+                        .map(|r| (mime, r.to_string()))
+                        .collect::<Vec<_>>()
+                })
+                .collect(),
+        }
+    }
+
+    /// Sorted entries of `self` missing from `other`
+    fn missing_from(&self, other: &Entries<'a>) -> DatabaseEntries<'a> {
+        let mut out = DatabaseEntries {
+            types: self.types.difference(&other.types).copied().collect(),
+            aliases: self.aliases.difference(&other.aliases).copied().collect(),
+            rules: self.rules.difference(&other.rules).cloned().collect(),
+        };
+        out.types.sort_unstable();
+        out.aliases.sort_unstable();
+        out.rules.sort_unstable();
+        out
+    }
+}
+
+pub(crate) fn diff<'a>(a: &'a TypeDatabase, b: &'a TypeDatabase) -> DatabaseDiff<'a> {
+    let a = Entries::new(a);
+    let b = Entries::new(b);
+    DatabaseDiff {
+        only_in_self: a.missing_from(&b),
+        only_in_other: b.missing_from(&a),
+    }
+}
@@ -0,0 +1,52 @@
+//! Open entries relative to a directory descriptor with `openat`, so a scan
+//! keeps looking at the directory it started in even if paths above it are
+//! renamed, and isn't bound by the length of full paths
+use crate::source::{self, PREFIX_LEN};
+use crate::{FileSource, TypeDatabase};
+use std::ffi::{CString, OsStr};
+use std::fs::File;
+use std::io;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+
+/// Opens `name` relative to the directory, without blocking on FIFOs and
+/// devices, which are never read from
+fn openat(dirfd: BorrowedFd<'_>, name: &OsStr) -> io::Result<File> {
+    let name = CString::new(name.as_bytes())?;
+    let flags = libc::O_RDONLY | libc::O_CLOEXEC | libc::O_NONBLOCK | libc::O_NOCTTY;
+    // Safety: `name` is a valid C string
+    let fd = unsafe { libc::openat(dirfd.as_raw_fd(), name.as_ptr(), flags) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    // Safety: the descriptor was just opened, and nothing else owns it
+    Ok(File::from(unsafe { OwnedFd::from_raw_fd(fd) }))
+}
+
+/// Opens the entry and takes its metadata from the open descriptor, so both
+/// are of the same file even if it's replaced in the meantime
+pub(crate) fn from_dirfd<'a>(
+    db: &'a TypeDatabase,
+    dirfd: BorrowedFd<'_>,
+    name: &OsStr,
+) -> Option<&'a str> {
+    let file = openat(dirfd, name).ok()?;
+    let meta = file.metadata().ok()?;
+
+    source::with_buffer(|buf| {
+        let source = if meta.is_file() && meta.len() > 0 {
+            FileSource::from_file(file, meta, buf, PREFIX_LEN).ok()?
+        } else {
+            let parent_meta = match meta.is_dir() {
+                true => openat(file.as_fd(), OsStr::new(".."))
+                    .and_then(|p| p.metadata())
+                    .ok(),
+                false => None,
+            };
+            FileSource::unread(meta, parent_meta, buf, PREFIX_LEN)
+        };
+        let out = db.from_source(&source);
+        *buf = source.into_buffer();
+        out
+    })
+}
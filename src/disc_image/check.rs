@@ -0,0 +1,85 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct DiscImage;
+
+impl crate::Checker for DiscImage {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, _mimetype: &str) -> bool {
+        from_source(source)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn variant(&self, source: &dyn DataSource, _mimetype: &str) -> Option<&'static str> {
+        variant(source)
+    }
+}
+
+const SECTOR_LEN: u64 = 2048;
+
+/// The first 16 sectors are left to the system, such as for boot code
+const FIRST_DESCRIPTOR: u64 = 16 * SECTOR_LEN;
+
+/// Stop following the descriptors after this many sectors
+const MAX_DESCRIPTORS: u64 = 32;
+
+/// Type, then the identifier of a volume descriptor
+const DESCRIPTOR_LEN: usize = 6;
+
+/// File systems found on a disc image
+#[derive(Default)]
+struct Volume {
+    iso9660: bool,
+    udf: bool,
+}
+
+/// Follows the volume descriptors, one per sector, which end at the first
+/// sector without a known identifier. Each is read on its own, so a file
+/// isn't read through to find them.
+fn volume(source: &dyn DataSource) -> Volume {
+    let mut out = Volume::default();
+    for i in 0..MAX_DESCRIPTORS {
+        let b = match source.read_at(FIRST_DESCRIPTOR + i * SECTOR_LEN, DESCRIPTOR_LEN) {
+            Ok(b) if b.len() == DESCRIPTOR_LEN => b,
+            _ => break,
+        };
+        match &b[1..] {
+            b"CD001" => out.iso9660 = true,
+            b"NSR02" | b"NSR03" => out.udf = true,
+            // The start and end of the extended area holding the UDF
+            // descriptors, and those of other standards
+            b"BEA01" | b"TEA01" | b"CDW02" | b"BOOT2" => {}
+            _ => break,
+        }
+    }
+    out
+}
+
+pub fn from_source(source: &dyn DataSource) -> bool {
+    let volume = volume(source);
+    volume.iso9660 || volume.udf
+}
+
+/// Reports the file system, `iso9660` or `udf`, or `udf-bridge` for discs
+/// with both, so older readers can open them
+pub fn variant(source: &dyn DataSource) -> Option<&'static str> {
+    match volume(source) {
+        Volume {
+            iso9660: true,
+            udf: true,
+        } => Some("udf-bridge"),
+        Volume { iso9660: true, .. } => Some("iso9660"),
+        Volume { udf: true, .. } => Some("udf"),
+        _ => None,
+    }
+}
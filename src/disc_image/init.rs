@@ -0,0 +1,18 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    vec![("application/x-raw-disk-image", "application/x-cd-image")]
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [("application/x-udf-image", "application/x-cd-image")]
+        .iter()
+        .copied()
+        .collect()
+}
@@ -0,0 +1,6 @@
+//! Handles CD, DVD and Blu-ray disc images, by the volume descriptors past
+//! the first 32 KiB
+const TYPES: [&str; 1] = ["application/x-cd-image"];
+
+pub mod check;
+pub mod init;
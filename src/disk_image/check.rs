@@ -0,0 +1,183 @@
+use crate::source::source_len;
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct DiskImage;
+
+impl crate::Checker for DiskImage {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "application/x-qemu-disk" => Some(QCOW_HEADER_LEN),
+            "application/x-vmdk-disk" => Some(VMDK_HEADER_LEN),
+            "application/x-vhdx-disk" => Some(VHDX_MAGIC.len()),
+            // A fixed VHD has its footer at the end, and a raw image may
+            // be an optical disc, with its descriptors past 32 KiB
+            _ => None,
+        }
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        variant(source, mimetype)
+    }
+}
+
+const QCOW_MAGIC: &[u8; 4] = b"QFI\xfb";
+/// Magic, then the version as a big-endian 32-bit integer
+const QCOW_HEADER_LEN: usize = 8;
+
+/// Magic of a hosted sparse extent, which the descriptor is embedded in
+const VMDK_MAGIC: &[u8; 4] = b"KDMV";
+/// Magic, then the version as a little-endian 32-bit integer
+const VMDK_HEADER_LEN: usize = 8;
+
+const VHD_COOKIE: &[u8; 8] = b"conectix";
+/// The footer is a sector long, but older tools wrote one byte less
+const VHD_FOOTER_LENS: [u64; 2] = [512, 511];
+/// Offset in the footer of the disk type, a big-endian 32-bit integer
+const VHD_DISK_TYPE: usize = 60;
+
+const VHDX_MAGIC: &[u8; 8] = b"vhdxfile";
+
+/// The four primary partitions, 16 bytes each, then `55 AA`
+const MBR_PARTITIONS: usize = 446;
+const MBR_LEN: usize = 512;
+
+const GPT_MAGIC: &[u8; 8] = b"EFI PART";
+/// The GPT header is in the second logical block, whose size isn't known
+const GPT_HEADER_OFFSETS: [u64; 2] = [512, 4096];
+
+fn be32(b: &[u8], at: usize) -> Option<u32> {
+    let b = b.get(at..at + 4)?;
+    Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn le32(b: &[u8], at: usize) -> Option<u32> {
+    let b = b.get(at..at + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read(source: &dyn DataSource, offset: u64, len: usize) -> Option<Vec<u8>> {
+    source.read_at(offset, len).ok().map(|b| b.into_owned())
+}
+
+/// Version of a QEMU copy-on-write image, from 1 to 3
+fn qcow_version(source: &dyn DataSource) -> Option<u32> {
+    let b = read(source, 0, QCOW_HEADER_LEN)?;
+    if !b.starts_with(QCOW_MAGIC) {
+        return None;
+    }
+    be32(&b, 4).filter(|v| (1..=3).contains(v))
+}
+
+fn is_vmdk(source: &dyn DataSource) -> bool {
+    match read(source, 0, VMDK_HEADER_LEN) {
+        Some(b) => b.starts_with(VMDK_MAGIC) && le32(&b, 4).is_some_and(|v| (1..=3).contains(&v)),
+        None => false,
+    }
+}
+
+/// The footer of a VHD. Dynamic and differencing disks keep a copy of it at
+/// the start, where fixed ones have their data.
+fn vhd_footer(source: &dyn DataSource) -> Option<Vec<u8>> {
+    let b = read(source, 0, MBR_LEN)?;
+    if b.starts_with(VHD_COOKIE) {
+        return Some(b);
+    }
+    let len = source_len(source)?;
+    VHD_FOOTER_LENS.iter().find_map(|&n| {
+        let b = read(source, len.checked_sub(n)?, n as usize)?;
+        Some(b).filter(|b| b.starts_with(VHD_COOKIE))
+    })
+}
+
+fn is_vhdx(source: &dyn DataSource) -> bool {
+    read(source, 0, VHDX_MAGIC.len()).is_some_and(|b| b[..] == VHDX_MAGIC[..])
+}
+
+/// A partition table in the master boot record, with at least one
+/// partition. Boot sectors of floppies and partitions also end in `55 AA`,
+/// but hold code where the table would be, so the entries are checked too.
+fn is_mbr(source: &dyn DataSource) -> bool {
+    let b = match read(source, 0, MBR_LEN) {
+        Some(b) if b.len() == MBR_LEN && b[MBR_LEN - 2..] == [0x55, 0xaa] => b,
+        _ => return false,
+    };
+    let mut partitions = 0;
+    for entry in b[MBR_PARTITIONS..MBR_LEN - 2].chunks(16) {
+        // Boot flag, and the partition type
+        if entry[0] & 0x7f != 0 {
+            return false;
+        }
+        if entry[4] == 0 {
+            continue;
+        }
+        if le32(entry, 12) == Some(0) {
+            return false;
+        }
+        partitions += 1;
+    }
+    partitions > 0
+}
+
+fn is_gpt(source: &dyn DataSource) -> bool {
+    GPT_HEADER_OFFSETS
+        .iter()
+        .any(|&at| read(source, at, GPT_MAGIC.len()).is_some_and(|b| b[..] == GPT_MAGIC[..]))
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    match mimetype {
+        // Optical discs and filesystems are raw images too, and are
+        // subclasses of them. A fixed VHD is a raw image with a footer, which
+        // makes it a VHD.
+        "application/x-raw-disk-image" => {
+            (is_gpt(source)
+                || is_mbr(source)
+                || crate::disc_image::check::from_source(source)
+                || crate::filesystem::check::from_source(source))
+                && vhd_footer(source).is_none()
+        }
+        "application/x-qemu-disk" => qcow_version(source).is_some(),
+        "application/x-vmdk-disk" => is_vmdk(source),
+        "application/x-vhd-disk" => vhd_footer(source).is_some(),
+        "application/x-vhdx-disk" => is_vhdx(source),
+        _ => false,
+    }
+}
+
+/// Reports the partitioning of raw images, `gpt` or `mbr`, the version of
+/// QEMU images, and whether a VHD is `fixed`, `dynamic` or `differencing`
+pub fn variant(source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+    match mimetype {
+        "application/x-raw-disk-image" if is_gpt(source) => Some("gpt"),
+        "application/x-raw-disk-image" if is_mbr(source) => Some("mbr"),
+        "application/x-qemu-disk" => match qcow_version(source)? {
+            1 => Some("1"),
+            2 => Some("2"),
+            _ => Some("3"),
+        },
+        "application/x-vhd-disk" => match be32(&vhd_footer(source)?, VHD_DISK_TYPE)? {
+            2 => Some("fixed"),
+            3 => Some("dynamic"),
+            4 => Some("differencing"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,22 @@
+use crate::{FnvHashMap, MIME};
+use alloc::vec::Vec;
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    Vec::new()
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/x-virtualbox-vmdk", "application/x-vmdk-disk"),
+        ("application/x-virtualbox-vhd", "application/x-vhd-disk"),
+        ("application/x-virtualbox-vhdx", "application/x-vhdx-disk"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
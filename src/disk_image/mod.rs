@@ -0,0 +1,12 @@
+//! Handles hard disk images, both raw ones, told by their partition table,
+//! and those of virtual machines
+const TYPES: [&str; 5] = [
+    "application/x-raw-disk-image",
+    "application/x-qemu-disk",
+    "application/x-vmdk-disk",
+    "application/x-vhd-disk",
+    "application/x-vhdx-disk",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,224 @@
+use crate::zip::{is_zip, zip_names};
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Ebook;
+
+impl crate::Checker for Ebook {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        let book = match mimetype {
+            "application/epub+zip" => return is_epub(source),
+            "application/x-kfx-ebook" => return kfx(source).is_some(),
+            _ => palm_book(source),
+        };
+        match (mimetype, book) {
+            ("application/vnd.amazon.ebook", None) => is_topaz(source),
+            (_, Some(book)) => book.is(mimetype),
+            // Data cut off before the first record still has the magic of
+            // the database to go by
+            (_, None) => fdo_magic::builtin::check::from_source(db, source, mimetype),
+        }
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "application/x-kfx-ebook" => Some(KFX_HEADER_LEN),
+            // The rest read the first record, or the end of the archive,
+            // wherever they are
+            _ => None,
+        }
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        match mimetype {
+            "application/x-mobipocket-ebook" => {
+                palm_book(source).and_then(|b| b.joint.then_some("joint"))
+            }
+            "application/vnd.amazon.ebook" if is_topaz(source) => Some("topaz"),
+            "application/x-kfx-ebook" => kfx(source),
+            _ => None,
+        }
+    }
+}
+
+const EPUB_MIMETYPE: &[u8] = b"application/epub+zip";
+/// Local header and the name of the first member, which holds the type
+const ZIP_HEADER_LEN: usize = 30;
+
+/// Palm database header, up to the offset of the first record
+const PDB_HEADER_LEN: usize = 86;
+const PDB_TYPE: usize = 60;
+const PDB_RECORD_COUNT: usize = 76;
+const PDB_FIRST_RECORD: usize = 78;
+/// The PalmDOC header, MOBI header and EXTH header of the first record
+const RECORD0_PROBE_LEN: usize = 16 * 1024;
+const PALMDOC_HEADER_LEN: usize = 16;
+const PALMDOC_ENCRYPTION: usize = 12;
+/// Mobipocket DRM, as sold by the Kindle store
+const MOBIPOCKET_DRM: u16 = 2;
+/// Offsets in the MOBI header, which follows the PalmDOC header
+const MOBI_LENGTH: usize = 4;
+const MOBI_VERSION: usize = 20;
+const MOBI_EXTH_FLAGS: usize = 0x70;
+const MOBI_HAS_EXTH: u32 = 0x40;
+/// EXTH records of the KF8 half of a joint file, and of the content type
+/// that books from the Kindle store are given
+const EXTH_KF8_BOUNDARY: u32 = 121;
+const EXTH_CDE_TYPE: u32 = 501;
+/// Records looked at before giving up on finding those
+const MAX_EXTH_RECORDS: usize = 256;
+
+/// The version of a KFX container, or `drmion` for one that's encrypted
+const KFX_HEADER_LEN: usize = 8;
+const KFX_DRMION: &[u8; 8] = b"\xeaDRMION\xee";
+
+const TOPAZ_MAGIC: &[u8; 4] = b"TPZ0";
+
+/// What the header of a Palm database, and of the first record, say
+struct PalmBook {
+    mobi: bool,
+    /// Only KF8, as in AZW3 files
+    kf8: bool,
+    /// A Mobipocket book with a KF8 book after it, for older and newer
+    /// readers alike
+    joint: bool,
+    /// Bought from the Kindle store
+    amazon: bool,
+}
+
+impl PalmBook {
+    fn is(&self, mimetype: &str) -> bool {
+        match mimetype {
+            "application/x-mobipocket-ebook" => self.mobi,
+            "application/vnd.amazon.mobi8-ebook" => self.mobi && self.kf8,
+            "application/vnd.amazon.ebook" => self.mobi && !self.kf8 && self.amazon,
+            "application/x-aportisdoc" => !self.mobi,
+            _ => false,
+        }
+    }
+}
+
+fn be_u16(b: &[u8], at: usize) -> Option<u16> {
+    let b = b.get(at..at + 2)?;
+    Some(u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn be_u32(b: &[u8], at: usize) -> Option<u32> {
+    let b = b.get(at..at + 4)?;
+    Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Whether the EXTH header has a record of `kind`
+fn has_exth(b: &[u8], at: usize, kind: u32) -> bool {
+    if b.get(at..at + 4) != Some(b"EXTH") {
+        return false;
+    }
+    let count = be_u32(b, at + 8).unwrap_or(0) as usize;
+    let mut at = at + 12;
+    for _ in 0..count.min(MAX_EXTH_RECORDS) {
+        match (be_u32(b, at), be_u32(b, at + 4)) {
+            (Some(k), _) if k == kind => return true,
+            (Some(_), Some(len)) if len >= 8 => at += len as usize,
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Books of Mobipocket, or of PalmDOC, which it extends with a header after
+/// that of PalmDOC in the first record
+fn palm_book(source: &dyn DataSource) -> Option<PalmBook> {
+    let header = source.read_at(0, PDB_HEADER_LEN).ok()?;
+    let mobi = match header.get(PDB_TYPE..PDB_TYPE + 8)? {
+        b"BOOKMOBI" => true,
+        b"TEXtREAd" => false,
+        _ => return None,
+    };
+    if be_u16(&header, PDB_RECORD_COUNT)? == 0 {
+        return None;
+    }
+    let offset = be_u32(&header, PDB_FIRST_RECORD)? as u64;
+    let record = source.read_at(offset, RECORD0_PROBE_LEN).ok()?;
+    let encryption = be_u16(&record, PALMDOC_ENCRYPTION)?;
+
+    let m = PALMDOC_HEADER_LEN;
+    if record.get(m..m + 4) != Some(b"MOBI") {
+        // Older Mobipocket books may be typed as PalmDOC, but never the
+        // other way around
+        return (!mobi).then_some(PalmBook {
+            mobi: false,
+            kf8: false,
+            joint: false,
+            amazon: false,
+        });
+    }
+    let exth = match be_u32(&record, m + MOBI_EXTH_FLAGS) {
+        Some(flags) if flags & MOBI_HAS_EXTH != 0 => {
+            Some(m + be_u32(&record, m + MOBI_LENGTH)? as usize)
+        }
+        _ => None,
+    };
+    let has = |kind| exth.is_some_and(|at| has_exth(&record, at, kind));
+    Some(PalmBook {
+        mobi: true,
+        kf8: be_u32(&record, m + MOBI_VERSION) == Some(8),
+        joint: has(EXTH_KF8_BOUNDARY),
+        amazon: encryption == MOBIPOCKET_DRM || has(EXTH_CDE_TYPE),
+    })
+}
+
+/// The `mimetype` member stored first, as the format requires, or failing
+/// that, one along with the container of the package
+fn is_epub(source: &dyn DataSource) -> bool {
+    let header = match source.read_at(0, ZIP_HEADER_LEN + 8) {
+        Ok(b) => b,
+        Err(_) => return false,
+    };
+    if header.len() == ZIP_HEADER_LEN + 8 && &header[ZIP_HEADER_LEN..] == b"mimetype" {
+        let extra = u16::from_le_bytes([header[28], header[29]]) as u64;
+        let at = (ZIP_HEADER_LEN + 8) as u64 + extra;
+        if let Ok(b) = source.read_at(at, EPUB_MIMETYPE.len()) {
+            if b[..] == EPUB_MIMETYPE[..] {
+                return true;
+            }
+        }
+    }
+    if !is_zip(source) {
+        return false;
+    }
+    let names = zip_names(source);
+    let has = |name: &[u8]| names.iter().any(|n| n == name);
+    has(b"mimetype") && has(b"META-INF/container.xml")
+}
+
+fn is_topaz(source: &dyn DataSource) -> bool {
+    match source.read_at(0, TOPAZ_MAGIC.len()) {
+        Ok(b) => b[..] == TOPAZ_MAGIC[..],
+        Err(_) => false,
+    }
+}
+
+/// `container` for a KFX container, whose version follows its magic, or
+/// `drmion` for one wrapped in DRM
+fn kfx(source: &dyn DataSource) -> Option<&'static str> {
+    let b = source.read_at(0, KFX_HEADER_LEN).ok()?;
+    if b[..] == KFX_DRMION[..] {
+        return Some("drmion");
+    }
+    match (b.get(..4)?, u16::from_le_bytes([*b.get(4)?, *b.get(5)?])) {
+        (b"CONT", 1..=2) => Some("container"),
+        _ => None,
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    vec![
+        ("application/zip", "application/epub+zip"),
+        (
+            "application/x-mobipocket-ebook",
+            "application/vnd.amazon.mobi8-ebook",
+        ),
+        (
+            "application/x-mobipocket-ebook",
+            "application/vnd.amazon.ebook",
+        ),
+        // Topaz books aren't Mobipocket underneath
+        ("application/octet-stream", "application/vnd.amazon.ebook"),
+    ]
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        (
+            "application/x-mobi8-ebook",
+            "application/vnd.amazon.mobi8-ebook",
+        ),
+        ("application/x-azw3", "application/vnd.amazon.mobi8-ebook"),
+        ("application/x-azw", "application/vnd.amazon.ebook"),
+        ("application/x-palmdoc", "application/x-aportisdoc"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
@@ -0,0 +1,12 @@
+//! Tells EPUB, Mobipocket, PalmDOC and the Kindle formats apart
+const TYPES: [&str; 6] = [
+    "application/epub+zip",
+    "application/x-mobipocket-ebook",
+    "application/vnd.amazon.mobi8-ebook",
+    "application/vnd.amazon.ebook",
+    "application/x-kfx-ebook",
+    "application/x-aportisdoc",
+];
+
+pub mod check;
+pub mod init;
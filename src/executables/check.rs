@@ -0,0 +1,227 @@
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Executables;
+
+impl crate::Checker for Executables {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(db, source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        variant(source, mimetype)
+    }
+}
+
+/// Long enough for the ELF header of either class, and the DOS header
+const HEADER_LEN: usize = 64;
+
+const ET_REL: u16 = 1;
+const ET_DYN: u16 = 3;
+const ET_CORE: u16 = 4;
+
+/// Program header naming the dynamic loader, which only executables have
+const PT_INTERP: u32 = 3;
+/// Stop looking for `PT_INTERP` after this many program headers
+const MAX_PHNUM: usize = 128;
+
+/// Where the DOS header keeps the offset of the PE header
+const E_LFANEW: usize = 0x3C;
+/// Signature, then the COFF header up to its characteristics
+const PE_HEADER_LEN: usize = 24;
+const IMAGE_FILE_DLL: u16 = 0x2000;
+
+/// The fields of an ELF header that say what kind of file it is
+struct Elf {
+    big_endian: bool,
+    is_64: bool,
+    e_type: u16,
+    machine: u16,
+    phoff: u64,
+    phentsize: usize,
+    phnum: usize,
+}
+
+impl Elf {
+    fn u16(&self, b: &[u8], off: usize) -> Option<u16> {
+        let x = b.get(off..off + 2)?;
+        let x = [x[0], x[1]];
+        Some(if self.big_endian {
+            u16::from_be_bytes(x)
+        } else {
+            u16::from_le_bytes(x)
+        })
+    }
+
+    fn u32(&self, b: &[u8], off: usize) -> Option<u32> {
+        let x = b.get(off..off + 4)?;
+        let x = [x[0], x[1], x[2], x[3]];
+        Some(if self.big_endian {
+            u32::from_be_bytes(x)
+        } else {
+            u32::from_le_bytes(x)
+        })
+    }
+
+    fn u64(&self, b: &[u8], off: usize) -> Option<u64> {
+        let x = b.get(off..off + 8)?;
+        let x = [x[0], x[1], x[2], x[3], x[4], x[5], x[6], x[7]];
+        Some(if self.big_endian {
+            u64::from_be_bytes(x)
+        } else {
+            u64::from_le_bytes(x)
+        })
+    }
+
+    fn parse(b: &[u8]) -> Option<Elf> {
+        if !b.starts_with(b"\x7fELF") {
+            return None;
+        }
+        let mut elf = Elf {
+            big_endian: match b.get(5)? {
+                1 => false,
+                2 => true,
+                _ => return None,
+            },
+            is_64: match b.get(4)? {
+                1 => false,
+                2 => true,
+                _ => return None,
+            },
+            e_type: 0,
+            machine: 0,
+            phoff: 0,
+            phentsize: 0,
+            phnum: 0,
+        };
+        elf.e_type = elf.u16(b, 16)?;
+        elf.machine = elf.u16(b, 18)?;
+        if elf.is_64 {
+            elf.phoff = elf.u64(b, 32)?;
+            elf.phentsize = elf.u16(b, 54)? as usize;
+            elf.phnum = elf.u16(b, 56)? as usize;
+        } else {
+            elf.phoff = elf.u32(b, 28)? as u64;
+            elf.phentsize = elf.u16(b, 42)? as usize;
+            elf.phnum = elf.u16(b, 44)? as usize;
+        }
+        Some(elf)
+    }
+
+    /// Whether a program header asks for a dynamic loader, which tells
+    /// position-independent executables from shared objects
+    fn has_interp(&self, source: &dyn DataSource) -> bool {
+        if self.phentsize < 4 {
+            return false;
+        }
+        let phnum = self.phnum.min(MAX_PHNUM);
+        let b = match source.read_at(self.phoff, phnum * self.phentsize) {
+            Ok(b) => b,
+            Err(_) => return false,
+        };
+        b.chunks_exact(self.phentsize)
+            .any(|ph| self.u32(ph, 0) == Some(PT_INTERP))
+    }
+
+    fn arch(&self) -> Option<&'static str> {
+        Some(match (self.machine, self.is_64) {
+            (2, _) => "sparc",
+            (3, _) => "i386",
+            (8, false) => "mips",
+            (8, true) => "mips64",
+            (20, _) => "ppc",
+            (21, _) => "ppc64",
+            (22, false) => "s390",
+            (22, true) => "s390x",
+            (40, _) => "arm",
+            (43, _) => "sparc64",
+            (62, _) => "x86_64",
+            (183, _) => "arm64",
+            (243, false) => "riscv32",
+            (243, true) => "riscv64",
+            (258, _) => "loongarch64",
+            _ => return None,
+        })
+    }
+}
+
+/// The machine and characteristics in the COFF header of a PE image
+fn pe_header(source: &dyn DataSource, b: &[u8]) -> Option<(u16, u16)> {
+    if !b.starts_with(b"MZ") {
+        return None;
+    }
+    let x = b.get(E_LFANEW..E_LFANEW + 4)?;
+    let offset = u32::from_le_bytes([x[0], x[1], x[2], x[3]]);
+    let pe = source.read_at(offset as u64, PE_HEADER_LEN).ok()?;
+    if pe.len() < PE_HEADER_LEN || !pe.starts_with(b"PE\0\0") {
+        return None;
+    }
+    let machine = u16::from_le_bytes([pe[4], pe[5]]);
+    let characteristics = u16::from_le_bytes([pe[22], pe[23]]);
+    Some((machine, characteristics))
+}
+
+fn pe_arch(machine: u16) -> Option<&'static str> {
+    Some(match machine {
+        0x014c => "i386",
+        0x0200 => "ia64",
+        0x01c0 | 0x01c4 => "arm",
+        0x5032 => "riscv32",
+        0x5064 => "riscv64",
+        0x8664 => "x86_64",
+        0xaa64 => "arm64",
+        _ => return None,
+    })
+}
+
+/// Files that aren't ELF are left to the magic rules of the type, which
+/// also cover older formats such as a.out
+pub fn from_source(db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+    let b = match source.read_at(0, HEADER_LEN) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+
+    match mimetype {
+        "application/vnd.microsoft.portable-executable" => pe_header(source, &b).is_some(),
+        "application/x-msdownload" => {
+            pe_header(source, &b).is_some_and(|(_, c)| c & IMAGE_FILE_DLL != 0)
+        }
+        _ => match Elf::parse(&b) {
+            Some(elf) => match mimetype {
+                "application/x-executable" => true,
+                "application/x-pie-executable" => elf.e_type == ET_DYN && elf.has_interp(source),
+                "application/x-sharedlib" => elf.e_type == ET_DYN && !elf.has_interp(source),
+                "application/x-core" => elf.e_type == ET_CORE,
+                "application/x-object" => elf.e_type == ET_REL,
+                _ => false,
+            },
+            None => fdo_magic::builtin::check::from_source(db, source, mimetype),
+        },
+    }
+}
+
+/// Reports the architecture a binary was built for, such as `x86_64` or
+/// `arm64`, as given by its ELF or COFF header
+pub fn variant(source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+    if !super::TYPES.contains(&mimetype) {
+        return None;
+    }
+    let b = source.read_at(0, HEADER_LEN).ok()?;
+    if let Some(elf) = Elf::parse(&b) {
+        return elf.arch();
+    }
+    pe_header(source, &b).and_then(|(machine, _)| pe_arch(machine))
+}
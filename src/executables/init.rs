@@ -0,0 +1,42 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    vec![
+        ("application/x-executable", "application/x-pie-executable"),
+        ("application/x-executable", "application/x-sharedlib"),
+        ("application/x-executable", "application/x-core"),
+        ("application/x-executable", "application/x-object"),
+        // MZ is magic of both, and either can come first
+        (
+            "application/x-executable",
+            "application/vnd.microsoft.portable-executable",
+        ),
+        (
+            "application/x-ms-dos-executable",
+            "application/vnd.microsoft.portable-executable",
+        ),
+        (
+            "application/vnd.microsoft.portable-executable",
+            "application/x-msdownload",
+        ),
+    ]
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/x-dosexec", "application/x-ms-dos-executable"),
+        (
+            "application/x-msdos-executable",
+            "application/x-ms-dos-executable",
+        ),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
@@ -0,0 +1,14 @@
+//! Tells apart the kinds of ELF and PE binaries, such as shared objects, core
+//! dumps and DLLs
+const TYPES: [&str; 7] = [
+    "application/x-executable",
+    "application/x-pie-executable",
+    "application/x-sharedlib",
+    "application/x-core",
+    "application/x-object",
+    "application/vnd.microsoft.portable-executable",
+    "application/x-msdownload",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,98 @@
+//! Export the type graph for visualization
+use crate::{database, TypeDatabase};
+use alloc::{string::String, vec::Vec};
+use core::fmt::Write;
+use petgraph::prelude::*;
+
+/// Output format for [`export_graph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// Graphviz DOT, with the rule count of each type in its label.
+    Dot,
+    /// A JSON object of the form
+    /// `{"nodes": [{"mime": "...", "rules": 0}], "edges": [["parent", "child"]]}`.
+    Json,
+}
+
+/// Escapes `"` and `\`, which is enough for both DOT and JSON strings.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Exports the loaded subclass graph, along with the number of magic rules
+/// for every type.
+///
+/// Edges point from parent to child, which is the direction detection walks
+/// the graph in. Nodes and edges are sorted, so the output is stable between
+/// runs.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::{export_graph, GraphFormat};
+///
+/// let dot = export_graph(GraphFormat::Dot);
+/// assert!(dot.contains("\"all/allfiles\" -> \"application/octet-stream\";"));
+/// ```
+pub fn export_graph(format: GraphFormat) -> String {
+    export(database(), format)
+}
+
+pub(crate) fn export(db: &TypeDatabase, format: GraphFormat) -> String {
+    let graph = db.graph();
+
+    let mut nodes: Vec<(&str, usize)> = graph
+        .node_indices()
+        .map(|n| (graph[n], db.rule_count(graph[n])))
+        .collect();
+    nodes.sort_unstable();
+
+    let mut edges: Vec<(&str, &str)> = graph
+        .edge_references()
+        .map(|e| (graph[e.source()], graph[e.target()]))
+        .collect();
+    edges.sort_unstable();
+
+    let mut out = String::new();
+    match format {
+        GraphFormat::Dot => {
+            out.push_str("digraph mime {\n");
+            for (mime, rules) in nodes {
+                let mime = escape(mime);
+                let _ = writeln!(
+                    out,
+                    "    \"{}\" [label=\"{}\\n{} rules\"];",
+                    mime, mime, rules
+                );
+            }
+            for (parent, child) in edges {
+                let _ = writeln!(out, "    \"{}\" -> \"{}\";", escape(parent), escape(child));
+            }
+            out.push_str("}\n");
+        }
+        GraphFormat::Json => {
+            out.push_str("{\"nodes\":[");
+            for (i, (mime, rules)) in nodes.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let _ = write!(out, "{{\"mime\":\"{}\",\"rules\":{}}}", escape(mime), rules);
+            }
+            out.push_str("],\"edges\":[");
+            for (i, (parent, child)) in edges.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let _ = write!(out, "[\"{}\",\"{}\"]", escape(parent), escape(child));
+            }
+            out.push_str("]}");
+        }
+    }
+    out
+}
@@ -0,0 +1,54 @@
+//! Broad groups of types, for yes/no checks that don't need the exact type
+use crate::MIME;
+
+/// Archive and compression formats. Types subclassing these, such as
+/// ZIP-based documents, are archives too.
+const ARCHIVES: &[MIME] = &[
+    "application/gzip",
+    "application/vnd.ms-cab-compressed",
+    "application/vnd.rar",
+    "application/x-7z-compressed",
+    "application/x-ace",
+    "application/x-archive",
+    "application/x-arj",
+    "application/x-bzip",
+    "application/x-bzip2",
+    "application/x-compress",
+    "application/x-cpio",
+    "application/x-lha",
+    "application/x-lrzip",
+    "application/x-lz4",
+    "application/x-lzip",
+    "application/x-lzma",
+    "application/x-rar",
+    "application/x-stuffit",
+    "application/x-tar",
+    "application/x-xar",
+    "application/x-xz",
+    "application/x-zoo",
+    "application/zip",
+    "application/zstd",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Family {
+    Image,
+    Video,
+    Audio,
+    Archive,
+}
+
+impl Family {
+    pub(crate) const ALL: [Family; 4] =
+        [Family::Image, Family::Video, Family::Audio, Family::Archive];
+
+    /// Whether the type itself belongs to this family
+    pub(crate) fn contains(self, mimetype: &str) -> bool {
+        match self {
+            Family::Image => mimetype.starts_with("image/"),
+            Family::Video => mimetype.starts_with("video/"),
+            Family::Audio => mimetype.starts_with("audio/"),
+            Family::Archive => ARCHIVES.contains(&mimetype),
+        }
+    }
+}
@@ -1,21 +1,20 @@
-use crate::{fdo_magic, read_bytes, MIME};
-use fnv::FnvHashMap;
+use crate::fdo_magic::{self, MagicRuleset};
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+use core::ops::Range;
 use petgraph::prelude::*;
-use std::path::Path;
 
 pub(crate) struct FdoMagic;
 
 impl crate::Checker for FdoMagic {
-    fn from_u8(&self, file: &[u8], mimetype: &str) -> bool {
-        from_u8(file, mimetype)
-    }
-
-    fn from_filepath(&self, filepath: &Path, mimetype: &str) -> bool {
-        from_filepath(filepath, mimetype)
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(db, source, mimetype)
     }
 
+    /// Magic types depend on the rules loaded into each database, which
+    /// registers them itself.
     fn get_supported(&self) -> Vec<MIME> {
-        super::init::get_supported()
+        Vec::new()
     }
 
     fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
@@ -25,20 +24,21 @@ impl crate::Checker for FdoMagic {
     fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
         super::init::get_aliaslist()
     }
-}
 
-/// Test against all rules
-#[allow(unused_variables)]
-pub fn from_u8(file: &[u8], mimetype: &str) -> bool {
-    // Get magic ruleset
-    let graph = match super::ALLRULES.get(mimetype) {
-        Some(item) => item,
-        None => return false, // No rule for this mime
-    };
+    fn needed_len(&self, db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        db.magic(mimetype).map(|r| scan_len(&r))
+    }
+
+    fn only_magic(&self) -> bool {
+        true
+    }
+}
 
+/// Test against all rules, ignoring exclusions
+pub fn from_u8(file: &[u8], rules: &MagicRuleset) -> bool {
     // Check all rulesets
-    for x in graph.externals(Incoming) {
-        if fdo_magic::check::from_u8_walker(file, mimetype, graph, x, true) {
+    for x in rules.externals(Incoming) {
+        if !rules[x].negate && fdo_magic::check::from_u8_walker(file, rules, x, true) {
             return true;
         }
     }
@@ -46,19 +46,26 @@ pub fn from_u8(file: &[u8], mimetype: &str) -> bool {
     false
 }
 
-/// This only exists for the case of a direct match_filepath call
-/// and even then we could probably get rid of this...
-#[allow(unused_variables)]
-pub fn from_filepath(filepath: &Path, mimetype: &str) -> bool {
-    // Get magic ruleset
-    let magic_rules = match super::ALLRULES.get(mimetype) {
-        Some(item) => item,
-        None => return false, // No rule for this mime
-    };
+/// Byte ranges that satisfied the first matching rule tree, if any matches
+pub fn evidence(file: &[u8], rules: &MagicRuleset) -> Option<Vec<Range<usize>>> {
+    let mut out = Vec::new();
+    for x in rules.externals(Incoming) {
+        if rules[x].negate {
+            continue;
+        }
+        out.clear();
+        if fdo_magic::check::evidence_walker(file, rules, x, true, &mut out) {
+            return Some(out);
+        }
+    }
 
-    // Get # of bytes to read
+    None
+}
+
+/// Get # of bytes the rules can look at
+pub(crate) fn scan_len(rules: &MagicRuleset) -> usize {
     let mut scanlen = 0;
-    for x in magic_rules.raw_nodes() {
+    for x in rules.raw_nodes() {
         let y = &x.weight;
         let tmplen = y.start_off as usize + y.val.len() + y.region_len as usize;
 
@@ -66,11 +73,59 @@ pub fn from_filepath(filepath: &Path, mimetype: &str) -> bool {
             scanlen = tmplen;
         }
     }
+    scanlen
+}
+
+/// Reads as many bytes as the rules for this type can look at, then
+/// tests against all of them.
+pub fn from_source(db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+    // Get magic ruleset
+    let magic_rules = match db.magic(mimetype) {
+        Some(item) => item,
+        None => return false, // No rule for this mime
+    };
+
+    from_source_rules(source, &magic_rules, scan_len(&magic_rules))
+}
+
+/// [`from_source`] with the rules and their [`scan_len`] already looked up
+pub(crate) fn from_source_rules(source: &dyn DataSource, rules: &MagicRuleset, len: usize) -> bool {
+    let b = match source.read_at(0, len) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+
+    from_u8(&b, rules)
+}
+
+/// Whether any negated top-level rule for this type matches, ruling it out
+/// regardless of its checker
+pub fn is_excluded(db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+    let magic_rules = match db.magic(mimetype) {
+        Some(item) => item,
+        None => return false,
+    };
+    if !has_exclusions(&magic_rules) {
+        return false;
+    }
+
+    is_excluded_rules(source, &magic_rules, scan_len(&magic_rules))
+}
+
+/// Whether the rules have any negated top-level rule
+pub(crate) fn has_exclusions(rules: &MagicRuleset) -> bool {
+    rules.externals(Incoming).any(|x| rules[x].negate)
+}
 
-    let b = match read_bytes(filepath, scanlen) {
+/// [`is_excluded`] with the rules and their [`scan_len`] already looked up
+pub(crate) fn is_excluded_rules(source: &dyn DataSource, rules: &MagicRuleset, len: usize) -> bool {
+    let b = match source.read_at(0, len) {
         Ok(x) => x,
         Err(_) => return false,
     };
 
-    from_u8(b.as_slice(), mimetype)
+    rules
+        .externals(Incoming)
+        .filter(|&x| rules[x].negate)
+        .any(|x| fdo_magic::check::from_u8_walker(&b, rules, x, true))
 }
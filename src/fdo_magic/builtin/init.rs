@@ -1,53 +1,52 @@
-use crate::MIME;
-use fnv::FnvHashMap;
+use crate::{FnvHashMap, MIME};
+use alloc::vec::Vec;
 
-#[cfg(not(feature = "with-gpl-data"))]
+#[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
 use super::runtime;
 
-fn aliases() -> &'static str {
-    #[cfg(feature = "with-gpl-data")]
-    return tree_magic_db::aliases();
-    #[cfg(not(feature = "with-gpl-data"))]
-    return runtime::aliases();
+/// Pairs of types, one pair per line
+#[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
+fn pairs(text: &'static str) -> impl Iterator<Item = (MIME, MIME)> {
+    text.lines().map(|line| {
+        let mut parts = line.split_whitespace();
+        let a = parts.next().unwrap();
+        let b = parts.next().unwrap();
+        (a, b)
+    })
 }
 
-fn subclasses() -> &'static str {
-    #[cfg(feature = "with-gpl-data")]
-    return tree_magic_db::subclasses();
-    #[cfg(not(feature = "with-gpl-data"))]
-    return runtime::subclasses();
+/// Aliases, each followed by the type it stands for
+#[cfg(any(feature = "with-gpl-data", feature = "builtin-minimal"))]
+fn aliases() -> impl Iterator<Item = (MIME, MIME)> {
+    super::tables::ALIASES.iter().copied()
 }
 
-pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
-    aliases()
-        .lines()
-        .map(|line| {
-            let mut parts = line.split_whitespace();
-            let a = parts.next().unwrap();
-            let b = parts.next().unwrap();
-            (a, b)
-        })
-        .collect()
+#[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
+fn aliases() -> impl Iterator<Item = (MIME, MIME)> {
+    pairs(runtime::aliases())
 }
 
-/// Get list of supported MIME types
-pub fn get_supported() -> Vec<MIME> {
-    super::ALLRULES.keys().cloned().collect()
+/// Types, each followed by one of its parents
+#[cfg(any(feature = "with-gpl-data", feature = "builtin-minimal"))]
+fn subclasses() -> impl Iterator<Item = (MIME, MIME)> {
+    super::tables::SUBCLASSES.iter().copied()
+}
+
+#[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
+fn subclasses() -> impl Iterator<Item = (MIME, MIME)> {
+    pairs(runtime::subclasses())
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    aliases().collect()
 }
 
 /// Get list of parent -> child subclass links
 pub fn get_subclasses() -> Vec<(MIME, MIME)> {
     subclasses()
-        .lines()
-        .map(|line| {
-            let mut parts = line.split_whitespace();
-
-            let child = parts.next().unwrap();
+        .map(|(child, parent)| {
             let child = super::ALIASES.get(child).copied().unwrap_or(child);
-
-            let parent = parts.next().unwrap();
             let parent = super::ALIASES.get(parent).copied().unwrap_or(parent);
-
             (parent, child)
         })
         .collect()
@@ -1,40 +1,49 @@
 //! Read magic file bundled in crate
 
-use super::MagicRule;
-use crate::MIME;
-use fnv::FnvHashMap;
+use super::lazy::Section;
+use crate::{FnvHashMap, MIME};
 use lazy_static::lazy_static;
-use petgraph::prelude::*;
 
 /// Preload alias list
 lazy_static! {
     static ref ALIASES: FnvHashMap<MIME, MIME> = init::get_aliaslist();
 }
 
-/// Load magic file before anything else.
-lazy_static! {
-    static ref ALLRULES: FnvHashMap<MIME, DiGraph<MagicRule<'static>, u32>> = rules();
-}
-
 pub mod check;
 pub mod init;
 
-#[cfg(not(feature = "with-gpl-data"))]
+#[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
 mod runtime;
 
-fn rules() -> FnvHashMap<MIME, DiGraph<MagicRule<'static>, u32>> {
-    #[cfg(feature = "with-gpl-data")]
+/// The embedded database as static tables, made by `build.rs` so nothing is
+/// parsed at run time. It's `tree_magic_db`, or with only `builtin-minimal`,
+/// the files in `minimal/`: rules written for this crate under its own
+/// license, in the format of the shared MIME-info database.
+#[cfg(any(feature = "with-gpl-data", feature = "builtin-minimal"))]
+mod tables {
+    use crate::fdo_magic::MagicRule;
+
+    include!(concat!(env!("OUT_DIR"), "/magic_tables.rs"));
+}
+
+/// Load the system (or bundled) magic rules with their priorities, left for
+/// each database to parse as it needs them.
+pub fn rules() -> FnvHashMap<MIME, (u32, Section)> {
+    #[cfg(any(feature = "with-gpl-data", feature = "builtin-minimal"))]
     return static_rules();
-    #[cfg(not(feature = "with-gpl-data"))]
+    #[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
     return runtime_rules();
 }
 
-#[cfg(feature = "with-gpl-data")]
-fn static_rules() -> FnvHashMap<MIME, DiGraph<MagicRule<'static>, u32>> {
-    super::ruleset::from_u8(tree_magic_db::magic()).unwrap_or_default()
+#[cfg(any(feature = "with-gpl-data", feature = "builtin-minimal"))]
+fn static_rules() -> FnvHashMap<MIME, (u32, Section)> {
+    tables::MAGIC
+        .iter()
+        .map(|&(mime, priority, rules)| (mime, (priority, Section::Table(rules))))
+        .collect()
 }
 
-#[cfg(not(feature = "with-gpl-data"))]
-fn runtime_rules() -> FnvHashMap<MIME, DiGraph<MagicRule<'static>, u32>> {
+#[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
+fn runtime_rules() -> FnvHashMap<MIME, (u32, Section)> {
     runtime::rules().unwrap_or_default()
 }
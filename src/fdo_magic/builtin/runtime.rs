@@ -1,31 +1,32 @@
-///! Enable loading the magic database files at runtime rather than embedding the GPLed database
+//! Enable loading the magic database files at runtime rather than embedding the GPLed database
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use std::fs::File;
 use std::io::Read;
+use std::path::PathBuf;
 
-use fnv::FnvHashMap;
+use crate::FnvHashMap;
 use once_cell::sync::OnceCell;
-use petgraph::prelude::DiGraph;
 
-use super::MagicRule;
+use super::super::lazy::Section;
 use crate::fdo_magic::ruleset;
+use crate::xdg;
 use crate::MIME;
 
 static RUNTIME_RULES: OnceCell<Vec<Vec<u8>>> = OnceCell::new();
 static ALIAS_STRING: OnceCell<String> = OnceCell::new();
 static SUBCLASS_STRING: OnceCell<String> = OnceCell::new();
 
-/// Load the magic database from the predefined locations in the XDG standard
+/// Load the magic database from the predefined locations in the XDG standard.
+/// The user's file comes last, so its types replace the system ones.
 fn load_xdg_shared_magic() -> Result<Vec<Vec<u8>>, String> {
-    const SEARCH_PATHS: &[&str; 3] = &[
-        "/usr/share/mime/magic",
-        "/usr/local/share/mime/magic",
-        "$HOME/.local/share/mime/magic",
-    ];
-
-    let files: Vec<Vec<u8>> = SEARCH_PATHS
+    let files: Vec<Vec<u8>> = xdg::search_paths("magic")
         .iter()
-        .map(|p| File::open(p).ok())
-        .filter_map(|f| f)
+        .filter_map(|p| File::open(p).ok())
         .map(|mut f| {
             let mut buf = vec![];
             f.read_to_end(&mut buf)
@@ -42,11 +43,10 @@ fn load_xdg_shared_magic() -> Result<Vec<Vec<u8>>, String> {
 }
 
 /// Load a number of files at `paths` and concatenate them together with a newline
-fn load_concat_strings(paths: &[&str]) -> String {
+fn load_concat_strings(paths: &[PathBuf]) -> String {
     let strings: Vec<String> = paths
         .iter()
-        .map(|p| File::open(p).ok())
-        .filter_map(|f| f)
+        .filter_map(|p| File::open(p).ok())
         .map(|mut f| {
             let mut s = String::new();
             f.read_to_string(&mut s)
@@ -60,24 +60,12 @@ fn load_concat_strings(paths: &[&str]) -> String {
 
 /// Load the magic aliases from the XDG standard locations and concatenate them together
 fn load_aliases() -> String {
-    const SEARCH_PATHS: &[&str; 3] = &[
-        "/usr/share/mime/aliases",
-        "/usr/local/share/mime/aliases",
-        "$HOME/.local/share/mime/aliases",
-    ];
-
-    load_concat_strings(SEARCH_PATHS)
+    load_concat_strings(&xdg::search_paths("aliases"))
 }
 
 /// Load the subclass definitions from the XDG standard locations and concatenate them together
 fn load_subclasses() -> String {
-    const SEARCH_PATHS: &[&str; 3] = &[
-        "/usr/share/mime/subclasses",
-        "/usr/local/share/mime/subclasses",
-        "$HOME/.local/share/mime/subclasses",
-    ];
-
-    load_concat_strings(SEARCH_PATHS)
+    load_concat_strings(&xdg::search_paths("subclasses"))
 }
 
 pub(crate) fn aliases() -> &'static str {
@@ -88,7 +76,11 @@ pub(crate) fn subclasses() -> &'static str {
     SUBCLASS_STRING.get_or_init(load_subclasses)
 }
 
-pub(crate) fn rules() -> Result<FnvHashMap<MIME, DiGraph<MagicRule<'static>, u32>>, String> {
+pub(crate) fn rules() -> Result<FnvHashMap<MIME, (u32, Section)>, String> {
     let files = RUNTIME_RULES.get_or_try_init(load_xdg_shared_magic)?;
-    ruleset::from_multiple(files)
+    let sections = ruleset::from_multiple(files)?;
+    Ok(sections
+        .into_iter()
+        .map(|(mime, (priority, b))| (mime, (priority, Section::Raw(b))))
+        .collect())
 }
@@ -1,99 +1,75 @@
+use alloc::vec::Vec;
+use core::ops::Range;
 use petgraph::prelude::*;
 
-fn from_u8_singlerule(file: &[u8], rule: &super::MagicRule) -> bool {
-    // Check if we're even in bounds
-    let bound_min = rule.start_off as usize;
-    let bound_max = rule.start_off as usize + rule.val.len() + rule.region_len as usize;
-
-    if (file.len()) < bound_max {
-        return false;
-    }
-
-    if rule.region_len == 0 {
-        //println!("Region == 0");
-
-        match rule.mask {
-            None => {
-                //println!("\tMask == None");
-                let x: Vec<u8> = file
-                    .iter()
-                    .skip(bound_min)
-                    .take(bound_max - bound_min)
-                    .copied()
-                    .collect();
-                //println!("\t{:?} / {:?}", x, rule.val);
-                //println!("\tIndent: {}, Start: {}", rule.indent_level, rule.start_off);
-                return rule.val.iter().eq(x.iter());
-            }
-            Some(ref mask) => {
-                //println!("\tMask == Some, len == {}", mask.len());
-                //println!("\tIndent: {}, Start: {}", rule.indent_level, rule.start_off);
-                let mut x: Vec<u8> = file
-                    .iter()
-                    .skip(bound_min) // Skip to start of area
-                    .take(bound_max - bound_min) // Take until end of area - region length
-                    .copied()
-                    .collect(); // Convert to vector
-                let mut val: Vec<u8> = rule.val.iter().copied().collect();
-                //println!("\t{:?} / {:?}", x, rule.val);
-
-                assert_eq!(x.len(), mask.len());
-                for i in 0..std::cmp::min(x.len(), mask.len()) {
-                    x[i] &= mask[i];
-                    val[i] &= mask[i];
-                }
-                //println!("\t & {:?} => {:?}", mask, x);
-
-                return rule.val.iter().eq(x.iter());
-            }
-        }
+/// Byte `i` of a value or mask. They're written big-endian, so on
+/// little-endian hosts each group of `word_len` bytes is reversed, as the
+/// shared MIME-info spec asks.
+fn pattern_byte(pattern: &[u8], i: usize, word_len: usize) -> u8 {
+    if cfg!(target_endian = "little") && word_len > 1 && pattern.len() % word_len == 0 {
+        pattern[i - i % word_len + word_len - 1 - i % word_len]
     } else {
-        //println!("\tRegion == {}", rule.region_len);
-        //println!("\tIndent: {}, Start: {}", rule.indent_level, rule.start_off);
-
-        // Define our testing slice
-        let x: &Vec<u8> = &file.iter().take(file.len()).copied().collect();
-        let testarea: Vec<u8> = x
-            .iter()
-            .skip(bound_min)
-            .take(bound_max - bound_min)
-            .copied()
-            .collect();
-        //println!("{:?}, {:?}, {:?}\n", file, testarea, rule.val);
+        pattern[i]
+    }
+}
 
-        // Search down until we find a hit
-        let mut y = Vec::<u8>::with_capacity(testarea.len());
-        for x in testarea.windows(rule.val.len()) {
-            y.clear();
+/// Whether `data`, as long as the value, matches the value of the rule
+/// under its mask
+fn matches_at(data: &[u8], rule: &super::MagicRule) -> bool {
+    let word_len = rule.word_len as usize;
+    data.iter().enumerate().all(|(i, &b)| {
+        let mask = rule.mask.map_or(0xff, |m| pattern_byte(m, i, word_len));
+        b & mask == pattern_byte(rule.val, i, word_len) & mask
+    })
+}
 
-            // Apply mask to value
-            let rule_mask = &rule.mask;
-            match *rule_mask {
-                Some(ref mask) => {
-                    for i in 0..rule.val.len() {
-                        y.push(x[i] & mask[i]);
-                    }
-                }
-                None => y = x.to_vec(),
-            }
+/// Where the pattern of a rule matched, if it did
+fn singlerule_range(file: &[u8], rule: &super::MagicRule) -> Option<Range<usize>> {
+    let len = rule.val.len();
+    // The value can start at any of the first `region_len` bytes from the
+    // offset, or at the offset alone without a range
+    let first = rule.start_off as usize;
+    let last = first.saturating_add((rule.region_len as usize).max(1));
+
+    (first..last)
+        .take_while(|&at| at.saturating_add(len) <= file.len())
+        .find(|&at| matches_at(&file[at..at + len], rule))
+        .map(|at| at..at + len)
+}
 
-            if y.iter().eq(rule.val.iter()) {
-                return true;
-            }
-        }
-    }
+/// Test every given rule by walking graph
+///
+/// Negation of the root rule is up to the caller, as it changes the
+/// meaning of the whole tree.
+pub fn from_u8_walker(
+    file: &[u8],
+    graph: &DiGraph<super::MagicRule, u32>,
+    node: NodeIndex,
+    isroot: bool,
+) -> bool {
+    walk(file, graph, node, isroot, &mut |_| {})
+}
 
-    false
+/// Like `from_u8_walker`, also collecting where each matching rule matched.
+///
+/// Negated rules hold because their pattern is absent, so they add nothing.
+pub fn evidence_walker(
+    file: &[u8],
+    graph: &DiGraph<super::MagicRule, u32>,
+    node: NodeIndex,
+    isroot: bool,
+    evidence: &mut Vec<Range<usize>>,
+) -> bool {
+    walk(file, graph, node, isroot, &mut |r| evidence.push(r))
 }
 
-/// Test every given rule by walking graph
 /// TODO: Not loving the code duplication here.
-pub fn from_u8_walker(
+fn walk(
     file: &[u8],
-    mimetype: &str,
     graph: &DiGraph<super::MagicRule, u32>,
     node: NodeIndex,
     isroot: bool,
+    found: &mut dyn FnMut(Range<usize>),
 ) -> bool {
     let n = graph.neighbors_directed(node, Outgoing);
 
@@ -101,8 +77,9 @@ pub fn from_u8_walker(
         let rule = &graph[node];
 
         // Check root
-        if !from_u8_singlerule(&file, rule) {
-            return false;
+        match singlerule_range(file, rule) {
+            Some(range) => found(range),
+            None => return false,
         }
 
         // Return if that was the only test
@@ -117,10 +94,15 @@ pub fn from_u8_walker(
     for y in n {
         let rule = &graph[y];
 
-        if from_u8_singlerule(&file, rule) {
+        let range = singlerule_range(file, rule);
+        if range.is_some() != rule.negate {
+            if let Some(range) = range {
+                found(range);
+            }
+
             // Check next indent level if needed
             if graph.neighbors_directed(y, Outgoing).count() != 0 {
-                return from_u8_walker(file, mimetype, graph, y, false);
+                return walk(file, graph, y, false, found);
             // Next indent level is lower, so this must be it
             } else {
                 return true;
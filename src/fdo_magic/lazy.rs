@@ -0,0 +1,166 @@
+//! Magic rules of a type, turned into a graph the first time they're needed
+use super::MagicRuleset;
+use crate::footprint::graph_size;
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::mem::size_of;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use once_cell::race::OnceBox;
+use petgraph::prelude::*;
+
+/// Rules of a type as they were found, in the order they're merged
+pub(crate) enum Section {
+    /// Rules of a magic file loaded at run time, not parsed yet
+    #[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
+    Raw(&'static [u8]),
+    /// Rules of the embedded tables, not nested yet
+    #[cfg(any(feature = "with-gpl-data", feature = "builtin-minimal"))]
+    Table(&'static [super::MagicRule<'static>]),
+    /// Rules parsed already, like those given to the builder
+    Parsed(MagicRuleset),
+}
+
+impl Section {
+    fn to_graph(&self) -> MagicRuleset {
+        match self {
+            #[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
+            Section::Raw(b) => super::ruleset::from_section(b),
+            #[cfg(any(feature = "with-gpl-data", feature = "builtin-minimal"))]
+            Section::Table(rules) => super::ruleset::gen_graph(rules.to_vec()),
+            Section::Parsed(rules) => rules.clone(),
+        }
+    }
+}
+
+/// The magic rules of a type, kept as sections until the type is first
+/// checked. Most processes only ever look at a few types, so building the
+/// rule trees of all of them up front is mostly wasted.
+#[derive(Default)]
+pub(crate) struct LazyRuleset {
+    sections: Vec<Section>,
+    /// Highest priority of the sections
+    priority: u32,
+    rules: OnceBox<MagicRuleset>,
+}
+
+/// Rule trees of a type, kept by the database or built for a single use
+/// once it's over its memory limit
+pub(crate) enum Rules<'a> {
+    Kept(&'a MagicRuleset),
+    Built(MagicRuleset),
+}
+
+impl Deref for Rules<'_> {
+    type Target = MagicRuleset;
+
+    fn deref(&self) -> &MagicRuleset {
+        match self {
+            Rules::Kept(rules) => rules,
+            Rules::Built(rules) => rules,
+        }
+    }
+}
+
+impl LazyRuleset {
+    /// The rule trees, built from the sections on the first call. They're
+    /// kept if their size fits in what's left of `budget`, if there is one.
+    pub(crate) fn get(&self, budget: Option<&AtomicUsize>) -> Rules<'_> {
+        if let Some(rules) = self.rules.get() {
+            return Rules::Kept(rules);
+        }
+        let rules = self.build();
+        let budget = match budget {
+            Some(x) => x,
+            None => return Rules::Kept(self.rules.get_or_init(|| Box::new(rules))),
+        };
+        let size = size_of::<MagicRuleset>() + graph_size(&rules);
+        if budget
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |left| {
+                left.checked_sub(size)
+            })
+            .is_err()
+        {
+            return Rules::Built(rules);
+        }
+        // Another thread kept the same trees first
+        if self.rules.set(Box::new(rules)).is_err() {
+            budget.fetch_add(size, Ordering::AcqRel);
+        }
+        Rules::Kept(self.rules.get().unwrap())
+    }
+
+    /// Bytes held by the sections and any kept rule trees
+    pub(crate) fn size(&self) -> usize {
+        let sections = self.sections.capacity() * size_of::<Section>()
+            + self
+                .sections
+                .iter()
+                .map(|s| match s {
+                    Section::Parsed(rules) => graph_size(rules),
+                    _ => 0,
+                })
+                .sum::<usize>();
+        let kept = self
+            .rules
+            .get()
+            .map_or(0, |r| size_of::<MagicRuleset>() + graph_size(r));
+        sections + kept
+    }
+
+    /// The rules of a single section
+    pub(crate) fn new(section: Section, priority: u32) -> Self {
+        LazyRuleset {
+            sections: vec![section],
+            priority,
+            rules: OnceBox::new(),
+        }
+    }
+
+    /// Appends the rule trees of a section to those already there
+    pub(crate) fn push(&mut self, section: Section, priority: u32) {
+        self.rules = OnceBox::new();
+        self.sections.push(section);
+        self.priority = self.priority.max(priority);
+    }
+
+    /// Priority of the rules, as given in their magic file. Of several
+    /// sections, the highest counts.
+    pub(crate) fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// The rule trees, built now so they can be changed
+    pub(crate) fn get_mut(&mut self) -> &mut MagicRuleset {
+        let rules = self.build();
+        *self = LazyRuleset::new(Section::Parsed(rules), self.priority);
+        match &mut self.sections[0] {
+            Section::Parsed(rules) => rules,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Merges the sections into a new graph
+    fn build(&self) -> MagicRuleset {
+        let mut rules = MagicRuleset::new();
+        for section in &self.sections {
+            merge_rules(&mut rules, section.to_graph());
+        }
+        rules
+    }
+}
+
+/// Appends the rule trees of `from` to `into`
+fn merge_rules(into: &mut MagicRuleset, from: MagicRuleset) {
+    let offset = into.node_count();
+    let (nodes, edges) = from.into_nodes_edges();
+    for n in nodes {
+        into.add_node(n.weight);
+    }
+    for e in edges {
+        into.add_edge(
+            NodeIndex::new(e.source().index() + offset),
+            NodeIndex::new(e.target().index() + offset),
+            e.weight,
+        );
+    }
+}
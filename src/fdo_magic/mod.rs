@@ -1,7 +1,13 @@
 // Common routines for all fdo_magic parsers
 
+use core::fmt;
+
 pub mod builtin;
 
+/// Priority of the rules of a type when its magic file doesn't give a
+/// number, and of types without magic
+pub(crate) const DEFAULT_PRIORITY: u32 = 50;
+
 #[derive(Debug, Clone)]
 pub struct MagicRule<'a> {
     pub indent_level: u32,
@@ -10,7 +16,52 @@ pub struct MagicRule<'a> {
     pub mask: Option<&'a [u8]>,
     pub word_len: u32,
     pub region_len: u32,
+    /// Extension to the shared-mime-info format, written as a leading `!`.
+    /// A negated rule holds when its pattern does *not* match; at the top
+    /// level it instead excludes the type when it matches.
+    pub negate: bool,
+}
+
+/// Writes bytes as ASCII, escaping anything else like `\x00`
+fn write_escaped(f: &mut fmt::Formatter<'_>, b: &[u8]) -> fmt::Result {
+    for &c in b {
+        if (c.is_ascii_graphic() && c != b'\\') || c == b' ' {
+            write!(f, "{}", c as char)?;
+        } else {
+            write!(f, "\\x{:02x}", c)?;
+        }
+    }
+    Ok(())
 }
 
+/// Formats a rule like a line of a magic file, with a readable pattern
+impl fmt::Display for MagicRule<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negate {
+            f.write_str("!")?;
+        }
+        if self.indent_level > 0 {
+            write!(f, "{}", self.indent_level)?;
+        }
+        write!(f, ">{}=", self.start_off)?;
+        write_escaped(f, self.val)?;
+        if let Some(mask) = &self.mask {
+            f.write_str("&")?;
+            write_escaped(f, mask)?;
+        }
+        if self.word_len != 1 {
+            write!(f, "~{}", self.word_len)?;
+        }
+        if self.region_len != 0 {
+            write!(f, "+{}", self.region_len)?;
+        }
+        Ok(())
+    }
+}
+
+/// All magic rules for a single type, as a forest of rule trees
+pub type MagicRuleset = petgraph::graph::DiGraph<MagicRule<'static>, u32>;
+
 pub mod check;
+pub mod lazy;
 pub mod ruleset;
@@ -1,6 +1,11 @@
 use super::MagicRule;
 
-use fnv::FnvHashMap;
+use crate::FnvHashMap;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::str;
 use nom::{
     bytes::complete::{is_not, tag, take, take_while},
     character::is_digit,
@@ -11,7 +16,6 @@ use nom::{
     IResult,
 };
 use petgraph::prelude::*;
-use std::str;
 
 // Singular magic ruleset
 fn magic_rules(input: &[u8]) -> IResult<&[u8], MagicRule<'_>> {
@@ -21,7 +25,8 @@ fn magic_rules(input: &[u8]) -> IResult<&[u8], MagicRule<'_>> {
         })
     };
 
-    let (input, (indent_level, start_off, val_len)) = tuple((
+    let (input, (negate, indent_level, start_off, val_len)) = tuple((
+        map(opt(tag("!")), |x| x.is_some()),
         terminated(int_or(0), tag(">")),
         terminated(int_or(0), tag("=")),
         be_u16,
@@ -46,31 +51,54 @@ fn magic_rules(input: &[u8]) -> IResult<&[u8], MagicRule<'_>> {
             mask,
             word_len: word_len.unwrap_or(1),
             region_len: region_len.unwrap_or(0),
+            negate,
         },
     ))
 }
 
+/// Priority and type of a section, from its `[priority:type]` line
+type Header<'a> = (u32, &'a str);
+
+/// Rule trees of a type
+type Rules<'a> = DiGraph<MagicRule<'a>, u32>;
+
+/// The priority, or the default if it isn't a number
+fn priority(b: &[u8]) -> u32 {
+    str::from_utf8(b)
+        .ok()
+        .and_then(|p| p.trim().parse().ok())
+        .unwrap_or(super::DEFAULT_PRIORITY)
+}
+
+// Parse the priority and MIME type from "[priority: mime]"
+fn mime(input: &[u8]) -> IResult<&[u8], Header<'_>> {
+    terminated(
+        tuple((
+            map(delimited(tag("["), is_not(":"), tag(":")), priority),
+            map_res(terminated(is_not("]"), tag("]")), str::from_utf8),
+        )),
+        tag("\n"),
+    )(input)
+}
+
 /// Converts a magic file given as a &[u8] array
 /// to a vector of MagicEntry structs
-fn ruleset(input: &[u8]) -> IResult<&[u8], Vec<(&str, Vec<MagicRule<'_>>)>> {
-    // Parse the MIME type from "[priority: mime]"
-    let mime = map_res(
-        terminated(
-            delimited(
-                delimited(tag("["), is_not(":"), tag(":")), // priority
-                is_not("]"),                                // mime
-                tag("]"),
-            ),
-            tag("\n"),
-        ),
-        str::from_utf8,
-    );
-
+fn ruleset(input: &[u8]) -> IResult<&[u8], Vec<(Header<'_>, Vec<MagicRule<'_>>)>> {
     let magic_entry = tuple((mime, many0(magic_rules)));
     preceded(tag("MIME-Magic\0\n"), many0(magic_entry))(input)
 }
 
-fn gen_graph(magic_rules: Vec<MagicRule<'_>>) -> DiGraph<MagicRule<'_>, u32> {
+#[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
+/// Splits a magic file into the rules of each type, still unparsed. The
+/// rules are stepped over to find where they end, without building them.
+fn sections(input: &[u8]) -> IResult<&[u8], Vec<(Header<'_>, &[u8])>> {
+    use nom::{combinator::recognize, multi::many0_count};
+
+    let magic_entry = tuple((mime, recognize(many0_count(magic_rules))));
+    preceded(tag("MIME-Magic\0\n"), many0(magic_entry))(input)
+}
+
+pub(crate) fn gen_graph(magic_rules: Vec<MagicRule<'_>>) -> DiGraph<MagicRule<'_>, u32> {
     use petgraph::prelude::*;
     // Whip up a graph real quick
     let mut graph = DiGraph::<MagicRule, u32>::new();
@@ -99,28 +127,31 @@ fn gen_graph(magic_rules: Vec<MagicRule<'_>>) -> DiGraph<MagicRule<'_>, u32> {
     graph
 }
 
-#[cfg(feature = "with-gpl-data")]
-pub fn from_u8(b: &[u8]) -> Result<FnvHashMap<&str, DiGraph<MagicRule<'_>, u32>>, String> {
+/// Parse a single magic file, into the priority and rules of each type
+pub fn from_u8(b: &[u8]) -> Result<FnvHashMap<&str, (u32, Rules<'_>)>, String> {
     let tuplevec = ruleset(b).map_err(|e| e.to_string())?.1;
     let res = tuplevec
         .into_iter()
-        .map(|x| (x.0, gen_graph(x.1)))
+        .map(|((priority, mime), rules)| (mime, (priority, gen_graph(rules))))
         .collect();
     Ok(res)
 }
 
-#[cfg(not(feature = "with-gpl-data"))]
-/// Parse multiple ruleset magic files and aggregate the tuples into a single graph
-pub fn from_multiple<'a>(
-    files: &'a [Vec<u8>],
-) -> Result<FnvHashMap<&'a str, DiGraph<MagicRule<'_>, u32>>, String> {
-    let mut tuplevec = vec![];
+#[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
+/// Parses the rules of a single type, as split off by [`from_multiple`]
+pub(crate) fn from_section(b: &[u8]) -> DiGraph<MagicRule<'_>, u32> {
+    gen_graph(many0(magic_rules)(b).map_or_else(|_| Vec::new(), |(_, rules)| rules))
+}
+
+#[cfg(not(any(feature = "with-gpl-data", feature = "builtin-minimal")))]
+/// Split multiple ruleset magic files into the priority and unparsed rules
+/// of each type, to be parsed by [`from_section`] when first needed.
+/// A type in a later file replaces its rules from earlier files.
+pub fn from_multiple(files: &[Vec<u8>]) -> Result<FnvHashMap<&str, (u32, &[u8])>, String> {
+    let mut res = FnvHashMap::default();
     for slice in files {
-        tuplevec.append(&mut ruleset(slice.as_ref()).map_err(|e| e.to_string())?.1);
+        let found = sections(slice.as_ref()).map_err(|e| e.to_string())?.1;
+        res.extend(found.into_iter().map(|((p, mime), b)| (mime, (p, b))));
     }
-    let res = tuplevec
-        .into_iter()
-        .map(|x| (x.0, gen_graph(x.1)))
-        .collect();
     Ok(res)
 }
@@ -0,0 +1,155 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Filesystem;
+
+impl crate::Checker for Filesystem {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        filesystem(source) == Some(mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        // Any of the superblocks can rule out the others
+        Some(BTRFS_MAGIC_OFFSET as usize + BTRFS_MAGIC.len())
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        match mimetype {
+            "application/x-fat" => fat_variant(source),
+            _ => None,
+        }
+    }
+}
+
+/// The first kilobyte is left for boot code
+const EXT_SUPERBLOCK: u64 = 1024;
+/// Up to the end of the feature flags
+const EXT_SUPERBLOCK_LEN: usize = 0x68;
+const EXT_MAGIC: [u8; 2] = [0x53, 0xef];
+const EXT_MAGIC_OFFSET: usize = 0x38;
+const EXT_FEATURE_COMPAT: usize = 0x5c;
+const EXT_FEATURE_INCOMPAT: usize = 0x60;
+const EXT_FEATURE_RO_COMPAT: usize = 0x64;
+const EXT_COMPAT_HAS_JOURNAL: u32 = 0x4;
+/// Extents, 64-bit block numbers, flexible block groups and the like
+const EXT4_INCOMPAT: u32 = 0x40 | 0x80 | 0x200 | 0x400 | 0x8000;
+/// Huge files, group descriptor checksums, large directories, large inodes
+/// and metadata checksums
+const EXT4_RO_COMPAT: u32 = 0x8 | 0x10 | 0x20 | 0x40 | 0x400;
+
+/// Magic in the primary superblock, which is 64 KiB in
+const BTRFS_MAGIC: &[u8; 8] = b"_BHRfS_M";
+const BTRFS_MAGIC_OFFSET: u64 = 0x10040;
+
+const XFS_MAGIC: &[u8; 4] = b"XFSB";
+
+/// The boot sector of FAT and NTFS volumes
+const BOOT_SECTOR_LEN: usize = 512;
+const NTFS_OEM_ID: &[u8; 8] = b"NTFS    ";
+
+fn le16(b: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([b[at], b[at + 1]])
+}
+
+fn le32(b: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([b[at], b[at + 1], b[at + 2], b[at + 3]])
+}
+
+/// ext2, 3 and 4 share a superblock, and are told apart by the features
+/// they use
+fn ext(source: &dyn DataSource) -> Option<MIME> {
+    let b = source.read_at(EXT_SUPERBLOCK, EXT_SUPERBLOCK_LEN).ok()?;
+    if b.len() < EXT_SUPERBLOCK_LEN || b[EXT_MAGIC_OFFSET..EXT_MAGIC_OFFSET + 2] != EXT_MAGIC {
+        return None;
+    }
+    if le32(&b, EXT_FEATURE_INCOMPAT) & EXT4_INCOMPAT != 0
+        || le32(&b, EXT_FEATURE_RO_COMPAT) & EXT4_RO_COMPAT != 0
+    {
+        Some("application/x-ext4")
+    } else if le32(&b, EXT_FEATURE_COMPAT) & EXT_COMPAT_HAS_JOURNAL != 0 {
+        Some("application/x-ext3")
+    } else {
+        Some("application/x-ext2")
+    }
+}
+
+fn is_btrfs(source: &dyn DataSource) -> bool {
+    match source.read_at(BTRFS_MAGIC_OFFSET, BTRFS_MAGIC.len()) {
+        Ok(b) => b[..] == BTRFS_MAGIC[..],
+        Err(_) => false,
+    }
+}
+
+fn boot_sector(source: &dyn DataSource) -> Option<Vec<u8>> {
+    let b = source.read_at(0, BOOT_SECTOR_LEN).ok()?;
+    if b.len() < BOOT_SECTOR_LEN || b[510..] != [0x55, 0xaa] {
+        return None;
+    }
+    Some(b.into_owned())
+}
+
+/// A BIOS parameter block, which has no magic, so each field is checked to
+/// hold a value it can take. NTFS and exFAT leave the number of FATs empty.
+fn is_fat(b: &[u8]) -> bool {
+    let jump = b[0] == 0xe9 || (b[0] == 0xeb && b[2] == 0x90);
+    let sector_len = le16(b, 11);
+    let cluster_len = b[13];
+    jump && (512..=4096).contains(&sector_len)
+        && sector_len.is_power_of_two()
+        && cluster_len.is_power_of_two()
+        && le16(b, 14) != 0
+        && matches!(b[16], 1 | 2)
+}
+
+/// The filesystem the superblocks are of
+fn filesystem(source: &dyn DataSource) -> Option<MIME> {
+    if let Some(b) = boot_sector(source) {
+        if b[3..11] == NTFS_OEM_ID[..] {
+            return Some("application/x-ntfs");
+        }
+        if is_fat(&b) {
+            return Some("application/x-fat");
+        }
+    }
+    if let Some(mime) = ext(source) {
+        return Some(mime);
+    }
+    if source
+        .read_at(0, XFS_MAGIC.len())
+        .is_ok_and(|b| b[..] == XFS_MAGIC[..])
+    {
+        return Some("application/x-xfs");
+    }
+    if is_btrfs(source) {
+        return Some("application/x-btrfs");
+    }
+    None
+}
+
+pub fn from_source(source: &dyn DataSource) -> bool {
+    filesystem(source).is_some()
+}
+
+/// Reports `fat12`, `fat16` or `fat32`, as the boot sector names it. It's
+/// only a label, but formatting tools set it to match.
+fn fat_variant(source: &dyn DataSource) -> Option<&'static str> {
+    let b = boot_sector(source)?;
+    match (&b[54..62], &b[82..90]) {
+        (_, b"FAT32   ") => Some("fat32"),
+        (b"FAT12   ", _) => Some("fat12"),
+        (b"FAT16   ", _) => Some("fat16"),
+        _ => None,
+    }
+}
@@ -0,0 +1,21 @@
+use crate::{FnvHashMap, MIME};
+use alloc::vec::Vec;
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    super::TYPES
+        .iter()
+        .map(|&t| ("application/x-raw-disk-image", t))
+        .collect()
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [("application/x-vfat", "application/x-fat")]
+        .iter()
+        .copied()
+        .collect()
+}
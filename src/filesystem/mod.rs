@@ -0,0 +1,17 @@
+//! Handles images of a single filesystem, such as dumps of a partition, by
+//! their superblock
+//!
+//! The shared MIME-info database has no types for these, so they're named
+//! after the filesystem.
+const TYPES: [&str; 7] = [
+    "application/x-ext2",
+    "application/x-ext3",
+    "application/x-ext4",
+    "application/x-btrfs",
+    "application/x-xfs",
+    "application/x-fat",
+    "application/x-ntfs",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,15 @@
+//! Estimates of the heap memory held by the parts of a database
+use crate::FnvHashMap;
+use core::mem::size_of;
+use petgraph::graph::{DiGraph, Edge, Node};
+
+/// Bytes held by the nodes and edges of a graph
+pub(crate) fn graph_size<N, E>(graph: &DiGraph<N, E>) -> usize {
+    let (nodes, edges) = graph.capacity();
+    nodes * size_of::<Node<N>>() + edges * size_of::<Edge<E>>()
+}
+
+/// Bytes held by the entries of a map, with a control byte for each
+pub(crate) fn map_size<K, V>(map: &FnvHashMap<K, V>) -> usize {
+    map.capacity() * (size_of::<(K, V)>() + 1)
+}
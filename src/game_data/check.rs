@@ -0,0 +1,282 @@
+use crate::source::source_len;
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct GameData;
+
+impl crate::Checker for GameData {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        (db.game_formats() && is_game_data(source, mimetype))
+            || fdo_magic::builtin::check::from_source(db, source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "application/x-nes-rom" => Some(NES_HEADER_LEN),
+            "application/x-gameboy-rom" | "application/x-gameboy-color-rom" => Some(GB_HEADER_END),
+            "application/x-gba-rom" => Some(GBA_HEADER_LEN),
+            "application/x-n64-rom" => Some(N64_MAGIC_LEN),
+            "application/x-gamecube-rom" | "application/x-wii-rom" => Some(DISC_HEADER_LEN),
+            "application/x-unity3d-bundle" => Some(UNITY_MAGIC_LEN),
+            // SNES headers sit after the first bank, or after a copier
+            // header, and the rest read at or near the end
+            _ => None,
+        }
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        kind(source, mimetype)
+    }
+}
+
+/// iNES header, whose flags also tell NES 2.0 headers apart
+const NES_HEADER_LEN: usize = 16;
+
+/// Copiers put a header of 512 bytes in front of the ROM, which leaves its
+/// length 512 more than a multiple of 1024
+const SNES_COPIER_LEN: u64 = 512;
+/// Offsets of the internal header for LoROM, HiROM and ExHiROM cartridges
+const SNES_HEADERS: [(u64, &str); 3] =
+    [(0x7fc0, "lorom"), (0xffc0, "hirom"), (0x40ffc0, "exhirom")];
+/// Title, map mode, cartridge type, sizes, region, maker, version, then the
+/// complement of the checksum and the checksum itself
+const SNES_HEADER_LEN: usize = 32;
+
+/// The logo the boot ROM checks before starting a cartridge
+const GB_LOGO_OFFSET: usize = 0x104;
+const GB_LOGO: [u8; 48] = [
+    0xce, 0xed, 0x66, 0x66, 0xcc, 0x0d, 0x00, 0x0b, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0c, 0x00, 0x0d,
+    0x00, 0x08, 0x11, 0x1f, 0x88, 0x89, 0x00, 0x0e, 0xdc, 0xcc, 0x6e, 0xe6, 0xdd, 0xdd, 0xd9, 0x99,
+    0xbb, 0xbb, 0x67, 0x63, 0x6e, 0x0e, 0xec, 0xcc, 0xdd, 0xdc, 0x99, 0x9f, 0xbb, 0xb9, 0x33, 0x3e,
+];
+/// Flag for games that use Game Boy Color features
+const GB_CGB_FLAG: usize = 0x143;
+/// The header checksum covers the title through the version
+const GB_CHECKSUM: usize = 0x14d;
+const GB_HEADER_START: usize = 0x134;
+const GB_HEADER_END: usize = 0x150;
+
+/// Branch to the start code, logo, title and codes, fixed value and the
+/// complement check
+const GBA_HEADER_LEN: usize = 0xc0;
+const GBA_FIXED: usize = 0xb2;
+const GBA_COMPLEMENT: usize = 0xbd;
+
+/// The first word of the ROM, in whichever byte order it was dumped with
+const N64_MAGIC_LEN: usize = 4;
+
+/// Disc headers of GameCube and Wii, with their magic words
+const DISC_HEADER_LEN: usize = 32;
+const GAMECUBE_MAGIC: (usize, &[u8; 4]) = (0x1c, b"\xc2\x33\x9f\x3d");
+const WII_MAGIC: (usize, &[u8; 4]) = (0x18, b"\x5d\x1c\x9e\xa3");
+
+/// The primary volume descriptor of an ISO 9660 disc, whose system
+/// identifier names the console
+const PVD_OFFSET: u64 = 16 * 2048;
+const PVD_SYSTEM_ID: usize = 8;
+const PVD_PROBE_LEN: usize = 40;
+
+/// Signatures of asset bundles, each followed by a null byte
+const UNITY_MAGIC_LEN: usize = 9;
+const UNITY_SIGNATURES: [(&[u8], &str); 3] = [
+    (b"UnityFS\0", "unityfs"),
+    (b"UnityWeb\0", "unityweb"),
+    (b"UnityRaw\0", "unityraw"),
+];
+
+/// The index of a pak file is found from a footer at its end, which grew
+/// with the versions of the format. The magic is followed by the version,
+/// the offset and size of the index, and a hash.
+const PAK_MAGIC: u32 = 0x5a6f_12e1;
+const PAK_FOOTER_LEN: u64 = 44;
+const PAK_MAX_VERSION: u32 = 12;
+/// Names of compression methods, then a flag for a frozen index, added
+/// after the rest of the footer by later versions
+const PAK_FOOTER_EXTRAS: [u64; 4] = [0, 4 * 32, 5 * 32, 5 * 32 + 1];
+
+fn read(source: &dyn DataSource, at: u64, len: usize) -> Option<Vec<u8>> {
+    match source.read_at(at, len) {
+        Ok(b) if b.len() == len => Some(b.into_owned()),
+        _ => None,
+    }
+}
+
+fn le_u32(b: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([b[at], b[at + 1], b[at + 2], b[at + 3]])
+}
+
+fn le_u64(b: &[u8], at: usize) -> u64 {
+    let mut x = [0; 8];
+    x.copy_from_slice(&b[at..at + 8]);
+    u64::from_le_bytes(x)
+}
+
+/// `ines` or `nes2.0`, from the iNES header, or `unif`
+fn nes(source: &dyn DataSource) -> Option<&'static str> {
+    let b = read(source, 0, NES_HEADER_LEN)?;
+    if b.starts_with(b"UNIF") {
+        return Some("unif");
+    }
+    if !b.starts_with(b"NES\x1a") || b[4] == 0 {
+        return None;
+    }
+    // Bits 2 and 3 of flags 7 are 2 in NES 2.0 headers
+    match b[7] & 0x0c {
+        0x08 => Some("nes2.0"),
+        _ => Some("ines"),
+    }
+}
+
+/// The mapping of the cartridge, from the first of its possible internal
+/// headers whose checksum and complement add up
+fn snes(source: &dyn DataSource) -> Option<&'static str> {
+    let len = source_len(source)?;
+    let skip = match len % 1024 {
+        0 => 0,
+        SNES_COPIER_LEN => SNES_COPIER_LEN,
+        _ => return None,
+    };
+    SNES_HEADERS.iter().find_map(|&(offset, name)| {
+        let b = read(source, skip + offset, SNES_HEADER_LEN)?;
+        let complement = u16::from_le_bytes([b[0x1c], b[0x1d]]);
+        let checksum = u16::from_le_bytes([b[0x1e], b[0x1f]]);
+        // The title is printable, and padded with spaces
+        let title = b[..21].iter().all(|&c| (0x20..0x7f).contains(&c));
+        (title && complement ^ checksum == 0xffff).then_some(name)
+    })
+}
+
+/// `dmg` for the original Game Boy, and `cgb` for games with Game Boy Color
+/// features, of which `cgb-only` ones run on nothing else
+fn gameboy(source: &dyn DataSource) -> Option<&'static str> {
+    let b = read(source, 0, GB_HEADER_END)?;
+    if b[GB_LOGO_OFFSET..GB_LOGO_OFFSET + GB_LOGO.len()] != GB_LOGO {
+        return None;
+    }
+    let sum = b[GB_HEADER_START..GB_CHECKSUM]
+        .iter()
+        .fold(0u8, |x, &c| x.wrapping_sub(c).wrapping_sub(1));
+    if sum != b[GB_CHECKSUM] {
+        return None;
+    }
+    match b[GB_CGB_FLAG] {
+        0x80 => Some("cgb"),
+        0xc0 => Some("cgb-only"),
+        _ => Some("dmg"),
+    }
+}
+
+/// A branch instruction to the start code, the fixed value and the
+/// complement check over the header
+fn is_gba(b: &[u8]) -> bool {
+    if b.len() < GBA_HEADER_LEN || b[3] != 0xea || b[GBA_FIXED] != 0x96 {
+        return false;
+    }
+    let sum = b[0xa0..GBA_COMPLEMENT]
+        .iter()
+        .fold(0u8, |x, &c| x.wrapping_sub(c));
+    sum.wrapping_sub(0x19) == b[GBA_COMPLEMENT]
+}
+
+/// The byte order the ROM was dumped in, named after the file extension
+/// each usually has
+fn n64(b: &[u8]) -> Option<&'static str> {
+    match b.get(..N64_MAGIC_LEN)? {
+        b"\x80\x37\x12\x40" => Some("z64"),
+        b"\x37\x80\x40\x12" => Some("v64"),
+        b"\x40\x12\x37\x80" => Some("n64"),
+        _ => None,
+    }
+}
+
+/// `psp` or `playstation` for the other consoles, which all use the same
+/// system identifier
+fn playstation(source: &dyn DataSource) -> Option<&'static str> {
+    let b = read(source, PVD_OFFSET, PVD_PROBE_LEN)?;
+    if &b[..6] != b"\x01CD001" {
+        return None;
+    }
+    let system = &b[PVD_SYSTEM_ID..];
+    if system.starts_with(b"PSP GAME") {
+        Some("psp")
+    } else if system.starts_with(b"PLAYSTATION") {
+        Some("playstation")
+    } else {
+        None
+    }
+}
+
+/// The version of a pak file, from a footer that holds the offset and size
+/// of an index within the file
+fn unreal_pak(source: &dyn DataSource) -> Option<&'static str> {
+    const VERSIONS: [&str; PAK_MAX_VERSION as usize] = [
+        "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "11", "12",
+    ];
+    let len = source_len(source)?;
+    PAK_FOOTER_EXTRAS.iter().find_map(|&extra| {
+        let at = len.checked_sub(PAK_FOOTER_LEN + extra)?;
+        let b = read(source, at, PAK_FOOTER_LEN as usize)?;
+        if le_u32(&b, 0) != PAK_MAGIC {
+            return None;
+        }
+        let version = le_u32(&b, 4);
+        let end = le_u64(&b, 8).checked_add(le_u64(&b, 16))?;
+        if version == 0 || version > PAK_MAX_VERSION || end > at {
+            return None;
+        }
+        Some(VERSIONS[version as usize - 1])
+    })
+}
+
+fn is_disc(b: &[u8], magic: (usize, &[u8; 4])) -> bool {
+    b.len() >= DISC_HEADER_LEN && b[magic.0..magic.0 + 4] == magic.1[..]
+}
+
+fn unity(b: &[u8]) -> Option<&'static str> {
+    UNITY_SIGNATURES
+        .iter()
+        .find(|(magic, _)| b.starts_with(magic))
+        .map(|&(_, name)| name)
+}
+
+/// Variant of each type, where the data tells it
+fn kind(source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+    match mimetype {
+        "application/x-nes-rom" => nes(source),
+        "application/vnd.nintendo.snes.rom" => snes(source),
+        "application/x-gameboy-rom" => gameboy(source),
+        "application/x-gameboy-color-rom" => gameboy(source).filter(|&k| k != "dmg"),
+        "application/x-n64-rom" => n64(&read(source, 0, N64_MAGIC_LEN)?),
+        "application/x-playstation-rom" => playstation(source),
+        "application/x-unity3d-bundle" => unity(&source.read_at(0, UNITY_MAGIC_LEN).ok()?),
+        "application/x-unreal-pak" => unreal_pak(source),
+        _ => None,
+    }
+}
+
+fn is_game_data(source: &dyn DataSource, mimetype: &str) -> bool {
+    let (len, check): (usize, fn(&[u8]) -> bool) = match mimetype {
+        "application/x-gba-rom" => (GBA_HEADER_LEN, is_gba),
+        "application/x-gamecube-rom" => (DISC_HEADER_LEN, |b| is_disc(b, GAMECUBE_MAGIC)),
+        "application/x-wii-rom" => (DISC_HEADER_LEN, |b| {
+            b.starts_with(b"WBFS") || is_disc(b, WII_MAGIC)
+        }),
+        _ => return kind(source, mimetype).is_some(),
+    };
+    match source.read_at(0, len) {
+        Ok(b) => check(&b),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    vec![("application/x-cd-image", "application/x-playstation-rom")]
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        (
+            "application/x-snes-rom",
+            "application/vnd.nintendo.snes.rom",
+        ),
+        (
+            "application/x-gamecube-iso-image",
+            "application/x-gamecube-rom",
+        ),
+        ("application/x-wii-iso-image", "application/x-wii-rom"),
+        ("application/x-wbfs", "application/x-wii-rom"),
+        ("application/x-psp-rom", "application/x-playstation-rom"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
@@ -0,0 +1,21 @@
+//! Handles game cartridge ROMs, console disc images and game engine asset
+//! containers.
+//!
+//! Only the magic rules of the database are checked, unless turned on with
+//! [`game_formats`](crate::TypeDatabaseBuilder::game_formats).
+const TYPES: [&str; 11] = [
+    "application/x-nes-rom",
+    "application/vnd.nintendo.snes.rom",
+    "application/x-gameboy-rom",
+    "application/x-gameboy-color-rom",
+    "application/x-gba-rom",
+    "application/x-n64-rom",
+    "application/x-gamecube-rom",
+    "application/x-wii-rom",
+    "application/x-playstation-rom",
+    "application/x-unity3d-bundle",
+    "application/x-unreal-pak",
+];
+
+pub mod check;
+pub mod init;
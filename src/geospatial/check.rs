@@ -0,0 +1,178 @@
+use crate::json::check::top_level;
+use crate::source::source_len;
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Geospatial;
+
+impl crate::Checker for Geospatial {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "application/geo+json" => Some(JSON_PROBE_LEN),
+            "application/gpx+xml" | "application/vnd.google-earth.kml+xml" => Some(XML_PROBE_LEN),
+            "application/vnd.flatgeobuf" => Some(FLATGEOBUF_MAGIC_LEN),
+            // The header of a shapefile gives the length of the file
+            _ => None,
+        }
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        match mimetype {
+            "application/geo+json" => geojson_type(&source.read_at(0, JSON_PROBE_LEN).ok()?),
+            "application/x-shapefile" => shape_type(source),
+            _ => None,
+        }
+    }
+}
+
+/// How much of GeoJSON to look at. The `type` of a feature collection may
+/// come after its features.
+const JSON_PROBE_LEN: usize = 16 * 1024;
+
+/// How much of XML to look at for its root element, which may come after
+/// comments and a doctype
+const XML_PROBE_LEN: usize = 4096;
+
+/// `fgb`, the major version, `fgb` and the patch version
+const FLATGEOBUF_MAGIC_LEN: usize = 8;
+
+/// The main file of a shapefile, and its index, start with the same header
+const SHAPEFILE_HEADER_LEN: usize = 100;
+const SHAPEFILE_CODE: u32 = 9994;
+const SHAPEFILE_VERSION: u32 = 1000;
+
+/// Geometry types, then the two that only make sense in a feature or a
+/// collection of them
+const GEOJSON_TYPES: [&str; 9] = [
+    "Point",
+    "MultiPoint",
+    "LineString",
+    "MultiLineString",
+    "Polygon",
+    "MultiPolygon",
+    "GeometryCollection",
+    "Feature",
+    "FeatureCollection",
+];
+
+/// The `type` of a GeoJSON object. Geometries need their coordinates too,
+/// as other JSON also has a `type` of `Point` or `Polygon`.
+fn geojson_type(b: &[u8]) -> Option<&'static str> {
+    let doc = top_level(b)?;
+    let kind = doc.value(b"type")?;
+    let kind = GEOJSON_TYPES
+        .iter()
+        .copied()
+        .find(|t| t.as_bytes() == kind)?;
+    match kind {
+        "Feature" | "FeatureCollection" => Some(kind),
+        "GeometryCollection" => doc.has(b"geometries").then_some(kind),
+        _ => doc.has(b"coordinates").then_some(kind),
+    }
+}
+
+/// Shape types of the shapefile specification, of which all the shapes in
+/// a file are one
+fn shape_name(code: u32) -> Option<&'static str> {
+    Some(match code {
+        0 => "null",
+        1 => "point",
+        3 => "polyline",
+        5 => "polygon",
+        8 => "multipoint",
+        11 => "pointz",
+        13 => "polylinez",
+        15 => "polygonz",
+        18 => "multipointz",
+        21 => "pointm",
+        23 => "polylinem",
+        25 => "polygonm",
+        28 => "multipointm",
+        31 => "multipatch",
+        _ => return None,
+    })
+}
+
+/// The shape type from the header, which mixes big and little-endian fields.
+/// The length it gives, in 16-bit words, is held to that of the file where
+/// that's known.
+fn shape_type(source: &dyn DataSource) -> Option<&'static str> {
+    let b = source.read_at(0, SHAPEFILE_HEADER_LEN).ok()?;
+    if b.len() < SHAPEFILE_HEADER_LEN {
+        return None;
+    }
+    let be = |at: usize| u32::from_be_bytes([b[at], b[at + 1], b[at + 2], b[at + 3]]);
+    let le = |at: usize| u32::from_le_bytes([b[at], b[at + 1], b[at + 2], b[at + 3]]);
+    if be(0) != SHAPEFILE_CODE || b[4..24].iter().any(|&c| c != 0) || le(28) != SHAPEFILE_VERSION {
+        return None;
+    }
+    let len = be(24) as u64 * 2;
+    if len < SHAPEFILE_HEADER_LEN as u64 || source_len(source).is_some_and(|n| n != len) {
+        return None;
+    }
+    shape_name(le(32))
+}
+
+/// The local name of the root element, after the XML declaration and any
+/// comments, processing instructions and doctype
+fn root_element(b: &[u8]) -> Option<&[u8]> {
+    let mut b = b.strip_prefix(b"\xef\xbb\xbf").unwrap_or(b);
+    loop {
+        let start = b.iter().position(|c| !c.is_ascii_whitespace())?;
+        b = b[start..].strip_prefix(b"<")?;
+        let end: &[u8] = match b.first()? {
+            b'?' => b"?>",
+            b'!' if b.starts_with(b"!--") => b"-->",
+            b'!' => b">",
+            _ => break,
+        };
+        let at = b.windows(end.len()).position(|w| w == end)?;
+        b = &b[at + end.len()..];
+    }
+    let len = b
+        .iter()
+        .position(|&c| c.is_ascii_whitespace() || matches!(c, b'>' | b'/'))?;
+    let name = &b[..len];
+    let local = match name.iter().rposition(|&c| c == b':') {
+        Some(n) => &name[n + 1..],
+        None => name,
+    };
+    (!local.is_empty()).then_some(local)
+}
+
+fn is_flatgeobuf(b: &[u8]) -> bool {
+    b.len() == FLATGEOBUF_MAGIC_LEN && b.starts_with(b"fgb\x03fgb")
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    let (len, check): (usize, fn(&[u8]) -> bool) = match mimetype {
+        "application/geo+json" => (JSON_PROBE_LEN, |b| geojson_type(b).is_some()),
+        "application/x-shapefile" => return shape_type(source).is_some(),
+        "application/gpx+xml" => (XML_PROBE_LEN, |b| root_element(b) == Some(b"gpx")),
+        "application/vnd.google-earth.kml+xml" => {
+            (XML_PROBE_LEN, |b| root_element(b) == Some(b"kml"))
+        }
+        "application/vnd.flatgeobuf" => (FLATGEOBUF_MAGIC_LEN, is_flatgeobuf),
+        _ => return false,
+    };
+    match source.read_at(0, len) {
+        Ok(b) => check(&b),
+        Err(_) => false,
+    }
+}
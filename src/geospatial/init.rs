@@ -0,0 +1,22 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    vec![
+        ("application/json", "application/geo+json"),
+        ("application/xml", "application/gpx+xml"),
+        ("application/xml", "application/vnd.google-earth.kml+xml"),
+    ]
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [("application/x-esri-shape", "application/x-shapefile")]
+        .iter()
+        .copied()
+        .collect()
+}
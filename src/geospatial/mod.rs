@@ -0,0 +1,11 @@
+//! Handles geospatial data: GeoJSON, shapefiles, GPX, KML and FlatGeobuf
+const TYPES: [&str; 5] = [
+    "application/geo+json",
+    "application/x-shapefile",
+    "application/gpx+xml",
+    "application/vnd.google-earth.kml+xml",
+    "application/vnd.flatgeobuf",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,187 @@
+//! Match file names against the glob patterns of the shared MIME-info database
+use crate::MIME;
+use alloc::{string::String, vec::Vec};
+
+/// A line of a `globs2` file
+#[derive(Debug, Clone)]
+struct Glob {
+    weight: u32,
+    mime: MIME,
+    /// Lowercased unless the glob is case-sensitive
+    pattern: String,
+    case_sensitive: bool,
+}
+
+/// Glob patterns, in the order they were loaded.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GlobSet {
+    globs: Vec<Glob>,
+}
+
+impl GlobSet {
+    /// Adds the `weight:type:pattern[:flags]` lines of a `globs2` file.
+    /// Comments and lines that don't parse are skipped. A `__NOGLOBS__`
+    /// pattern drops the globs loaded so far for its type.
+    pub(crate) fn extend(&mut self, text: &'static str) {
+        for line in text.lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split(':');
+            let (weight, mime, pattern) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(w), Some(m), Some(p)) if !m.is_empty() && !p.is_empty() => match w.parse() {
+                    Ok(w) => (w, m, p),
+                    Err(_) => continue,
+                },
+                _ => continue,
+            };
+            if pattern == "__NOGLOBS__" {
+                self.globs.retain(|g| g.mime != mime);
+                continue;
+            }
+
+            let case_sensitive = fields.any(|flags| flags.split(',').any(|f| f == "cs"));
+            self.globs.push(Glob {
+                weight,
+                mime,
+                pattern: if case_sensitive {
+                    pattern.into()
+                } else {
+                    pattern.to_lowercase()
+                },
+                case_sensitive,
+            });
+        }
+    }
+
+    /// Types whose globs match the file name. Only the globs with the highest
+    /// weight, and of those the longest patterns, count.
+    pub(crate) fn matches(&self, filename: &str) -> Vec<MIME> {
+        self.ranked_matches(filename).0
+    }
+
+    /// Types whose globs match the file name: those [`matches`](Self::matches)
+    /// gives, and the others matched by globs of a lower weight, as
+    /// QMimeDatabase keeps them. Shorter patterns of the highest weight are
+    /// left out of both.
+    pub(crate) fn ranked_matches(&self, filename: &str) -> (Vec<MIME>, Vec<MIME>) {
+        let lower = filename.to_lowercase();
+        let found: Vec<&Glob> = self
+            .globs
+            .iter()
+            .filter(|g| {
+                let name = if g.case_sensitive { filename } else { &lower };
+                glob_match(&g.pattern, name)
+            })
+            .collect();
+
+        let best = match found.iter().map(|g| (g.weight, g.pattern.len())).max() {
+            Some(x) => x,
+            None => return (Vec::new(), Vec::new()),
+        };
+        let mut out = Vec::<MIME>::new();
+        let mut weaker = Vec::<MIME>::new();
+        for g in &found {
+            if (g.weight, g.pattern.len()) == best && !out.contains(&g.mime) {
+                out.push(g.mime);
+            }
+        }
+        for g in found {
+            if g.weight < best.0 && !out.contains(&g.mime) && !weaker.contains(&g.mime) {
+                weaker.push(g.mime);
+            }
+        }
+        (out, weaker)
+    }
+}
+
+fn is_special(c: char) -> bool {
+    matches!(c, '*' | '?' | '[')
+}
+
+/// Whether the name matches the pattern, with shortcuts for literal names
+/// and `*.ext` patterns, which are nearly all of them
+fn glob_match(pattern: &str, name: &str) -> bool {
+    if !pattern.contains(is_special) {
+        return pattern == name;
+    }
+    if let Some(suffix) = pattern.strip_prefix('*') {
+        if !suffix.contains(is_special) {
+            return name.ends_with(suffix);
+        }
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    fnmatch(&pattern, &name)
+}
+
+/// Shell-style matching of `*`, `?` and `[...]`
+fn fnmatch(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| fnmatch(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && fnmatch(rest, &name[1..]),
+        Some(('[', rest)) => match class_match(rest, name.first().copied()) {
+            Some((matched, rest)) => matched && fnmatch(rest, &name[1..]),
+            // No closing bracket, so it's a plain `[`
+            None => name.first() == Some(&'[') && fnmatch(rest, &name[1..]),
+        },
+        Some((c, rest)) => name.first() == Some(c) && fnmatch(rest, &name[1..]),
+    }
+}
+
+/// Matches `c` against a bracket expression, given what follows the `[`.
+/// Returns whether it matched and the pattern past the `]`.
+fn class_match(pattern: &[char], c: Option<char>) -> Option<(bool, &[char])> {
+    let (negate, body) = match pattern.split_first() {
+        Some(('!', rest)) => (true, rest),
+        _ => (false, pattern),
+    };
+    // A `]` right at the start is part of the set
+    let end = 1 + body.get(1..)?.iter().position(|&x| x == ']')?;
+    let set = &body[..end];
+
+    let c = match c {
+        Some(c) => c,
+        None => return Some((false, &body[end + 1..])),
+    };
+    let mut found = false;
+    let mut i = 0;
+    while i < set.len() {
+        if i + 2 < set.len() && set[i + 1] == '-' {
+            found |= set[i] <= c && c <= set[i + 2];
+            i += 3;
+        } else {
+            found |= set[i] == c;
+            i += 1;
+        }
+    }
+    Some((found != negate, &body[end + 1..]))
+}
+
+/// Loads the globs from the XDG standard locations
+#[cfg(feature = "std")]
+pub(crate) fn system() -> GlobSet {
+    use once_cell::sync::OnceCell;
+    use std::fs;
+
+    static GLOB_STRINGS: OnceCell<Vec<String>> = OnceCell::new();
+
+    let files = GLOB_STRINGS.get_or_init(|| {
+        crate::xdg::search_paths("globs2")
+            .iter()
+            .filter_map(|p| fs::read_to_string(p).ok())
+            .collect()
+    });
+    let mut set = GlobSet::default();
+    for text in files {
+        set.extend(text);
+    }
+    set
+}
+
+/// Without `std` there are no system globs to load
+#[cfg(not(feature = "std"))]
+pub(crate) fn system() -> GlobSet {
+    GlobSet::default()
+}
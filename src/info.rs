@@ -0,0 +1,145 @@
+//! Descriptive metadata of types, read from the per-type XML files of the
+//! shared MIME-info database (`/usr/share/mime/image/gif.xml`)
+use crate::xdg;
+use alloc::{string::String, vec::Vec};
+use std::fs;
+
+/// The XML file of the type, looking through the MIME directories from most
+/// to least important
+fn type_xml(mimetype: &str) -> Option<String> {
+    // Keep odd names from walking out of the MIME directories
+    let (media, sub) = mimetype.split_once('/')?;
+    if [media, sub]
+        .iter()
+        .any(|p| p.is_empty() || p.starts_with('.') || p.contains(['/', '\\']))
+    {
+        return None;
+    }
+
+    let file = alloc::format!("{}/{}.xml", media, sub);
+    xdg::search_paths(&file)
+        .iter()
+        .rev()
+        .find_map(|p| fs::read_to_string(p).ok())
+}
+
+/// Text of the untranslated `tag` element in the XML file of the type
+pub(crate) fn element(mimetype: &str, tag: &str) -> Option<String> {
+    element_text(&type_xml(mimetype)?, tag, None)
+}
+
+/// Text of the `tag` element translated for `locale`, falling back to less
+/// specific locales and then to the untranslated text
+pub(crate) fn localized_element(mimetype: &str, tag: &str, locale: &str) -> Option<String> {
+    let xml = type_xml(mimetype)?;
+    locale_fallbacks(locale)
+        .iter()
+        .find_map(|l| element_text(&xml, tag, Some(l)))
+        .or_else(|| element_text(&xml, tag, None))
+}
+
+/// Locales the `tag` element is translated into, in the order of the file
+pub(crate) fn element_locales(mimetype: &str, tag: &str) -> Vec<String> {
+    match type_xml(mimetype) {
+        Some(xml) => elements(&xml, tag)
+            .filter_map(|(lang, _)| lang.map(String::from))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Locales to look for, from most to least specific, as gettext does:
+/// `sr_RS.UTF-8@latin` gives `sr_RS@latin`, `sr_RS`, `sr@latin` and `sr`.
+/// Tags like `pt-BR` are taken as `pt_BR`. The `C` and `POSIX` locales have
+/// no translations.
+fn locale_fallbacks(locale: &str) -> Vec<String> {
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, m)) => (rest, Some(m)),
+        None => (locale, None),
+    };
+    let rest = rest.split('.').next().unwrap_or_default();
+    let (lang, country) = match rest.split_once(['_', '-']) {
+        Some((lang, c)) => (lang, Some(c)),
+        None => (rest, None),
+    };
+    if lang.is_empty() || lang == "C" || lang == "POSIX" {
+        return Vec::new();
+    }
+
+    let mut out = Vec::new();
+    for country in [country, None] {
+        let base = match country {
+            Some(c) => alloc::format!("{}_{}", lang, c),
+            None => String::from(lang),
+        };
+        if let Some(m) = modifier {
+            out.push(alloc::format!("{}@{}", base, m));
+        }
+        out.push(base);
+    }
+    out.dedup();
+    out
+}
+
+/// Each `<tag>text</tag>` in the file, with the language of translations
+fn elements<'a>(xml: &'a str, tag: &'a str) -> impl Iterator<Item = (Option<&'a str>, &'a str)> {
+    let open = alloc::format!("<{}", tag);
+    let close = alloc::format!("</{}>", tag);
+    let mut rest = xml;
+    core::iter::from_fn(move || loop {
+        let start = rest.find(&open)? + open.len();
+        rest = &rest[start..];
+        // Longer names starting with the tag, such as `<comments>`
+        let attrs_end = rest.find('>')?;
+        let attrs = &rest[..attrs_end];
+        if !(attrs.is_empty() || attrs.starts_with(char::is_whitespace)) {
+            continue;
+        }
+        let body = &rest[attrs_end + 1..];
+        let len = body.find(&close)?;
+        rest = &body[len + close.len()..];
+        let lang = attrs.split_once("xml:lang=").and_then(|(_, v)| {
+            let quote = v.chars().next().filter(|&q| q == '"' || q == '\'')?;
+            v[1..].split(quote).next()
+        });
+        return Some((lang, body[..len].trim()));
+    })
+}
+
+/// Text of the element translated into `lang`, or the untranslated one
+fn element_text(xml: &str, tag: &str, lang: Option<&str>) -> Option<String> {
+    elements(xml, tag)
+        .find(|&(l, _)| l == lang)
+        .map(|(_, text)| unescape(text))
+}
+
+/// Replaces the predefined XML entities
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let entity = [
+            ("&amp;", '&'),
+            ("&lt;", '<'),
+            ("&gt;", '>'),
+            ("&quot;", '"'),
+            ("&apos;", '\''),
+        ]
+        .iter()
+        .find(|(e, _)| rest.starts_with(e));
+        match entity {
+            Some((e, c)) => {
+                out.push(*c);
+                rest = &rest[e.len()..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
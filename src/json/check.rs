@@ -0,0 +1,363 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Json;
+
+impl crate::Checker for Json {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        Some(PROBE_LEN)
+    }
+}
+
+/// How much of the text is parsed. Text cut off here is taken for JSON if
+/// it's valid as far as it goes.
+const PROBE_LEN: usize = 4096;
+
+/// Deepest nesting followed, to keep the parser off the end of the stack
+const MAX_DEPTH: usize = 64;
+
+/// Why parsing stopped early
+#[derive(Debug, PartialEq, Eq)]
+enum Stop {
+    /// The text isn't valid
+    Invalid,
+    /// The text ran out, and was valid as far as it went
+    End,
+}
+
+type Parsed = Result<(), Stop>;
+
+/// Parser for JSON, or with `json5` set, for JSON5, which adds comments,
+/// trailing commas, single quotes, unquoted keys and more kinds of numbers
+struct Parser<'a> {
+    b: &'a [u8],
+    pos: usize,
+    json5: bool,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.b.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Parsed {
+        match self.peek() {
+            Some(x) if x == c => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(_) => Err(Stop::Invalid),
+            None => Err(Stop::End),
+        }
+    }
+
+    fn skip_ws(&mut self) -> Parsed {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => self.pos += 1,
+                Some(b'\x0b' | b'\x0c') if self.json5 => self.pos += 1,
+                Some(b'/') if self.json5 => {
+                    let rest = &self.b[self.pos..];
+                    // Length of the comment, up to where it ends
+                    let len = match rest.get(1) {
+                        Some(b'/') => rest.iter().position(|&c| c == b'\n').map(|n| n + 1),
+                        Some(b'*') => rest[2..].windows(2).position(|w| w == b"*/").map(|n| n + 4),
+                        Some(_) => return Err(Stop::Invalid),
+                        None => None,
+                    };
+                    match len {
+                        Some(n) => self.pos += n,
+                        None => return Err(Stop::End),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// A word such as `true`, which may be cut off
+    fn word(&mut self, word: &[u8]) -> Parsed {
+        let rest = &self.b[self.pos..];
+        if rest.starts_with(word) {
+            self.pos += word.len();
+            Ok(())
+        } else if word.starts_with(rest) {
+            Err(Stop::End)
+        } else {
+            Err(Stop::Invalid)
+        }
+    }
+
+    fn string(&mut self, quote: u8) -> Parsed {
+        self.pos += 1;
+        while let Some(c) = self.peek() {
+            match c {
+                b'\\' => self.pos += 2,
+                c if c == quote => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                c if c < 0x20 && c != b'\t' => return Err(Stop::Invalid),
+                _ => self.pos += 1,
+            }
+        }
+        Err(Stop::End)
+    }
+
+    /// Unquoted JSON5 key, which is an identifier
+    fn identifier(&mut self) -> Parsed {
+        let start = self.pos;
+        while let Some(b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' | b'$') = self.peek() {
+            self.pos += 1;
+        }
+        match self.b[start..self.pos].first() {
+            Some(c) if !c.is_ascii_digit() => Ok(()),
+            Some(_) => Err(Stop::Invalid),
+            None if self.pos == self.b.len() => Err(Stop::End),
+            None => Err(Stop::Invalid),
+        }
+    }
+
+    /// Takes the characters numbers are made of, which must include a digit
+    fn number(&mut self) -> Parsed {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            let ok = match c {
+                b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E' => true,
+                b'x' | b'X' | b'a'..=b'f' | b'A'..=b'F' => self.json5,
+                _ => false,
+            };
+            if !ok {
+                break;
+            }
+            self.pos += 1;
+        }
+        let digits = &self.b[start..self.pos];
+        if self.pos == self.b.len() {
+            Err(Stop::End)
+        } else if digits.iter().any(u8::is_ascii_digit) {
+            Ok(())
+        } else if self.json5 && digits.len() <= 1 {
+            // Signed `Infinity` and `NaN`
+            match self.peek() {
+                Some(b'I') => self.word(b"Infinity"),
+                Some(b'N') => self.word(b"NaN"),
+                _ => Err(Stop::Invalid),
+            }
+        } else {
+            Err(Stop::Invalid)
+        }
+    }
+
+    /// Items of an object or array, up to its closing bracket
+    fn items(&mut self, close: u8, depth: usize) -> Parsed {
+        self.pos += 1;
+        self.skip_ws()?;
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            if close == b'}' {
+                match self.peek() {
+                    Some(b'"') => self.string(b'"')?,
+                    Some(b'\'') if self.json5 => self.string(b'\'')?,
+                    Some(_) if self.json5 => self.identifier()?,
+                    Some(_) => return Err(Stop::Invalid),
+                    None => return Err(Stop::End),
+                }
+                self.skip_ws()?;
+                self.expect(b':')?;
+            }
+            self.value(depth + 1)?;
+            self.skip_ws()?;
+            match self.peek() {
+                Some(b',') => {
+                    self.pos += 1;
+                    self.skip_ws()?;
+                    if self.json5 && self.peek() == Some(close) {
+                        self.pos += 1;
+                        return Ok(());
+                    }
+                }
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    return Ok(());
+                }
+                Some(_) => return Err(Stop::Invalid),
+                None => return Err(Stop::End),
+            }
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Parsed {
+        if depth > MAX_DEPTH {
+            return Err(Stop::Invalid);
+        }
+        self.skip_ws()?;
+        match self.peek() {
+            Some(b'{') => self.items(b'}', depth),
+            Some(b'[') => self.items(b']', depth),
+            Some(b'"') => self.string(b'"'),
+            Some(b'\'') if self.json5 => self.string(b'\''),
+            Some(b't') => self.word(b"true"),
+            Some(b'f') => self.word(b"false"),
+            Some(b'n') => self.word(b"null"),
+            Some(b'I') if self.json5 => self.word(b"Infinity"),
+            Some(b'N') if self.json5 => self.word(b"NaN"),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(b'+' | b'.') if self.json5 => self.number(),
+            Some(_) => Err(Stop::Invalid),
+            None => Err(Stop::End),
+        }
+    }
+
+    /// An object or array, with nothing but whitespace after it
+    fn document(&mut self, complete: bool) -> bool {
+        let result = self.skip_ws().and_then(|_| match self.peek() {
+            Some(b'{' | b'[') => self.value(0),
+            Some(_) => Err(Stop::Invalid),
+            None => Err(Stop::End),
+        });
+        match result.and_then(|_| self.skip_ws()) {
+            Ok(()) => self.pos == self.b.len(),
+            Err(Stop::End) => !complete && self.pos > 0,
+            Err(Stop::Invalid) => false,
+        }
+    }
+}
+
+fn is_json(b: &[u8], complete: bool, json5: bool) -> bool {
+    Parser { b, pos: 0, json5 }.document(complete)
+}
+
+/// Two or more objects or arrays, one per line
+fn is_ndjson(b: &[u8], complete: bool) -> bool {
+    let mut values = 0;
+    let mut lines = b.split(|&c| c == b'\n').peekable();
+    while let Some(line) = lines.next() {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let last = lines.peek().is_none();
+        if !is_json(line, complete || !last, false) {
+            return false;
+        }
+        values += 1;
+    }
+    values >= 2
+}
+
+/// Top-level keys of a JSON object, and the values of those that are
+/// strings or numbers
+#[derive(Default)]
+pub(crate) struct TopLevel<'a> {
+    keys: Vec<&'a [u8]>,
+    values: Vec<(&'a [u8], &'a [u8])>,
+}
+
+impl TopLevel<'_> {
+    pub(crate) fn has(&self, key: &[u8]) -> bool {
+        self.keys.contains(&key)
+    }
+
+    /// The contents of the string, or the digits of the number, given for
+    /// `key`
+    pub(crate) fn value(&self, key: &[u8]) -> Option<&[u8]> {
+        self.values.iter().find(|(k, _)| *k == key).map(|&(_, v)| v)
+    }
+}
+
+/// The string starting after the quote at `start`, and the index after its
+/// closing quote
+fn json_string(b: &[u8], start: usize) -> Option<(&[u8], usize)> {
+    let mut i = start;
+    while i < b.len() {
+        match b[i] {
+            b'"' => return Some((&b[start..i], i + 1)),
+            b'\\' => i += 2,
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Goes through an object as far as the data goes, which may end partway,
+/// noting the keys at the top level
+pub(crate) fn top_level(b: &[u8]) -> Option<TopLevel<'_>> {
+    let b = b.strip_prefix(b"\xef\xbb\xbf").unwrap_or(b);
+    let start = b.iter().position(|c| !c.is_ascii_whitespace())?;
+    if b[start] != b'{' {
+        return None;
+    }
+
+    let mut out = TopLevel::default();
+    let mut key: &[u8] = b"";
+    let mut depth = 1;
+    let mut i = start + 1;
+    while i < b.len() && depth > 0 {
+        match b[i] {
+            b'"' => {
+                let (s, end) = json_string(b, i + 1)?;
+                i = end;
+                if depth != 1 {
+                    continue;
+                }
+                let next = b[i..].iter().find(|c| !c.is_ascii_whitespace());
+                if next == Some(&b':') {
+                    key = s;
+                    out.keys.push(s);
+                } else {
+                    out.values.push((key, s));
+                }
+            }
+            b'0'..=b'9' if depth == 1 => {
+                let len = b[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+                out.values.push((key, &b[i..i + len]));
+                i += len;
+            }
+            b'{' | b'[' => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' | b']' => {
+                depth -= 1;
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    Some(out)
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    let b = match source.read_at(0, PROBE_LEN) {
+        Ok(b) => b,
+        Err(_) => return false,
+    };
+    let complete = b.len() < PROBE_LEN;
+    let b = b.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&b);
+
+    match mimetype {
+        "application/json" => is_json(b, complete, false),
+        "application/json5" => is_json(b, complete, true),
+        "application/x-ndjson" => is_ndjson(b, complete),
+        _ => false,
+    }
+}
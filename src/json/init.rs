@@ -0,0 +1,22 @@
+use crate::{FnvHashMap, MIME};
+use alloc::vec::Vec;
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    super::TYPES.iter().map(|&t| ("text/plain", t)).collect()
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/jsonl", "application/x-ndjson"),
+        ("application/jsonlines", "application/x-ndjson"),
+        ("application/x-jsonlines", "application/x-ndjson"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
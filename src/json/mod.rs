@@ -0,0 +1,10 @@
+//! Tells JSON, JSON5 and newline-delimited JSON apart from other text, by
+//! parsing the start of it
+const TYPES: [&str; 3] = [
+    "application/json",
+    "application/json5",
+    "application/x-ndjson",
+];
+
+pub mod check;
+pub mod init;
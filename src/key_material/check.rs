@@ -0,0 +1,251 @@
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct KeyMaterial;
+
+impl crate::Checker for KeyMaterial {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        let b = match source.read_at(0, PROBE_LEN) {
+            Ok(b) => b,
+            Err(_) => return false,
+        };
+        if mimetype == "application/x-pem-file" {
+            return pem_label(&b).is_some_and(|label| !label.starts_with(b"PGP "));
+        }
+        match material(&b) {
+            Some(found) => found == mimetype,
+            None => fdo_magic::builtin::check::from_source(db, source, mimetype),
+        }
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        Some(PROBE_LEN)
+    }
+
+    fn variant(&self, source: &dyn DataSource, _mimetype: &str) -> Option<&'static str> {
+        let b = source.read_at(0, PROBE_LEN).ok()?;
+        let label = pem_label(&b)?;
+        PEM_LABELS.iter().copied().find(|l| l.as_bytes() == label)
+    }
+}
+
+/// How much is looked at. The block may come after some text, such as the
+/// fields of a certificate written out.
+const PROBE_LEN: usize = 4096;
+
+/// Labels of PEM blocks with a type of their own
+pub(super) const PEM_TYPES: [(&str, MIME); 16] = [
+    ("CERTIFICATE", "application/pkix-cert+pem"),
+    ("X509 CERTIFICATE", "application/pkix-cert+pem"),
+    ("TRUSTED CERTIFICATE", "application/pkix-cert+pem"),
+    ("CA CERTIFICATE", "application/pkix-cert+pem"),
+    ("X509 CRL", "application/pkix-crl+pem"),
+    ("CERTIFICATE REQUEST", "application/pkcs10+pem"),
+    ("NEW CERTIFICATE REQUEST", "application/pkcs10+pem"),
+    ("PRIVATE KEY", "application/pkcs8+pem"),
+    ("ENCRYPTED PRIVATE KEY", "application/pkcs8+pem"),
+    ("PKCS7", "application/pkcs7-mime+pem"),
+    ("PKCS12", "application/pkcs12+pem"),
+    ("RSA PRIVATE KEY", "application/x-pem-key"),
+    ("DSA PRIVATE KEY", "application/x-pem-key"),
+    ("EC PRIVATE KEY", "application/x-pem-key"),
+    ("OPENSSH PRIVATE KEY", "application/x-pem-key"),
+    ("TSS2 PRIVATE KEY", "application/x-pem-key"),
+];
+
+/// Labels of PGP armor, which looks like PEM but isn't
+const PGP_TYPES: [(&str, MIME); 4] = [
+    ("PGP PUBLIC KEY BLOCK", "application/pgp-keys"),
+    ("PGP PRIVATE KEY BLOCK", "application/pgp-keys"),
+    ("PGP MESSAGE", "application/pgp-encrypted"),
+    ("PGP SIGNATURE", "application/pgp-signature"),
+];
+
+/// Labels reported by `variant`, those of RFC 7468 and others in common use
+const PEM_LABELS: [&str; 27] = [
+    "CERTIFICATE",
+    "X509 CERTIFICATE",
+    "TRUSTED CERTIFICATE",
+    "CA CERTIFICATE",
+    "X509 CRL",
+    "CERTIFICATE REQUEST",
+    "NEW CERTIFICATE REQUEST",
+    "PRIVATE KEY",
+    "ENCRYPTED PRIVATE KEY",
+    "PUBLIC KEY",
+    "RSA PRIVATE KEY",
+    "RSA PUBLIC KEY",
+    "DSA PRIVATE KEY",
+    "EC PRIVATE KEY",
+    "EC PARAMETERS",
+    "DH PARAMETERS",
+    "OPENSSH PRIVATE KEY",
+    "PKCS7",
+    "PKCS12",
+    "CMS",
+    "ATTRIBUTE CERTIFICATE",
+    "PGP PUBLIC KEY BLOCK",
+    "PGP PRIVATE KEY BLOCK",
+    "PGP MESSAGE",
+    "PGP SIGNATURE",
+    "TSS2 PRIVATE KEY",
+    "X9.42 DH PARAMETERS",
+];
+
+const PEM_BEGIN: &[u8] = b"-----BEGIN ";
+const PEM_DASHES: &[u8] = b"-----";
+
+fn trim(b: &[u8]) -> &[u8] {
+    let start = b.iter().position(|c| !c.is_ascii_whitespace());
+    let end = b.iter().rposition(|c| !c.is_ascii_whitespace());
+    match (start, end) {
+        (Some(s), Some(e)) => &b[s..=e],
+        _ => &[],
+    }
+}
+
+fn is_base64(line: &[u8]) -> bool {
+    line.iter()
+        .all(|&c| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'/' | b'='))
+}
+
+/// The label of the first PEM or PGP armored block, whose `BEGIN` line must
+/// be followed by base64 or by headers such as `Proc-Type:`
+fn pem_label(b: &[u8]) -> Option<&[u8]> {
+    let mut lines = b.split(|&c| c == b'\n').map(trim);
+    let label = lines.by_ref().find_map(|line| {
+        let label = line.strip_prefix(PEM_BEGIN)?.strip_suffix(PEM_DASHES)?;
+        let ok = !label.is_empty()
+            && label
+                .iter()
+                .all(|&c| (c.is_ascii_graphic() && c != b'-') || c == b' ');
+        ok.then_some(label)
+    })?;
+    let next = lines.find(|line| !line.is_empty())?;
+    let header = next.iter().position(|&c| c == b':').is_some_and(|n| {
+        n > 0
+            && next[..n]
+                .iter()
+                .all(|&c| c.is_ascii_alphanumeric() || c == b'-')
+    });
+    (header || is_base64(next)).then_some(label)
+}
+
+/// The tag of a DER element, its contents and what follows it
+fn der(b: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let tag = *b.first()?;
+    let first = *b.get(1)?;
+    let (len, start) = match first {
+        0..=0x7f => (first as usize, 2),
+        // Lengths of up to four bytes, and no indefinite ones
+        0x81..=0x84 => {
+            let n = (first & 0x7f) as usize;
+            let bytes = b.get(2..2 + n)?;
+            (
+                bytes.iter().fold(0usize, |len, &c| len << 8 | c as usize),
+                2 + n,
+            )
+        }
+        _ => return None,
+    };
+    let contents = &b[start..];
+    // The data may have been cut off within the element
+    let (contents, rest) = contents.split_at(len.min(contents.len()));
+    Some((tag, contents, rest))
+}
+
+/// An X.509 certificate: a sequence starting with the sequence of signed
+/// fields, which starts with a version of 1 to 3, or, in version 1, the
+/// serial number and signature algorithm
+fn is_der_cert(b: &[u8]) -> bool {
+    let fields = match der(b) {
+        Some((0x30, c, _)) => match der(c) {
+            Some((0x30, fields, _)) => fields,
+            _ => return false,
+        },
+        _ => return false,
+    };
+    match der(fields) {
+        Some((0xa0, version, _)) => matches!(version, [0x02, 0x01, 0..=2]),
+        Some((0x02, _, rest)) => matches!(der(rest), Some((0x30, [0x06, ..], _))),
+        _ => false,
+    }
+}
+
+/// PKCS #7 data and signed data, one of which holds the contents of a
+/// PKCS #12 file
+const PKCS7_DATA: &[u8] = b"\x06\x09\x2a\x86\x48\x86\xf7\x0d\x01\x07\x01";
+const PKCS7_SIGNED_DATA: &[u8] = b"\x06\x09\x2a\x86\x48\x86\xf7\x0d\x01\x07\x02";
+
+/// A sequence of version 3, then the contents
+fn is_pkcs12(b: &[u8]) -> bool {
+    let pfx = match der(b) {
+        Some((0x30, c, _)) => c,
+        _ => return false,
+    };
+    match der(pfx) {
+        Some((0x02, [3], rest)) => match der(rest) {
+            Some((0x30, content, _)) => {
+                content.starts_with(PKCS7_DATA) || content.starts_with(PKCS7_SIGNED_DATA)
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// A public or secret key packet of OpenPGP, in the old or new format,
+/// holding a key of a known version and algorithm
+fn is_pgp_key_packet(b: &[u8]) -> bool {
+    let body = match b {
+        // Old format, with a one or two byte length
+        [0x98 | 0x94, _, body @ ..] => body,
+        [0x99 | 0x95, _, _, body @ ..] => body,
+        // New format, with a one or two byte length
+        [0xc6 | 0xc5, 0..=191, body @ ..] => body,
+        [0xc6 | 0xc5, 192..=223, _, body @ ..] => body,
+        _ => return false,
+    };
+    // Version, creation time, then the algorithm for version 4 and on
+    match body {
+        [2 | 3, ..] => true,
+        [4..=6, _, _, _, _, algorithm, ..] => {
+            matches!(algorithm, 1..=3 | 16..=19 | 22 | 25..=28)
+        }
+        _ => false,
+    }
+}
+
+/// The type of the certificate or key, or of the first PEM block
+fn material(b: &[u8]) -> Option<MIME> {
+    if let Some(label) = pem_label(b) {
+        let types = PEM_TYPES.iter().chain(PGP_TYPES.iter());
+        return Some(
+            types
+                .copied()
+                .find(|(l, _)| l.as_bytes() == label)
+                .map_or("application/x-pem-file", |(_, t)| t),
+        );
+    }
+    if is_der_cert(b) {
+        Some("application/pkix-cert")
+    } else if is_pkcs12(b) {
+        Some("application/pkcs12")
+    } else if is_pgp_key_packet(b) {
+        Some("application/pgp-keys")
+    } else {
+        None
+    }
+}
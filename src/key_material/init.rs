@@ -0,0 +1,37 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+///
+/// OpenPGP keys can be armored text or binary, so they're placed under both.
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    let mut out = vec![
+        ("text/plain", "application/x-pem-file"),
+        ("application/octet-stream", "application/pgp-keys"),
+    ];
+    out.extend(
+        super::check::PEM_TYPES
+            .iter()
+            .map(|&(_, t)| ("application/x-pem-file", t)),
+    );
+    out
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/x-x509-cert", "application/pkix-cert"),
+        ("application/x-x509-user-cert", "application/pkix-cert"),
+        ("application/x-pkcs12", "application/pkcs12"),
+        (
+            "application/pem-certificate-chain",
+            "application/x-pem-file",
+        ),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
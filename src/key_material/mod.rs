@@ -0,0 +1,20 @@
+//! Handles certificates and keys, whether PEM or PGP armored text or binary
+//! DER and OpenPGP packets
+const TYPES: [&str; 13] = [
+    "application/x-pem-file",
+    "application/pkix-cert+pem",
+    "application/pkix-crl+pem",
+    "application/pkcs10+pem",
+    "application/pkcs8+pem",
+    "application/pkcs7-mime+pem",
+    "application/pkcs12+pem",
+    "application/x-pem-key",
+    "application/pkix-cert",
+    "application/pkcs12",
+    "application/pgp-keys",
+    "application/pgp-encrypted",
+    "application/pgp-signature",
+];
+
+pub mod check;
+pub mod init;
@@ -39,6 +39,29 @@
 //! **As the magic database files themselves are licensed under the GPL, you must
 //! make sure your project uses a compatible license if you enable this behaviour.**
 //!
+//! For offline use without GPL data, the `builtin-minimal` feature embeds a
+//! smaller set of rules written for this crate and covered by its own
+//! license instead. It knows fewer types than the shared MIME-info database,
+//! mostly common image, audio, video, document and archive formats, on top of
+//! the types this crate's own checkers detect. `with-gpl-data` takes
+//! precedence if both are enabled.
+//!
+//! Embedded data is turned into static tables when the crate is built, so
+//! none of it is parsed at run time.
+//!
+//! ## `no_std` support
+//!
+//! The matching engine only needs `alloc`. Disable the default `std` feature
+//! and enable `with-gpl-data` or `builtin-minimal` to use it without the
+//! standard library:
+//!
+//! ```toml
+//! tree_magic_mini = { version = "3", default-features = false, features = ["with-gpl-data"] }
+//! ```
+//!
+//! Without `std`, only in-memory input is supported: `from_u8`, `match_u8` and
+//! the `DataSource` based functions. Filepath APIs and `FileSource` are unavailable.
+//!
 //! # Example
 //! ```rust
 //! // Load a GIF file
@@ -54,21 +77,153 @@
 //! ```
 
 #![allow(unused_doc_comments)]
+#![no_std]
+
+#[cfg(not(any(
+    feature = "std",
+    feature = "with-gpl-data",
+    feature = "builtin-minimal"
+)))]
+compile_error!(
+    "Without the `std` feature, the `with-gpl-data` or `builtin-minimal` feature is required."
+);
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
-use fnv::FnvHashMap;
-use fnv::FnvHashSet;
+#[cfg(feature = "std")]
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::Range;
 use lazy_static::lazy_static;
-use petgraph::prelude::*;
+#[cfg(feature = "std")]
 use std::path::Path;
 
+mod accept;
+#[cfg(feature = "archive")]
+mod archive;
+mod archive_formats;
+mod arena;
+mod audio;
 mod basetype;
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench;
+mod bytecode;
+#[cfg(all(feature = "std", unix))]
+mod cache;
+mod compliance;
+mod config_formats;
+mod data_formats;
+mod database;
+mod delimited;
+mod detector;
+mod diff;
+#[cfg(all(feature = "unix-io", unix))]
+mod dirfd;
+mod disc_image;
+mod disk_image;
+mod ebook;
+mod executables;
+mod export;
+mod family;
 mod fdo_magic;
+mod filesystem;
+mod footprint;
+mod game_data;
+mod geospatial;
+mod glob;
+#[cfg(feature = "std")]
+mod info;
+mod json;
+mod key_material;
+mod lint;
+mod mail;
+mod matcher;
+mod media_text;
+mod models;
+mod oci;
+mod ogg;
+mod ole;
+mod options;
+mod packages;
+mod pdf;
+mod perfect;
+#[cfg(all(feature = "registry", windows))]
+pub mod registry;
+#[cfg(feature = "remote")]
+pub mod remote;
+mod riff;
+mod scan;
+mod scientific;
+mod sensitivity;
+mod sfnt;
+mod source;
+mod source_code;
+mod sqlite;
+mod stats;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "std")]
+mod symlink;
+mod tar;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod tiff;
+#[cfg(feature = "std")]
+mod treemagic;
+#[cfg(feature = "uti")]
+pub mod uti;
+#[cfg(feature = "walk")]
+pub mod walk;
+#[cfg(feature = "web")]
+pub mod web;
+#[cfg(all(feature = "unix-io", any(target_os = "linux", target_os = "macos")))]
+mod xattr;
+#[cfg(feature = "std")]
+mod xdg;
+mod zip;
 
+#[cfg(all(feature = "std", unix))]
+pub use cache::DetectionCache;
+pub use compliance::{compliance, SpecFeature};
+pub use database::{DatabaseError, TypeDatabase, TypeDatabaseBuilder};
+pub use detector::{Detector, DetectorBuilder, ResultHook, Strategy};
+pub use diff::{DatabaseDiff, DatabaseEntries};
+pub use export::{export_graph, GraphFormat};
+pub use lint::Lint;
+pub use matcher::TypeMatcher;
+pub use options::DetectOptions;
+pub use scan::ScanOptions;
+#[cfg(feature = "std")]
+pub use source::FileSource;
+pub use source::{DataSource, MultiSlice, ReadError};
+pub use stats::{Stats, StatsHook};
+#[cfg(feature = "std")]
+pub use symlink::FollowedPath;
+
+#[allow(clippy::upper_case_acronyms)]
 type MIME = &'static str;
 
-/// Check these types first
+/// Hasher of every map and set, picked by the `ahash` and `std-hasher`
+/// features. The maps keep their FNV names, as that's the default.
+#[cfg(feature = "ahash")]
+type BuildHasher = core::hash::BuildHasherDefault<ahash::AHasher>;
+#[cfg(all(feature = "std-hasher", not(feature = "ahash")))]
+type BuildHasher = std::collections::hash_map::RandomState;
+#[cfg(not(any(feature = "ahash", feature = "std-hasher")))]
+type BuildHasher = fnv::FnvBuildHasher;
+
+type FnvHashMap<K, V> = hashbrown::HashMap<K, V, BuildHasher>;
+type FnvHashSet<T> = hashbrown::HashSet<T, BuildHasher>;
+
+/// Check these types first, in this order, before their other siblings
 /// TODO: Poll these from the checkers? Feels a bit arbitrary
-const TYPEORDER: [&str; 6] = [
+const TYPEORDER: [&str; 7] = [
+    "application/x-zerosize",
     "image/png",
     "image/jpeg",
     "image/gif",
@@ -77,224 +232,237 @@ const TYPEORDER: [&str; 6] = [
     "application/pdf",
 ];
 
+#[allow(clippy::wrong_self_convention)]
 pub(crate) trait Checker: Send + Sync {
-    fn from_u8(&self, file: &[u8], mimetype: &str) -> bool;
-    fn from_filepath(&self, filepath: &Path, mimetype: &str) -> bool;
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool;
     fn get_supported(&self) -> Vec<MIME>;
     fn get_subclasses(&self) -> Vec<(MIME, MIME)>;
     fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME>;
-}
 
-static CHECKERS: &[&'static dyn Checker] = &[
-    &fdo_magic::builtin::check::FdoMagic,
-    &basetype::check::BaseType,
-];
+    /// How many bytes from the start `from_source` reads for the type, or
+    /// `None` if it also reads elsewhere, such as at offsets found in the data
+    #[allow(unused_variables)]
+    fn needed_len(&self, db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        None
+    }
 
-/// Mappings between modules and supported mimes
-lazy_static! {
-    static ref CHECKER_SUPPORT: FnvHashMap<MIME, &'static dyn Checker> = {
-        let mut out = FnvHashMap::<MIME, &'static dyn Checker>::default();
-        for &c in CHECKERS {
-            for m in c.get_supported() {
-                out.insert(m, c);
-            }
-        }
-        out
-    };
+    /// Checker-specific variant of a matched type, if the checker knows it
+    #[allow(unused_variables)]
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        None
+    }
+
+    /// Whether `from_source` checks the magic rules of the type and nothing
+    /// else, so a [`TypeMatcher`] can check them directly
+    fn only_magic(&self) -> bool {
+        false
+    }
 }
 
-lazy_static! {
-    static ref ALIASES: FnvHashMap<MIME, MIME> = {
-        let mut out = FnvHashMap::<MIME, MIME>::default();
-        for &c in CHECKERS {
-            out.extend(c.get_aliaslist());
+/// Declares [`AnyChecker`], with a variant for each checker, and the list of
+/// all checkers in the order they're loaded
+macro_rules! checkers {
+    ($($name:ident => $checker:expr,)*) => {
+        /// One of the checkers. Calls go through a `match` instead of a
+        /// vtable, so the compiler can inline them, such as the magic rule
+        /// checks of most types.
+        #[derive(Clone, Copy)]
+        pub(crate) enum AnyChecker {
+            $($name,)*
         }
-        out
-    };
-}
 
-/// Information about currently loaded MIME types
-///
-/// The `graph` contains subclass relations between all given mimes.
-/// (EX: `application/json` -> `text/plain` -> `application/octet-stream`)
-/// This is a `petgraph` DiGraph, so you can walk the tree if needed.
-///
-/// The `hash` is a mapping between MIME types and nodes on the graph.
-/// The root of the graph is "all/all", so start traversing there unless
-/// you need to jump to a particular node.
-struct TypeStruct {
-    graph: DiGraph<MIME, u32>,
-}
+        static CHECKERS: &[AnyChecker] = &[$(AnyChecker::$name,)*];
 
-lazy_static! {
-    /// The TypeStruct autogenerated at library init, and used by the library.
-    static ref TYPE: TypeStruct = graph_init();
-}
+        impl Checker for AnyChecker {
+            #[inline]
+            fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+                match self {
+                    $(AnyChecker::$name => $checker.from_source(db, source, mimetype),)*
+                }
+            }
 
-// Initialize filetype graph
-fn graph_init() -> TypeStruct {
-    let mut graph = DiGraph::<MIME, u32>::new();
-    let mut added_mimes = FnvHashMap::<MIME, NodeIndex>::default();
+            fn get_supported(&self) -> Vec<MIME> {
+                match self {
+                    $(AnyChecker::$name => $checker.get_supported(),)*
+                }
+            }
 
-    // Get list of MIME types and MIME relations
-    let mut mimelist = Vec::<MIME>::new();
-    let mut edgelist_raw = Vec::<(MIME, MIME)>::new();
-    for &c in CHECKERS {
-        mimelist.extend(c.get_supported());
-        edgelist_raw.extend(c.get_subclasses());
-    }
-    mimelist.sort_unstable();
-    mimelist.dedup();
-    let mimelist = mimelist;
-
-    // Create all nodes
-    for mimetype in mimelist.iter() {
-        let node = graph.add_node(mimetype);
-        added_mimes.insert(mimetype, node);
-    }
+            fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+                match self {
+                    $(AnyChecker::$name => $checker.get_subclasses(),)*
+                }
+            }
 
-    let mut edge_list = FnvHashSet::<(NodeIndex, NodeIndex)>::with_capacity_and_hasher(
-        edgelist_raw.len(),
-        Default::default(),
-    );
-    for x in edgelist_raw {
-        let child_raw = x.0;
-        let parent_raw = x.1;
-
-        let parent = match added_mimes.get(&parent_raw) {
-            Some(node) => *node,
-            None => {
-                continue;
+            fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+                match self {
+                    $(AnyChecker::$name => $checker.get_aliaslist(),)*
+                }
             }
-        };
 
-        let child = match added_mimes.get(&child_raw) {
-            Some(node) => *node,
-            None => {
-                continue;
+            fn needed_len(&self, db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+                match self {
+                    $(AnyChecker::$name => $checker.needed_len(db, mimetype),)*
+                }
             }
-        };
 
-        edge_list.insert((child, parent));
-    }
+            fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+                match self {
+                    $(AnyChecker::$name => $checker.variant(source, mimetype),)*
+                }
+            }
 
-    graph.extend_with_edges(&edge_list);
-
-    //Add to applicaton/octet-stream, all/all, or text/plain, depending on top-level
-    //(We'll just do it here because having the graph makes it really nice)
-    let added_mimes_tmp = added_mimes.clone();
-    let node_text = match added_mimes_tmp.get("text/plain") {
-        Some(x) => *x,
-        None => {
-            let node = graph.add_node("text/plain");
-            added_mimes.insert("text/plain", node);
-            node
-        }
-    };
-    let node_octet = match added_mimes_tmp.get("application/octet-stream") {
-        Some(x) => *x,
-        None => {
-            let node = graph.add_node("application/octet-stream");
-            added_mimes.insert("application/octet-stream", node);
-            node
-        }
-    };
-    let node_allall = match added_mimes_tmp.get("all/all") {
-        Some(x) => *x,
-        None => {
-            let node = graph.add_node("all/all");
-            added_mimes.insert("all/all", node);
-            node
-        }
-    };
-    let node_allfiles = match added_mimes_tmp.get("all/allfiles") {
-        Some(x) => *x,
-        None => {
-            let node = graph.add_node("all/allfiles");
-            added_mimes.insert("all/allfiles", node);
-            node
+            #[inline]
+            fn only_magic(&self) -> bool {
+                match self {
+                    $(AnyChecker::$name => $checker.only_magic(),)*
+                }
+            }
         }
     };
+}
 
-    let mut edge_list_2 = FnvHashSet::<(NodeIndex, NodeIndex)>::default();
-    for mimenode in graph.externals(Incoming) {
-        let mimetype = &graph[mimenode];
-        let toplevel = mimetype.split('/').next().unwrap_or("");
-
-        if mimenode == node_text
-            || mimenode == node_octet
-            || mimenode == node_allfiles
-            || mimenode == node_allall
-        {
-            continue;
-        }
+checkers! {
+    FdoMagic => fdo_magic::builtin::check::FdoMagic,
+    Ole => ole::check::Ole,
+    Tar => tar::check::Tar,
+    Riff => riff::check::Riff,
+    Sfnt => sfnt::check::Sfnt,
+    Sqlite => sqlite::check::Sqlite,
+    Mail => mail::check::Mail,
+    Tiff => tiff::check::Tiff,
+    Bytecode => bytecode::check::Bytecode,
+    DataFormats => data_formats::check::DataFormats,
+    Oci => oci::check::Oci,
+    Executables => executables::check::Executables,
+    Pdf => pdf::check::Pdf,
+    Json => json::check::Json,
+    ConfigFormats => config_formats::check::ConfigFormats,
+    Delimited => delimited::check::Delimited,
+    SourceCode => source_code::check::SourceCode,
+    DiscImage => disc_image::check::DiscImage,
+    DiskImage => disk_image::check::DiskImage,
+    Filesystem => filesystem::check::Filesystem,
+    Audio => audio::check::Audio,
+    Ogg => ogg::check::Ogg,
+    MediaText => media_text::check::MediaText,
+    KeyMaterial => key_material::check::KeyMaterial,
+    Models => models::check::Models,
+    Geospatial => geospatial::check::Geospatial,
+    Scientific => scientific::check::Scientific,
+    GameData => game_data::check::GameData,
+    Ebook => ebook::check::Ebook,
+    ArchiveFormats => archive_formats::check::ArchiveFormats,
+    Packages => packages::check::Packages,
+    BaseType => basetype::check::BaseType,
+}
 
-        if toplevel == "text" {
-            edge_list_2.insert((node_text, mimenode));
-        } else if toplevel == "inode" {
-            edge_list_2.insert((node_allall, mimenode));
-        } else {
-            edge_list_2.insert((node_octet, mimenode));
-        }
-    }
-    // Don't add duplicate entries
-    graph.extend_with_edges(edge_list_2.difference(&edge_list));
-
-    TypeStruct { graph }
-}
-
-/// Just the part of from_*_node that walks the graph
-fn typegraph_walker<T, F>(parentnode: NodeIndex, input: &T, matchfn: F) -> Option<MIME>
-where
-    T: ?Sized,
-    F: Fn(&str, &T) -> bool,
-{
-    // Pull most common types towards top
-    let mut children: Vec<NodeIndex> = TYPE
-        .graph
-        .neighbors_directed(parentnode, Outgoing)
-        .collect();
-
-    for i in 0..children.len() {
-        let x = children[i];
-        if TYPEORDER.contains(&&*TYPE.graph[x]) {
-            children.remove(i);
-            children.insert(0, x);
-        }
-    }
+lazy_static! {
+    /// The database of system types, used by the free functions unless
+    /// another one is set.
+    static ref DATABASE: TypeDatabase = TypeDatabase::default();
 
-    // Walk graph
-    for childnode in children {
-        let mimetype = &TYPE.graph[childnode];
-
-        let result = matchfn(mimetype, input);
-        match result {
-            true => match typegraph_walker(childnode, input, matchfn) {
-                Some(foundtype) => return Some(foundtype),
-                None => return Some(mimetype),
-            },
-            false => continue,
-        }
+    /// Detector of [`guess_content_type`], with the system globs loaded
+    static ref GIO_DETECTOR: Detector = Detector::builder().build();
+}
+
+/// The database set by [`set_global_database`], if any
+#[cfg(feature = "std")]
+static GLOBAL_DATABASE: arc_swap::ArcSwapOption<&'static TypeDatabase> =
+    arc_swap::ArcSwapOption::const_empty();
+
+#[cfg(feature = "std")]
+lazy_static! {
+    /// Every database set by [`set_global_database`] so far, each leaked
+    /// once, by the address of its `Arc`
+    static ref LEAKED_DATABASES: std::sync::Mutex<FnvHashMap<usize, &'static TypeDatabase>> =
+        std::sync::Mutex::new(FnvHashMap::default());
+}
+
+/// The database used by the free functions
+fn database() -> &'static TypeDatabase {
+    #[cfg(feature = "std")]
+    if let Some(&db) = GLOBAL_DATABASE.load().as_deref() {
+        return db;
     }
+    &DATABASE
+}
+
+/// Sets the database used by the free functions, such as [`from_u8`] and
+/// [`match_u8`], in place of the system database.
+///
+/// This is a one-time setup call, made at startup, that lets code calling the
+/// free functions use a custom database without being changed to call it
+/// directly. It's safe to call from any thread; calls already running finish
+/// with the database they started with.
+///
+/// # Memory
+/// The database is leaked: it's never freed, even after another database
+/// replaces it or [`reset_global_database`] is called. The free functions
+/// return types as `&'static str`, which may be names only the given
+/// database knows, so it must outlive every result. Each new database set
+/// here stays in memory until the process exits, so don't call this on
+/// every configuration reload; keep the database in your own `Arc` and call
+/// its methods, or use a [`Detector`] built with
+/// [`DetectorBuilder::database`], instead. Setting a database that was set
+/// before, through a clone of the same `Arc`, doesn't leak it again.
+///
+/// # Examples
+/// ```rust
+/// use std::sync::Arc;
+/// use tree_magic_mini::TypeDatabase;
+///
+/// let db = TypeDatabase::builder()
+///     .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+///     .unwrap()
+///     .build();
+/// tree_magic_mini::set_global_database(Arc::new(db));
+/// assert_eq!(tree_magic_mini::from_u8(b"FOObar"), "application/x-foo");
+///
+/// tree_magic_mini::reset_global_database();
+/// assert_eq!(tree_magic_mini::from_u8(b"FOObar"), "text/plain");
+/// ```
+#[cfg(feature = "std")]
+pub fn set_global_database(db: Arc<TypeDatabase>) {
+    let mut leaked = LEAKED_DATABASES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let db: &'static TypeDatabase = leaked
+        .entry(Arc::as_ptr(&db) as usize)
+        .or_insert_with(|| &**alloc::boxed::Box::leak(alloc::boxed::Box::new(db)));
+    GLOBAL_DATABASE.store(Some(Arc::new(db)));
+}
 
-    None
+/// Goes back to the system database for the free functions, after
+/// [`set_global_database`].
+#[cfg(feature = "std")]
+pub fn reset_global_database() {
+    GLOBAL_DATABASE.store(None);
 }
 
 /// Transforms an alias into it's real type
+#[cfg(feature = "testing")]
 fn get_alias(mimetype: &str) -> &str {
-    match ALIASES.get(mimetype) {
-        Some(x) => x,
-        None => mimetype,
-    }
+    database().get_alias(mimetype)
 }
 
-/// Internal function. Checks if an alias exists, and if it does,
-/// then runs `from_u8`.
-fn match_u8_noalias(mimetype: &str, bytes: &[u8]) -> bool {
-    match CHECKER_SUPPORT.get(mimetype) {
-        None => false,
-        Some(y) => y.from_u8(bytes, mimetype),
-    }
+/// Checks if the given data source matches the given MIME type.
+///
+/// Returns true or false if it matches or not. If the given MIME type is not known,
+/// the function will always return false.
+/// If mimetype is an alias of a known MIME, the source will be checked agains that MIME.
+/// Case and parameters of the MIME type are ignored.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// // Check if the MIME and the data are a match
+/// let result = tree_magic_mini::match_source("image/gif", &input);
+/// assert_eq!(result, true);
+/// ```
+pub fn match_source(mimetype: &str, source: &dyn DataSource) -> bool {
+    database().match_source(mimetype, source)
 }
 
 /// Checks if the given bytestream matches the given MIME type.
@@ -302,6 +470,8 @@ fn match_u8_noalias(mimetype: &str, bytes: &[u8]) -> bool {
 /// Returns true or false if it matches or not. If the given MIME type is not known,
 /// the function will always return false.
 /// If mimetype is an alias of a known MIME, the file will be checked agains that MIME.
+/// Case and parameters are ignored, so a value taken from a `Content-Type`
+/// header such as `IMAGE/GIF; q=0.8` can be passed as is.
 ///
 /// # Examples
 /// ```rust
@@ -313,27 +483,54 @@ fn match_u8_noalias(mimetype: &str, bytes: &[u8]) -> bool {
 /// assert_eq!(result, true);
 /// ```
 pub fn match_u8(mimetype: &str, bytes: &[u8]) -> bool {
-    match_u8_noalias(get_alias(mimetype), bytes)
+    database().match_u8(mimetype, bytes)
 }
 
-/// Gets the type of a file from a raw bytestream, starting at a certain node
-/// in the type graph.
+/// Looks up a type once, to check many inputs against it.
 ///
-/// Returns MIME as string wrapped in Some if a type matches, or
-/// None if no match is found under the given node.
-/// Retreive the node from the `TYPE.hash` HashMap, using the MIME as the key.
+/// Returns `None` if the MIME type is not known. Aliases, case and
+/// parameters are handled as in [`match_u8`], which this saves repeating
+/// for every input.
+///
+/// # Examples
+/// ```rust
+/// let zip = tree_magic_mini::matcher("application/x-zip-compressed").unwrap();
+/// assert_eq!(zip.mimetype(), "application/zip");
+///
+/// let input: &[u8] = include_bytes!("../tests/application/zip");
+/// assert!(zip.matches_u8(input));
+/// ```
+pub fn matcher(mimetype: &str) -> Option<TypeMatcher<'static>> {
+    database().matcher(mimetype)
+}
+
+/// Gets the type of a data source.
+///
+/// Checkers only read the parts of the source they need, so this works for
+/// sources too large (or too slow) to load completely. Returns MIME as string
+/// wrapped in Some if a type matches, or None otherwise.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "std")] {
+/// use std::path::Path;
+/// use tree_magic_mini::FileSource;
 ///
-/// # Panics
-/// Will panic if the given node is not found in the graph.
-/// As the graph is immutable, this should not happen if the node index comes from
-/// TYPE.hash.
-fn from_u8_node(parentnode: NodeIndex, bytes: &[u8]) -> Option<MIME> {
-    typegraph_walker(parentnode, bytes, match_u8_noalias)
+/// // Open a GIF file
+/// let source = FileSource::open(Path::new("tests/image/gif")).unwrap();
+///
+/// // Find the MIME type of the GIF
+/// let result = tree_magic_mini::from_source(&source);
+/// assert_eq!(result, Some("image/gif"));
+/// # }
+/// ```
+pub fn from_source(source: &dyn DataSource) -> Option<MIME> {
+    database().from_source(source)
 }
 
 /// Gets the type of a file from a byte stream.
 ///
-/// Returns MIME as string.
+/// Returns MIME as string. Empty input is `application/x-zerosize`.
 ///
 /// # Examples
 /// ```rust
@@ -345,26 +542,358 @@ fn from_u8_node(parentnode: NodeIndex, bytes: &[u8]) -> Option<MIME> {
 /// assert_eq!(result, "image/gif");
 /// ```
 pub fn from_u8(bytes: &[u8]) -> MIME {
-    let node = match TYPE.graph.externals(Incoming).next() {
-        Some(foundnode) => foundnode,
-        None => panic!("No filetype definitions are loaded."),
-    };
-    from_u8_node(node, bytes).unwrap()
+    database().from_u8(bytes)
+}
+
+/// Gets the type of a file from a byte stream, followed by every type it's a
+/// subclass of.
+///
+/// The chain runs from the detected type up to the most general one,
+/// usually `application/octet-stream`, leaving out the `all/*` types above
+/// it. Where a type has several parents, the one the data matches is given,
+/// so the chain follows the path detection took through the type graph.
+///
+/// # Examples
+/// ```rust
+/// let input: &[u8] = include_bytes!("../tests/text/vcard");
+///
+/// let lineage = tree_magic_mini::from_u8_with_lineage(input);
+/// assert_eq!(lineage, ["text/vcard", "text/plain", "application/octet-stream"]);
+/// ```
+pub fn from_u8_with_lineage(bytes: &[u8]) -> Vec<MIME> {
+    database().from_u8_with_lineage(bytes)
+}
+
+/// Guesses a type from a file name, the start of the data, or both, the way
+/// GIO's `g_content_type_guess` does. Also returns whether the guess is
+/// uncertain, as GIO does when the name and data don't settle on a type.
+///
+/// Uses the system globs. See
+/// [`Detector::guess_content_type`] for how the guess is made, and for using
+/// other globs.
+///
+/// # Examples
+/// ```rust
+/// let gif: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// assert_eq!(tree_magic_mini::guess_content_type(None, Some(gif)), ("image/gif", false));
+/// assert_eq!(tree_magic_mini::guess_content_type(Some("photos/"), None), ("inode/directory", false));
+/// assert_eq!(
+///     tree_magic_mini::guess_content_type(None, None),
+///     ("application/octet-stream", true)
+/// );
+/// ```
+pub fn guess_content_type(filename: Option<&str>, data: Option<&[u8]>) -> (MIME, bool) {
+    GIO_DETECTOR.guess_content_type(filename, data)
+}
+
+/// Gets the type of a single member of a ZIP or TAR archive, without
+/// extracting the rest of the archive.
+///
+/// Only the start of the member is read, and decompressed if needed. Stored
+/// and deflated ZIP members are supported, as are GNU long names and ustar
+/// prefixes in TAR archives. Returns `None` if `archive` isn't a ZIP or TAR
+/// archive, or has no member named `member`.
+///
+/// Requires the `archive` feature.
+///
+/// # Examples
+/// ```rust
+/// let input: &[u8] = include_bytes!("../tests/application/zip-members");
+///
+/// let result = tree_magic_mini::from_archive_member(input, "pics/dog.png");
+/// assert_eq!(result, Some("image/png"));
+/// ```
+#[cfg(feature = "archive")]
+pub fn from_archive_member(archive: &[u8], member: &str) -> Option<MIME> {
+    database().from_archive_member(archive, member)
+}
+
+/// Gets the type of a data source, with the given options.
+///
+/// With default options this is the same as [`from_source`].
+pub fn detect_source(source: &dyn DataSource, options: &DetectOptions) -> Option<MIME> {
+    database().detect(source, options)
+}
+
+/// Gets the type of a file from a byte stream, with the given options.
+///
+/// Returns `None` if nothing matches, which with the default options only
+/// happens for types the options map to `None`.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::DetectOptions;
+///
+/// let opts = DetectOptions::new().text_fallback(Some("text/x-unknown"));
+/// assert_eq!(tree_magic_mini::detect_u8(b"hello", &opts), Some("text/x-unknown"));
+///
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+/// assert_eq!(tree_magic_mini::detect_u8(input, &opts), Some("image/gif"));
+/// ```
+pub fn detect_u8(bytes: &[u8], options: &DetectOptions) -> Option<MIME> {
+    database().detect(&bytes, options)
+}
+
+/// Gets the type of a data source, with the given options, while counting
+/// the work done.
+///
+/// `stats` hears about every type checked and every read from the source,
+/// for finding slow rules or tuning the walk order. Pass a [`Stats`] to add
+/// everything up, or your own [`StatsHook`] for more detail. Detection
+/// stays sequential while counting, even with the `rayon` feature.
+///
+/// See [`Stats`] for an example.
+pub fn detect_with_stats(
+    source: &dyn DataSource,
+    options: &DetectOptions,
+    stats: &dyn StatsHook,
+) -> Option<MIME> {
+    database().detect_with_stats(source, options, stats)
+}
+
+/// Gets the type of data split into several consecutive slices.
+///
+/// This gives the same result as [`from_u8`] on the concatenated slices,
+/// without copying them unless a check reads across a slice boundary.
+///
+/// # Examples
+/// ```rust
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+/// let (head, tail) = input.split_at(3);
+///
+/// let result = tree_magic_mini::from_u8_multi(&[head, tail]);
+/// assert_eq!(result, "image/gif");
+/// ```
+pub fn from_u8_multi(parts: &[&[u8]]) -> MIME {
+    database().from_source(&MultiSlice::new(parts)).unwrap()
+}
+
+/// Checks if data split into several consecutive slices matches the given
+/// MIME type.
+///
+/// # Examples
+/// ```rust
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+/// let (head, tail) = input.split_at(3);
+///
+/// let result = tree_magic_mini::match_u8_multi("image/gif", &[head, tail]);
+/// assert_eq!(result, true);
+/// ```
+pub fn match_u8_multi(mimetype: &str, parts: &[&[u8]]) -> bool {
+    database().match_source(mimetype, &MultiSlice::new(parts))
+}
+
+/// Checks if the given bytestream is an image, of any type.
+///
+/// Only the topmost `image/*` types are checked, which is much faster than
+/// finding the exact type with [`from_u8`].
+///
+/// # Examples
+/// ```rust
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+/// assert!(tree_magic_mini::is_image_u8(input));
+/// assert!(!tree_magic_mini::is_audio_u8(input));
+/// ```
+pub fn is_image_u8(bytes: &[u8]) -> bool {
+    database().match_family(family::Family::Image, &bytes)
 }
 
-/// Internal function. Checks if an alias exists, and if it does,
-/// then runs `from_filepath`.
-fn match_filepath_noalias(mimetype: &str, filepath: &Path) -> bool {
-    match CHECKER_SUPPORT.get(mimetype) {
-        None => false,
-        Some(c) => c.from_filepath(&filepath, mimetype),
+/// Checks if the given bytestream is a video, of any type.
+///
+/// Only the topmost `video/*` types are checked, which is much faster than
+/// finding the exact type with [`from_u8`].
+pub fn is_video_u8(bytes: &[u8]) -> bool {
+    database().match_family(family::Family::Video, &bytes)
+}
+
+/// Checks if the given bytestream is audio, of any type.
+///
+/// Only the topmost `audio/*` types are checked, which is much faster than
+/// finding the exact type with [`from_u8`].
+pub fn is_audio_u8(bytes: &[u8]) -> bool {
+    database().match_family(family::Family::Audio, &bytes)
+}
+
+/// Checks if the given bytestream is an archive or compressed file, such as
+/// ZIP, tar, gzip or 7-Zip.
+///
+/// Formats built on top of an archive format, such as OpenDocument files
+/// (ZIP), count as archives too.
+pub fn is_archive_u8(bytes: &[u8]) -> bool {
+    database().match_family(family::Family::Archive, &bytes)
+}
+
+/// Gets every top-level type the given bytestream matches, sorted by name.
+///
+/// Unlike [`from_u8`], which stops at the first type that matches, this
+/// checks all types directly below `application/octet-stream` and
+/// `text/plain`, without walking down to their subclasses. More than one
+/// result means the data is valid as several unrelated formats at once (EX: a
+/// file that is both a ZIP archive and a PDF), which is a common way to
+/// smuggle content past upload filters.
+///
+/// # Examples
+/// ```rust
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+/// assert_eq!(tree_magic_mini::detect_polyglot(input), vec!["image/gif"]);
+/// ```
+pub fn detect_polyglot(bytes: &[u8]) -> Vec<MIME> {
+    database().detect_polyglot(&bytes)
+}
+
+/// Finds files embedded within a larger blob, such as thumbnails inside
+/// images or archives appended to executables.
+///
+/// Looks for the magic signatures of known types at every offset past the
+/// start of `bytes`, within the bounds set by `options`. Returns the offset
+/// and type of every file found, in order. Use [`from_u8`] for the type of
+/// the blob itself.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::ScanOptions;
+///
+/// let gif: &[u8] = include_bytes!("../tests/image/gif");
+/// let mut blob = vec![0; 100];
+/// blob.extend_from_slice(gif);
+///
+/// let found = tree_magic_mini::scan_embedded(&blob, &ScanOptions::new());
+/// assert_eq!(found[0], (100, "image/gif"));
+/// ```
+pub fn scan_embedded(bytes: &[u8], options: &ScanOptions) -> Vec<(usize, MIME)> {
+    database().scan_embedded(bytes, options)
+}
+
+/// Details about how a MIME type was detected, as returned by [`explain_u8`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// The detected MIME type, identical to what [`from_u8`] returns.
+    pub mime: MIME,
+    /// The variant of the detected type reported by its checker, if any.
+    /// (EX: `"gnu"`, `"ustar"`, `"pax"` or `"v7"` for `application/x-tar`)
+    pub variant: Option<&'static str>,
+    /// Byte ranges of the input that satisfied the magic rules of the
+    /// detected type, in the order they were checked. Empty if no magic rule
+    /// matched, such as for types found by a structured checker.
+    pub evidence: Vec<Range<usize>>,
+}
+
+/// Gets the type of a file from a byte stream, along with any extra detail
+/// the responsible checker can give about it.
+///
+/// # Examples
+/// ```rust
+/// // Load a GIF file
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// let result = tree_magic_mini::explain_u8(input);
+/// assert_eq!(result.mime, "image/gif");
+/// assert_eq!(result.variant, None);
+///
+/// // Where the GIF signature was found
+/// let range = result.evidence[0].clone();
+/// assert!(input[range].starts_with(b"GIF8"));
+/// ```
+pub fn explain_u8(bytes: &[u8]) -> Explanation {
+    let db = database();
+    let mime = db.from_u8(bytes);
+    let variant = db.checker(mime).and_then(|c| c.variant(&bytes, mime));
+    let evidence = db.evidence(bytes, mime);
+    Explanation {
+        mime,
+        variant,
+        evidence,
     }
 }
 
+/// Finds the byte ranges of a byte stream that the detected type hinges on,
+/// along with the type detected instead when they're altered.
+///
+/// Each byte the detection reads is altered in turn, by flipping its lowest
+/// and then its highest bit, and the stream detected again. Runs of bytes
+/// leading to the same other type are reported as one range. This is slow,
+/// detecting the stream once for every byte read, and meant for finding out
+/// how little it takes to disguise a file as another type.
+///
+/// The [`evidence`](Explanation::evidence) of a type found by its magic rules
+/// usually lies within the ranges found.
+///
+/// # Examples
+/// ```rust
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+///
+/// let ranges = tree_magic_mini::sensitivity(input);
+/// // Altering the signature turns the image into something else
+/// assert_eq!(ranges[0].0.start, 0);
+/// assert_ne!(ranges[0].1, "image/gif");
+/// ```
+pub fn sensitivity(bytes: &[u8]) -> Vec<(Range<usize>, MIME)> {
+    database().sensitivity(bytes)
+}
+
+/// Picks the type to send as `Content-Type` for a detected type, from the
+/// value of an HTTP `Accept` header.
+///
+/// The detected type is offered first, then the types it's a subclass of,
+/// nearest first, so a client that only accepts a parent type, such as
+/// `text/plain` for source code, gets that. Each is given the quality of the
+/// most specific media range naming it, `type/subtype` before `type/*`
+/// before `*/*`, and the first with the highest quality above zero wins.
+/// Aliases name the type they stand for, and a type accepted under an alias
+/// is returned as the client wrote it.
+///
+/// Returns `None` if the client accepts none of them, where a server would
+/// answer `406 Not Acceptable`. An empty header accepts the detected type.
+///
+/// # Examples
+/// ```rust
+/// let svg = "image/svg+xml";
+/// assert_eq!(tree_magic_mini::negotiate("image/*", svg), Some(svg));
+/// // SVG is XML
+/// assert_eq!(
+///     tree_magic_mini::negotiate("text/html, application/xml;q=0.9", svg),
+///     Some("application/xml")
+/// );
+/// assert_eq!(tree_magic_mini::negotiate("text/html", svg), None);
+/// ```
+pub fn negotiate(accept_header: &str, detected: MIME) -> Option<MIME> {
+    database().negotiate(accept_header, detected)
+}
+
+/// Checks the loaded database for magic rules and aliases that can't work,
+/// such as custom definitions before they're deployed.
+///
+/// Reports rules with empty patterns, identical rules given for more than
+/// one type, types detected by magic alone without any rule that can match,
+/// and aliases leading back to themselves. Lints come sorted by kind, and
+/// then by type.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::{Lint, TypeDatabase};
+///
+/// let db = TypeDatabase::builder()
+///     .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+///     .unwrap()
+///     .magic(b"MIME-Magic\0\n[50:application/x-bar]\n>0=\0\x03FOO\n")
+///     .unwrap()
+///     .build();
+///
+/// assert!(db.validate().contains(&Lint::DuplicateRule {
+///     mimetypes: vec!["application/x-bar", "application/x-foo"],
+///     rule: ">0=FOO".to_string(),
+/// }));
+/// ```
+pub fn validate_database() -> Vec<Lint<'static>> {
+    database().validate()
+}
+
 /// Check if the given filepath matches the given MIME type.
 ///
-/// Returns true or false if it matches or not, or an Error if the file could
-/// not be read. If the given MIME type is not known, it will always return false.
+/// Returns true or false if it matches or not. If the file could not be read,
+/// or the given MIME type is not known, it will always return false.
+/// Case and parameters of the MIME type are ignored.
 ///
 /// # Examples
 /// ```rust
@@ -377,48 +906,43 @@ fn match_filepath_noalias(mimetype: &str, filepath: &Path) -> bool {
 /// let result = tree_magic_mini::match_filepath("image/gif", path);
 /// assert_eq!(result, true);
 /// ```
+#[cfg(feature = "std")]
 pub fn match_filepath(mimetype: &str, filepath: &Path) -> bool {
-    match_filepath_noalias(get_alias(mimetype), filepath)
+    database().match_filepath(mimetype, filepath)
 }
 
-/// Gets the type of a file from a filepath, starting at a certain node
-/// in the type graph.
+/// Check if the given filepath matches the given MIME type, telling a
+/// mismatch from a file that can't be read.
 ///
-/// Returns MIME as string wrapped in Some if a type matches, or
-/// None if the file is not found or cannot be opened.
-/// Retreive the node from the `TYPE.hash` FnvHashMap, using the MIME as the key.
-///
-/// # Panics
-/// Will panic if the given node is not found in the graph.
-/// As the graph is immutable, this should not happen if the node index comes from
-/// `TYPE.hash`.
-fn from_filepath_node(parentnode: NodeIndex, filepath: &Path) -> Option<MIME> {
-    // We're actually just going to thunk this down to a u8
-    // unless we're checking via basetype for speed reasons.
-
-    // Ensure it's at least a application/octet-stream
-    if !match_filepath("application/octet-stream", filepath) {
-        // Check the other base types
-        return typegraph_walker(parentnode, filepath, match_filepath_noalias);
-    }
-
-    // Load the first 2K of file and parse as u8
-    // for batch processing like this
-
-    let b = match read_bytes(filepath, 2048) {
-        Ok(x) => x,
-        Err(_) => return None,
-    };
-
-    from_u8_node(parentnode, b.as_slice())
+/// Like [`match_filepath`], but returns the error when the file can't be
+/// opened, such as for missing permissions, or when reading it fails partway.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+///
+/// let path = Path::new("tests/image/gif");
+/// assert!(tree_magic_mini::try_match_filepath("image/gif", path).unwrap());
+///
+/// let missing = Path::new("tests/image/missing");
+/// assert!(tree_magic_mini::try_match_filepath("image/gif", missing).is_err());
+/// ```
+#[cfg(feature = "std")]
+pub fn try_match_filepath(mimetype: &str, filepath: &Path) -> std::io::Result<bool> {
+    database().try_match_filepath(mimetype, filepath)
 }
 
 /// Gets the type of a file from a filepath.
 ///
-/// Does not look at file name or extension, just the contents.
+/// Does not look at file name or extension, just the contents, so the
+/// result is what [`from_u8`] gives for them, however short the file.
 /// Returns MIME as string wrapped in Some if a type matches, or
 /// None if the file is not found or cannot be opened.
 ///
+/// Files whose size is zero are `application/x-zerosize`, without being
+/// opened. Like other shared MIME-info implementations, this includes
+/// files such as those in `/proc` that report no size.
+///
 /// # Examples
 /// ```rust
 /// use std::path::Path;
@@ -430,22 +954,259 @@ fn from_filepath_node(parentnode: NodeIndex, filepath: &Path) -> Option<MIME> {
 /// let result = tree_magic_mini::from_filepath(path);
 /// assert_eq!(result, Some("image/gif"));
 /// ```
+#[cfg(feature = "std")]
 pub fn from_filepath(filepath: &Path) -> Option<MIME> {
-    let node = match TYPE.graph.externals(Incoming).next() {
-        Some(foundnode) => foundnode,
-        None => panic!("No filetype definitions are loaded."),
-    };
+    database().from_filepath(filepath)
+}
 
-    from_filepath_node(node, filepath)
+/// Gets the type of a file from a filepath and metadata already looked up
+/// for it.
+///
+/// Works like [`from_filepath`], without looking up the metadata again, for
+/// directory walkers that have it for every entry anyway. `meta` has to be
+/// of the entry the path resolves to, as returned by
+/// [`std::fs::metadata`], rather than of a symbolic link itself.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+///
+/// for entry in std::fs::read_dir("tests/image").unwrap() {
+///     let path = entry.unwrap().path();
+///     let meta = std::fs::metadata(&path).unwrap();
+///     let result = tree_magic_mini::from_filepath_with_meta(&path, &meta);
+///     assert_eq!(result, tree_magic_mini::from_filepath(&path));
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn from_filepath_with_meta(filepath: &Path, meta: &std::fs::Metadata) -> Option<MIME> {
+    database().from_filepath_with_meta(filepath, meta)
+}
+
+/// Gets the type of a stream while writing all of it to a hasher, so tools
+/// that hash every file, such as for backups, read each only once.
+///
+/// Every byte goes to `hasher` from the buffer it was read into, before the
+/// type is detected, and the stream is read to its end. Any writer works:
+/// the hashers of the `sha2`, `blake3` and most other crates are writers.
+/// The start and end of the stream are kept for detection, which is enough
+/// for nearly every type; checks reading elsewhere in a long stream find
+/// nothing there. Fails with the first error from reading or writing.
+///
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+///
+/// // A copy instead of a hash
+/// let mut copy = Vec::new();
+/// let file = File::open("tests/image/gif").unwrap();
+/// let result = tree_magic_mini::detect_and_hash(file, &mut copy).unwrap();
+///
+/// assert_eq!(result, Some("image/gif"));
+/// assert_eq!(copy, std::fs::read("tests/image/gif").unwrap());
+/// ```
+#[cfg(feature = "std")]
+pub fn detect_and_hash(
+    reader: impl std::io::Read,
+    hasher: &mut impl std::io::Write,
+) -> std::io::Result<Option<MIME>> {
+    database().detect_and_hash(reader, hasher)
+}
+
+/// Gets the type of a file from a handle that's already open.
+///
+/// Works like [`from_filepath`], for callers that hold the file open anyway,
+/// such as to lock it. The file is read from the start, and its position is
+/// put back where it was afterwards. Returns `None` if the file can't be
+/// read or its position can't be restored.
+///
+/// # Examples
+/// ```rust
+/// use std::fs::File;
+/// use std::io::{Seek, SeekFrom};
+///
+/// let mut file = File::open("tests/image/gif").unwrap();
+/// file.seek(SeekFrom::Start(3)).unwrap();
+///
+/// assert_eq!(tree_magic_mini::from_file(&file), Some("image/gif"));
+/// assert_eq!(file.stream_position().unwrap(), 3);
+/// ```
+#[cfg(feature = "std")]
+pub fn from_file(file: &std::fs::File) -> Option<MIME> {
+    database().from_file(file)
+}
+
+/// Gets the type of a file in a directory, opened with `openat` relative to
+/// the directory's descriptor.
+///
+/// Works like [`from_filepath`], but `name` is looked up in the directory
+/// `dirfd` refers to, so a scan isn't thrown off by directories above it
+/// being renamed, and isn't limited by the length of full paths. The type
+/// comes from the metadata and content of the very file opened, even if
+/// `name` is replaced along the way. Symbolic links are followed.
+///
+/// Needs the `unix-io` feature.
+///
+/// # Examples
+/// ```rust
+/// use std::ffi::OsStr;
+/// use std::fs::File;
+/// use std::os::fd::AsFd;
+///
+/// let dir = File::open("tests/image").unwrap();
+/// let result = tree_magic_mini::from_dirfd(dir.as_fd(), OsStr::new("gif"));
+/// assert_eq!(result, Some("image/gif"));
+/// ```
+#[cfg(all(feature = "unix-io", unix))]
+pub fn from_dirfd(dirfd: std::os::fd::BorrowedFd<'_>, name: &std::ffi::OsStr) -> Option<MIME> {
+    database().from_dirfd(dirfd, name)
+}
+
+/// Detects the type of a file from its content and tags the file with it, in
+/// its `user.mime_type` extended attribute.
+///
+/// Meant for indexing files ahead of time, so later lookups with
+/// [`DetectorBuilder::xattr`] enabled take the tag instead of reading the
+/// content. Any tag the file had is replaced, not consulted. Fails if the
+/// file can't be opened, no type is found, or its file system doesn't allow
+/// user attributes.
+///
+/// Needs the `unix-io` feature, on Linux or macOS.
+///
+/// # Examples
+/// ```rust,no_run
+/// use std::path::Path;
+///
+/// let mime = tree_magic_mini::tag_filepath(Path::new("upload.bin")).unwrap();
+/// println!("tagged as {}", mime);
+/// ```
+#[cfg(all(feature = "unix-io", any(target_os = "linux", target_os = "macos")))]
+pub fn tag_filepath(filepath: &std::path::Path) -> std::io::Result<MIME> {
+    database().tag_filepath(filepath)
+}
+
+/// Gets the type of a file from a filepath, reading the file into `buf`.
+///
+/// Works like [`from_filepath`], but the start of the file is read into the
+/// caller's buffer, so a scan over many files only allocates it once. The
+/// previous contents of `buf` are discarded.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+///
+/// let mut buf = Vec::new();
+/// for path in ["tests/image/gif", "tests/image/png"] {
+///     let result = tree_magic_mini::from_filepath_buf(Path::new(path), &mut buf);
+///     assert!(result.is_some());
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn from_filepath_buf(filepath: &Path, buf: &mut Vec<u8>) -> Option<MIME> {
+    database().from_filepath_buf(filepath, buf)
+}
+
+/// Gets the type of a symbolic link and of the file at the end of its chain.
+///
+/// Links are resolved one at a time, up to `max_depth` of them, so both the
+/// link itself (`inode/symlink`) and its target are reported in one pass.
+/// [`from_filepath`] only reports the target.
+///
+/// Returns an error if `filepath` doesn't exist, or if the chain loops or is
+/// longer than `max_depth`. A chain ending at a missing file is not an
+/// error, it has no target type.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+///
+/// // Not a link
+/// let result = tree_magic_mini::from_filepath_following(Path::new("tests/image/gif"), 8).unwrap();
+/// assert_eq!(result.link, None);
+/// assert_eq!(result.target, Some("image/gif"));
+/// ```
+#[cfg(feature = "std")]
+pub fn from_filepath_following(
+    filepath: &Path,
+    max_depth: usize,
+) -> std::io::Result<FollowedPath<'static>> {
+    database().from_filepath_following(filepath, max_depth)
+}
+
+/// Gets the type of a file from a filepath, with the given options.
+///
+/// Returns `None` if the file can't be opened, or if nothing matches.
+#[cfg(feature = "std")]
+pub fn detect_filepath(filepath: &Path, options: &DetectOptions) -> Option<MIME> {
+    let source = FileSource::open(filepath).ok()?;
+    database().detect(&source, options)
+}
+
+/// Gets the acronym of a type, such as `PDF` for `application/pdf`.
+///
+/// Read from the type's XML file in the shared MIME-info database, so it's
+/// only known for types with an installed definition, even when the
+/// `with-gpl-data` or `builtin-minimal` feature embeds the magic rules.
+/// Aliases are resolved first. Returns `None` if the type has no acronym.
+///
+/// # Examples
+/// ```rust
+/// # if std::path::Path::new("/usr/share/mime/application/pdf.xml").exists() {
+/// assert_eq!(tree_magic_mini::acronym("application/pdf").as_deref(), Some("PDF"));
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn acronym(mimetype: &str) -> Option<String> {
+    database().acronym(mimetype)
 }
 
-/// Reads the given number of bytes from a file
-fn read_bytes(filepath: &Path, bytecount: usize) -> Result<Vec<u8>, std::io::Error> {
-    use std::fs::File;
-    use std::io::prelude::*;
+/// Gets what the acronym of a type stands for, such as
+/// `Portable Document Format` for `application/pdf`.
+///
+/// See [`acronym`] for where it comes from.
+///
+/// # Examples
+/// ```rust
+/// # if std::path::Path::new("/usr/share/mime/application/pdf.xml").exists() {
+/// assert_eq!(
+///     tree_magic_mini::expanded_acronym("application/pdf").as_deref(),
+///     Some("Portable Document Format")
+/// );
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn expanded_acronym(mimetype: &str) -> Option<String> {
+    database().expanded_acronym(mimetype)
+}
+
+/// Gets the description of a type, such as `PDF document` for
+/// `application/pdf`, translated for a locale.
+///
+/// The locale is given as in `LANG`, such as `pt_BR.UTF-8`, or as a tag
+/// such as `pt-BR`. Without a translation for it, less specific locales are
+/// tried, as gettext does: `pt_BR`, then `pt`, then the untranslated
+/// description, which is also what the `C` locale gets. See [`acronym`] for
+/// where descriptions come from.
+///
+/// # Examples
+/// ```rust
+/// # if std::path::Path::new("/usr/share/mime/application/pdf.xml").exists() {
+/// use tree_magic_mini::description;
+///
+/// assert_eq!(description("application/pdf", "C").as_deref(), Some("PDF document"));
+/// assert_eq!(description("application/pdf", "de_AT.UTF-8").as_deref(), Some("PDF-Dokument"));
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn description(mimetype: &str, locale: &str) -> Option<String> {
+    database().description(mimetype, locale)
+}
 
-    let mut b = Vec::<u8>::with_capacity(bytecount);
-    let f = File::open(filepath)?;
-    f.take(bytecount as u64).read_to_end(&mut b)?;
-    Ok(b)
+/// Lists the locales the description of a type is translated into, such as
+/// `pt_BR` and `pt`, in the order of the type's XML file.
+///
+/// See [`description`].
+#[cfg(feature = "std")]
+pub fn description_locales(mimetype: &str) -> Vec<String> {
+    database().description_locales(mimetype)
 }
@@ -0,0 +1,141 @@
+//! Checks of a type database for rules and aliases that can't work
+use crate::database::alias_cycles;
+use crate::fdo_magic::MagicRuleset;
+use crate::{Checker, FnvHashMap, TypeDatabase};
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+use petgraph::prelude::*;
+
+/// A problem found by [`TypeDatabase::validate`].
+///
+/// Rules are written like lines of a magic file, with printable bytes left as
+/// they are. (EX: `>0=GIF8`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lint<'a> {
+    /// A rule with an empty pattern, which matches anything, or nothing
+    /// when negated.
+    EmptyPattern {
+        /// Type the rule belongs to.
+        mimetype: &'a str,
+        /// The rule.
+        rule: String,
+    },
+    /// The same rule, along with any rules nested in it, given for more than
+    /// one type. Whichever type is checked first wins.
+    DuplicateRule {
+        /// Types sharing the rule, sorted.
+        mimetypes: Vec<&'a str>,
+        /// The top-level rule.
+        rule: String,
+    },
+    /// A type detected by magic alone, with no rule that can match, so
+    /// neither it nor any of its subclasses is ever the result.
+    UnreachableType {
+        /// The type.
+        mimetype: &'a str,
+    },
+    /// Aliases that lead back to themselves without reaching a type.
+    AliasCycle {
+        /// Aliases in the cycle, starting from the lowest.
+        aliases: Vec<&'a str>,
+    },
+}
+
+impl fmt::Display for Lint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lint::EmptyPattern { mimetype, rule } => {
+                write!(f, "{}: empty pattern in rule {}", mimetype, rule)
+            }
+            Lint::DuplicateRule { mimetypes, rule } => {
+                write!(f, "rule {} given for {}", rule, mimetypes.join(", "))
+            }
+            Lint::UnreachableType { mimetype } => {
+                write!(f, "{}: no magic rule can match", mimetype)
+            }
+            Lint::AliasCycle { aliases } => {
+                write!(f, "alias cycle: {} -> {}", aliases.join(" -> "), aliases[0])
+            }
+        }
+    }
+}
+
+/// The rule at `root` and everything nested in it, one rule per line
+fn tree_text(rules: &MagicRuleset, root: NodeIndex) -> String {
+    let mut out = String::new();
+    let mut stack = vec![root];
+    while let Some(n) = stack.pop() {
+        out.push_str(&rules[n].to_string());
+        out.push('\n');
+        stack.extend(rules.neighbors_directed(n, Outgoing));
+    }
+    out
+}
+
+pub(crate) fn validate(db: &TypeDatabase) -> Vec<Lint<'_>> {
+    let mut lints = Vec::new();
+
+    let mut magic: Vec<_> = db.all_magic().collect();
+    magic.sort_unstable_by_key(|&(mime, _)| mime);
+
+    for &(mime, ref rules) in &magic {
+        for rule in rules.node_weights().filter(|r| r.val.is_empty()) {
+            lints.push(Lint::EmptyPattern {
+                mimetype: mime,
+                rule: rule.to_string(),
+            });
+        }
+    }
+
+    // Keyed by the whole tree, as nested rules can tell types apart
+    let mut trees = FnvHashMap::<String, (String, Vec<&str>)>::default();
+    for &(mime, ref rules) in &magic {
+        for root in rules.externals(Incoming) {
+            let (_, types) = trees
+                .entry(tree_text(rules, root))
+                .or_insert_with(|| (rules[root].to_string(), Vec::new()));
+            if !types.contains(&mime) {
+                types.push(mime);
+            }
+        }
+    }
+    let mut duplicates: Vec<_> = trees
+        .into_values()
+        .filter(|(_, types)| types.len() > 1)
+        .map(|(rule, mut types)| {
+            types.sort_unstable();
+            (types, rule)
+        })
+        .collect();
+    duplicates.sort_unstable();
+    lints.extend(
+        duplicates
+            .into_iter()
+            .map(|(mimetypes, rule)| Lint::DuplicateRule { mimetypes, rule }),
+    );
+
+    let mut types: Vec<&str> = db.graph().node_weights().copied().collect();
+    types.sort_unstable();
+    for mime in types {
+        let only_magic = db.checker(mime).is_some_and(|c| c.only_magic());
+        let can_match = db
+            .magic(mime)
+            .is_some_and(|r| r.externals(Incoming).any(|n| !r[n].negate));
+        if only_magic && !can_match {
+            lints.push(Lint::UnreachableType { mimetype: mime });
+        }
+    }
+
+    let aliases: FnvHashMap<&str, &str> = db.aliases().map(|(&a, &b)| (a, b)).collect();
+    lints.extend(
+        alias_cycles(&aliases)
+            .into_iter()
+            .map(|aliases| Lint::AliasCycle { aliases }),
+    );
+
+    lints
+}
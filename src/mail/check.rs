@@ -0,0 +1,163 @@
+use crate::fdo_magic::builtin::check::scan_len;
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Mail;
+
+impl crate::Checker for Mail {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        // The magic rules still catch what the lines don't, such as news
+        // articles starting with `#! rnews`
+        from_source(source, mimetype)
+            || fdo_magic::builtin::check::from_source(db, source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        Some(PROBE_LEN.max(db.magic(mimetype).map_or(0, |r| scan_len(&r))))
+    }
+}
+
+/// How much of the data to look at for header lines
+const PROBE_LEN: usize = 4096;
+
+/// Header fields of which a message has at least one near the top
+const MESSAGE_FIELDS: [&[u8]; 9] = [
+    b"from",
+    b"to",
+    b"date",
+    b"subject",
+    b"message-id",
+    b"received",
+    b"return-path",
+    b"delivered-to",
+    b"mime-version",
+];
+
+/// Splits off the first line, ended by LF, CRLF or a lone CR. The line is
+/// `None` if it isn't ended within the data, and might be cut off.
+fn split_line(b: &[u8]) -> (Option<&[u8]>, &[u8]) {
+    match b.iter().position(|&c| c == b'\n' || c == b'\r') {
+        Some(i) => {
+            let end = match &b[i..] {
+                [b'\r', b'\n', ..] => i + 2,
+                _ => i + 1,
+            };
+            (Some(&b[..i]), &b[end..])
+        }
+        None => (None, b),
+    }
+}
+
+/// Skips a byte order mark and any blank lines or indentation before the
+/// first line
+fn skip_leading_space(b: &[u8]) -> &[u8] {
+    let b = b.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(b);
+    let start = b
+        .iter()
+        .position(|c| !c.is_ascii_whitespace())
+        .unwrap_or(b.len());
+    &b[start..]
+}
+
+/// First line of the data, without leading space or trailing whitespace,
+/// even if it isn't ended yet
+fn first_line(b: &[u8]) -> &[u8] {
+    let b = skip_leading_space(b);
+    let line = match split_line(b) {
+        (Some(line), _) => line,
+        (None, rest) => rest,
+    };
+    trim_end(line)
+}
+
+fn trim_end(line: &[u8]) -> &[u8] {
+    let end = line
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(0, |i| i + 1);
+    &line[..end]
+}
+
+/// Name of the header field on the line, if it's a `Name: value` line
+fn field_name(line: &[u8]) -> Option<&[u8]> {
+    let colon = line.iter().position(|&c| c == b':')?;
+    let name = trim_end(&line[..colon]);
+    // Printable ASCII other than the colon, see RFC 5322
+    match !name.is_empty() && name.iter().all(|&c| (0x21..=0x7E).contains(&c)) {
+        true => Some(name),
+        false => None,
+    }
+}
+
+/// Whether the data starts with a message header: only header fields and
+/// their folded continuations, at least two of them, up to the blank line
+/// or the end of the data
+fn is_header(b: &[u8]) -> bool {
+    let mut rest = b;
+    let mut fields = 0;
+    let mut known = false;
+    // A cut off line could be anything, so only judge whole lines
+    while let (Some(line), next) = split_line(rest) {
+        rest = next;
+        if line.is_empty() {
+            break;
+        }
+        if line[0] == b' ' || line[0] == b'\t' {
+            if fields == 0 {
+                return false;
+            }
+            continue;
+        }
+        match field_name(line) {
+            Some(name) => {
+                fields += 1;
+                known |= MESSAGE_FIELDS.iter().any(|f| name.eq_ignore_ascii_case(f));
+            }
+            None => return false,
+        }
+    }
+    fields >= 2 && known
+}
+
+/// An mbox starts with the `From ` line of its first message, and that
+/// message's header
+fn is_mbox(b: &[u8]) -> bool {
+    let b = skip_leading_space(b);
+    match split_line(b) {
+        (Some(line), rest) => {
+            line.starts_with(b"From ") && line.len() > 5 && line[5] != b' ' && is_header(rest)
+        }
+        (None, _) => false,
+    }
+}
+
+fn is_message(b: &[u8]) -> bool {
+    is_header(skip_leading_space(b))
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    let b = match source.read_at(0, PROBE_LEN) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+
+    match mimetype {
+        "application/mbox" => is_mbox(&b),
+        "message/rfc822" => is_message(&b),
+        "text/vcard" => first_line(&b).eq_ignore_ascii_case(b"BEGIN:VCARD"),
+        "text/calendar" => first_line(&b).eq_ignore_ascii_case(b"BEGIN:VCALENDAR"),
+        _ => false,
+    }
+}
@@ -0,0 +1,11 @@
+//! Handles mailboxes, e-mail messages, vCards and iCalendar files by reading
+//! their first lines
+const TYPES: [&str; 4] = [
+    "application/mbox",
+    "message/rfc822",
+    "text/vcard",
+    "text/calendar",
+];
+
+pub mod check;
+pub mod init;
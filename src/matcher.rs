@@ -0,0 +1,112 @@
+//! Types looked up once, to check many inputs against
+use crate::fdo_magic::builtin::check::{
+    from_source_rules, has_exclusions, is_excluded_rules, scan_len,
+};
+use crate::fdo_magic::lazy::LazyRuleset;
+use crate::{AnyChecker, Checker, DataSource, TypeDatabase};
+
+/// A type resolved by [`TypeDatabase::matcher`], for checking many inputs
+/// against the same type.
+///
+/// Aliases, the checker of the type and its magic rules are all looked up
+/// when the matcher is made, with a probe of the perfect hash map of the
+/// database, so a check does no lookups and allocates nothing, unless the
+/// rules don't fit in the
+/// [memory limit](crate::TypeDatabaseBuilder::memory_limit) of the database.
+///
+/// # Examples
+/// ```rust
+/// let gif = tree_magic_mini::matcher("image/gif").unwrap();
+///
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+/// assert!(gif.matches_u8(input));
+/// assert!(!gif.matches_u8(b"Hello, world!"));
+/// ```
+#[derive(Clone, Copy)]
+pub struct TypeMatcher<'a> {
+    db: &'a TypeDatabase,
+    mimetype: &'a str,
+    checker: AnyChecker,
+    /// Magic rules of the type, with how many bytes they look at
+    magic: Option<(&'a LazyRuleset, usize)>,
+    /// Whether any of the rules rule the type out
+    excludes: bool,
+}
+
+impl<'a> TypeMatcher<'a> {
+    pub(crate) fn new(
+        db: &'a TypeDatabase,
+        mimetype: &'a str,
+        checker: AnyChecker,
+        magic: Option<&'a LazyRuleset>,
+    ) -> Self {
+        let mut excludes = false;
+        let magic = magic.map(|entry| {
+            let rules = db.rules(entry);
+            excludes = has_exclusions(&rules);
+            (entry, scan_len(&rules))
+        });
+        TypeMatcher {
+            db,
+            mimetype,
+            checker,
+            magic,
+            excludes,
+        }
+    }
+
+    /// The type checked for, with any alias resolved.
+    ///
+    /// Borrowed from the database, as types of custom databases live only as
+    /// long as it does.
+    ///
+    /// ```rust,compile_fail
+    /// use tree_magic_mini::TypeDatabase;
+    ///
+    /// let mimetype = {
+    ///     let db = TypeDatabase::builder()
+    ///         .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+    ///         .unwrap()
+    ///         .build();
+    ///     db.matcher("application/x-foo").unwrap().mimetype()
+    /// };
+    /// ```
+    pub fn mimetype(&self) -> &'a str {
+        self.mimetype
+    }
+
+    /// Checks if the given data source matches the type.
+    ///
+    /// Same as [`TypeDatabase::match_source`].
+    pub fn matches_source(&self, source: &dyn DataSource) -> bool {
+        let only_magic = self.checker.only_magic();
+        match self.magic {
+            Some((entry, len)) if self.excludes || only_magic => {
+                let rules = self.db.rules(entry);
+                if self.excludes && is_excluded_rules(source, &rules, len) {
+                    return false;
+                }
+                if only_magic {
+                    return from_source_rules(source, &rules, len);
+                }
+            }
+            _ => {}
+        }
+        self.checker.from_source(self.db, source, self.mimetype)
+    }
+
+    /// Checks if the given bytestream matches the type.
+    ///
+    /// Same as [`TypeDatabase::match_u8`].
+    pub fn matches_u8(&self, bytes: &[u8]) -> bool {
+        self.matches_source(&bytes)
+    }
+}
+
+impl core::fmt::Debug for TypeMatcher<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TypeMatcher")
+            .field("mimetype", &self.mimetype)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,168 @@
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct MediaText;
+
+impl crate::Checker for MediaText {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        let found = match source.read_at(0, PROBE_LEN) {
+            Ok(b) => format(&b),
+            Err(_) => None,
+        };
+        match found {
+            // HLS playlists are M3U playlists too
+            Some("application/vnd.apple.mpegurl") if mimetype == "audio/x-mpegurl" => true,
+            Some(found) => found == mimetype,
+            None => fdo_magic::builtin::check::from_source(db, source, mimetype),
+        }
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        Some(PROBE_LEN)
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        match mimetype {
+            "text/x-ssa" => ssa_variant(&source.read_at(0, PROBE_LEN).ok()?),
+            _ => None,
+        }
+    }
+}
+
+/// How much of the text is looked at. HLS tags can come after a few
+/// comments.
+const PROBE_LEN: usize = 4096;
+
+const BOM: &[u8; 3] = b"\xef\xbb\xbf";
+
+fn trim(b: &[u8]) -> &[u8] {
+    let start = b.iter().position(|c| !c.is_ascii_whitespace());
+    let end = b.iter().rposition(|c| !c.is_ascii_whitespace());
+    match (start, end) {
+        (Some(s), Some(e)) => &b[s..=e],
+        _ => &[],
+    }
+}
+
+/// Lines of the text after any byte order mark, trimmed, leaving out blank
+/// ones
+fn lines(b: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let b = b.strip_prefix(BOM).unwrap_or(b);
+    b.split(|&c| c == b'\n')
+        .map(trim)
+        .filter(|line| !line.is_empty())
+}
+
+fn starts_with_ignore_case(line: &[u8], prefix: &[u8]) -> bool {
+    line.len() >= prefix.len() && line[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+/// `HH:MM:SS,mmm`, allowing one or more digits for the hours, and a dot
+/// for the comma as some tools write
+fn is_srt_time(t: &[u8]) -> bool {
+    let (hours, rest) = match t.iter().position(|&c| c == b':') {
+        Some(n) => t.split_at(n),
+        None => return false,
+    };
+    let digits = |b: &[u8]| !b.is_empty() && b.iter().all(u8::is_ascii_digit);
+    match rest {
+        [b':', m1, m2, b':', s1, s2, b',' | b'.', ms @ ..] => {
+            digits(hours) && digits(&[*m1, *m2, *s1, *s2]) && ms.len() == 3 && digits(ms)
+        }
+        _ => false,
+    }
+}
+
+/// A cue number, then the times it's shown between, which may be followed
+/// by a position
+fn is_srt(first: &[u8], second: Option<&[u8]>) -> bool {
+    let second = match second {
+        Some(line) if first.iter().all(u8::is_ascii_digit) => line,
+        _ => return false,
+    };
+    let mut times = second.split(|&c| c == b' ').filter(|t| !t.is_empty());
+    matches!(
+        (times.next(), times.next(), times.next()),
+        (Some(start), Some(b"-->"), Some(end)) if is_srt_time(start) && is_srt_time(end)
+    )
+}
+
+/// The sections of an SSA or ASS script, which are in this order, though
+/// the first is sometimes left out
+const SSA_SECTIONS: [&[u8]; 4] = [
+    b"[Script Info]",
+    b"[V4+ Styles]",
+    b"[V4 Styles]",
+    b"[Events]",
+];
+
+fn is_ssa(first: &[u8]) -> bool {
+    SSA_SECTIONS.iter().any(|s| first.eq_ignore_ascii_case(s))
+}
+
+fn is_m3u(first: &[u8]) -> bool {
+    // Some players write entries without the header
+    starts_with_ignore_case(first, b"#EXTM3U") || starts_with_ignore_case(first, b"#EXTINF:")
+}
+
+/// The format of the text, from its first lines
+fn format(b: &[u8]) -> Option<MIME> {
+    let mut lines = lines(b);
+    let first = lines.next()?;
+    if starts_with_ignore_case(first, b"WEBVTT")
+        && matches!(first.get(6), None | Some(b' ' | b'\t'))
+    {
+        return Some("text/vtt");
+    }
+    if is_ssa(first) {
+        return Some("text/x-ssa");
+    }
+    if first.eq_ignore_ascii_case(b"[playlist]") {
+        return Some("audio/x-scpls");
+    }
+    if is_m3u(first) {
+        // Tags of HTTP Live Streaming, such as the target duration of a
+        // media playlist or the streams of a master playlist
+        if lines.any(|line| line.starts_with(b"#EXT-X-")) {
+            return Some("application/vnd.apple.mpegurl");
+        }
+        return Some("audio/x-mpegurl");
+    }
+    if is_srt(first, lines.next()) {
+        return Some("application/x-subrip");
+    }
+    None
+}
+
+/// Reports `ass` for Advanced SubStation Alpha scripts, and `ssa` for older
+/// ones, by the script type or the styles section
+fn ssa_variant(b: &[u8]) -> Option<&'static str> {
+    lines(b).find_map(|line| {
+        if line.eq_ignore_ascii_case(b"[V4+ Styles]") {
+            return Some("ass");
+        }
+        if line.eq_ignore_ascii_case(b"[V4 Styles]") {
+            return Some("ssa");
+        }
+        let version = trim(line.strip_prefix(b"ScriptType:")?);
+        if version.eq_ignore_ascii_case(b"v4.00+") {
+            Some("ass")
+        } else if version.eq_ignore_ascii_case(b"v4.00") {
+            Some("ssa")
+        } else {
+            None
+        }
+    })
+}
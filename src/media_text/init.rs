@@ -0,0 +1,21 @@
+use crate::{FnvHashMap, MIME};
+use alloc::vec::Vec;
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    super::TYPES.iter().map(|&t| ("text/plain", t)).collect()
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/x-mpegurl", "application/vnd.apple.mpegurl"),
+        ("text/x-ass", "text/x-ssa"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
@@ -0,0 +1,13 @@
+//! Handles subtitles (SubRip, WebVTT, SSA) and playlists (M3U, PLS) by their
+//! first lines, allowing for byte order marks, blank lines and case
+const TYPES: [&str; 6] = [
+    "application/x-subrip",
+    "text/vtt",
+    "text/x-ssa",
+    "audio/x-mpegurl",
+    "application/vnd.apple.mpegurl",
+    "audio/x-scpls",
+];
+
+pub mod check;
+pub mod init;
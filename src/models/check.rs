@@ -0,0 +1,243 @@
+use crate::source::source_len;
+use crate::zip::{is_zip, zip_names};
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Models;
+
+impl crate::Checker for Models {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        let found = match mimetype {
+            "model/gltf-binary" => read(source, GLB_HEADER_LEN).is_some_and(|b| is_glb(&b)),
+            "model/gltf+json" => read(source, PROBE_LEN).is_some_and(|b| is_gltf(&b)),
+            "model/stl" => stl(source).is_some(),
+            "model/obj" => {
+                db.text_heuristics() && read(source, PROBE_LEN).is_some_and(|b| is_obj(&b))
+            }
+            "model/x-ply" => read(source, PROBE_LEN).is_some_and(|b| ply_format(&b).is_some()),
+            "model/step" => read(source, PROBE_LEN).is_some_and(|b| is_step(&b)),
+            "model/3mf" => is_3mf(source),
+            _ => false,
+        };
+        found || fdo_magic::builtin::check::from_source(db, source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "model/gltf-binary" => Some(GLB_HEADER_LEN),
+            "model/gltf+json" | "model/obj" | "model/x-ply" | "model/step" => Some(PROBE_LEN),
+            // The length of binary STL, and the central directory of 3MF,
+            // are at the end
+            _ => None,
+        }
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        match mimetype {
+            "model/stl" => stl(source),
+            "model/x-ply" => ply_format(&read(source, PROBE_LEN)?),
+            _ => None,
+        }
+    }
+}
+
+/// How much of text formats is looked at
+const PROBE_LEN: usize = 4096;
+
+/// Magic, version and length, then the length and type of the first chunk
+const GLB_HEADER_LEN: usize = 20;
+
+/// A binary STL has an 80 byte header, which may well start with `solid`
+/// too, then the number of triangles, each of which takes 50 bytes
+const STL_HEADER_LEN: usize = 84;
+const STL_TRIANGLE_LEN: u64 = 50;
+
+const STEP_MAGIC: &[u8] = b"ISO-10303-21;";
+
+fn read(source: &dyn DataSource, len: usize) -> Option<Vec<u8>> {
+    source.read_at(0, len).ok().map(|b| b.into_owned())
+}
+
+fn le32(b: &[u8], at: usize) -> Option<u32> {
+    let b = b.get(at..at + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn trim(b: &[u8]) -> &[u8] {
+    let start = b.iter().position(|c| !c.is_ascii_whitespace());
+    let end = b.iter().rposition(|c| !c.is_ascii_whitespace());
+    match (start, end) {
+        (Some(s), Some(e)) => &b[s..=e],
+        _ => &[],
+    }
+}
+
+/// Lines of the text after any byte order mark, without the last one if the
+/// text was cut off within it
+fn lines(b: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let b = if b.len() < PROBE_LEN {
+        b
+    } else {
+        match b.iter().rposition(|&c| c == b'\n') {
+            Some(n) => &b[..n],
+            None => &[],
+        }
+    };
+    let b = b.strip_prefix(b"\xef\xbb\xbf").unwrap_or(b);
+    b.split(|&c| c == b'\n').map(trim)
+}
+
+fn is_number(b: &[u8]) -> bool {
+    b.iter().any(u8::is_ascii_digit)
+        && b.iter()
+            .all(|&c| c.is_ascii_digit() || matches!(c, b'.' | b'-' | b'+' | b'e' | b'E'))
+}
+
+/// Version 2, with the JSON chunk first, or version 1, with JSON content
+fn is_glb(b: &[u8]) -> bool {
+    if !b.starts_with(b"glTF") {
+        return false;
+    }
+    match le32(b, 4) {
+        Some(2) => b.get(16..20) == Some(b"JSON"),
+        Some(1) => le32(b, 16) == Some(0),
+        _ => false,
+    }
+}
+
+/// An `asset` object giving the glTF version, which every glTF document
+/// has, and which exporters write first
+fn is_gltf(b: &[u8]) -> bool {
+    let start = match b.windows(7).position(|w| w == b"\"asset\"") {
+        Some(n) => n + 7,
+        None => return false,
+    };
+    let rest = trim(&b[start..]);
+    let rest = match rest.strip_prefix(b":") {
+        Some(x) => trim(x),
+        None => return false,
+    };
+    rest.starts_with(b"{")
+        && rest[..rest.len().min(256)]
+            .windows(9)
+            .any(|w| w == b"\"version\"")
+}
+
+/// Whether an STL file is `binary`, by its length, or `ascii`
+fn stl(source: &dyn DataSource) -> Option<&'static str> {
+    let b = source.read_at(0, PROBE_LEN).ok()?;
+    if let (Some(len), Some(count)) = (source_len(source), le32(&b, 80)) {
+        if count > 0 && len == STL_HEADER_LEN as u64 + count as u64 * STL_TRIANGLE_LEN {
+            return Some("binary");
+        }
+    }
+    let mut lines = lines(&b).filter(|line| !line.is_empty());
+    let first = lines.next()?;
+    let solid = first.len() >= 5
+        && first[..5].eq_ignore_ascii_case(b"solid")
+        && first.get(5).map_or(true, u8::is_ascii_whitespace);
+    let next = lines.next()?;
+    let facet = next.len() >= 5 && next[..5].eq_ignore_ascii_case(b"facet");
+    let end = next.len() >= 8 && next[..8].eq_ignore_ascii_case(b"endsolid");
+    (solid && (facet || end)).then_some("ascii")
+}
+
+/// Statements of Wavefront OBJ, other than the vertex and face ones
+const OBJ_STATEMENTS: [&[u8]; 17] = [
+    b"vt", b"vn", b"vp", b"l", b"p", b"o", b"g", b"s", b"mtllib", b"usemtl", b"cstype", b"deg",
+    b"curv", b"curv2", b"surf", b"parm", b"end",
+];
+
+/// Every line a comment or a statement, with a few vertices given as three
+/// or more numbers
+fn is_obj(b: &[u8]) -> bool {
+    let mut vertices = 0;
+    for line in lines(b) {
+        if line.is_empty() || line.starts_with(b"#") {
+            continue;
+        }
+        let mut words = line
+            .split(|c| c.is_ascii_whitespace())
+            .filter(|w| !w.is_empty());
+        let statement = words.next().unwrap_or_default();
+        match statement {
+            b"v" => {
+                let numbers = words.take_while(|w| is_number(w)).count();
+                if numbers < 3 {
+                    return false;
+                }
+                vertices += 1;
+            }
+            b"f" => {
+                // Indexes of vertex, texture and normal, slash-separated
+                let ok = words.all(|w| {
+                    w.iter()
+                        .all(|&c| c.is_ascii_digit() || c == b'/' || c == b'-')
+                });
+                if !ok {
+                    return false;
+                }
+            }
+            _ if OBJ_STATEMENTS.contains(&statement) => {}
+            _ => return false,
+        }
+    }
+    vertices >= 3
+}
+
+/// `ascii`, `binary_little_endian` or `binary_big_endian`, from the format
+/// line after the magic
+fn ply_format(b: &[u8]) -> Option<&'static str> {
+    let mut lines = lines(b);
+    if lines.next()? != b"ply" {
+        return None;
+    }
+    let format = lines.find(|line| !line.starts_with(b"comment"))?;
+    let mut words = format.split(|&c| c == b' ').filter(|w| !w.is_empty());
+    if words.next()? != b"format" {
+        return None;
+    }
+    match words.next()? {
+        b"ascii" => Some("ascii"),
+        b"binary_little_endian" => Some("binary_little_endian"),
+        b"binary_big_endian" => Some("binary_big_endian"),
+        _ => None,
+    }
+}
+
+/// The exchange structure of ISO 10303-21 starts with its magic, then the
+/// header section
+fn is_step(b: &[u8]) -> bool {
+    let mut lines = lines(b).filter(|line| !line.is_empty());
+    match lines.next() {
+        Some(line) if line.starts_with(STEP_MAGIC) => {
+            let rest = trim(&line[STEP_MAGIC.len()..]);
+            rest.starts_with(b"HEADER;") || lines.next().is_some_and(|l| l.starts_with(b"HEADER;"))
+        }
+        _ => false,
+    }
+}
+
+/// The 3D model part, which OPC names without regard to case
+fn is_model_part(name: &[u8]) -> bool {
+    name.len() > 3 && name[..3].eq_ignore_ascii_case(b"3D/") && {
+        let n = name.len();
+        n > 6 && name[n - 6..].eq_ignore_ascii_case(b".model")
+    }
+}
+
+fn is_3mf(source: &dyn DataSource) -> bool {
+    is_zip(source) && zip_names(source).iter().any(|name| is_model_part(name))
+}
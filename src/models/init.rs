@@ -0,0 +1,29 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    vec![
+        ("application/json", "model/gltf+json"),
+        ("text/plain", "model/obj"),
+        ("text/plain", "model/step"),
+        ("application/zip", "model/3mf"),
+    ]
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/sla", "model/stl"),
+        ("application/vnd.ms-3mfdocument", "model/3mf"),
+        ("application/x-ply", "model/x-ply"),
+        ("application/step", "model/step"),
+        ("application/x-step", "model/step"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
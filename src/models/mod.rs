@@ -0,0 +1,13 @@
+//! Handles 3D models and CAD data: glTF, STL, OBJ, PLY, STEP and 3MF
+const TYPES: [&str; 7] = [
+    "model/gltf-binary",
+    "model/gltf+json",
+    "model/stl",
+    "model/obj",
+    "model/x-ply",
+    "model/step",
+    "model/3mf",
+];
+
+pub mod check;
+pub mod init;
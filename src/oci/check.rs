@@ -0,0 +1,62 @@
+use crate::json::check::top_level;
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Oci;
+
+impl crate::Checker for Oci {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        Some(PROBE_LEN)
+    }
+}
+
+/// How much of the document to look at. The keys telling the documents
+/// apart come before the lists of layers and manifests, which can be long.
+const PROBE_LEN: usize = 16 * 1024;
+
+/// The type a document gives as its `mediaType`, or one told by its keys
+/// where the field is left out, as OCI allows
+fn document_type(b: &[u8]) -> Option<MIME> {
+    let doc = top_level(b)?;
+    if let Some(media_type) = doc.value(b"mediaType") {
+        return super::TYPES
+            .iter()
+            .copied()
+            .find(|t| t.as_bytes() == media_type);
+    }
+    if doc.value(b"schemaVersion") == Some(b"2") {
+        if doc.has(b"manifests") {
+            return Some("application/vnd.oci.image.index.v1+json");
+        }
+        if doc.has(b"config") && doc.has(b"layers") {
+            return Some("application/vnd.oci.image.manifest.v1+json");
+        }
+    }
+    if doc.has(b"architecture") && doc.has(b"os") && doc.has(b"rootfs") {
+        return Some("application/vnd.oci.image.config.v1+json");
+    }
+    None
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    match source.read_at(0, PROBE_LEN) {
+        Ok(b) => document_type(&b) == Some(mimetype),
+        Err(_) => false,
+    }
+}
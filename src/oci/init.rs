@@ -0,0 +1,44 @@
+use crate::{FnvHashMap, MIME};
+use alloc::vec::Vec;
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    super::TYPES
+        .iter()
+        .map(|&t| ("application/json", t))
+        .collect()
+}
+
+/// Media types of image layers are the archives they hold, so a blob can
+/// be checked against the type a registry gives for it
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        (
+            "application/vnd.oci.image.layer.v1.tar",
+            "application/x-tar",
+        ),
+        (
+            "application/vnd.oci.image.layer.v1.tar+gzip",
+            "application/x-compressed-tar",
+        ),
+        (
+            "application/vnd.oci.image.layer.v1.tar+zstd",
+            "application/zstd",
+        ),
+        (
+            "application/vnd.docker.image.rootfs.diff.tar.gzip",
+            "application/x-compressed-tar",
+        ),
+        (
+            "application/vnd.docker.container.image.v1+json",
+            "application/vnd.oci.image.config.v1+json",
+        ),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
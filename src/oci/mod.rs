@@ -0,0 +1,12 @@
+//! Handles the JSON documents of OCI and Docker container images, by the
+//! keys of their top-level object
+const TYPES: [&str; 5] = [
+    "application/vnd.oci.image.manifest.v1+json",
+    "application/vnd.oci.image.index.v1+json",
+    "application/vnd.oci.image.config.v1+json",
+    "application/vnd.docker.distribution.manifest.v2+json",
+    "application/vnd.docker.distribution.manifest.list.v2+json",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,127 @@
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Ogg;
+
+impl crate::Checker for Ogg {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        let streams = streams(source);
+        if streams.is_empty() {
+            return fdo_magic::builtin::check::from_source(db, source, mimetype);
+        }
+        let video = streams.iter().any(|c| c.is_video());
+        // The codec the audio is named for, skipping metadata streams
+        let audio = streams.iter().find(|c| c.is_audio());
+        match mimetype {
+            "audio/ogg" => !video,
+            "video/ogg" => video,
+            "audio/x-vorbis+ogg" => audio == Some(&Codec::Vorbis),
+            "audio/x-opus+ogg" => audio == Some(&Codec::Opus),
+            "audio/x-flac+ogg" => audio == Some(&Codec::Flac),
+            "audio/x-speex+ogg" => audio == Some(&Codec::Speex),
+            "video/x-theora+ogg" => streams.contains(&Codec::Theora),
+            _ => false,
+        }
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        // Pages are found from the lengths in the ones before them
+        None
+    }
+}
+
+const PAGE_MAGIC: &[u8; 4] = b"OggS";
+/// Up to and including the number of segments, which the lacing values
+/// follow
+const PAGE_HEADER_LEN: usize = 27;
+/// Set on the first page of each logical stream
+const BEGINNING_OF_STREAM: u8 = 0x02;
+/// Enough of a first packet to tell the codec
+const PACKET_PROBE_LEN: usize = 8;
+/// All streams start before any data, but only so many are looked at
+const MAX_STREAMS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Codec {
+    Vorbis,
+    Opus,
+    Flac,
+    Speex,
+    OtherAudio,
+    Theora,
+    OtherVideo,
+    /// Skeleton, subtitles and the like
+    Other,
+}
+
+impl Codec {
+    fn from_packet(b: &[u8]) -> Codec {
+        const CODECS: [(&[u8], Codec); 10] = [
+            (b"\x01vorbis", Codec::Vorbis),
+            (b"OpusHead", Codec::Opus),
+            (b"\x7fFLAC", Codec::Flac),
+            (b"fLaC", Codec::Flac),
+            (b"Speex   ", Codec::Speex),
+            (b"CELT    ", Codec::OtherAudio),
+            (b"\x80theora", Codec::Theora),
+            (b"\x80daala", Codec::OtherVideo),
+            (b"BBCD\x00", Codec::OtherVideo),
+            (b"OVP80", Codec::OtherVideo),
+        ];
+        CODECS
+            .iter()
+            .find(|(magic, _)| b.starts_with(magic))
+            .map_or(Codec::Other, |&(_, codec)| codec)
+    }
+
+    fn is_audio(&self) -> bool {
+        matches!(
+            self,
+            Codec::Vorbis | Codec::Opus | Codec::Flac | Codec::Speex | Codec::OtherAudio
+        )
+    }
+
+    fn is_video(&self) -> bool {
+        matches!(self, Codec::Theora | Codec::OtherVideo)
+    }
+}
+
+/// Codecs of the logical streams, from the pages that begin them, which all
+/// come first
+fn streams(source: &dyn DataSource) -> Vec<Codec> {
+    let mut out = Vec::new();
+    let mut at = 0u64;
+    while out.len() < MAX_STREAMS {
+        let header = match source.read_at(at, PAGE_HEADER_LEN) {
+            Ok(b) if b.len() == PAGE_HEADER_LEN && b.starts_with(PAGE_MAGIC) => b,
+            _ => break,
+        };
+        if header[5] & BEGINNING_OF_STREAM == 0 {
+            break;
+        }
+        let segments = header[26] as usize;
+        let lacing = match source.read_at(at + PAGE_HEADER_LEN as u64, segments) {
+            Ok(b) if b.len() == segments => b,
+            _ => break,
+        };
+        let body = at + (PAGE_HEADER_LEN + segments) as u64;
+        match source.read_at(body, PACKET_PROBE_LEN) {
+            Ok(b) => out.push(Codec::from_packet(&b)),
+            Err(_) => break,
+        }
+        at = body + lacing.iter().map(|&n| n as u64).sum::<u64>();
+    }
+    out
+}
@@ -0,0 +1,14 @@
+//! Handles Ogg files by the codecs of their logical streams, rather than
+//! only the first one
+const TYPES: [&str; 7] = [
+    "audio/ogg",
+    "video/ogg",
+    "audio/x-vorbis+ogg",
+    "audio/x-opus+ogg",
+    "audio/x-flac+ogg",
+    "audio/x-speex+ogg",
+    "video/x-theora+ogg",
+];
+
+pub mod check;
+pub mod init;
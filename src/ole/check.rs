@@ -0,0 +1,168 @@
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+
+pub(crate) struct Ole;
+
+impl crate::Checker for Ole {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(db, source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+}
+
+const SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// CLSID {000C1084-0000-0000-C000-000000000046}, as stored on disk
+const MSI_CLSID: [u8; 16] = [
+    0x84, 0x10, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46,
+];
+
+/// Sector numbers at or above this are special markers (end of chain, free, ...)
+const MAXREGSECT: u32 = 0xFFFF_FFFA;
+
+/// Number of FAT sector locations stored in the header itself
+const HEADER_DIFAT_LEN: usize = 109;
+
+/// Stop following the directory chain after this many sectors
+const MAX_DIR_SECTORS: usize = 64;
+
+const HEADER_LEN: usize = 512;
+const DIRENTRY_LEN: usize = 128;
+
+/// The parts of a compound file directory we care about
+struct Directory {
+    root_clsid: Option<[u8; 16]>,
+    names: Vec<String>,
+}
+
+impl Directory {
+    fn has_entry(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n == name)
+    }
+}
+
+fn le_u16(b: &[u8], off: usize) -> Option<u16> {
+    let x = b.get(off..off + 2)?;
+    Some(u16::from_le_bytes([x[0], x[1]]))
+}
+
+fn le_u32(b: &[u8], off: usize) -> Option<u32> {
+    let x = b.get(off..off + 4)?;
+    Some(u32::from_le_bytes([x[0], x[1], x[2], x[3]]))
+}
+
+/// Walks the directory sector chain of a compound file, as far as the
+/// source allows.
+fn read_directory(source: &dyn DataSource, header: &[u8]) -> Option<Directory> {
+    let sector_shift = le_u16(header, 0x1E)?;
+    if !(7..=16).contains(&sector_shift) {
+        return None;
+    }
+    let sector_size = 1usize << sector_shift;
+
+    let sector = |n: u32| -> Option<Cow<[u8]>> {
+        let start = (n as u64 + 1) * sector_size as u64;
+        match source.read_at(start, sector_size) {
+            Ok(x) if x.len() == sector_size => Some(x),
+            _ => None,
+        }
+    };
+    let next_sector = |n: u32| {
+        let per_sector = sector_size / 4;
+        let idx = n as usize / per_sector;
+        if idx >= HEADER_DIFAT_LEN {
+            return None;
+        }
+        let fat_sector = le_u32(header, 0x4C + idx * 4)?;
+        if fat_sector >= MAXREGSECT {
+            return None;
+        }
+        le_u32(&sector(fat_sector)?, (n as usize % per_sector) * 4)
+    };
+
+    let mut dir = Directory {
+        root_clsid: None,
+        names: Vec::new(),
+    };
+    let mut current = le_u32(header, 0x30)?;
+    for _ in 0..MAX_DIR_SECTORS {
+        if current >= MAXREGSECT {
+            break;
+        }
+        let data = match sector(current) {
+            Some(x) => x,
+            None => break,
+        };
+
+        for entry in data.chunks_exact(DIRENTRY_LEN) {
+            let name_len = core::cmp::min(le_u16(entry, 0x40)? as usize, 64);
+            let name: Vec<u16> = entry[..name_len]
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .take_while(|&c| c != 0)
+                .collect();
+
+            match entry[0x42] {
+                // Root storage
+                5 => {
+                    let mut clsid = [0; 16];
+                    clsid.copy_from_slice(&entry[0x50..0x60]);
+                    dir.root_clsid = Some(clsid);
+                }
+                // Storage or stream
+                1 | 2 => dir.names.push(String::from_utf16_lossy(&name)),
+                _ => {}
+            }
+        }
+
+        current = match next_sector(current) {
+            Some(x) => x,
+            None => break,
+        };
+    }
+
+    if dir.root_clsid.is_none() && dir.names.is_empty() {
+        None
+    } else {
+        Some(dir)
+    }
+}
+
+pub fn from_source(db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+    let header = match source.read_at(0, HEADER_LEN) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+    if !header.starts_with(&SIGNATURE) {
+        // Some of these types also have non-OLE variants (e.g. Word for DOS),
+        // leave those to the magic rules.
+        return fdo_magic::builtin::check::from_source(db, source, mimetype);
+    }
+    if mimetype == "application/x-ole-storage" {
+        return true;
+    }
+
+    let dir = match read_directory(source, &header) {
+        Some(x) => x,
+        None => return false,
+    };
+
+    match mimetype {
+        "application/msword" => dir.has_entry("WordDocument"),
+        "application/vnd.ms-excel" => dir.has_entry("Workbook") || dir.has_entry("Book"),
+        "application/vnd.ms-powerpoint" => dir.has_entry("PowerPoint Document"),
+        "application/x-msi" => dir.root_clsid == Some(MSI_CLSID),
+        _ => false,
+    }
+}
@@ -0,0 +1,20 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    vec![
+        ("application/x-ole-storage", "application/msword"),
+        ("application/x-ole-storage", "application/vnd.ms-excel"),
+        ("application/x-ole-storage", "application/vnd.ms-powerpoint"),
+        ("application/x-ole-storage", "application/x-msi"),
+    ]
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    FnvHashMap::default()
+}
@@ -0,0 +1,11 @@
+//! Handles OLE2 / Compound File Binary containers such as legacy Office documents
+const TYPES: [&str; 5] = [
+    "application/x-ole-storage",
+    "application/msword",
+    "application/vnd.ms-excel",
+    "application/vnd.ms-powerpoint",
+    "application/x-msi",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,254 @@
+//! Per-call settings for detection
+use crate::{StatsHook, MIME};
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// Settings for a single detection, used by [`detect_u8`](crate::detect_u8)
+/// and the other `detect_*` functions.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::DetectOptions;
+///
+/// // Report unknown binary data as nothing at all
+/// let opts = DetectOptions::new().binary_fallback(None);
+/// assert_eq!(tree_magic_mini::detect_u8(b"\x00\x01\x02", &opts), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectOptions {
+    pub(crate) binary_fallback: Option<MIME>,
+    pub(crate) text_fallback: Option<MIME>,
+    pub(crate) empty_fallback: Option<MIME>,
+    pub(crate) max_depth: usize,
+    pub(crate) max_io_bytes: usize,
+    pub(crate) max_reads: usize,
+    pub(crate) family_preference: Vec<String>,
+    pub(crate) by_priority: bool,
+    #[cfg(feature = "std")]
+    pub(crate) time_budget: Option<Duration>,
+}
+
+impl Default for DetectOptions {
+    fn default() -> Self {
+        DetectOptions {
+            binary_fallback: Some("application/octet-stream"),
+            text_fallback: Some("text/plain"),
+            empty_fallback: Some("text/plain"),
+            max_depth: usize::MAX,
+            max_io_bytes: usize::MAX,
+            max_reads: usize::MAX,
+            family_preference: Vec::new(),
+            by_priority: false,
+            #[cfg(feature = "std")]
+            time_budget: None,
+        }
+    }
+}
+
+impl DetectOptions {
+    /// Options giving the same results as [`from_source`](crate::from_source).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Type reported for binary data no specific type matches, instead of
+    /// `application/octet-stream`. `None` reports no type at all.
+    pub fn binary_fallback(mut self, mimetype: Option<MIME>) -> Self {
+        self.binary_fallback = mimetype;
+        self
+    }
+
+    /// Type reported for text no specific type matches, instead of
+    /// `text/plain`. `None` reports no type at all.
+    pub fn text_fallback(mut self, mimetype: Option<MIME>) -> Self {
+        self.text_fallback = mimetype;
+        self
+    }
+
+    /// Type reported for empty data when the database has no
+    /// `application/x-zerosize` type, such as one built with
+    /// [`retain_types`](crate::TypeDatabaseBuilder::retain_types), instead of
+    /// `text/plain`. `None` reports no type at all.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::{DetectOptions, TypeDatabase};
+    ///
+    /// let db = TypeDatabase::builder().retain_types(&["image/*"]).build();
+    /// let opts = DetectOptions::new().empty_fallback(Some("application/octet-stream"));
+    /// assert_eq!(db.detect(&&b""[..], &opts), Some("application/octet-stream"));
+    ///
+    /// // The full database knows the type of empty data
+    /// assert_eq!(tree_magic_mini::detect_u8(b"", &opts), Some("application/x-zerosize"));
+    /// ```
+    pub fn empty_fallback(mut self, mimetype: Option<MIME>) -> Self {
+        self.empty_fallback = mimetype;
+        self
+    }
+
+    /// Walks at most this many levels of subclasses down from the root type.
+    /// When the limit is reached, the most specific type found so far is
+    /// reported.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Reads at most this many bytes of the data over the whole detection,
+    /// for sources where reading is costly, such as files on network or
+    /// FUSE mounts. Checks whose reads would go over the limit don't match,
+    /// so the most specific type found before is reported.
+    ///
+    /// Every read the checks make counts, including of data already in
+    /// memory. A read asking for more than what's left is refused, even if
+    /// the data turns out to be shorter.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::DetectOptions;
+    ///
+    /// let gif: &[u8] = include_bytes!("../tests/image/gif");
+    /// let opts = DetectOptions::new().max_io_bytes(0);
+    /// assert_eq!(tree_magic_mini::detect_u8(gif, &opts), Some("application/octet-stream"));
+    /// ```
+    pub fn max_io_bytes(mut self, bytes: usize) -> Self {
+        self.max_io_bytes = bytes;
+        self
+    }
+
+    /// Makes at most this many reads of the data over the whole detection,
+    /// like [`max_io_bytes`](Self::max_io_bytes) limits their size.
+    pub fn max_reads(mut self, reads: usize) -> Self {
+        self.max_reads = reads;
+        self
+    }
+
+    /// Checks types of the given top-level media types first, in the given
+    /// order, wherever data could match more than one type at the same level
+    /// of the type graph. Types of other media types follow in the usual
+    /// order.
+    ///
+    /// Without a preference, sibling types are checked in a fixed order that
+    /// puts common types first, and the first match wins.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use tree_magic_mini::{DetectOptions, TypeDatabase};
+    ///
+    /// let db = TypeDatabase::builder()
+    ///     .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+    ///     .unwrap()
+    ///     .magic(b"MIME-Magic\0\n[50:image/x-foo]\n>0=\0\x03FOO\n")
+    ///     .unwrap()
+    ///     .build();
+    ///
+    /// let opts = DetectOptions::new().family_preference(&["image", "application"]);
+    /// assert_eq!(db.detect(&&b"FOO"[..], &opts), Some("image/x-foo"));
+    /// ```
+    pub fn family_preference(mut self, media_types: &[&str]) -> Self {
+        self.family_preference = media_types.iter().map(|t| t.to_ascii_lowercase()).collect();
+        self
+    }
+
+    /// Checks sibling types by the priority of their magic rules, highest
+    /// first, then by name, like QMimeDatabase picks between magic matches
+    pub(crate) fn by_priority(mut self) -> Self {
+        self.by_priority = true;
+        self
+    }
+
+    /// Whether reads are limited at all
+    pub(crate) fn has_io_budget(&self) -> bool {
+        self.max_io_bytes != usize::MAX || self.max_reads != usize::MAX
+    }
+
+    /// Stops checking further types once this much time has passed since
+    /// detection started, and reports the most specific type found so far.
+    /// A type being checked when time runs out is still checked to the end.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tree_magic_mini::DetectOptions;
+    ///
+    /// let opts = DetectOptions::new().time_budget(Duration::from_millis(5));
+    /// let gif: &[u8] = include_bytes!("../tests/image/gif");
+    /// assert_eq!(tree_magic_mini::detect_u8(gif, &opts), Some("image/gif"));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Limits for a walk starting now
+    pub(crate) fn walk_limits(&self) -> WalkLimits<'_> {
+        WalkLimits {
+            max_depth: self.max_depth,
+            #[cfg(feature = "std")]
+            deadline: self.time_budget.and_then(|b| Instant::now().checked_add(b)),
+            stats: None,
+            family_preference: &self.family_preference,
+            by_priority: self.by_priority,
+        }
+    }
+
+    /// Replaces the generic types the graph walk ends at when nothing more
+    /// specific matches
+    pub(crate) fn apply_fallback<'a>(&self, mimetype: &'a str) -> Option<&'a str> {
+        match mimetype {
+            "application/octet-stream" | "all/allfiles" => self.binary_fallback,
+            "text/plain" => self.text_fallback,
+            _ => Some(mimetype),
+        }
+    }
+}
+
+/// Limits of a single walk through the type graph, and who to tell about
+/// its progress
+pub(crate) struct WalkLimits<'a> {
+    pub(crate) max_depth: usize,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    pub(crate) stats: Option<&'a dyn StatsHook>,
+    /// Media types whose types are checked before their siblings, in order
+    pub(crate) family_preference: &'a [String],
+    /// Whether siblings are checked by the priority of their magic
+    pub(crate) by_priority: bool,
+}
+
+impl Default for WalkLimits<'_> {
+    fn default() -> Self {
+        WalkLimits {
+            max_depth: usize::MAX,
+            #[cfg(feature = "std")]
+            deadline: None,
+            stats: None,
+            family_preference: &[],
+            by_priority: false,
+        }
+    }
+}
+
+impl WalkLimits<'_> {
+    /// Sort key putting the preferred media types first
+    pub(crate) fn preference(&self, mimetype: &str) -> usize {
+        let media = mimetype.split('/').next().unwrap_or("");
+        self.family_preference
+            .iter()
+            .position(|t| t == media)
+            .unwrap_or(self.family_preference.len())
+    }
+
+    /// Whether the time budget has run out
+    pub(crate) fn expired(&self) -> bool {
+        #[cfg(feature = "std")]
+        if let Some(deadline) = self.deadline {
+            return Instant::now() >= deadline;
+        }
+        false
+    }
+}
@@ -0,0 +1,239 @@
+use crate::zip::{is_zip, zip_names};
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Packages;
+
+impl crate::Checker for Packages {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "application/x-rpm" | "application/x-source-rpm" => Some(RPM_PROBE_LEN),
+            "application/x-alpine-package" => Some(ALPINE_PROBE_LEN),
+            // These look at the members, wherever they are
+            _ => None,
+        }
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        match mimetype {
+            "application/x-alpine-package" => alpine(&source.read_at(0, ALPINE_PROBE_LEN).ok()?),
+            _ => None,
+        }
+    }
+}
+
+/// The lead, then the magic of the signature header
+const RPM_PROBE_LEN: usize = 100;
+const RPM_LEAD_MAGIC: &[u8; 4] = b"\xed\xab\xee\xdb";
+const RPM_HEADER_MAGIC: &[u8; 4] = b"\x8e\xad\xe8\x01";
+const RPM_LEAD_LEN: usize = 96;
+/// The package type of the lead, with source packages being 1
+const RPM_TYPE: usize = 6;
+
+/// Enough of a gzip stream to inflate the first tar header from
+const ALPINE_PROBE_LEN: usize = 4096;
+/// Packages of the third version start with the magic of the format and
+/// the schema, or with a compressed form of that
+const ADB_PACKAGE: &[u8; 8] = b"ADB.pckg";
+const ADB_DEFLATE_MAGIC: &[u8; 4] = b"ADBd";
+/// Followed by the algorithm, of which 1 is deflate, and the level
+const ADB_COMPRESSED_MAGIC: &[u8; 4] = b"ADBc";
+
+const SQUASHFS_MAGIC: &[u8; 4] = b"hsqs";
+const SQUASHFS_SUPERBLOCK_LEN: usize = 96;
+#[cfg(feature = "archive")]
+const SQUASHFS_GZIP: u16 = 1;
+/// Set in the length of a metadata block that's stored as it is
+const METADATA_UNCOMPRESSED: u16 = 0x8000;
+#[cfg(feature = "archive")]
+const METADATA_MAX_LEN: usize = 8192;
+/// Metadata blocks of the directory table looked at
+const MAX_METADATA_BLOCKS: usize = 16;
+/// A directory entry's name is preceded by its length less one
+const SNAP_YAML_ENTRY: &[u8; 11] = b"\x08\x00snap.yaml";
+
+fn le_u16(b: &[u8], at: usize) -> Option<u16> {
+    let b = b.get(at..at + 2)?;
+    Some(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn le_u64(b: &[u8], at: usize) -> Option<u64> {
+    let b = b.get(at..at + 8)?;
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(b);
+    Some(u64::from_le_bytes(bytes))
+}
+
+/// The lead, whose magic the databases go by, is followed by the
+/// signature header
+fn rpm_type(b: &[u8]) -> Option<u8> {
+    if !b.starts_with(RPM_LEAD_MAGIC)
+        || !matches!(b.get(4), Some(3 | 4))
+        || !b
+            .get(RPM_LEAD_LEN..)
+            .is_some_and(|h| h.starts_with(RPM_HEADER_MAGIC))
+    {
+        return None;
+    }
+    match b.get(RPM_TYPE..RPM_TYPE + 2)? {
+        [0, t @ (0 | 1)] => Some(*t),
+        _ => None,
+    }
+}
+
+/// The first member of a package of the second version is the signature,
+/// or the control data if it isn't signed
+#[cfg(feature = "archive")]
+fn is_alpine_v2(b: &[u8]) -> bool {
+    use crate::tar::check::{gzip_header, inflate_block, is_valid_header};
+
+    let start = match gzip_header(b) {
+        Some((_, start)) => start,
+        None => return false,
+    };
+    let block = inflate_block(b.get(start..).unwrap_or(&[]));
+    is_valid_header(&block) && (block.starts_with(b".SIGN.") || block.starts_with(b".PKGINFO\0"))
+}
+
+/// Telling the packages from other gzipped tar archives takes inflating
+/// them
+#[cfg(not(feature = "archive"))]
+fn is_alpine_v2(_b: &[u8]) -> bool {
+    false
+}
+
+#[cfg(feature = "archive")]
+fn is_deflated_adb(data: &[u8]) -> bool {
+    crate::tar::check::inflate_block(data).starts_with(ADB_PACKAGE)
+}
+
+#[cfg(not(feature = "archive"))]
+fn is_deflated_adb(_data: &[u8]) -> bool {
+    false
+}
+
+fn alpine(b: &[u8]) -> Option<&'static str> {
+    let v3 = if let Some(data) = b.strip_prefix(ADB_DEFLATE_MAGIC) {
+        is_deflated_adb(data)
+    } else if let Some(data) = b.strip_prefix(ADB_COMPRESSED_MAGIC) {
+        data.first() == Some(&1) && is_deflated_adb(data.get(2..).unwrap_or(&[]))
+    } else {
+        b.starts_with(ADB_PACKAGE)
+    };
+    if v3 {
+        return Some("v3");
+    }
+    is_alpine_v2(b).then_some("v2")
+}
+
+/// An APK is a JAR, which newer signing schemes leave without a manifest,
+/// holding the binary manifest and the compiled code or resources
+fn is_android_package(source: &dyn DataSource) -> bool {
+    if !is_zip(source) {
+        return false;
+    }
+    let names = zip_names(source);
+    let has = |name: &[u8]| names.iter().any(|n| n == name);
+    has(b"AndroidManifest.xml") && (has(b"classes.dex") || has(b"resources.arsc"))
+}
+
+/// Reads a metadata block at `at`, returning it along with its length as
+/// stored
+fn metadata_block(source: &dyn DataSource, at: u64, compression: u16) -> Option<(Vec<u8>, u64)> {
+    let header = le_u16(&source.read_at(at, 2).ok()?, 0)?;
+    let len = (header & !METADATA_UNCOMPRESSED) as usize;
+    let data = source.read_at(at + 2, len).ok()?;
+    if data.len() != len || len == 0 {
+        return None;
+    }
+    let block = if header & METADATA_UNCOMPRESSED != 0 {
+        data.into_owned()
+    } else {
+        inflate_metadata(&data, compression)?
+    };
+    Some((block, 2 + len as u64))
+}
+
+#[cfg(feature = "archive")]
+fn inflate_metadata(data: &[u8], compression: u16) -> Option<Vec<u8>> {
+    use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
+
+    if compression != SQUASHFS_GZIP {
+        return None;
+    }
+    decompress_to_vec_zlib_with_limit(data, METADATA_MAX_LEN).ok()
+}
+
+#[cfg(not(feature = "archive"))]
+fn inflate_metadata(_data: &[u8], _compression: u16) -> Option<Vec<u8>> {
+    None
+}
+
+/// A squashfs image with `meta/snap.yaml`. Snaps are usually compressed
+/// with xz, which can't be read here, so only images whose directory
+/// table is stored as it is, or with the `archive` feature gzipped, are
+/// told apart from other squashfs images.
+fn is_snap(source: &dyn DataSource) -> bool {
+    let sb = match source.read_at(0, SQUASHFS_SUPERBLOCK_LEN) {
+        Ok(b) if b.len() == SQUASHFS_SUPERBLOCK_LEN && b.starts_with(SQUASHFS_MAGIC) => b,
+        _ => return false,
+    };
+    // Compression, the major version, and where the directory table starts
+    let (compression, mut at) = match (le_u16(&sb, 20), le_u16(&sb, 28), le_u64(&sb, 72)) {
+        (Some(c), Some(4), Some(at)) => (c, at),
+        _ => return false,
+    };
+    for _ in 0..MAX_METADATA_BLOCKS {
+        let (block, len) = match metadata_block(source, at, compression) {
+            Some(x) => x,
+            None => return false,
+        };
+        if block
+            .windows(SNAP_YAML_ENTRY.len())
+            .any(|w| w == SNAP_YAML_ENTRY)
+        {
+            return true;
+        }
+        at += len;
+    }
+    false
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    match mimetype {
+        "application/x-rpm" | "application/x-source-rpm" => {
+            let b = match source.read_at(0, RPM_PROBE_LEN) {
+                Ok(b) => b,
+                Err(_) => return false,
+            };
+            match rpm_type(&b) {
+                Some(1) => true,
+                Some(_) => mimetype == "application/x-rpm",
+                None => false,
+            }
+        }
+        "application/x-alpine-package" => match source.read_at(0, ALPINE_PROBE_LEN) {
+            Ok(b) => alpine(&b).is_some(),
+            Err(_) => false,
+        },
+        "application/vnd.android.package-archive" => is_android_package(source),
+        "application/vnd.snap" => is_snap(source),
+        _ => false,
+    }
+}
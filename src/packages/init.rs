@@ -0,0 +1,30 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    vec![
+        ("application/x-rpm", "application/x-source-rpm"),
+        // Packages of the second version are gzipped tar archives, the third
+        // version has a format of its own
+        (
+            "application/x-compressed-tar",
+            "application/x-alpine-package",
+        ),
+        ("application/octet-stream", "application/x-alpine-package"),
+        // Packages signed by the newer schemes only have no JAR manifest
+        ("application/zip", "application/vnd.android.package-archive"),
+        ("application/vnd.squashfs", "application/vnd.snap"),
+    ]
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [("application/x-redhat-package-manager", "application/x-rpm")]
+        .iter()
+        .copied()
+        .collect()
+}
@@ -0,0 +1,12 @@
+//! Tells software packages apart from the archives, compressed streams and
+//! file systems they're built on
+const TYPES: [&str; 5] = [
+    "application/x-rpm",
+    "application/x-source-rpm",
+    "application/x-alpine-package",
+    "application/vnd.android.package-archive",
+    "application/vnd.snap",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,110 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Pdf;
+
+impl crate::Checker for Pdf {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, _mimetype: &str) -> bool {
+        from_source(db, source)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        if db.strict_pdf() {
+            Some(HEADER_LEN)
+        } else {
+            Some(MAX_JUNK + HEADER_LEN)
+        }
+    }
+
+    fn variant(&self, source: &dyn DataSource, _mimetype: &str) -> Option<&'static str> {
+        variant(source)
+    }
+}
+
+/// `%PDF-` and a version, such as `%PDF-1.7`
+const HEADER_LEN: usize = 8;
+
+/// Readers find the header anywhere in the first 1024 bytes, so files
+/// often carry junk before it, such as mail or HTTP headers
+const MAX_JUNK: usize = 1024;
+
+/// The linearization dictionary of a file optimized for the web is the
+/// first object, and lies within the first 1024 bytes
+const LINEARIZED_LEN: usize = 1024;
+
+const VERSIONS: [&str; 9] = [
+    "1.0", "1.1", "1.2", "1.3", "1.4", "1.5", "1.6", "1.7", "2.0",
+];
+const LINEARIZED: [&str; 9] = [
+    "1.0-linearized",
+    "1.1-linearized",
+    "1.2-linearized",
+    "1.3-linearized",
+    "1.4-linearized",
+    "1.5-linearized",
+    "1.6-linearized",
+    "1.7-linearized",
+    "2.0-linearized",
+];
+
+/// Whether a header with a version starts at the start of `b`
+fn is_header(b: &[u8]) -> bool {
+    match b.get(..HEADER_LEN) {
+        Some([b'%', b'P', b'D', b'F', b'-', major, b'.', minor]) => {
+            major.is_ascii_digit() && minor.is_ascii_digit()
+        }
+        _ => false,
+    }
+}
+
+/// Where the header starts, if it starts within the first 1024 bytes
+fn find_header(b: &[u8]) -> Option<usize> {
+    b.windows(HEADER_LEN).take(MAX_JUNK).position(is_header)
+}
+
+/// In strict mode, see [`strict_pdf`](crate::TypeDatabaseBuilder::strict_pdf),
+/// the header must come first
+pub fn from_source(db: &TypeDatabase, source: &dyn DataSource) -> bool {
+    if db.strict_pdf() {
+        return match source.read_at(0, HEADER_LEN) {
+            Ok(b) => is_header(&b),
+            Err(_) => false,
+        };
+    }
+    match source.read_at(0, MAX_JUNK + HEADER_LEN) {
+        Ok(b) => find_header(&b).is_some(),
+        Err(_) => false,
+    }
+}
+
+/// Reports the version in the header, such as `1.7`, followed by
+/// `-linearized` for files optimized for the web
+pub fn variant(source: &dyn DataSource) -> Option<&'static str> {
+    let b = source
+        .read_at(0, MAX_JUNK + HEADER_LEN + LINEARIZED_LEN)
+        .ok()?;
+    let start = find_header(&b)?;
+    let index = VERSIONS
+        .iter()
+        .position(|v| v.as_bytes() == &b[start + 5..start + HEADER_LEN])?;
+
+    let first = &b[start..b.len().min(start + LINEARIZED_LEN)];
+    let linearized = first.windows(11).any(|w| w == b"/Linearized");
+    if linearized {
+        Some(LINEARIZED[index])
+    } else {
+        Some(VERSIONS[index])
+    }
+}
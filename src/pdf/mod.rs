@@ -0,0 +1,5 @@
+//! Handles PDF documents, whose header may come after some leading junk
+const TYPES: [&str; 1] = ["application/pdf"];
+
+pub mod check;
+pub mod init;
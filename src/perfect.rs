@@ -0,0 +1,173 @@
+//! Maps from type names built once with a perfect hash function, so a lookup
+//! hashes the name once and compares it with a single entry, whatever the
+//! number of types.
+//!
+//! The hash function is found with the CHD algorithm, as by the `phf` crate:
+//! names are hashed into buckets, then the buckets, largest first, are each
+//! given the displacement that moves all their names to free slots. Bucket
+//! and slot counts are powers of two, so finding them takes no division.
+use crate::MIME;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hasher;
+use core::mem::size_of;
+use fnv::FnvHasher;
+
+/// Average number of names hashed into a bucket
+const LAMBDA: usize = 4;
+
+/// The three hashes of a name: of its bucket, and the two a displacement
+/// combines into its slot
+struct Hashes {
+    g: u32,
+    f1: u32,
+    f2: u32,
+}
+
+/// Spreads the bits of FNV, which are too alike between close names
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+fn hash(seed: u64, key: &str) -> Hashes {
+    let mut hasher = FnvHasher::with_key(seed);
+    hasher.write(key.as_bytes());
+    let a = mix(hasher.finish());
+    Hashes {
+        g: (a >> 32) as u32,
+        f1: a as u32 | 1,
+        f2: (a >> 16) as u32,
+    }
+}
+
+/// Displacement of the names of a bucket
+type Disp = (u32, u32);
+
+fn displace(h: &Hashes, (d1, d2): Disp) -> u32 {
+    d2.wrapping_add(h.f1.wrapping_mul(d1)).wrapping_add(h.f2)
+}
+
+/// A map from names to values that can't change once built.
+pub(crate) struct PerfectMap<V> {
+    seed: u64,
+    /// Displacements of each bucket
+    disps: Vec<Disp>,
+    /// Entries in the slots the hash function gives them
+    slots: Vec<Option<(MIME, V)>>,
+}
+
+impl<V> Default for PerfectMap<V> {
+    fn default() -> Self {
+        PerfectMap {
+            seed: 0,
+            disps: Vec::new(),
+            slots: Vec::new(),
+        }
+    }
+}
+
+impl<V> PerfectMap<V> {
+    /// Finds a hash function for the names and places the entries. Names
+    /// must be unique.
+    pub(crate) fn new(entries: impl IntoIterator<Item = (MIME, V)>) -> Self {
+        let entries: Vec<(MIME, V)> = entries.into_iter().collect();
+        if entries.is_empty() {
+            return Self::default();
+        }
+        // A seed fails only for the odd set of names, so the next one is
+        // all but certain to work
+        let (seed, disps, placed) = (0..)
+            .find_map(|seed| generate(seed, &entries).map(|(d, p)| (seed, d, p)))
+            .unwrap();
+
+        let slots_len = entries.len().next_power_of_two();
+        let mut slots: Vec<Option<(MIME, V)>> = (0..slots_len).map(|_| None).collect();
+        for (entry, slot) in entries.into_iter().zip(placed) {
+            slots[slot] = Some(entry);
+        }
+        PerfectMap { seed, disps, slots }
+    }
+
+    /// Entry of the name, if it's in the map
+    pub(crate) fn get_key_value(&self, key: &str) -> Option<(&MIME, &V)> {
+        if self.disps.is_empty() {
+            return None;
+        }
+        let h = hash(self.seed, key);
+        let disp = self.disps[h.g as usize & (self.disps.len() - 1)];
+        match &self.slots[displace(&h, disp) as usize & (self.slots.len() - 1)] {
+            Some((k, v)) if *k == key => Some((k, v)),
+            _ => None,
+        }
+    }
+
+    /// Value of the name, if it's in the map
+    pub(crate) fn get(&self, key: &str) -> Option<&V> {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    /// All entries, in no particular order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&MIME, &V)> {
+        self.slots.iter().flatten().map(|(k, v)| (k, v))
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+
+    /// Bytes held by the slots and the displacements
+    pub(crate) fn size(&self) -> usize {
+        self.slots.capacity() * size_of::<Option<(MIME, V)>>()
+            + self.disps.capacity() * size_of::<Disp>()
+    }
+}
+
+/// The displacement of each bucket and the slot of each entry for the seed,
+/// if every bucket can be placed
+fn generate<V>(seed: u64, entries: &[(MIME, V)]) -> Option<(Vec<Disp>, Vec<usize>)> {
+    let hashes: Vec<Hashes> = entries.iter().map(|(k, _)| hash(seed, k)).collect();
+    let slots_len = entries.len().next_power_of_two();
+    let buckets_len = entries.len().div_ceil(LAMBDA).next_power_of_two();
+
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); buckets_len];
+    for (i, h) in hashes.iter().enumerate() {
+        buckets[h.g as usize & (buckets_len - 1)].push(i);
+    }
+    let mut order: Vec<usize> = (0..buckets_len).collect();
+    order.sort_by_key(|&b| core::cmp::Reverse(buckets[b].len()));
+
+    let mut taken = vec![false; slots_len];
+    let mut placed = vec![0; entries.len()];
+    let mut disps = vec![(0, 0); buckets_len];
+    // Slots taken by the displacement being tried, marked with its number so
+    // they needn't be cleared between tries
+    let mut tried = vec![0u64; slots_len];
+    let mut attempt = 0;
+
+    'buckets: for &b in &order {
+        for d1 in 0..slots_len as u32 {
+            'disps: for d2 in 0..slots_len as u32 {
+                attempt += 1;
+                for &i in &buckets[b] {
+                    let slot = displace(&hashes[i], (d1, d2)) as usize & (slots_len - 1);
+                    if taken[slot] || tried[slot] == attempt {
+                        continue 'disps;
+                    }
+                    tried[slot] = attempt;
+                }
+                disps[b] = (d1, d2);
+                for &i in &buckets[b] {
+                    let slot = displace(&hashes[i], (d1, d2)) as usize & (slots_len - 1);
+                    taken[slot] = true;
+                    placed[i] = slot;
+                }
+                continue 'buckets;
+            }
+        }
+        return None;
+    }
+    Some((disps, placed))
+}
@@ -0,0 +1,98 @@
+//! Map types to the file extensions of the Windows registry and back, for
+//! working with the associations of the shell.
+//!
+//! An extension key such as `HKEY_CLASSES_ROOT\.png` names its type in its
+//! `Content Type` value, and a type key under
+//! `HKEY_CLASSES_ROOT\MIME\Database\Content Type` names its extension in its
+//! `Extension` value. Types are compared by their canonical names, so
+//! aliases map too.
+//!
+//! Needs the `registry` feature, on Windows.
+//!
+//! # Examples
+//! ```rust
+//! use tree_magic_mini::registry;
+//!
+//! let gif: &[u8] = include_bytes!("../tests/image/gif");
+//! let ext = registry::to_extension(tree_magic_mini::from_u8(gif));
+//! assert_eq!(ext.as_deref(), Some(".gif"));
+//! assert_eq!(registry::from_extension("gif"), Some("image/gif"));
+//! ```
+use crate::{database, MIME};
+use alloc::{format, string::String, vec::Vec};
+use std::io;
+use windows_registry::{Key, CLASSES_ROOT, CURRENT_USER};
+
+/// Where the types of the database are kept, under a classes key
+const DATABASE: &str = r"MIME\Database\Content Type";
+
+/// Where per-user classes are written, which `HKEY_CLASSES_ROOT` merges with
+/// those of the machine
+const USER_CLASSES: &str = r"Software\Classes";
+
+/// The extension as the registry keeps it, with its leading dot
+fn extension_key(extension: &str) -> String {
+    format!(".{}", extension.trim_start_matches('.'))
+}
+
+/// Reads a string value, if the key and the value are there
+fn get_string(root: &Key, path: &str, name: &str) -> Option<String> {
+    root.open(path).ok()?.get_string(name).ok()
+}
+
+/// Gets the type the registry gives a file extension, such as `image/png`
+/// for `png` or `.png`.
+///
+/// The type is resolved against the database, so aliases come back as the
+/// type they stand for, and types the database doesn't know as `None`.
+pub fn from_extension(extension: &str) -> Option<MIME> {
+    let content_type = get_string(CLASSES_ROOT, &extension_key(extension), "Content Type")?;
+    let db = database();
+    let node = db.node(db.get_alias(&content_type.to_ascii_lowercase()))?;
+    Some(db.graph()[node])
+}
+
+/// Gets the extension the registry gives a type, with its leading dot, such
+/// as `.png` for `image/png`.
+///
+/// The canonical name of the type is looked up first, then its aliases, as
+/// some types are registered under an older name.
+pub fn to_extension(mimetype: &str) -> Option<String> {
+    let db = database();
+    let mimetype = db.get_alias(mimetype);
+    let mut names = Vec::from([mimetype]);
+    let mut aliases: Vec<MIME> = db
+        .aliases()
+        .filter(|&(_, &m)| m == mimetype)
+        .map(|(&a, _)| a)
+        .collect();
+    aliases.sort_unstable();
+    names.extend(aliases);
+    names.into_iter().find_map(|name| {
+        get_string(
+            CLASSES_ROOT,
+            &format!(r"{}\{}", DATABASE, name),
+            "Extension",
+        )
+    })
+}
+
+/// Registers a type for a file extension for the current user, in both
+/// directions, so [`from_extension`] and [`to_extension`] find them. The
+/// type is written under its canonical name.
+///
+/// Only the keys of the current user are written, which needs no
+/// administrator rights, and takes precedence over the keys of the machine.
+/// Any type or extension registered before is replaced.
+pub fn register_extension(extension: &str, mimetype: &str) -> io::Result<()> {
+    let mimetype = database().get_alias(mimetype);
+    let extension = extension_key(extension);
+    let classes = CURRENT_USER.create(USER_CLASSES)?;
+    classes
+        .create(&extension)?
+        .set_string("Content Type", mimetype)?;
+    classes
+        .create(format!(r"{}\{}", DATABASE, mimetype))?
+        .set_string("Extension", &extension)?;
+    Ok(())
+}
@@ -0,0 +1,244 @@
+//! Detect the types of remote objects, such as those in S3 or behind HTTP,
+//! with range requests for only the bytes detection reads.
+//!
+//! Detection runs over the ranges fetched so far, noting the reads they
+//! can't serve. Those are fetched together, and detection runs again, until
+//! it has everything it read. The start of the object is fetched up front,
+//! as nearly every check looks there, so most objects take one or two
+//! rounds of requests.
+//!
+//! Storage is read through [`RangeRead`], which the `object-store` feature
+//! implements for the stores of the `object_store` crate, see
+//! [`from_object_store`].
+//!
+//! Needs the `remote` feature.
+//!
+//! # Examples
+//! ```rust
+//! use std::io;
+//! use std::ops::Range;
+//! use tree_magic_mini::remote::{self, RangeRead};
+//!
+//! /// An object in memory, standing in for remote storage
+//! struct Object(&'static [u8]);
+//!
+//! impl RangeRead for Object {
+//!     async fn read_range(&self, range: Range<u64>) -> io::Result<Vec<u8>> {
+//!         let end = (range.end as usize).min(self.0.len());
+//!         Ok(self.0[(range.start as usize).min(end)..end].to_vec())
+//!     }
+//! }
+//!
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let gif = Object(include_bytes!("../tests/image/gif"));
+//! let mime = remote::from_reader(&gif, Some(gif.0.len() as u64)).await?;
+//! assert_eq!(mime, Some("image/gif"));
+//! # io::Result::Ok(())
+//! # }).unwrap();
+//! ```
+use crate::source::PREFIX_LEN;
+use crate::{database, DataSource, ReadError, TypeDatabase, MIME};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::future::Future;
+use core::ops::Range;
+use std::io;
+
+/// Gap between two missed reads below which they're fetched as one range,
+/// as a request costs more than the bytes in between
+const MERGE_GAP: u64 = 4096;
+
+/// Storage an object can be read from by byte ranges, such as with HTTP
+/// range requests.
+pub trait RangeRead: Sync {
+    /// Reads the bytes of the object in `range`.
+    ///
+    /// Returns fewer bytes, possibly none, only if the object ends before
+    /// the end of the range.
+    fn read_range(&self, range: Range<u64>) -> impl Future<Output = io::Result<Vec<u8>>> + Send;
+
+    /// Reads the bytes of the object in several ranges, in order.
+    ///
+    /// Reads them one after the other, unless the storage can do better,
+    /// such as with a single request or concurrent ones.
+    fn read_ranges(
+        &self,
+        ranges: &[Range<u64>],
+    ) -> impl Future<Output = io::Result<Vec<Vec<u8>>>> + Send {
+        async move {
+            let mut out = Vec::with_capacity(ranges.len());
+            for range in ranges {
+                out.push(self.read_range(range.clone()).await?);
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// The ranges of an object fetched so far, merged where they touch, and
+/// the reads they couldn't serve
+struct Fetched {
+    chunks: Vec<(u64, Vec<u8>)>,
+    /// Where the object ends, once known
+    end: Option<u64>,
+    misses: RefCell<Vec<Range<u64>>>,
+}
+
+impl Fetched {
+    /// The range clamped to the end of the object
+    fn clamp(&self, range: Range<u64>) -> Range<u64> {
+        match self.end {
+            Some(end) => range.start.min(end)..range.end.min(end),
+            None => range,
+        }
+    }
+
+    fn insert(&mut self, start: u64, data: Vec<u8>, asked: u64) {
+        let end = start + data.len() as u64;
+        if (data.len() as u64) < asked {
+            self.end = Some(self.end.map_or(end, |e| e.min(end)));
+        }
+        self.chunks.push((start, data));
+        self.chunks.sort_by_key(|c| c.0);
+
+        let mut merged: Vec<(u64, Vec<u8>)> = Vec::with_capacity(self.chunks.len());
+        for (start, data) in self.chunks.drain(..) {
+            match merged.last_mut() {
+                Some((s, d)) if start <= *s + d.len() as u64 => {
+                    let overlap = (*s + d.len() as u64 - start) as usize;
+                    if overlap < data.len() {
+                        d.extend_from_slice(&data[overlap..]);
+                    }
+                }
+                _ => merged.push((start, data)),
+            }
+        }
+        self.chunks = merged;
+    }
+
+    /// Takes the missed reads, merged where they're close together
+    fn take_misses(&mut self) -> Vec<Range<u64>> {
+        let mut misses = core::mem::take(self.misses.get_mut());
+        misses.sort_by_key(|r| r.start);
+        let mut merged: Vec<Range<u64>> = Vec::with_capacity(misses.len());
+        for r in misses {
+            match merged.last_mut() {
+                Some(m) if r.start <= m.end.saturating_add(MERGE_GAP) => m.end = m.end.max(r.end),
+                _ => merged.push(r),
+            }
+        }
+        merged
+    }
+}
+
+fn not_fetched() -> ReadError {
+    io::Error::other("range not fetched yet")
+}
+
+impl DataSource for Fetched {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>, ReadError> {
+        let range = self.clamp(offset..offset.saturating_add(len as u64));
+        if range.is_empty() {
+            return Ok(Cow::Borrowed(&[]));
+        }
+        for (start, data) in &self.chunks {
+            let end = start + data.len() as u64;
+            if *start <= range.start && range.end <= end {
+                let from = (range.start - start) as usize;
+                let to = (range.end - start) as usize;
+                return Ok(Cow::Borrowed(&data[from..to]));
+            }
+        }
+        // Refused whole rather than cut short, which checks would take for
+        // the end of the data
+        self.misses.borrow_mut().push(range);
+        Err(not_fetched())
+    }
+
+    fn len_hint(&self) -> Option<u64> {
+        self.end
+    }
+}
+
+/// Gets the type of a remote object with the given database, fetching only
+/// the ranges detection reads.
+///
+/// The size of the object, when known, saves reads past its end, and lets
+/// checks that look at its end work. Fails with the first error the storage
+/// returns.
+pub async fn detect<'a, R: RangeRead>(
+    db: &'a TypeDatabase,
+    reader: &R,
+    size: Option<u64>,
+) -> io::Result<Option<&'a str>> {
+    let mut fetched = Fetched {
+        chunks: Vec::new(),
+        end: size,
+        misses: RefCell::new(Vec::new()),
+    };
+    let mut ranges = Vec::from([fetched.clamp(0..PREFIX_LEN as u64)]);
+    loop {
+        ranges.retain(|r| !r.is_empty());
+        let data = reader.read_ranges(&ranges).await?;
+        for (range, data) in ranges.iter().zip(data) {
+            fetched.insert(range.start, data, range.end - range.start);
+        }
+
+        let found = db.from_source(&fetched);
+        ranges = fetched.take_misses();
+        if ranges.is_empty() {
+            return Ok(found);
+        }
+    }
+}
+
+/// Gets the type of a remote object, fetching only the ranges detection
+/// reads.
+///
+/// See [`detect`], which takes a database.
+pub async fn from_reader<R: RangeRead>(reader: &R, size: Option<u64>) -> io::Result<Option<MIME>> {
+    detect(database(), reader, size).await
+}
+
+#[cfg(feature = "object-store")]
+mod store {
+    use super::RangeRead;
+    use alloc::vec::Vec;
+    use core::ops::Range;
+    use object_store::{path::Path, ObjectStore};
+    use std::io;
+
+    /// An object of a store
+    pub(super) struct StoreObject<'a> {
+        pub(super) store: &'a dyn ObjectStore,
+        pub(super) location: &'a Path,
+    }
+
+    impl RangeRead for StoreObject<'_> {
+        async fn read_range(&self, range: Range<u64>) -> io::Result<Vec<u8>> {
+            let bytes = self.store.get_range(self.location, range).await?;
+            Ok(bytes.to_vec())
+        }
+
+        async fn read_ranges(&self, ranges: &[Range<u64>]) -> io::Result<Vec<Vec<u8>>> {
+            let bytes = self.store.get_ranges(self.location, ranges).await?;
+            Ok(bytes.iter().map(|b| b.to_vec()).collect())
+        }
+    }
+}
+
+/// Gets the type of an object of an `object_store` store, such as S3,
+/// fetching only the ranges detection reads.
+///
+/// Asks the store for the size of the object first. Needs the
+/// `object-store` feature.
+#[cfg(feature = "object-store")]
+pub async fn from_object_store(
+    store: &dyn object_store::ObjectStore,
+    location: &object_store::path::Path,
+) -> io::Result<Option<MIME>> {
+    let size = store.head(location).await?.size;
+    let object = store::StoreObject { store, location };
+    from_reader(&object, Some(size)).await
+}
@@ -0,0 +1,103 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Riff;
+
+impl crate::Checker for Riff {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        Some(HEADER_LEN)
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        variant(source, mimetype)
+    }
+}
+
+/// RIFF header, form type and the header of the first chunk
+const HEADER_LEN: usize = 20;
+
+/// The parts of a RIFF header we care about
+struct Header {
+    /// `RIFF`, or `RF64` for WAV files over 4 GiB
+    magic: [u8; 4],
+    form: [u8; 4],
+    first_chunk: [u8; 4],
+}
+
+/// Chunk IDs are four printable ASCII characters, padded with spaces
+fn is_fourcc(id: &[u8]) -> bool {
+    id.iter().all(|&c| (0x20..=0x7E).contains(&c))
+}
+
+fn read_header(source: &dyn DataSource) -> Option<Header> {
+    let b = source.read_at(0, HEADER_LEN).ok()?;
+    if b.len() < HEADER_LEN {
+        return None;
+    }
+    let mut header = Header {
+        magic: [0; 4],
+        form: [0; 4],
+        first_chunk: [0; 4],
+    };
+    header.magic.copy_from_slice(&b[0..4]);
+    header.form.copy_from_slice(&b[8..12]);
+    header.first_chunk.copy_from_slice(&b[12..16]);
+
+    if !(&header.magic == b"RIFF" || &header.magic == b"RF64") || !is_fourcc(&header.first_chunk) {
+        return None;
+    }
+    Some(header)
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    let header = match read_header(source) {
+        Some(x) => x,
+        None => return false,
+    };
+
+    match mimetype {
+        "application/x-riff" => is_fourcc(&header.form),
+        "audio/x-wav" => &header.form == b"WAVE",
+        "video/x-msvideo" => {
+            &header.magic == b"RIFF" && (&header.form == b"AVI " || &header.form == b"AVIX")
+        }
+        "image/webp" => {
+            &header.magic == b"RIFF"
+                && &header.form == b"WEBP"
+                && matches!(&header.first_chunk, b"VP8 " | b"VP8L" | b"VP8X")
+        }
+        _ => false,
+    }
+}
+
+/// Reports WebP compression, and whether a WAV file uses the RF64 extension
+pub fn variant(source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+    let header = read_header(source)?;
+
+    match mimetype {
+        "audio/x-wav" if &header.magic == b"RF64" => Some("rf64"),
+        "image/webp" => match &header.first_chunk {
+            b"VP8 " => Some("lossy"),
+            b"VP8L" => Some("lossless"),
+            b"VP8X" => Some("extended"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,10 @@
+//! Handles RIFF containers (WAV, AVI, WebP) by reading the form type and first chunk
+const TYPES: [&str; 4] = [
+    "application/x-riff",
+    "audio/x-wav",
+    "video/x-msvideo",
+    "image/webp",
+];
+
+pub mod check;
+pub mod init;
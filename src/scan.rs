@@ -0,0 +1,140 @@
+//! Find files embedded in larger blobs by their magic signatures
+use crate::{TypeDatabase, MIME};
+use alloc::vec::Vec;
+use petgraph::prelude::*;
+
+/// Settings for [`scan_embedded`](crate::scan_embedded).
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::ScanOptions;
+///
+/// // Only look at the first megabyte, for signatures of 6 bytes or more
+/// let opts = ScanOptions::new().max_offset(1 << 20).min_signature_len(6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+    pub(crate) max_offset: usize,
+    pub(crate) min_signature_len: usize,
+    pub(crate) max_results: usize,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            max_offset: usize::MAX,
+            min_signature_len: 4,
+            max_results: usize::MAX,
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Scan the whole buffer, for signatures of at least 4 bytes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Don't look for files starting past this offset.
+    pub fn max_offset(mut self, offset: usize) -> Self {
+        self.max_offset = offset;
+        self
+    }
+
+    /// Ignore types whose signature is shorter than this. Short signatures
+    /// such as `BM` occur by chance in most large blobs.
+    pub fn min_signature_len(mut self, len: usize) -> Self {
+        self.min_signature_len = len;
+        self
+    }
+
+    /// Stop after finding this many files.
+    pub fn max_results(mut self, count: usize) -> Self {
+        self.max_results = count;
+        self
+    }
+}
+
+/// Types with a fixed signature at the start of the file, by first byte
+struct SignatureIndex<'a> {
+    buckets: Vec<Vec<(&'a [u8], MIME)>>,
+}
+
+impl<'a> SignatureIndex<'a> {
+    fn new(db: &'a TypeDatabase, min_len: usize) -> Self {
+        let mut buckets = Vec::new();
+        buckets.resize_with(256, Vec::new);
+
+        for (mime, rules) in db.all_magic() {
+            for root in rules.externals(Incoming) {
+                let rule = &rules[root];
+                if rule.negate
+                    || rule.start_off != 0
+                    || rule.region_len != 0
+                    || rule.mask.is_some()
+                    || rule.val.len() < min_len.max(1)
+                {
+                    continue;
+                }
+                buckets[rule.val[0] as usize].push((rule.val, mime));
+            }
+        }
+
+        // Longer signatures are more specific, so try them first. A type can
+        // have several, such as `GIF87a` and `GIF89a`; only exact repeats go.
+        for bucket in buckets.iter_mut() {
+            bucket.sort_by(|a, b| {
+                b.0.len()
+                    .cmp(&a.0.len())
+                    .then(a.1.cmp(b.1))
+                    .then(a.0.cmp(b.0))
+            });
+            bucket.dedup();
+        }
+        SignatureIndex { buckets }
+    }
+
+    /// Types whose signature `data` starts with
+    fn candidates<'s>(&'s self, data: &'s [u8]) -> impl Iterator<Item = MIME> + 's {
+        let bucket = match data.first() {
+            Some(&b) => &self.buckets[b as usize][..],
+            None => &[],
+        };
+        bucket
+            .iter()
+            .filter(move |(sig, _)| data.starts_with(sig))
+            .map(|&(_, mime)| mime)
+    }
+}
+
+/// Slides through `bytes`, reporting files that start at a non-zero offset.
+pub(crate) fn scan_embedded<'a>(
+    db: &'a TypeDatabase,
+    bytes: &[u8],
+    options: &ScanOptions,
+) -> Vec<(usize, &'a str)> {
+    let index = SignatureIndex::new(db, options.min_signature_len);
+    let last = core::cmp::min(options.max_offset, bytes.len().saturating_sub(1));
+
+    let mut out = Vec::new();
+    for offset in 1..=last {
+        if out.len() >= options.max_results {
+            break;
+        }
+        let data = &bytes[offset..];
+
+        for mime in index.candidates(data) {
+            // The signature is only the first rule, let the checker decide
+            if !db.match_source_noalias(mime, &data) {
+                continue;
+            }
+            let found = match db.node(mime) {
+                Some(node) => db.typegraph_walker(node, &data, &mut 0).unwrap_or(mime),
+                None => mime,
+            };
+            out.push((offset, found));
+            break;
+        }
+    }
+    out
+}
@@ -0,0 +1,258 @@
+use crate::source::source_len;
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Scientific;
+
+impl crate::Checker for Scientific {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        let found = match mimetype {
+            "application/x-hdf" => hdf(source).is_some(),
+            "application/x-netcdf" => netcdf(source).is_some(),
+            "application/fits" => read(source, FITS_HEADER_LEN).is_some_and(|b| is_fits(&b)),
+            "application/dicom" => read(source, DICOM_PROBE_LEN).is_some_and(|b| is_dicom(&b)),
+            "application/x-nifti" => {
+                read(source, NIFTI1_HEADER_LEN).is_some_and(|b| nifti(&b).is_some())
+            }
+            _ => false,
+        };
+        found || fdo_magic::builtin::check::from_source(db, source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "application/fits" => Some(FITS_HEADER_LEN),
+            "application/dicom" => Some(DICOM_PROBE_LEN),
+            "application/x-nifti" => Some(NIFTI1_HEADER_LEN),
+            // HDF5 may start after a user block of any power of two
+            _ => None,
+        }
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        match mimetype {
+            "application/x-hdf" => hdf(source),
+            "application/x-netcdf" => netcdf(source),
+            "application/x-nifti" => nifti(&read(source, NIFTI1_HEADER_LEN)?),
+            _ => None,
+        }
+    }
+}
+
+const HDF4_MAGIC: &[u8; 4] = b"\x0e\x03\x13\x01";
+const HDF5_MAGIC: &[u8; 8] = b"\x89HDF\r\n\x1a\n";
+/// HDF5 data can follow a user block, so the superblock is also looked for
+/// at 512 and each power of two after it
+const HDF5_FIRST_OFFSET: u64 = 512;
+const HDF5_MAX_OFFSETS: usize = 24;
+
+/// Attribute netCDF-C writes to the root group of every NetCDF-4 file
+const NETCDF4_ATTRIBUTE: &[u8] = b"_NCProperties";
+/// How far past the superblock to look for it
+const NETCDF4_PROBE_LEN: usize = 8192;
+
+/// Two header cards of 80 characters: `SIMPLE = T`, then `BITPIX`
+const FITS_HEADER_LEN: usize = 160;
+const FITS_CARD_LEN: usize = 80;
+
+/// A preamble of 128 bytes, then `DICM`
+const DICOM_MAGIC_OFFSET: usize = 128;
+const DICOM_PROBE_LEN: usize = 512;
+/// Value representations, of which these have a 32-bit length after two
+/// reserved bytes, rather than a 16-bit one
+const DICOM_VRS: [&[u8; 2]; 34] = [
+    b"AE", b"AS", b"AT", b"CS", b"DA", b"DS", b"DT", b"FL", b"FD", b"IS", b"LO", b"LT", b"PN",
+    b"SH", b"SL", b"SS", b"ST", b"TM", b"UI", b"UL", b"US", b"OB", b"OD", b"OF", b"OL", b"OV",
+    b"OW", b"SQ", b"SV", b"UC", b"UN", b"UR", b"UT", b"UV",
+];
+const DICOM_LONG_VRS: [&[u8; 2]; 13] = [
+    b"OB", b"OD", b"OF", b"OL", b"OV", b"OW", b"SQ", b"SV", b"UC", b"UN", b"UR", b"UT", b"UV",
+];
+/// Elements that must parse at the start of a file without the preamble
+const DICOM_MIN_ELEMENTS: usize = 3;
+
+/// `sizeof_hdr`, which NIfTI uses to tell the byte order too
+const NIFTI1_HEADER_LEN: usize = 348;
+const NIFTI2_HEADER_LEN: u32 = 540;
+/// The NIfTI-1 magic comes at the end of the header, as the ANALYZE 7.5
+/// format it extends has nothing there
+const NIFTI1_MAGIC_OFFSET: usize = 344;
+
+fn read(source: &dyn DataSource, len: usize) -> Option<Vec<u8>> {
+    source.read_at(0, len).ok().map(|b| b.into_owned())
+}
+
+fn le16(b: &[u8], at: usize) -> Option<u16> {
+    let b = b.get(at..at + 2)?;
+    Some(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn le32(b: &[u8], at: usize) -> Option<u32> {
+    let b = b.get(at..at + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Where the HDF5 superblock is
+fn hdf5_offset(source: &dyn DataSource) -> Option<u64> {
+    let len = source_len(source);
+    let mut offset = 0;
+    for _ in 0..HDF5_MAX_OFFSETS {
+        if len.is_some_and(|len| offset + HDF5_MAGIC.len() as u64 > len) {
+            return None;
+        }
+        match source.read_at(offset, HDF5_MAGIC.len()) {
+            Ok(b) if b[..] == HDF5_MAGIC[..] => return Some(offset),
+            Ok(b) if b.len() == HDF5_MAGIC.len() => {}
+            _ => return None,
+        }
+        offset = match offset {
+            0 => HDF5_FIRST_OFFSET,
+            x => x * 2,
+        };
+    }
+    None
+}
+
+/// Whether the data is `hdf4` or `hdf5`
+fn hdf(source: &dyn DataSource) -> Option<&'static str> {
+    match source.read_at(0, HDF4_MAGIC.len()) {
+        Ok(b) if b[..] == HDF4_MAGIC[..] => Some("hdf4"),
+        _ => hdf5_offset(source).map(|_| "hdf5"),
+    }
+}
+
+/// The format of NetCDF data: `classic`, `64bit-offset` or `cdf5`, each
+/// with its own version byte, or `netcdf4`, which is HDF5 written by the
+/// NetCDF library
+fn netcdf(source: &dyn DataSource) -> Option<&'static str> {
+    let b = source.read_at(0, 4).ok()?;
+    if let Some(version) = b.strip_prefix(b"CDF") {
+        return match version {
+            [1] => Some("classic"),
+            [2] => Some("64bit-offset"),
+            [5] => Some("cdf5"),
+            _ => None,
+        };
+    }
+    let offset = hdf5_offset(source)?;
+    let b = source.read_at(offset, NETCDF4_PROBE_LEN).ok()?;
+    b.windows(NETCDF4_ATTRIBUTE.len())
+        .any(|w| w == NETCDF4_ATTRIBUTE)
+        .then_some("netcdf4")
+}
+
+/// A card giving `keyword`, which is padded to 8 characters, and its value
+fn fits_card<'a>(card: &'a [u8], keyword: &[u8]) -> Option<&'a [u8]> {
+    let (name, rest) = card.split_at(8);
+    if !name.starts_with(keyword) || name[keyword.len()..].iter().any(|&c| c != b' ') {
+        return None;
+    }
+    rest.strip_prefix(b"= ")
+}
+
+/// The primary header starts with `SIMPLE` set to true in column 30, then
+/// the number of bits per pixel, which is one of a few
+fn is_fits(b: &[u8]) -> bool {
+    if b.len() < FITS_HEADER_LEN {
+        return false;
+    }
+    let (simple, bitpix) = b.split_at(FITS_CARD_LEN);
+    let simple = match fits_card(simple, b"SIMPLE") {
+        Some(x) => x,
+        None => return false,
+    };
+    let bitpix = match fits_card(bitpix, b"BITPIX") {
+        Some(x) => core::str::from_utf8(x).ok().and_then(|x| {
+            let x = x.split('/').next()?.trim();
+            x.parse::<i32>().ok()
+        }),
+        None => return false,
+    };
+    simple.get(19) == Some(&b'T') && matches!(bitpix, Some(8 | 16 | 32 | 64 | -32 | -64))
+}
+
+/// The length of the element at `at` and of its header, and its tag, if the
+/// element is in explicit or implicit little-endian form
+fn dicom_element(b: &[u8], at: usize) -> Option<((u16, u16), usize)> {
+    let tag = (le16(b, at)?, le16(b, at + 2)?);
+    let vr = b.get(at + 4..at + 6)?;
+    let (header, len) = if DICOM_LONG_VRS.iter().any(|v| &v[..] == vr) {
+        if b.get(at + 6..at + 8)? != [0, 0] {
+            return None;
+        }
+        (12, le32(b, at + 8)?)
+    } else if DICOM_VRS.iter().any(|v| &v[..] == vr) {
+        (8, le16(b, at + 6)? as u32)
+    } else {
+        (8, le32(b, at + 4)?)
+    };
+    // Sequences of undefined length end the elements followed
+    if len == u32::MAX {
+        return Some((tag, usize::MAX));
+    }
+    Some((tag, header + len as usize))
+}
+
+/// `DICM` after the preamble, or, for files written without one, elements
+/// of the file meta or identifying group in ascending order
+fn is_dicom(b: &[u8]) -> bool {
+    if b.get(DICOM_MAGIC_OFFSET..DICOM_MAGIC_OFFSET + 4) == Some(b"DICM") {
+        return true;
+    }
+    let group = match le16(b, 0) {
+        Some(g @ (0x0002 | 0x0008)) => g,
+        _ => return false,
+    };
+    let mut at = 0;
+    let mut last = None;
+    for _ in 0..DICOM_MIN_ELEMENTS {
+        let (tag, len) = match dicom_element(b, at) {
+            Some(x) => x,
+            None => return false,
+        };
+        if tag.0 != group || last.is_some_and(|l| tag <= l) {
+            return false;
+        }
+        last = Some(tag);
+        at = match at.checked_add(len) {
+            Some(x) if x <= b.len() => x,
+            _ => return false,
+        };
+    }
+    true
+}
+
+/// `nifti1-single` or `nifti2-single` for a `.nii` file holding the image
+/// too, or `nifti1-pair` or `nifti2-pair` for a `.hdr` file with the image
+/// in an `.img` file next to it
+fn nifti(b: &[u8]) -> Option<&'static str> {
+    let size = le32(b, 0)?;
+    let swapped = size.swap_bytes();
+    if size == NIFTI1_HEADER_LEN as u32 || swapped == NIFTI1_HEADER_LEN as u32 {
+        return match b.get(NIFTI1_MAGIC_OFFSET..NIFTI1_HEADER_LEN)? {
+            b"n+1\0" => Some("nifti1-single"),
+            b"ni1\0" => Some("nifti1-pair"),
+            _ => None,
+        };
+    }
+    if size == NIFTI2_HEADER_LEN || swapped == NIFTI2_HEADER_LEN {
+        return match b.get(4..12)? {
+            b"n+2\0\r\n\x1a\n" => Some("nifti2-single"),
+            b"ni2\0\r\n\x1a\n" => Some("nifti2-pair"),
+            _ => None,
+        };
+    }
+    None
+}
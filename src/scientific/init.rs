@@ -0,0 +1,28 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    // NetCDF-4 is stored as HDF5, while the older formats are not
+    vec![
+        ("application/x-hdf", "application/x-netcdf"),
+        ("application/octet-stream", "application/x-netcdf"),
+    ]
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/x-hdf5", "application/x-hdf"),
+        ("application/netcdf", "application/x-netcdf"),
+        ("image/fits", "application/fits"),
+        ("image/x-fits", "application/fits"),
+        ("image/dicom", "application/dicom"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
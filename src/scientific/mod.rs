@@ -0,0 +1,11 @@
+//! Handles scientific and medical data: HDF, NetCDF, FITS, DICOM and NIfTI
+const TYPES: [&str; 5] = [
+    "application/x-hdf",
+    "application/x-netcdf",
+    "application/fits",
+    "application/dicom",
+    "application/x-nifti",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,61 @@
+//! Find the bytes a detection hinges on, by altering them one at a time
+use crate::{DataSource, ReadError, TypeDatabase};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::ops::Range;
+
+/// Bits flipped in turn to alter a byte: the lowest, which changes letters
+/// and digits, and the highest, which moves a byte in or out of ASCII
+const FLIPS: [u8; 2] = [0x01, 0x80];
+
+/// Bytes that remembers how far into them it was read
+struct Extent<'a> {
+    bytes: &'a [u8],
+    end: Cell<usize>,
+}
+
+impl DataSource for Extent<'_> {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>, ReadError> {
+        let out = self.bytes.read_at(offset, len)?;
+        if !out.is_empty() {
+            let end = offset as usize + out.len();
+            self.end.set(self.end.get().max(end));
+        }
+        Ok(out)
+    }
+
+    // No `as_slice`, so every read is seen
+}
+
+pub(crate) fn sensitivity<'a>(db: &'a TypeDatabase, bytes: &[u8]) -> Vec<(Range<usize>, &'a str)> {
+    let source = Extent {
+        bytes,
+        end: Cell::new(0),
+    };
+    let found = match db.from_source(&source) {
+        Some(x) => x,
+        None => return Vec::new(),
+    };
+
+    // Bytes past the furthest read can't change the walk
+    let mut out: Vec<(Range<usize>, &str)> = Vec::new();
+    let mut altered = bytes.to_vec();
+    for i in 0..source.end.get() {
+        let original = altered[i];
+        let changed = FLIPS.iter().find_map(|flip| {
+            altered[i] = original ^ flip;
+            let mime = db.from_u8(&altered);
+            (mime != found).then_some(mime)
+        });
+        altered[i] = original;
+
+        if let Some(mime) = changed {
+            match out.last_mut() {
+                Some((range, last)) if range.end == i && *last == mime => range.end += 1,
+                _ => out.push((i..i + 1, mime)),
+            }
+        }
+    }
+    out
+}
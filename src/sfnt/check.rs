@@ -0,0 +1,151 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Sfnt;
+
+impl crate::Checker for Sfnt {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        variant(source, mimetype)
+    }
+}
+
+/// sfnt versions for TrueType outlines
+const TRUETYPE: [&[u8; 4]; 3] = [b"\x00\x01\x00\x00", b"true", b"typ1"];
+/// sfnt version for CFF outlines
+const CFF: &[u8; 4] = b"OTTO";
+
+/// Every table directory entry is a tag, checksum, offset and length
+const TABLE_RECORD_LEN: usize = 16;
+const OFFSET_TABLE_LEN: usize = 12;
+
+/// Fonts can't do without at least one of these
+const REQUIRED_TABLES: [&[u8; 4]; 3] = [b"head", b"cmap", b"name"];
+
+fn be_u16(b: &[u8], off: usize) -> Option<u16> {
+    let x = b.get(off..off + 2)?;
+    Some(u16::from_be_bytes([x[0], x[1]]))
+}
+
+fn be_u32(b: &[u8], off: usize) -> Option<u32> {
+    let x = b.get(off..off + 4)?;
+    Some(u32::from_be_bytes([x[0], x[1], x[2], x[3]]))
+}
+
+fn is_sfnt_version(v: &[u8]) -> bool {
+    v == CFF || TRUETYPE.iter().any(|t| v == *t)
+}
+
+/// Checks the offset table and table directory of an sfnt font starting at
+/// `start`, returning its version tag
+fn read_sfnt(source: &dyn DataSource, start: u64) -> Option<[u8; 4]> {
+    let header = source.read_at(start, OFFSET_TABLE_LEN).ok()?;
+    let version = header.get(0..4)?;
+    if !is_sfnt_version(version) {
+        return None;
+    }
+    let num_tables = be_u16(&header, 4)? as usize;
+    if num_tables == 0 {
+        return None;
+    }
+
+    let dir_len = num_tables * TABLE_RECORD_LEN;
+    let dir = source
+        .read_at(start + OFFSET_TABLE_LEN as u64, dir_len)
+        .ok()?;
+    if dir.len() < dir_len {
+        return None;
+    }
+
+    let mut has_required = false;
+    for record in dir.chunks_exact(TABLE_RECORD_LEN) {
+        let tag = &record[0..4];
+        if !tag.iter().all(|&c| (0x20..=0x7E).contains(&c)) {
+            return None;
+        }
+        // Tables come after the directory. Offsets are from the start of
+        // the file, even within a collection.
+        let offset = be_u32(record, 8)? as u64;
+        if offset < start + (OFFSET_TABLE_LEN + dir_len) as u64 {
+            return None;
+        }
+        has_required |= REQUIRED_TABLES.iter().any(|t| tag == *t);
+    }
+    if !has_required {
+        return None;
+    }
+
+    let mut out = [0; 4];
+    out.copy_from_slice(version);
+    Some(out)
+}
+
+/// Checks the header of a collection and the first font in it
+fn is_collection(source: &dyn DataSource) -> bool {
+    let header = match source.read_at(0, OFFSET_TABLE_LEN + 4) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+    if !header.starts_with(b"ttcf") || !matches!(be_u16(&header, 4), Some(1) | Some(2)) {
+        return false;
+    }
+    match (be_u32(&header, 8), be_u32(&header, 12)) {
+        (Some(n), Some(first)) if n > 0 => read_sfnt(source, first as u64).is_some(),
+        _ => false,
+    }
+}
+
+/// Gets the wrapped sfnt version of a WOFF or WOFF2 file
+fn woff_flavor(source: &dyn DataSource, signature: &[u8; 4]) -> Option<[u8; 4]> {
+    let header = source.read_at(0, 16).ok()?;
+    if !header.starts_with(signature) {
+        return None;
+    }
+    let flavor = header.get(4..8)?;
+    if !is_sfnt_version(flavor) || be_u16(&header, 12)? == 0 {
+        return None;
+    }
+    let mut out = [0; 4];
+    out.copy_from_slice(flavor);
+    Some(out)
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    match mimetype {
+        "font/ttf" => read_sfnt(source, 0).is_some(),
+        "font/otf" => read_sfnt(source, 0).as_ref() == Some(CFF),
+        "font/collection" => is_collection(source),
+        "font/woff" => woff_flavor(source, b"wOFF").is_some(),
+        "font/woff2" => woff_flavor(source, b"wOF2").is_some(),
+        _ => false,
+    }
+}
+
+/// Reports the outline format wrapped by web fonts
+pub fn variant(source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+    let flavor = match mimetype {
+        "font/woff" => woff_flavor(source, b"wOFF")?,
+        "font/woff2" => woff_flavor(source, b"wOF2")?,
+        _ => return None,
+    };
+    if &flavor == CFF {
+        Some("cff")
+    } else {
+        Some("truetype")
+    }
+}
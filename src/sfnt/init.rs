@@ -0,0 +1,26 @@
+use crate::{FnvHashMap, MIME};
+use alloc::{vec, vec::Vec};
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    // CFF based fonts are in the same container as TrueType ones
+    vec![("font/ttf", "font/otf")]
+}
+
+/// Older names, for databases that predate the `font/` top-level type
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("application/font-sfnt", "font/ttf"),
+        ("application/x-font-ttf", "font/ttf"),
+        ("application/x-font-otf", "font/otf"),
+        ("application/font-woff", "font/woff"),
+        ("application/x-font-ttc", "font/collection"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
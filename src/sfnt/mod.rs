@@ -0,0 +1,11 @@
+//! Handles OpenType fonts, their collections and web font wrappers by reading the table directory
+const TYPES: [&str; 5] = [
+    "font/ttf",
+    "font/otf",
+    "font/collection",
+    "font/woff",
+    "font/woff2",
+];
+
+pub mod check;
+pub mod init;
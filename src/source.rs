@@ -0,0 +1,390 @@
+//! Sources of data that MIME types can be detected from
+use alloc::{borrow::Cow, vec::Vec};
+use core::cell::Cell;
+#[cfg(feature = "std")]
+use core::cell::RefCell;
+#[cfg(feature = "std")]
+use core::mem;
+#[cfg(feature = "std")]
+use std::fs::{self, File, Metadata};
+#[cfg(feature = "std")]
+use std::io::{self, Read, Seek, SeekFrom};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// How much of a file `FileSource` loads up front
+#[cfg(feature = "std")]
+pub(crate) const PREFIX_LEN: usize = 2048;
+
+/// Error returned when a `DataSource` can't be read.
+#[cfg(feature = "std")]
+pub type ReadError = std::io::Error;
+
+/// Error returned when a `DataSource` can't be read.
+#[cfg(not(feature = "std"))]
+#[derive(Debug)]
+pub struct ReadError;
+
+/// Something a MIME type can be detected from.
+///
+/// Checkers ask for exactly the byte ranges they need through `read_at`, so a
+/// source backed by a file, a memory map or remote storage only has to produce
+/// those ranges instead of the whole content.
+pub trait DataSource {
+    /// Reads up to `len` bytes starting at `offset`.
+    ///
+    /// Returns fewer bytes, possibly none, if the data ends before `offset + len`.
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>, ReadError>;
+
+    /// Filesystem metadata, if the source is an entry on a filesystem.
+    ///
+    /// Sources without metadata are treated as regular files.
+    #[cfg(feature = "std")]
+    fn metadata(&self) -> Option<&Metadata> {
+        None
+    }
+
+    /// Filesystem metadata of the parent directory, if the source is a
+    /// directory on a filesystem. Tells mount points from other directories.
+    #[cfg(feature = "std")]
+    fn parent_metadata(&self) -> Option<&Metadata> {
+        None
+    }
+
+    /// Length of the data, if it's known without reading all of it, such as
+    /// the size of a remote object. Lets checks look at the end of the data.
+    fn len_hint(&self) -> Option<u64> {
+        None
+    }
+
+    /// The whole data, if it's in memory in one piece.
+    ///
+    /// With the `rayon` feature, large in-memory sources are checked against
+    /// many sibling types at once.
+    fn as_slice(&self) -> Option<&[u8]> {
+        None
+    }
+}
+
+/// Length of the data, where it's known without reading all of it
+pub(crate) fn source_len(source: &dyn DataSource) -> Option<u64> {
+    if let Some(len) = source.len_hint() {
+        return Some(len);
+    }
+    if let Some(b) = source.as_slice() {
+        return Some(b.len() as u64);
+    }
+    #[cfg(feature = "std")]
+    if let Some(m) = source.metadata().filter(|m| m.is_file()) {
+        return Some(m.len());
+    }
+    None
+}
+
+/// The part of `b` covered by `offset..offset + len`
+fn slice_at(b: &[u8], offset: u64, len: usize) -> &[u8] {
+    let start = core::cmp::min(offset, b.len() as u64) as usize;
+    let end = core::cmp::min(start.saturating_add(len), b.len());
+    &b[start..end]
+}
+
+impl DataSource for &[u8] {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>, ReadError> {
+        Ok(Cow::Borrowed(slice_at(self, offset, len)))
+    }
+
+    fn as_slice(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+/// A `DataSource` over data split into several consecutive slices, such as
+/// chained buffers or network segments, without concatenating them first.
+///
+/// Reads within a single slice are borrowed; reads spanning slices are
+/// gathered into a new buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct MultiSlice<'a> {
+    parts: &'a [&'a [u8]],
+}
+
+impl<'a> MultiSlice<'a> {
+    /// Treats `parts` as one contiguous stream of bytes.
+    pub fn new(parts: &'a [&'a [u8]]) -> Self {
+        MultiSlice { parts }
+    }
+}
+
+impl DataSource for MultiSlice<'_> {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>, ReadError> {
+        let mut start = offset;
+        let mut parts = self.parts.iter();
+
+        // Skip parts before the requested range
+        let first = loop {
+            match parts.next() {
+                Some(p) if start >= p.len() as u64 => start -= p.len() as u64,
+                Some(p) => break slice_at(p, start, len),
+                None => return Ok(Cow::Borrowed(&[])),
+            }
+        };
+        if first.len() == len {
+            return Ok(Cow::Borrowed(first));
+        }
+
+        let mut buf = Vec::<u8>::with_capacity(len);
+        buf.extend_from_slice(first);
+        for p in parts {
+            if buf.len() == len {
+                break;
+            }
+            buf.extend_from_slice(slice_at(p, 0, len - buf.len()));
+        }
+        Ok(Cow::Owned(buf))
+    }
+}
+
+/// Refuses reads once a detection has read `max_bytes` bytes or made
+/// `max_reads` reads, for sources where reading is costly
+pub(crate) struct BudgetedSource<'a> {
+    inner: &'a dyn DataSource,
+    max_bytes: usize,
+    max_reads: usize,
+    bytes: Cell<usize>,
+    reads: Cell<usize>,
+}
+
+impl<'a> BudgetedSource<'a> {
+    pub(crate) fn new(inner: &'a dyn DataSource, max_bytes: usize, max_reads: usize) -> Self {
+        BudgetedSource {
+            inner,
+            max_bytes,
+            max_reads,
+            bytes: Cell::new(0),
+            reads: Cell::new(0),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn budget_exhausted() -> ReadError {
+    io::Error::other("detection read budget used up")
+}
+
+#[cfg(not(feature = "std"))]
+fn budget_exhausted() -> ReadError {
+    ReadError
+}
+
+impl DataSource for BudgetedSource<'_> {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>, ReadError> {
+        // A read is refused whole rather than cut short, which checks would
+        // take for the end of the data
+        if self.reads.get() >= self.max_reads || len > self.max_bytes - self.bytes.get() {
+            return Err(budget_exhausted());
+        }
+        let out = self.inner.read_at(offset, len)?;
+        self.reads.set(self.reads.get() + 1);
+        self.bytes.set(self.bytes.get() + out.len());
+        Ok(out)
+    }
+
+    #[cfg(feature = "std")]
+    fn metadata(&self) -> Option<&Metadata> {
+        self.inner.metadata()
+    }
+
+    #[cfg(feature = "std")]
+    fn parent_metadata(&self) -> Option<&Metadata> {
+        self.inner.parent_metadata()
+    }
+
+    fn len_hint(&self) -> Option<u64> {
+        self.inner.len_hint()
+    }
+
+    // No `as_slice`, so every read goes through the budget
+}
+
+/// A `DataSource` reading from a filesystem entry.
+///
+/// The start of a regular file is read once when the source is opened, since
+/// nearly every check looks there. Anything past that is read on demand.
+/// Other entries such as directories have metadata but no content.
+#[cfg(feature = "std")]
+pub struct FileSource {
+    meta: Metadata,
+    /// Only looked up for directories
+    parent_meta: Option<Metadata>,
+    file: Option<RefCell<File>>,
+    prefix: Vec<u8>,
+    /// How much was asked for when reading the prefix
+    prefix_len: usize,
+    /// First error reading past the prefix, which checks take as a mismatch
+    error: RefCell<Option<io::Error>>,
+}
+
+#[cfg(feature = "std")]
+impl FileSource {
+    /// Opens the entry at `filepath`.
+    pub fn open(filepath: &Path) -> io::Result<FileSource> {
+        Self::open_with_buffer(filepath, &mut Vec::with_capacity(PREFIX_LEN))
+    }
+
+    /// Opens the entry at `filepath`, holding the start of the file in `buf`
+    /// instead of a new allocation.
+    ///
+    /// `buf` is taken over by the source, get it back with
+    /// [`into_buffer`](FileSource::into_buffer) to reuse it for the next file.
+    /// It is left untouched if the entry can't be opened.
+    pub fn open_with_buffer(filepath: &Path, buf: &mut Vec<u8>) -> io::Result<FileSource> {
+        Self::open_with_prefix(filepath, buf, PREFIX_LEN)
+    }
+
+    /// Opens the entry at `filepath`, reading only the first `prefix_len`
+    /// bytes up front, for checks known to need no more than that
+    pub(crate) fn open_with_prefix(
+        filepath: &Path,
+        buf: &mut Vec<u8>,
+        prefix_len: usize,
+    ) -> io::Result<FileSource> {
+        let meta = fs::metadata(filepath)?;
+        Self::open_with_metadata_prefix(filepath, meta, buf, prefix_len)
+    }
+
+    /// Opens the entry at `filepath`, given its metadata, so it isn't looked
+    /// up again. Holds the start of the file in `buf` like
+    /// [`open_with_buffer`](FileSource::open_with_buffer).
+    ///
+    /// `meta` has to be of the entry the path resolves to, as returned by
+    /// [`fs::metadata`], rather than of a symbolic link itself. The file
+    /// isn't opened at all if it's empty or not a regular file.
+    pub fn open_with_metadata(
+        filepath: &Path,
+        meta: &Metadata,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<FileSource> {
+        Self::open_with_metadata_prefix(filepath, meta.clone(), buf, PREFIX_LEN)
+    }
+
+    fn open_with_metadata_prefix(
+        filepath: &Path,
+        meta: Metadata,
+        buf: &mut Vec<u8>,
+        prefix_len: usize,
+    ) -> io::Result<FileSource> {
+        // Nothing to read from directories, devices and empty files
+        if !meta.is_file() || meta.len() == 0 {
+            let parent_meta = match meta.is_dir() {
+                true => fs::metadata(filepath.join("..")).ok(),
+                false => None,
+            };
+            return Ok(Self::unread(meta, parent_meta, buf, prefix_len));
+        }
+
+        Self::from_file(File::open(filepath)?, meta, buf, prefix_len)
+    }
+
+    /// A source that's never read, for entries other than non-empty files
+    pub(crate) fn unread(
+        meta: Metadata,
+        parent_meta: Option<Metadata>,
+        buf: &mut Vec<u8>,
+        prefix_len: usize,
+    ) -> FileSource {
+        buf.clear();
+        FileSource {
+            meta,
+            parent_meta,
+            file: None,
+            prefix: mem::take(buf),
+            prefix_len,
+            error: RefCell::new(None),
+        }
+    }
+
+    /// Reads the start of a regular file that's already open
+    pub(crate) fn from_file(
+        mut file: File,
+        meta: Metadata,
+        buf: &mut Vec<u8>,
+        prefix_len: usize,
+    ) -> io::Result<FileSource> {
+        buf.clear();
+        buf.reserve(prefix_len);
+        (&mut file).take(prefix_len as u64).read_to_end(buf)?;
+
+        Ok(FileSource {
+            meta,
+            parent_meta: None,
+            file: Some(RefCell::new(file)),
+            prefix: mem::take(buf),
+            prefix_len,
+            error: RefCell::new(None),
+        })
+    }
+
+    /// Takes the first error hit while reading the file after it was
+    /// opened. Checks can't report errors, so they take unreadable data as
+    /// not matching; this tells such a mismatch from a real one.
+    pub fn take_error(&self) -> Option<io::Error> {
+        self.error.borrow_mut().take()
+    }
+
+    /// Gives back the buffer holding the start of the file.
+    pub fn into_buffer(self) -> Vec<u8> {
+        self.prefix
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// Reused by filepath detection, to avoid an allocation per file
+    static BUFFER: RefCell<Vec<u8>> = RefCell::new(Vec::with_capacity(PREFIX_LEN));
+}
+
+/// Runs `f` with this thread's reusable buffer, or a new one if it's in use
+#[cfg(feature = "std")]
+pub(crate) fn with_buffer<R>(f: impl FnOnce(&mut Vec<u8>) -> R) -> R {
+    BUFFER.with(|b| match b.try_borrow_mut() {
+        Ok(mut b) => f(&mut b),
+        Err(_) => f(&mut Vec::new()),
+    })
+}
+
+#[cfg(feature = "std")]
+impl DataSource for FileSource {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>, ReadError> {
+        let end = offset.saturating_add(len as u64);
+        // A short prefix means we already hold the whole file
+        if end <= self.prefix.len() as u64 || self.prefix.len() < self.prefix_len {
+            return Ok(Cow::Borrowed(slice_at(&self.prefix, offset, len)));
+        }
+
+        let file = match self.file {
+            Some(ref x) => x,
+            None => return Ok(Cow::Borrowed(&[])),
+        };
+        let mut file = file.borrow_mut();
+        let mut buf = Vec::<u8>::new();
+        let read = file
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| (&mut *file).take(len as u64).read_to_end(&mut buf));
+        match read {
+            Ok(_) => Ok(Cow::Owned(buf)),
+            Err(e) => {
+                let out = io::Error::from(e.kind());
+                self.error.borrow_mut().get_or_insert(e);
+                Err(out)
+            }
+        }
+    }
+
+    fn metadata(&self) -> Option<&Metadata> {
+        Some(&self.meta)
+    }
+
+    fn parent_metadata(&self) -> Option<&Metadata> {
+        self.parent_meta.as_ref()
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{fdo_magic, DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct SourceCode;
+
+impl crate::Checker for SourceCode {
+    fn from_source(&self, db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(db, source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, _mimetype: &str) -> Option<usize> {
+        Some(PROBE_LEN)
+    }
+}
+
+/// How much of the code is looked at
+const PROBE_LEN: usize = 4096;
+
+/// Lowest score a language must reach
+const MIN_SCORE: u32 = 4;
+
+const PYTHON: MIME = "text/x-python";
+const RUST: MIME = "text/rust";
+const GO: MIME = "text/x-go";
+const JAVA: MIME = "text/x-java";
+const C: MIME = "text/x-csrc";
+const CPP: MIME = "text/x-c++src";
+
+/// A line of code typical of a language, with how much it counts for it.
+/// Lines are trimmed, and empty parts of a cue match any line.
+struct Cue {
+    language: MIME,
+    start: &'static str,
+    contains: &'static str,
+    end: &'static str,
+    weight: u32,
+}
+
+impl Cue {
+    fn matches(&self, line: &[u8]) -> bool {
+        line.starts_with(self.start.as_bytes())
+            && line.ends_with(self.end.as_bytes())
+            && (self.contains.is_empty()
+                || line
+                    .windows(self.contains.len())
+                    .any(|w| w == self.contains.as_bytes()))
+    }
+}
+
+const fn cue(
+    language: MIME,
+    start: &'static str,
+    contains: &'static str,
+    end: &'static str,
+    weight: u32,
+) -> Cue {
+    Cue {
+        language,
+        start,
+        contains,
+        end,
+        weight,
+    }
+}
+
+const CUES: &[Cue] = &[
+    cue(PYTHON, "#!", "python", "", 5),
+    cue(PYTHON, "def ", "", ":", 2),
+    cue(PYTHON, "class ", "", ":", 2),
+    cue(PYTHON, "from ", " import ", "", 3),
+    cue(PYTHON, "elif ", "", ":", 2),
+    cue(PYTHON, "if __name__ ==", "", "", 3),
+    cue(PYTHON, "", "self.", "", 1),
+    cue(PYTHON, "", "print(", "", 1),
+    cue(RUST, "fn ", "", "", 2),
+    cue(RUST, "pub fn ", "", "", 3),
+    cue(RUST, "pub(crate) ", "", "", 3),
+    cue(RUST, "use std::", "", "", 3),
+    cue(RUST, "use crate::", "", "", 3),
+    cue(RUST, "let mut ", "", "", 3),
+    cue(RUST, "impl ", "", "{", 2),
+    cue(RUST, "impl<", "", "", 3),
+    cue(RUST, "#[derive(", "", "", 3),
+    cue(RUST, "mod ", "", ";", 2),
+    cue(RUST, "", "println!(", "", 3),
+    cue(GO, "package ", "", "", 1),
+    cue(GO, "func ", "", "", 3),
+    cue(GO, "import (", "", "", 3),
+    cue(GO, "", " := ", "", 2),
+    cue(GO, "", "fmt.", "", 2),
+    cue(GO, "", "err != nil", "", 3),
+    cue(JAVA, "package ", "", ";", 3),
+    cue(JAVA, "import java.", "", "", 3),
+    cue(JAVA, "import ", "", ";", 1),
+    cue(JAVA, "public class ", "", "", 3),
+    cue(JAVA, "@Override", "", "", 3),
+    cue(JAVA, "", "public static void main", "", 3),
+    cue(JAVA, "", "System.out.", "", 3),
+    cue(JAVA, "private ", "", ";", 1),
+    cue(C, "#include <", "", ".h>", 1),
+    cue(C, "#include \"", "", "", 1),
+    cue(C, "#define ", "", "", 1),
+    cue(C, "typedef struct", "", "", 2),
+    cue(C, "int main(", "", "", 1),
+    cue(C, "", "printf(", "", 2),
+    cue(C, "", "malloc(", "", 2),
+    cue(CPP, "#include <", "", ">", 1),
+    cue(CPP, "#include \"", "", "", 1),
+    cue(CPP, "#define ", "", "", 1),
+    cue(CPP, "int main(", "", "", 1),
+    cue(CPP, "#include <iostream>", "", "", 3),
+    cue(CPP, "using namespace ", "", "", 3),
+    cue(CPP, "namespace ", "", "{", 3),
+    cue(CPP, "template", "<", "", 3),
+    cue(CPP, "public:", "", "", 2),
+    cue(CPP, "private:", "", "", 2),
+    cue(CPP, "", "std::cout", "", 3),
+    cue(CPP, "", "std::string", "", 3),
+    cue(CPP, "", "std::vector", "", 3),
+    cue(CPP, "", "nullptr", "", 2),
+];
+
+fn trim(b: &[u8]) -> &[u8] {
+    let start = b.iter().position(|c| !c.is_ascii_whitespace());
+    let end = b.iter().rposition(|c| !c.is_ascii_whitespace());
+    match (start, end) {
+        (Some(s), Some(e)) => &b[s..=e],
+        _ => &[],
+    }
+}
+
+/// The language scoring highest over all lines, if it scores enough and
+/// more than any other
+fn language(b: &[u8]) -> Option<MIME> {
+    let mut scores = [0u32; super::TYPES.len()];
+    for line in b.split(|&c| c == b'\n').map(trim) {
+        for cue in CUES.iter().filter(|c| c.matches(line)) {
+            if let Some(i) = super::TYPES.iter().position(|&t| t == cue.language) {
+                scores[i] += cue.weight;
+            }
+        }
+    }
+
+    let (best, &score) = scores.iter().enumerate().max_by_key(|&(_, s)| s)?;
+    let runner_up = scores
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != best)
+        .map(|(_, &s)| s)
+        .max()
+        .unwrap_or(0);
+    (score >= MIN_SCORE && score > runner_up).then_some(super::TYPES[best])
+}
+
+/// Without [`source_heuristics`](crate::TypeDatabaseBuilder::source_heuristics),
+/// or when no language stands out, only the magic rules of the type are
+/// checked, such as for the shebang of a Python script
+pub fn from_source(db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+    if db.source_heuristics() {
+        let b = match source.read_at(0, PROBE_LEN) {
+            Ok(b) => b,
+            Err(_) => return false,
+        };
+        if let Some(found) = language(&b) {
+            // C++ and Java are subclasses of C
+            return found == mimetype || (mimetype == C && (found == CPP || found == JAVA));
+        }
+    }
+    fdo_magic::builtin::check::from_source(db, source, mimetype)
+}
@@ -0,0 +1,25 @@
+use crate::{FnvHashMap, MIME};
+use alloc::vec::Vec;
+
+pub fn get_supported() -> Vec<MIME> {
+    super::TYPES.to_vec()
+}
+
+/// Returns Vec of parent->child relations
+///
+/// Java and C++ are subclasses of C in the shared MIME-info database, and
+/// are also placed under `text/plain` so they're found when C isn't.
+pub fn get_subclasses() -> Vec<(MIME, MIME)> {
+    super::TYPES.iter().map(|&t| ("text/plain", t)).collect()
+}
+
+pub fn get_aliaslist() -> FnvHashMap<MIME, MIME> {
+    [
+        ("text/x-rust", "text/rust"),
+        ("text/x-golang", "text/x-go"),
+        ("text/x-c++", "text/x-c++src"),
+    ]
+    .iter()
+    .copied()
+    .collect()
+}
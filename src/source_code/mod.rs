@@ -0,0 +1,16 @@
+//! Tells the language of source code from keywords and syntax, for code
+//! without a file name to go by.
+//!
+//! Off unless turned on with
+//! [`source_heuristics`](crate::TypeDatabaseBuilder::source_heuristics).
+const TYPES: [&str; 6] = [
+    "text/x-python",
+    "text/rust",
+    "text/x-go",
+    "text/x-java",
+    "text/x-csrc",
+    "text/x-c++src",
+];
+
+pub mod check;
+pub mod init;
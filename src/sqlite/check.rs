@@ -0,0 +1,155 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Sqlite;
+
+impl crate::Checker for Sqlite {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "application/vnd.sqlite3" => Some(DB_HEADER_LEN),
+            "application/x-sqlite3-wal" => Some(WAL_HEADER_LEN),
+            "application/x-sqlite3-shm" => Some(2 * SHM_HEADER_LEN),
+            "application/x-sqlite3-journal" => Some(JOURNAL_HEADER_LEN),
+            _ => None,
+        }
+    }
+}
+
+const DB_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+/// Magic, page size and file format versions
+const DB_HEADER_LEN: usize = 20;
+
+/// Magic for checksums of little- and big-endian words, respectively
+const WAL_MAGIC: [u32; 2] = [0x377f_0682, 0x377f_0683];
+const WAL_HEADER_LEN: usize = 32;
+
+/// Version written to WAL and shared-memory headers by every SQLite since
+/// 3.7.0
+const WAL_VERSION: u32 = 3_007_000;
+
+/// The shared-memory index header, which is written twice in a row
+const SHM_HEADER_LEN: usize = 48;
+
+const JOURNAL_MAGIC: &[u8; 8] = b"\xd9\xd5\x05\xf9\x20\xa1\x63\xd7";
+/// Magic, page count, nonce, original size, sector size and page size
+const JOURNAL_HEADER_LEN: usize = 28;
+
+fn be_u16(b: &[u8], off: usize) -> u32 {
+    u16::from_be_bytes([b[off], b[off + 1]]) as u32
+}
+
+fn be_u32(b: &[u8], off: usize) -> u32 {
+    u32::from_be_bytes([b[off], b[off + 1], b[off + 2], b[off + 3]])
+}
+
+/// Page sizes are powers of two from 512 to 65536
+fn is_page_size(size: u32) -> bool {
+    size.is_power_of_two() && (512..=65536).contains(&size)
+}
+
+fn is_database(b: &[u8]) -> bool {
+    if b.len() < DB_HEADER_LEN || !b.starts_with(DB_MAGIC) {
+        return false;
+    }
+    // 65536 doesn't fit, and is written as 1
+    let page_size = match be_u16(b, 16) {
+        1 => 65536,
+        x => x,
+    };
+    // Write and read versions: 1 for rollback journals, 2 for WAL
+    is_page_size(page_size) && (1..=2).contains(&b[18]) && (1..=2).contains(&b[19])
+}
+
+/// Checksum of the WAL header, added up over 32-bit words in the byte order
+/// the magic says
+fn wal_checksum(b: &[u8], big_endian: bool) -> (u32, u32) {
+    let (mut s0, mut s1) = (0u32, 0u32);
+    for pair in b.chunks_exact(8) {
+        let word = |i: usize| {
+            let x = [pair[i], pair[i + 1], pair[i + 2], pair[i + 3]];
+            match big_endian {
+                true => u32::from_be_bytes(x),
+                false => u32::from_le_bytes(x),
+            }
+        };
+        s0 = s0.wrapping_add(word(0)).wrapping_add(s1);
+        s1 = s1.wrapping_add(word(4)).wrapping_add(s0);
+    }
+    (s0, s1)
+}
+
+fn is_wal(b: &[u8]) -> bool {
+    if b.len() < WAL_HEADER_LEN {
+        return false;
+    }
+    let magic = be_u32(b, 0);
+    WAL_MAGIC.contains(&magic)
+        && be_u32(b, 4) == WAL_VERSION
+        && is_page_size(be_u32(b, 8))
+        && wal_checksum(&b[..24], magic & 1 == 1) == (be_u32(b, 24), be_u32(b, 28))
+}
+
+/// The shared-memory index is in native byte order, and starts with two
+/// identical copies of its header
+fn is_shm(b: &[u8]) -> bool {
+    if b.len() < 2 * SHM_HEADER_LEN {
+        return false;
+    }
+    let version = [b[0], b[1], b[2], b[3]];
+    let native = u32::from_le_bytes(version) == WAL_VERSION;
+    if !native && u32::from_be_bytes(version) != WAL_VERSION {
+        return false;
+    }
+    // The page size is stored like in the database header
+    let page_size = match native {
+        true => u16::from_le_bytes([b[14], b[15]]),
+        false => u16::from_be_bytes([b[14], b[15]]),
+    };
+    let page_size = match page_size {
+        1 => 65536,
+        x => x as u32,
+    };
+    // Set once the header has been written
+    let initialized = b[12] == 1;
+    initialized
+        && is_page_size(page_size)
+        && b[..SHM_HEADER_LEN] == b[SHM_HEADER_LEN..2 * SHM_HEADER_LEN]
+}
+
+fn is_journal(b: &[u8]) -> bool {
+    if b.len() < JOURNAL_HEADER_LEN || !b.starts_with(JOURNAL_MAGIC) {
+        return false;
+    }
+    let sector_size = be_u32(b, 20);
+    sector_size.is_power_of_two() && sector_size >= 32 && is_page_size(be_u32(b, 24))
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    let (len, check): (usize, fn(&[u8]) -> bool) = match mimetype {
+        "application/vnd.sqlite3" => (DB_HEADER_LEN, is_database),
+        "application/x-sqlite3-wal" => (WAL_HEADER_LEN, is_wal),
+        "application/x-sqlite3-shm" => (2 * SHM_HEADER_LEN, is_shm),
+        "application/x-sqlite3-journal" => (JOURNAL_HEADER_LEN, is_journal),
+        _ => return false,
+    };
+    match source.read_at(0, len) {
+        Ok(b) => check(&b),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,11 @@
+//! Handles SQLite databases and the write-ahead log, shared-memory index and
+//! rollback journal files kept next to them
+const TYPES: [&str; 4] = [
+    "application/vnd.sqlite3",
+    "application/x-sqlite3-wal",
+    "application/x-sqlite3-shm",
+    "application/x-sqlite3-journal",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,138 @@
+//! Opt-in counters of the work detection does
+use crate::source::{DataSource, ReadError};
+use alloc::borrow::Cow;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Called back during [`detect_with_stats`](crate::detect_with_stats), to
+/// see where detection spends its time.
+///
+/// Every method does nothing by default. [`Stats`] adds everything up.
+pub trait StatsHook: Sync {
+    /// A type was checked against the data, and `matched` or not.
+    fn type_checked(&self, _mimetype: &str, _matched: bool) {}
+
+    /// The checks read `len` bytes from the data.
+    fn bytes_read(&self, _len: usize) {}
+
+    /// A detection finished with the given result.
+    fn finished(&self, _mimetype: Option<&str>) {}
+}
+
+/// Counters added up over any number of detections, from any number of
+/// threads.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::{DetectOptions, Stats};
+///
+/// let stats = Stats::new();
+/// let input: &[u8] = include_bytes!("../tests/image/gif");
+/// let result = tree_magic_mini::detect_with_stats(&input, &DetectOptions::new(), &stats);
+///
+/// assert_eq!(result, Some("image/gif"));
+/// assert_eq!(stats.calls(), 1);
+/// assert_eq!(stats.nodes_visited(), stats.rule_hits() + stats.rule_misses());
+/// assert!(stats.bytes_read() > 0);
+/// ```
+#[derive(Debug, Default)]
+pub struct Stats {
+    calls: AtomicUsize,
+    nodes_visited: AtomicUsize,
+    bytes_read: AtomicUsize,
+    rule_hits: AtomicUsize,
+    rule_misses: AtomicUsize,
+}
+
+impl Stats {
+    /// All counters at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Detections recorded.
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    /// Types checked against the data, over all detections.
+    pub fn nodes_visited(&self) -> usize {
+        self.nodes_visited.load(Ordering::Relaxed)
+    }
+
+    /// Bytes the checks read, over all detections. Bytes read more than
+    /// once count every time.
+    pub fn bytes_read(&self) -> usize {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    /// Checked types that matched the data.
+    pub fn rule_hits(&self) -> usize {
+        self.rule_hits.load(Ordering::Relaxed)
+    }
+
+    /// Checked types that didn't match the data.
+    pub fn rule_misses(&self) -> usize {
+        self.rule_misses.load(Ordering::Relaxed)
+    }
+
+    /// Sets all counters back to zero.
+    pub fn reset(&self) {
+        for c in [
+            &self.calls,
+            &self.nodes_visited,
+            &self.bytes_read,
+            &self.rule_hits,
+            &self.rule_misses,
+        ] {
+            c.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+impl StatsHook for Stats {
+    fn type_checked(&self, _mimetype: &str, matched: bool) {
+        self.nodes_visited.fetch_add(1, Ordering::Relaxed);
+        match matched {
+            true => self.rule_hits.fetch_add(1, Ordering::Relaxed),
+            false => self.rule_misses.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+
+    fn bytes_read(&self, len: usize) {
+        self.bytes_read.fetch_add(len, Ordering::Relaxed);
+    }
+
+    fn finished(&self, _mimetype: Option<&str>) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Reports every read of the wrapped source to the hook
+pub(crate) struct CountedSource<'a> {
+    pub(crate) inner: &'a dyn DataSource,
+    pub(crate) hook: &'a dyn StatsHook,
+}
+
+impl DataSource for CountedSource<'_> {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>, ReadError> {
+        let out = self.inner.read_at(offset, len)?;
+        self.hook.bytes_read(out.len());
+        Ok(out)
+    }
+
+    #[cfg(feature = "std")]
+    fn metadata(&self) -> Option<&std::fs::Metadata> {
+        self.inner.metadata()
+    }
+
+    #[cfg(feature = "std")]
+    fn parent_metadata(&self) -> Option<&std::fs::Metadata> {
+        self.inner.parent_metadata()
+    }
+
+    fn len_hint(&self) -> Option<u64> {
+        self.inner.len_hint()
+    }
+
+    // No `as_slice`, so every read is seen, and the walk stays sequential
+}
@@ -0,0 +1,94 @@
+//! Detect the type of a stream while passing its bytes on, so a file that is
+//! hashed anyway only gets read once
+use crate::zip::{MAX_CENTRAL_DIRECTORY_LEN, ZIP_END_SEARCH_LEN};
+use crate::{DataSource, ReadError};
+use alloc::borrow::Cow;
+use alloc::vec;
+use alloc::vec::Vec;
+use std::io::{self, Read, Write};
+
+/// How much of the start of a stream is kept. Checks reaching past it, as
+/// for the end of a ZIP archive, see the end instead.
+const HEAD_LEN: usize = 64 * 1024;
+
+/// How much of the end of a stream is kept, enough for the central directory
+/// of a ZIP archive
+const TAIL_LEN: usize = ZIP_END_SEARCH_LEN as usize + MAX_CENTRAL_DIRECTORY_LEN;
+
+/// Bytes read at a time past the start
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// The start and the end of a stream, which was written out whole while
+/// they were read
+pub(crate) struct StreamSource {
+    head: Vec<u8>,
+    tail: Vec<u8>,
+    /// Offset of the first byte of `tail`
+    tail_start: u64,
+    len: u64,
+}
+
+impl StreamSource {
+    /// Reads the stream to its end, writing every byte to `hasher` from the
+    /// buffer it's read into
+    pub(crate) fn read(mut reader: impl Read, hasher: &mut impl Write) -> io::Result<Self> {
+        let mut head = Vec::with_capacity(HEAD_LEN);
+        (&mut reader).take(HEAD_LEN as u64).read_to_end(&mut head)?;
+        hasher.write_all(&head)?;
+        let mut len = head.len() as u64;
+
+        let mut tail = Vec::new();
+        if head.len() == HEAD_LEN {
+            let mut buf = vec![0; CHUNK_LEN];
+            loop {
+                let n = match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => return Err(e),
+                };
+                hasher.write_all(&buf[..n])?;
+                len += n as u64;
+                tail.extend_from_slice(&buf[..n]);
+                // Dropped in batches, rather than moving the rest every read
+                if tail.len() >= 2 * TAIL_LEN {
+                    tail.drain(..tail.len() - TAIL_LEN);
+                }
+            }
+            if tail.len() > TAIL_LEN {
+                tail.drain(..tail.len() - TAIL_LEN);
+            }
+        }
+        Ok(StreamSource {
+            head,
+            tail_start: len - tail.len() as u64,
+            tail,
+            len,
+        })
+    }
+}
+
+impl DataSource for StreamSource {
+    fn read_at(&self, offset: u64, len: usize) -> Result<Cow<'_, [u8]>, ReadError> {
+        let end = offset.saturating_add(len as u64).min(self.len);
+        let start = offset.min(end);
+        let head_end = self.head.len() as u64;
+        if end <= head_end {
+            return Ok(Cow::Borrowed(&self.head[start as usize..end as usize]));
+        }
+        if start >= self.tail_start {
+            let range = (start - self.tail_start) as usize..(end - self.tail_start) as usize;
+            return Ok(Cow::Borrowed(&self.tail[range]));
+        }
+        if self.tail_start == head_end {
+            let mut out = self.head[start as usize..].to_vec();
+            out.extend_from_slice(&self.tail[..(end - self.tail_start) as usize]);
+            return Ok(Cow::Owned(out));
+        }
+        Err(io::Error::other("read between the start and end kept"))
+    }
+
+    fn len_hint(&self) -> Option<u64> {
+        Some(self.len)
+    }
+}
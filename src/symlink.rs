@@ -0,0 +1,74 @@
+//! Detect the type of a symlink and of what it points to
+use crate::{TypeDatabase, MIME};
+use alloc::{format, vec::Vec};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Type reported for symbolic links themselves
+const SYMLINK: MIME = "inode/symlink";
+
+/// Result of [`from_filepath_following`](crate::from_filepath_following).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FollowedPath<'a> {
+    /// `inode/symlink` if the given path is a symbolic link, `None` otherwise.
+    pub link: Option<&'a str>,
+    /// Type of the file at the end of the chain, or `None` if the chain ends
+    /// at a path that doesn't exist.
+    pub target: Option<&'a str>,
+    /// Path at the end of the chain.
+    pub target_path: PathBuf,
+    /// Every link followed, starting with the given path.
+    pub chain: Vec<PathBuf>,
+}
+
+/// Resolves the symlink chain starting at `filepath` one link at a time.
+pub(crate) fn from_filepath_following<'a>(
+    db: &'a TypeDatabase,
+    filepath: &Path,
+    max_depth: usize,
+) -> io::Result<FollowedPath<'a>> {
+    let mut current = filepath.to_path_buf();
+    let mut chain = Vec::<PathBuf>::new();
+
+    loop {
+        let meta = match fs::symlink_metadata(&current) {
+            Ok(x) => x,
+            // Dangling link
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !chain.is_empty() => break,
+            Err(e) => return Err(e),
+        };
+        if !meta.file_type().is_symlink() {
+            break;
+        }
+        if chain.contains(&current) {
+            return Err(io::Error::other(format!(
+                "symbolic link loop at {}",
+                current.display()
+            )));
+        }
+        if chain.len() >= max_depth {
+            return Err(io::Error::other("too many levels of symbolic links"));
+        }
+
+        let next = fs::read_link(&current)?;
+        // Relative targets are relative to the directory holding the link
+        let next = match current.parent() {
+            Some(dir) if next.is_relative() => dir.join(next),
+            _ => next,
+        };
+        chain.push(current);
+        current = next;
+    }
+
+    Ok(FollowedPath {
+        link: if chain.is_empty() {
+            None
+        } else {
+            Some(SYMLINK)
+        },
+        target: db.from_filepath(&current),
+        target_path: current,
+        chain,
+    })
+}
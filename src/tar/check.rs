@@ -0,0 +1,192 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Tar;
+
+impl crate::Checker for Tar {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+
+    fn needed_len(&self, _db: &TypeDatabase, mimetype: &str) -> Option<usize> {
+        match mimetype {
+            "application/x-compressed-tar" => Some(GZIP_PROBE_LEN),
+            _ => Some(BLOCK_LEN),
+        }
+    }
+
+    fn variant(&self, source: &dyn DataSource, mimetype: &str) -> Option<&'static str> {
+        match (mimetype, source.read_at(0, BLOCK_LEN)) {
+            ("application/x-tar", Ok(b)) => variant(&b),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) const BLOCK_LEN: usize = 512;
+const CHKSUM: core::ops::Range<usize> = 148..156;
+pub(crate) const TYPEFLAG: usize = 156;
+const MAGIC: core::ops::Range<usize> = 257..265;
+
+/// Parses a NUL- or space-terminated octal field
+pub(crate) fn parse_octal(field: &[u8]) -> Option<u32> {
+    let digits = field
+        .iter()
+        .skip_while(|&&c| c == b' ')
+        .take_while(|&&c| c != 0 && c != b' ');
+    let mut out: u32 = 0;
+    let mut seen = false;
+    for &c in digits {
+        if !(b'0'..=b'7').contains(&c) {
+            return None;
+        }
+        out = out.checked_mul(8)?.checked_add((c - b'0') as u32)?;
+        seen = true;
+    }
+    if seen {
+        Some(out)
+    } else {
+        None
+    }
+}
+
+/// Checks the header checksum, which is the sum of all header bytes with the
+/// checksum field itself counted as spaces. Some historic implementations
+/// summed signed bytes, so accept either.
+pub(crate) fn is_valid_header(block: &[u8]) -> bool {
+    if block.len() < BLOCK_LEN {
+        return false;
+    }
+    let stored = match parse_octal(&block[CHKSUM]) {
+        Some(x) => x,
+        None => return false,
+    };
+
+    let mut unsigned: u32 = 0;
+    let mut signed: i32 = 0;
+    for (i, &c) in block[..BLOCK_LEN].iter().enumerate() {
+        let c = if CHKSUM.contains(&i) { b' ' } else { c };
+        unsigned += c as u32;
+        signed += c as i8 as i32;
+    }
+
+    stored == unsigned || stored as i32 == signed
+}
+
+/// Identifies the tar dialect from the first header block
+fn variant(b: &[u8]) -> Option<&'static str> {
+    if !is_valid_header(b) {
+        return None;
+    }
+    let magic = &b[MAGIC];
+    if magic == b"ustar  \0" {
+        Some("gnu")
+    } else if magic == b"ustar\x0000" {
+        match b[TYPEFLAG] {
+            b'x' | b'g' => Some("pax"),
+            _ => Some("ustar"),
+        }
+    } else {
+        Some("v7")
+    }
+}
+
+/// How much of a gzip stream to look at for a tar archive inside. Enough
+/// for a long original file name, or to inflate the first header block.
+const GZIP_PROBE_LEN: usize = 4096;
+
+const GZIP_FHCRC: u8 = 0x02;
+const GZIP_FEXTRA: u8 = 0x04;
+const GZIP_FNAME: u8 = 0x08;
+const GZIP_FCOMMENT: u8 = 0x10;
+
+/// The original file name of a gzip stream, if it kept one, and where the
+/// compressed data starts
+pub(crate) fn gzip_header(b: &[u8]) -> Option<(Option<&[u8]>, usize)> {
+    if !b.starts_with(&[0x1F, 0x8B, 0x08]) {
+        return None;
+    }
+    let flags = *b.get(3)?;
+    let mut pos = 10;
+    if flags & GZIP_FEXTRA != 0 {
+        let len = u16::from_le_bytes([*b.get(pos)?, *b.get(pos + 1)?]) as usize;
+        pos += 2 + len;
+    }
+    let mut name = None;
+    if flags & GZIP_FNAME != 0 {
+        let len = b.get(pos..)?.iter().position(|&c| c == 0)?;
+        name = Some(&b[pos..pos + len]);
+        pos += len + 1;
+    }
+    if flags & GZIP_FCOMMENT != 0 {
+        pos += b.get(pos..)?.iter().position(|&c| c == 0)? + 1;
+    }
+    if flags & GZIP_FHCRC != 0 {
+        pos += 2;
+    }
+    Some((name, pos))
+}
+
+/// A gzip stream holding a tar archive. Told by the original file name
+/// ending in `.tar`, or with the `archive` feature, by inflating the first
+/// header block.
+fn is_compressed_tar(b: &[u8]) -> bool {
+    let (name, start) = match gzip_header(b) {
+        Some(x) => x,
+        None => return false,
+    };
+    if let Some(name) = name {
+        if name.len() >= 4 && name[name.len() - 4..].eq_ignore_ascii_case(b".tar") {
+            return true;
+        }
+    }
+    is_deflated_tar(b.get(start..).unwrap_or(&[]))
+}
+
+/// Inflates the first block of a deflate stream
+#[cfg(feature = "archive")]
+pub(crate) fn inflate_block(data: &[u8]) -> Vec<u8> {
+    use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+    // The probe usually ends partway through the stream, so take what
+    // inflated before the input ran out
+    match decompress_to_vec_with_limit(data, BLOCK_LEN) {
+        Ok(x) => x,
+        Err(e) => e.output,
+    }
+}
+
+#[cfg(feature = "archive")]
+fn is_deflated_tar(data: &[u8]) -> bool {
+    is_valid_header(&inflate_block(data))
+}
+
+#[cfg(not(feature = "archive"))]
+fn is_deflated_tar(_data: &[u8]) -> bool {
+    false
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    match mimetype {
+        "application/x-compressed-tar" => match source.read_at(0, GZIP_PROBE_LEN) {
+            Ok(b) => is_compressed_tar(&b),
+            Err(_) => false,
+        },
+        _ => match source.read_at(0, BLOCK_LEN) {
+            Ok(b) => is_valid_header(&b),
+            Err(_) => false,
+        },
+    }
+}
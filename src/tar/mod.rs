@@ -0,0 +1,6 @@
+//! Handles tar archives by validating the header block checksum, including
+//! gzipped ones
+const TYPES: [&str; 2] = ["application/x-tar", "application/x-compressed-tar"];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,189 @@
+//! Compare detection results against other implementations of the
+//! shared MIME-info database, to track correctness regressions.
+use crate::{from_filepath, from_u8, get_alias, FnvHashMap, MIME};
+use alloc::{string::String, vec::Vec};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs, io};
+
+/// Another MIME type detector to compare against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reference {
+    /// `file -b --mime-type`, from libmagic.
+    File,
+    /// `xdg-mime query filetype`, from xdg-utils.
+    XdgMime,
+}
+
+impl Reference {
+    /// Asks the reference implementation for the type of a file.
+    fn query(&self, filepath: &Path) -> io::Result<String> {
+        let output = match self {
+            Reference::File => Command::new("file")
+                .args(["-b", "--mime-type"])
+                .arg(filepath)
+                .output()?,
+            Reference::XdgMime => Command::new("xdg-mime")
+                .args(["query", "filetype"])
+                .arg(filepath)
+                .output()?,
+        };
+        if !output.status.success() {
+            return Err(io::Error::other(String::from_utf8_lossy(&output.stderr)));
+        }
+        let out = String::from_utf8_lossy(&output.stdout);
+        // xdg-mime may append parameters, such as "; charset=binary"
+        let mime = out.split(';').next().unwrap_or("").trim();
+        Ok(mime.to_ascii_lowercase())
+    }
+}
+
+/// A file on which we disagree with the reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub path: PathBuf,
+    /// What `from_filepath` returned.
+    pub ours: Option<MIME>,
+    /// What the reference returned.
+    pub theirs: String,
+}
+
+/// Result of comparing a corpus against a reference, as returned by [`compare`].
+#[derive(Debug, Default)]
+pub struct Comparison {
+    /// Number of files both sides gave an answer for.
+    pub total: usize,
+    /// Number of files where both sides agree, after resolving aliases.
+    pub matched: usize,
+    pub mismatches: Vec<Mismatch>,
+    /// Files the reference could not classify.
+    pub errors: Vec<(PathBuf, io::Error)>,
+}
+
+impl Comparison {
+    /// Fraction of compared files we disagree on, between 0 and 1.
+    pub fn mismatch_rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.mismatches.len() as f64 / self.total as f64
+        }
+    }
+
+    /// Number of mismatches for each `(ours, theirs)` pair, most common first.
+    pub fn mismatch_counts(&self) -> Vec<((Option<MIME>, &str), usize)> {
+        let mut counts = FnvHashMap::<(Option<MIME>, &str), usize>::default();
+        for m in &self.mismatches {
+            *counts.entry((m.ours, m.theirs.as_str())).or_insert(0) += 1;
+        }
+        let mut out: Vec<_> = counts.into_iter().collect();
+        out.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        out
+    }
+}
+
+/// Detects the type of every given file and compares it with what `reference`
+/// reports for it.
+///
+/// Returns an error if the reference tool can't be run at all.
+pub fn compare<I, P>(paths: I, reference: Reference) -> io::Result<Comparison>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut out = Comparison::default();
+
+    for path in paths {
+        let path = path.as_ref();
+        let theirs = match reference.query(path) {
+            Ok(x) => x,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(e),
+            Err(e) => {
+                out.errors.push((path.to_path_buf(), e));
+                continue;
+            }
+        };
+        let ours = from_filepath(path);
+
+        out.total += 1;
+        if ours.map(get_alias) == Some(get_alias(&theirs)) {
+            out.matched += 1;
+        } else {
+            out.mismatches.push(Mismatch {
+                path: path.to_path_buf(),
+                ours,
+                theirs,
+            });
+        }
+    }
+
+    Ok(out)
+}
+
+/// The files in `tests/`, or in the directory `TREE_MAGIC_CORPUS` points at,
+/// such as one filled by `scripts/fetch-corpus.sh`. Files with an extension
+/// are left out, as they're usually notes about the others.
+pub fn corpus() -> Vec<PathBuf> {
+    let root = env::var_os("TREE_MAGIC_CORPUS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("tests"));
+    let mut out = Vec::new();
+    collect(&root, &mut out);
+    out.sort();
+    out
+}
+
+fn collect(dir: &Path, out: &mut Vec<PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(x) => x,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(&path, out);
+        } else if path.extension().is_none() {
+            out.push(path);
+        }
+    }
+}
+
+/// A file whose type depends on whether it's read from its path or from
+/// its bytes, as found by [`parity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub path: PathBuf,
+    /// What `from_filepath` returned.
+    pub from_path: Option<MIME>,
+    /// What `from_u8` returned for the whole file.
+    pub from_bytes: MIME,
+}
+
+/// Checks that [`from_filepath`] gives the same type as [`from_u8`] on the
+/// contents of every given file, and returns the files where it doesn't.
+///
+/// Only regular files are compared, since the `inode/*` types can only be
+/// told from a path. Returns an error if a file can't be read.
+pub fn parity<I, P>(paths: I) -> io::Result<Vec<Divergence>>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut out = Vec::new();
+    for path in paths {
+        let path = path.as_ref();
+        if !fs::metadata(path)?.is_file() {
+            continue;
+        }
+        let from_bytes = from_u8(&fs::read(path)?);
+        let from_path = from_filepath(path);
+        if from_path != Some(from_bytes) {
+            out.push(Divergence {
+                path: path.to_path_buf(),
+                from_path,
+                from_bytes,
+            });
+        }
+    }
+    Ok(out)
+}
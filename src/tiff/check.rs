@@ -0,0 +1,141 @@
+use crate::{DataSource, FnvHashMap, TypeDatabase, MIME};
+use alloc::vec::Vec;
+
+pub(crate) struct Tiff;
+
+impl crate::Checker for Tiff {
+    fn from_source(&self, _db: &TypeDatabase, source: &dyn DataSource, mimetype: &str) -> bool {
+        from_source(source, mimetype)
+    }
+
+    fn get_supported(&self) -> Vec<MIME> {
+        super::init::get_supported()
+    }
+
+    fn get_subclasses(&self) -> Vec<(MIME, MIME)> {
+        super::init::get_subclasses()
+    }
+
+    fn get_aliaslist(&self) -> FnvHashMap<MIME, MIME> {
+        super::init::get_aliaslist()
+    }
+}
+
+const TAG_MAKE: u16 = 0x010F;
+const TAG_DNG_VERSION: u16 = 0xC612;
+const TAG_GEO_KEY_DIRECTORY: u16 = 0x87AF;
+const TYPE_ASCII: u16 = 2;
+
+const HEADER_LEN: usize = 16;
+const ENTRY_LEN: usize = 12;
+
+/// Don't trust entry counts past this
+const MAX_ENTRIES: usize = 512;
+
+/// The parts of the first IFD we care about
+struct Ifd0 {
+    make: Vec<u8>,
+    is_dng: bool,
+    is_geotiff: bool,
+}
+
+/// Reads integers in the byte order given by the file header
+#[derive(Clone, Copy)]
+struct ByteOrder {
+    little: bool,
+}
+
+impl ByteOrder {
+    fn u16(self, b: &[u8], off: usize) -> Option<u16> {
+        let x = b.get(off..off + 2)?;
+        let x = [x[0], x[1]];
+        Some(if self.little {
+            u16::from_le_bytes(x)
+        } else {
+            u16::from_be_bytes(x)
+        })
+    }
+
+    fn u32(self, b: &[u8], off: usize) -> Option<u32> {
+        let x = b.get(off..off + 4)?;
+        let x = [x[0], x[1], x[2], x[3]];
+        Some(if self.little {
+            u32::from_le_bytes(x)
+        } else {
+            u32::from_be_bytes(x)
+        })
+    }
+}
+
+fn byte_order(header: &[u8]) -> Option<ByteOrder> {
+    if header.starts_with(b"II*\0") {
+        Some(ByteOrder { little: true })
+    } else if header.starts_with(b"MM\0*") {
+        Some(ByteOrder { little: false })
+    } else {
+        None
+    }
+}
+
+fn read_ifd0(source: &dyn DataSource, header: &[u8]) -> Option<Ifd0> {
+    let order = byte_order(header)?;
+    let offset = order.u32(header, 4)? as u64;
+
+    let count = order.u16(&source.read_at(offset, 2).ok()?, 0)? as usize;
+    if count == 0 || count > MAX_ENTRIES {
+        return None;
+    }
+    let entries = source.read_at(offset + 2, count * ENTRY_LEN).ok()?;
+
+    let mut ifd = Ifd0 {
+        make: Vec::new(),
+        is_dng: false,
+        is_geotiff: false,
+    };
+    for entry in entries.chunks_exact(ENTRY_LEN) {
+        match order.u16(entry, 0)? {
+            TAG_DNG_VERSION => ifd.is_dng = true,
+            TAG_GEO_KEY_DIRECTORY => ifd.is_geotiff = true,
+            TAG_MAKE if order.u16(entry, 2)? == TYPE_ASCII => {
+                let len = order.u32(entry, 4)? as usize;
+                // Values of up to 4 bytes are stored in the entry itself
+                ifd.make = if len <= 4 {
+                    entry[8..8 + len].to_vec()
+                } else {
+                    let at = order.u32(entry, 8)? as u64;
+                    source.read_at(at, len.min(64)).ok()?.into_owned()
+                };
+            }
+            _ => {}
+        }
+    }
+    Some(ifd)
+}
+
+pub fn from_source(source: &dyn DataSource, mimetype: &str) -> bool {
+    let header = match source.read_at(0, HEADER_LEN) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+
+    // Canon marks CR2 files right after the TIFF header
+    if mimetype == "image/x-canon-cr2" {
+        return byte_order(&header).is_some() && header.get(8..11) == Some(b"CR\x02");
+    }
+
+    let ifd = match read_ifd0(source, &header) {
+        Some(x) => x,
+        None => return false,
+    };
+    let make_is =
+        |m: &[u8]| ifd.make.len() >= m.len() && ifd.make[..m.len()].eq_ignore_ascii_case(m);
+
+    match mimetype {
+        "image/x-adobe-dng" => ifd.is_dng,
+        // Cameras that shoot DNG themselves still have their own Make
+        "image/x-nikon-nef" => !ifd.is_dng && make_is(b"NIKON"),
+        "image/x-sony-arw" => !ifd.is_dng && make_is(b"SONY"),
+        "image/x-geotiff" => ifd.is_geotiff,
+        _ => false,
+    }
+}
@@ -0,0 +1,12 @@
+//! Handles camera RAW formats and GeoTIFF, which are built on TIFF, by
+//! inspecting the first IFD
+const TYPES: [&str; 5] = [
+    "image/x-canon-cr2",
+    "image/x-nikon-nef",
+    "image/x-sony-arw",
+    "image/x-adobe-dng",
+    "image/x-geotiff",
+];
+
+pub mod check;
+pub mod init;
@@ -0,0 +1,216 @@
+//! Detect the content type of directories, such as mounted media, from the
+//! files they hold
+use crate::{TypeDatabase, MIME};
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::str;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What a path must point to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    File,
+    Directory,
+    Link,
+    Any,
+}
+
+/// A line of a treemagic file
+#[derive(Debug, Clone)]
+struct TreeRule {
+    indent: u32,
+    path: &'static str,
+    kind: Kind,
+    match_case: bool,
+    executable: bool,
+    non_empty: bool,
+    mimetype: Option<MIME>,
+}
+
+#[derive(Debug, Clone)]
+struct TreeEntry {
+    priority: u32,
+    mime: MIME,
+    rules: Vec<TreeRule>,
+}
+
+/// Treemagic rules, highest priority first.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TreeMagic {
+    entries: Vec<TreeEntry>,
+}
+
+impl TreeMagic {
+    /// Parses a treemagic file, skipping lines that don't parse.
+    pub(crate) fn extend(&mut self, data: &'static [u8]) {
+        let text = match data.strip_prefix(b"MIME-TreeMagic\0\n") {
+            Some(x) => str::from_utf8(x).unwrap_or(""),
+            None => return,
+        };
+        for line in text.lines() {
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let mut parts = header.splitn(2, ':');
+                if let (Some(Ok(priority)), Some(mime)) =
+                    (parts.next().map(str::parse), parts.next())
+                {
+                    self.entries.push(TreeEntry {
+                        priority,
+                        mime,
+                        rules: Vec::new(),
+                    });
+                }
+            } else if let (Some(entry), Some(rule)) = (self.entries.last_mut(), parse_rule(line)) {
+                entry.rules.push(rule);
+            }
+        }
+        self.entries.sort_by_key(|e| Reverse(e.priority));
+    }
+
+    /// Content type of the directory at `root`, if any entry matches
+    pub(crate) fn detect(&self, db: &TypeDatabase, root: &Path) -> Option<MIME> {
+        self.entries
+            .iter()
+            .find(|e| any_match(db, root, &e.rules, 0))
+            .map(|e| e.mime)
+    }
+}
+
+/// Parses `[indent]>"path"=type[,flag]...[,mimetype]`
+fn parse_rule(line: &'static str) -> Option<TreeRule> {
+    let (indent, rest) = line.split_at(line.find('>')?);
+    let indent = if indent.is_empty() {
+        0
+    } else {
+        indent.parse().ok()?
+    };
+    let rest = rest.strip_prefix(">\"")?;
+    let (path, rest) = rest.split_at(rest.find('"')?);
+    let mut fields = rest.strip_prefix("\"=")?.split(',');
+
+    let kind = match fields.next()? {
+        "file" => Kind::File,
+        "directory" => Kind::Directory,
+        "link" => Kind::Link,
+        "any" => Kind::Any,
+        _ => return None,
+    };
+    let mut rule = TreeRule {
+        indent,
+        path,
+        kind,
+        match_case: false,
+        executable: false,
+        non_empty: false,
+        mimetype: None,
+    };
+    for field in fields {
+        match field {
+            "match-case" => rule.match_case = true,
+            "executable" => rule.executable = true,
+            "non-empty" => rule.non_empty = true,
+            // Can't be told from here, so treat every path as on a disc
+            "on-disc" => {}
+            mime => rule.mimetype = Some(mime),
+        }
+    }
+    Some(rule)
+}
+
+/// Whether any rule at `level` matches, along with one of its nested rules
+/// if it has any
+fn any_match(db: &TypeDatabase, root: &Path, rules: &[TreeRule], level: u32) -> bool {
+    let mut i = 0;
+    while i < rules.len() {
+        let end = rules[i + 1..]
+            .iter()
+            .position(|r| r.indent <= level)
+            .map_or(rules.len(), |p| i + 1 + p);
+        let children = &rules[i + 1..end];
+
+        if rule_match(db, root, &rules[i])
+            && (children.is_empty() || any_match(db, root, children, level + 1))
+        {
+            return true;
+        }
+        i = end;
+    }
+    false
+}
+
+fn rule_match(db: &TypeDatabase, root: &Path, rule: &TreeRule) -> bool {
+    let path = match resolve(root, rule.path, rule.match_case) {
+        Some(x) => x,
+        None => return false,
+    };
+    let link_meta = match fs::symlink_metadata(&path) {
+        Ok(x) => x,
+        Err(_) => return false,
+    };
+    let meta = fs::metadata(&path).unwrap_or_else(|_| link_meta.clone());
+
+    let kind_ok = match rule.kind {
+        Kind::File => meta.is_file(),
+        Kind::Directory => meta.is_dir(),
+        Kind::Link => link_meta.file_type().is_symlink(),
+        Kind::Any => true,
+    };
+    let non_empty_ok = !rule.non_empty
+        || if meta.is_dir() {
+            fs::read_dir(&path).is_ok_and(|mut d| d.next().is_some())
+        } else {
+            meta.len() > 0
+        };
+    let mimetype_ok = rule.mimetype.map_or(true, |m| db.match_filepath(m, &path));
+
+    kind_ok && non_empty_ok && (!rule.executable || is_executable(&meta)) && mimetype_ok
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &fs::Metadata) -> bool {
+    false
+}
+
+/// Joins a relative path onto `root`, comparing each component without
+/// regard to case unless `match_case` is set
+fn resolve(root: &Path, path: &str, match_case: bool) -> Option<PathBuf> {
+    if match_case {
+        return Some(root.join(path));
+    }
+    let mut out = root.to_path_buf();
+    for part in path.split('/').filter(|p| !p.is_empty()) {
+        let lower = part.to_lowercase();
+        let found = fs::read_dir(&out)
+            .ok()?
+            .filter_map(Result::ok)
+            .map(|e| e.file_name())
+            .find(|n| n.to_str().map(str::to_lowercase).as_deref() == Some(lower.as_str()))?;
+        out.push(found);
+    }
+    Some(out)
+}
+
+/// Loads the treemagic rules from the XDG standard locations
+pub(crate) fn system() -> TreeMagic {
+    use once_cell::sync::OnceCell;
+
+    static TREEMAGIC_FILES: OnceCell<Vec<Vec<u8>>> = OnceCell::new();
+
+    let files = TREEMAGIC_FILES.get_or_init(|| {
+        crate::xdg::search_paths("treemagic")
+            .iter()
+            .filter_map(|p| fs::read(p).ok())
+            .collect()
+    });
+    let mut out = TreeMagic::default();
+    for data in files {
+        out.extend(data);
+    }
+    out
+}
@@ -0,0 +1,211 @@
+//! Map types to the Uniform Type Identifiers of Apple platforms and back,
+//! for the pasteboard and file association APIs of macOS and iOS.
+//!
+//! The mapping is a table of the identifiers the system declares, bundled
+//! with the crate, so it works on any platform. Types are looked up by
+//! their canonical names, so aliases map too.
+//!
+//! Needs the `uti` feature.
+//!
+//! # Examples
+//! ```rust
+//! use tree_magic_mini::uti;
+//!
+//! let gif: &[u8] = include_bytes!("../tests/image/gif");
+//! assert_eq!(uti::to_uti(tree_magic_mini::from_u8(gif)), Some("com.compuserve.gif"));
+//! assert_eq!(uti::from_uti("public.png"), Some("image/png"));
+//! ```
+use crate::{database, MIME};
+use alloc::{collections::VecDeque, vec::Vec};
+use petgraph::prelude::*;
+
+/// Types and their identifiers. Where several types share an identifier,
+/// the first is the one it maps back to.
+const UTIS: &[(MIME, &str)] = &[
+    // Text
+    ("text/plain", "public.plain-text"),
+    ("text/html", "public.html"),
+    ("application/xml", "public.xml"),
+    ("application/json", "public.json"),
+    ("application/yaml", "public.yaml"),
+    ("text/css", "public.css"),
+    ("text/csv", "public.comma-separated-values-text"),
+    (
+        "text/tab-separated-values",
+        "public.tab-separated-values-text",
+    ),
+    ("application/rtf", "public.rtf"),
+    ("text/markdown", "net.daringfireball.markdown"),
+    ("text/vcard", "public.vcard"),
+    ("text/calendar", "public.calendar-event"),
+    ("message/rfc822", "public.email-message"),
+    // Source code and scripts
+    ("text/x-csrc", "public.c-source"),
+    ("text/x-chdr", "public.c-header"),
+    ("text/x-c++src", "public.c-plus-plus-source"),
+    ("text/x-objcsrc", "public.objective-c-source"),
+    ("text/x-swift", "public.swift-source"),
+    ("text/x-java", "com.sun.java-source"),
+    ("text/javascript", "com.netscape.javascript-source"),
+    ("text/x-python", "public.python-script"),
+    ("application/x-shellscript", "public.shell-script"),
+    ("application/x-ruby", "public.ruby-script"),
+    ("application/x-perl", "public.perl-script"),
+    ("application/x-php", "public.php-script"),
+    // Images
+    ("image/png", "public.png"),
+    ("image/jpeg", "public.jpeg"),
+    ("image/gif", "com.compuserve.gif"),
+    ("image/tiff", "public.tiff"),
+    ("image/bmp", "com.microsoft.bmp"),
+    ("image/vnd.microsoft.icon", "com.microsoft.ico"),
+    ("image/x-icns", "com.apple.icns"),
+    ("image/heic", "public.heic"),
+    ("image/heif", "public.heif"),
+    ("image/avif", "public.avif"),
+    ("image/webp", "org.webmproject.webp"),
+    ("image/svg+xml", "public.svg-image"),
+    ("image/jp2", "public.jpeg-2000"),
+    ("image/x-exr", "com.ilm.openexr-image"),
+    ("image/x-tga", "com.truevision.tga-image"),
+    ("image/vnd.adobe.photoshop", "com.adobe.photoshop-image"),
+    // Audio
+    ("audio/mpeg", "public.mp3"),
+    ("audio/mp4", "public.mpeg-4-audio"),
+    ("audio/aac", "public.aac-audio"),
+    ("audio/x-wav", "com.microsoft.waveform-audio"),
+    ("audio/x-aiff", "public.aiff-audio"),
+    ("audio/flac", "org.xiph.flac"),
+    ("audio/midi", "public.midi-audio"),
+    // Video
+    ("video/mp4", "public.mpeg-4"),
+    ("video/quicktime", "com.apple.quicktime-movie"),
+    ("video/x-msvideo", "public.avi"),
+    ("video/mpeg", "public.mpeg"),
+    ("video/mp2t", "public.mpeg-2-transport-stream"),
+    ("video/webm", "org.webmproject.webm"),
+    ("video/3gpp", "public.3gpp"),
+    ("video/3gpp2", "public.3gpp2"),
+    ("video/x-ms-wmv", "com.microsoft.windows-media-wmv"),
+    ("video/x-ms-asf", "com.microsoft.advanced-systems-format"),
+    // Documents
+    ("application/pdf", "com.adobe.pdf"),
+    ("application/postscript", "com.adobe.postscript"),
+    ("application/epub+zip", "org.idpf.epub-container"),
+    ("application/msword", "com.microsoft.word.doc"),
+    (
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "org.openxmlformats.wordprocessingml.document",
+    ),
+    ("application/vnd.ms-excel", "com.microsoft.excel.xls"),
+    (
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "org.openxmlformats.spreadsheetml.sheet",
+    ),
+    (
+        "application/vnd.ms-powerpoint",
+        "com.microsoft.powerpoint.ppt",
+    ),
+    (
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "org.openxmlformats.presentationml.presentation",
+    ),
+    (
+        "application/vnd.oasis.opendocument.text",
+        "org.oasis-open.opendocument.text",
+    ),
+    (
+        "application/vnd.oasis.opendocument.spreadsheet",
+        "org.oasis-open.opendocument.spreadsheet",
+    ),
+    (
+        "application/vnd.oasis.opendocument.presentation",
+        "org.oasis-open.opendocument.presentation",
+    ),
+    // Fonts
+    ("font/ttf", "public.truetype-ttf-font"),
+    ("font/otf", "public.opentype-font"),
+    // Archives and disk images
+    ("application/zip", "public.zip-archive"),
+    ("application/gzip", "org.gnu.gnu-zip-archive"),
+    ("application/x-bzip2", "public.bzip2-archive"),
+    ("application/x-tar", "public.tar-archive"),
+    ("application/x-7z-compressed", "org.7-zip.7-zip-archive"),
+    ("application/vnd.rar", "com.rarlab.rar-archive"),
+    ("application/x-java-archive", "com.sun.java-archive"),
+    ("application/x-apple-diskimage", "com.apple.disk-image-udif"),
+    ("application/x-cd-image", "public.iso-image"),
+    // Executables
+    ("application/x-executable", "public.unix-executable"),
+    (
+        "application/x-ms-dos-executable",
+        "com.microsoft.windows-executable",
+    ),
+    // Generic types the others conform to
+    ("inode/directory", "public.folder"),
+    ("application/octet-stream", "public.data"),
+];
+
+/// Identifier of the type itself, if the table has one
+fn exact_uti(mimetype: &str) -> Option<&'static str> {
+    let db = database();
+    UTIS.iter()
+        .find(|(m, _)| db.get_alias(m) == mimetype)
+        .map(|&(_, uti)| uti)
+}
+
+/// Gets the Uniform Type Identifier of a type, such as `public.png` for
+/// `image/png`.
+///
+/// Types without an identifier of their own get that of the closest type
+/// they're a subclass of, which the identifier of the type would conform
+/// to: `public.plain-text` for most text, and `public.data` for binary
+/// data. Returns `None` for types the database doesn't know.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::uti::to_uti;
+///
+/// assert_eq!(to_uti("image/png"), Some("public.png"));
+/// // An alias of `application/zip`
+/// assert_eq!(to_uti("application/x-zip-compressed"), Some("public.zip-archive"));
+/// // A text type without an identifier of its own
+/// assert_eq!(to_uti("text/x-rust"), Some("public.plain-text"));
+/// ```
+pub fn to_uti(mimetype: &str) -> Option<&'static str> {
+    let db = database();
+    let mimetype = db.get_alias(mimetype);
+    if let Some(uti) = exact_uti(mimetype) {
+        return Some(uti);
+    }
+
+    // Nearest ancestor first, trying parents by name at each level
+    let graph = db.graph();
+    let mut seen = Vec::new();
+    let mut queue: VecDeque<NodeIndex> = db.node(mimetype).into_iter().collect();
+    while let Some(n) = queue.pop_front() {
+        if let Some(uti) = exact_uti(graph[n]) {
+            return Some(uti);
+        }
+        let mut parents: Vec<NodeIndex> = graph
+            .neighbors_directed(n, Incoming)
+            .filter(|p| !seen.contains(p))
+            .collect();
+        parents.sort_by_key(|&p| graph[p]);
+        seen.extend(&parents);
+        queue.extend(parents);
+    }
+    None
+}
+
+/// Gets the type of a Uniform Type Identifier, such as `image/png` for
+/// `public.png`. Identifiers are compared without regard to case, as on
+/// Apple platforms.
+///
+/// Only identifiers in the bundled table are known; identifiers they
+/// conform to, like `public.image`, have no type of their own.
+pub fn from_uti(uti: &str) -> Option<MIME> {
+    UTIS.iter()
+        .find(|(_, u)| u.eq_ignore_ascii_case(uti))
+        .map(|&(mime, _)| database().get_alias(mime))
+}
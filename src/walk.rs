@@ -0,0 +1,152 @@
+//! Detect the types of files found by a directory walk, with the entries of
+//! the `walkdir` and `ignore` crates.
+//!
+//! The metadata the walk read is reused instead of asking for it again, and
+//! every thread reads into a buffer it keeps between files. [`walk`] runs
+//! the detection on the threads of a parallel walk.
+//!
+//! Needs the `walk` feature.
+//!
+//! # Examples
+//! ```rust
+//! use tree_magic_mini::walk::{detect_entry, walkdir::WalkDir};
+//!
+//! for entry in WalkDir::new("tests/image").into_iter().filter_map(Result::ok) {
+//!     if entry.file_name() == "gif" {
+//!         assert_eq!(detect_entry(&entry), Some("image/gif"));
+//!     }
+//! }
+//! ```
+use crate::{database, MIME};
+use alloc::boxed::Box;
+use ignore::{WalkBuilder, WalkState};
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+pub use ignore;
+pub use walkdir;
+
+/// Results a parallel walk gets ahead of the caller by, before its threads
+/// wait for them to be taken
+const QUEUE_LEN: usize = 256;
+
+/// An entry found by a directory walk.
+pub trait WalkEntry {
+    /// Path of the entry.
+    fn path(&self) -> &Path;
+
+    /// Whether the entry is a symbolic link, as told by the walk.
+    fn is_symlink(&self) -> bool;
+
+    /// Metadata of the entry, as the walk reads it. Only asked for entries
+    /// that aren't symbolic links.
+    fn metadata(&self) -> Option<Metadata>;
+}
+
+impl WalkEntry for walkdir::DirEntry {
+    fn path(&self) -> &Path {
+        self.path()
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.path_is_symlink()
+    }
+
+    fn metadata(&self) -> Option<Metadata> {
+        self.metadata().ok()
+    }
+}
+
+impl WalkEntry for ignore::DirEntry {
+    fn path(&self) -> &Path {
+        self.path()
+    }
+
+    fn is_symlink(&self) -> bool {
+        self.path_is_symlink()
+    }
+
+    fn metadata(&self) -> Option<Metadata> {
+        self.metadata().ok()
+    }
+}
+
+/// Gets the type of an entry of a directory walk.
+///
+/// Works like [`from_filepath_with_meta`](crate::from_filepath_with_meta),
+/// with the metadata of the entry. Symbolic links are followed, as by
+/// [`from_filepath`](crate::from_filepath).
+pub fn detect_entry(entry: &(impl WalkEntry + ?Sized)) -> Option<MIME> {
+    let path = entry.path();
+    let meta = match entry.is_symlink() {
+        true => std::fs::metadata(path).ok()?,
+        false => entry.metadata()?,
+    };
+    database().from_filepath_with_meta(path, &meta)
+}
+
+/// Walks a directory tree in parallel, yielding every entry with its type.
+///
+/// All entries are visited, including hidden ones and those a `.gitignore`
+/// would leave out; use [`walk_with`] to filter them. See there for how the
+/// walk goes.
+///
+/// # Examples
+/// ```rust
+/// use std::path::Path;
+///
+/// let found: Vec<_> = tree_magic_mini::walk::walk("tests/image").collect();
+/// let gif = found.iter().find(|(path, _)| path == Path::new("tests/image/gif"));
+/// assert_eq!(gif.unwrap().1, Some("image/gif"));
+/// ```
+pub fn walk(root: impl AsRef<Path>) -> Walk {
+    let mut builder = WalkBuilder::new(root);
+    builder.standard_filters(false);
+    walk_with(&builder)
+}
+
+/// Walks the directory trees the builder is set up for in parallel,
+/// yielding every entry with its type.
+///
+/// Types are detected on the threads of the walk, as many as the builder
+/// says, and the entries come in no particular order. The directories
+/// walked come too, as well as the roots. Entries the walk fails to read
+/// are left out. Dropping the iterator stops the walk.
+pub fn walk_with(builder: &WalkBuilder) -> Walk {
+    let walker = builder.build_parallel();
+    let (tx, rx) = mpsc::sync_channel(QUEUE_LEN);
+    thread::spawn(move || {
+        walker.run(|| {
+            let tx = tx.clone();
+            Box::new(move |entry| {
+                let entry = match entry {
+                    Ok(x) => x,
+                    Err(_) => return WalkState::Continue,
+                };
+                let mime = detect_entry(&entry);
+                match tx.send((entry.into_path(), mime)) {
+                    Ok(()) => WalkState::Continue,
+                    Err(_) => WalkState::Quit,
+                }
+            })
+        })
+    });
+    Walk { results: rx }
+}
+
+/// Entries of a parallel walk with their types, returned by [`walk`] and
+/// [`walk_with`].
+#[derive(Debug)]
+pub struct Walk {
+    results: Receiver<(PathBuf, Option<MIME>)>,
+}
+
+impl Iterator for Walk {
+    type Item = (PathBuf, Option<MIME>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.results.recv().ok()
+    }
+}
@@ -0,0 +1,67 @@
+//! Extract uploads in Actix Web
+use super::{Upload, UploadError, UploadPolicy, Uploads};
+use actix_multipart::Multipart;
+use actix_web::dev::Payload;
+use actix_web::http::StatusCode;
+use actix_web::{web, FromRequest, HttpRequest, ResponseError};
+use alloc::boxed::Box;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use futures_util::future::LocalBoxFuture;
+use futures_util::StreamExt;
+
+fn read_error(e: impl ToString) -> UploadError {
+    UploadError::Read(e.to_string())
+}
+
+/// The policy given to the app, as is or as `web::Data`
+fn policy(req: &HttpRequest) -> UploadPolicy {
+    req.app_data::<UploadPolicy>()
+        .or_else(|| {
+            req.app_data::<web::Data<UploadPolicy>>()
+                .map(|x| x.get_ref())
+        })
+        .cloned()
+        .unwrap_or_else(UploadPolicy::any)
+}
+
+impl FromRequest for Uploads {
+    type Error = UploadError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let policy = policy(req);
+        let mut multipart = Multipart::new(req.headers(), payload.take());
+        Box::pin(async move {
+            let limit = policy.len_limit().unwrap_or(usize::MAX);
+            let mut uploads = Vec::new();
+            while let Some(field) = multipart.next().await {
+                let mut field = field.map_err(read_error)?;
+                let data = match field.bytes(limit).await {
+                    Ok(x) => x.map_err(read_error)?,
+                    Err(_) => {
+                        return Err(UploadError::TooLarge {
+                            field: field.name().map(ToString::to_string),
+                        })
+                    }
+                };
+                let file_name = field.content_disposition().and_then(|x| x.get_filename());
+                let declared = field.content_type().map(|x| x.essence_str());
+                let upload = Upload::new(field.name(), file_name, declared, data.to_vec());
+                policy.check(&upload)?;
+                uploads.push(upload);
+            }
+            Ok(Uploads(uploads))
+        })
+    }
+}
+
+impl ResponseError for UploadError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            UploadError::Read(_) => StatusCode::BAD_REQUEST,
+            UploadError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            UploadError::NotAllowed { .. } => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Extract uploads in axum
+use super::{Upload, UploadError, UploadPolicy, Uploads};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use axum::extract::multipart::{Field, Multipart};
+use axum::extract::{FromRequest, Request};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+/// Reads a part, stopping as soon as it's longer than the policy allows
+async fn read_field(field: &mut Field<'_>, policy: &UploadPolicy) -> Result<Vec<u8>, UploadError> {
+    let mut data = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(read_error)? {
+        data.extend_from_slice(&chunk);
+        if policy.len_limit().is_some_and(|max| data.len() > max) {
+            return Err(UploadError::TooLarge {
+                field: field.name().map(ToString::to_string),
+            });
+        }
+    }
+    Ok(data)
+}
+
+fn read_error(e: impl ToString) -> UploadError {
+    UploadError::Read(e.to_string())
+}
+
+impl<S> FromRequest<S> for Uploads
+where
+    S: Send + Sync,
+{
+    type Rejection = UploadError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let policy = req
+            .extensions()
+            .get::<UploadPolicy>()
+            .cloned()
+            .unwrap_or_else(UploadPolicy::any);
+        let mut multipart = Multipart::from_request(req, state)
+            .await
+            .map_err(|e| read_error(e.body_text()))?;
+
+        let mut uploads = Vec::new();
+        while let Some(mut field) = multipart.next_field().await.map_err(read_error)? {
+            let data = read_field(&mut field, &policy).await?;
+            let upload = Upload::new(field.name(), field.file_name(), field.content_type(), data);
+            policy.check(&upload)?;
+            uploads.push(upload);
+        }
+        Ok(Uploads(uploads))
+    }
+}
+
+impl IntoResponse for UploadError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            UploadError::Read(_) => StatusCode::BAD_REQUEST,
+            UploadError::TooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            UploadError::NotAllowed { .. } => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
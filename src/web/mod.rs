@@ -0,0 +1,237 @@
+//! Detect the types of files uploaded to web applications, and turn away
+//! those of types they don't take.
+//!
+//! An [`Upload`] is a part of a multipart form, with the type detected from
+//! its content rather than the one the client declared, which it can't be
+//! trusted with. An [`UploadPolicy`] says which types are allowed, and how
+//! large a part may be.
+//!
+//! The web frameworks get extractors and form guards handing out uploads,
+//! each behind a feature of its own:
+//!
+//! * `axum`: [`Uploads`] extracts every part of a multipart body, with the
+//!   policy of an `axum::Extension` layer.
+//! * `actix`: [`Uploads`] does the same in Actix Web, with the policy given
+//!   to `App::app_data`.
+//! * `rocket`: [`Upload`] is a form field, with the policy given to
+//!   `Rocket::manage`.
+//!
+//! Without a policy, parts of any type and length are let through, short of
+//! the limits of the framework. Other frameworks can use [`Upload::new`].
+//!
+//! Needs the `web` feature.
+//!
+//! # Examples
+//! ```rust
+//! use tree_magic_mini::web::{Upload, UploadError, UploadPolicy};
+//!
+//! let policy = UploadPolicy::new().allow("image/*").max_len(1 << 20);
+//! let gif = include_bytes!("../../tests/image/gif").to_vec();
+//!
+//! // Declared as text, but an image
+//! let upload = Upload::new(Some("avatar"), Some("avatar.txt"), Some("text/plain"), gif);
+//! assert_eq!(upload.mime.0, "image/gif");
+//! assert!(policy.check(&upload).is_ok());
+//!
+//! let pdf = Upload::new(None, None, None, b"%PDF-1.4\n".to_vec());
+//! assert!(matches!(policy.check(&pdf), Err(UploadError::NotAllowed { .. })));
+//! ```
+use crate::{database, MIME};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+#[cfg(feature = "actix")]
+mod actix;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "rocket")]
+mod rocket;
+
+/// The type of an upload, as detected from its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DetectedMime(pub MIME);
+
+impl fmt::Display for DetectedMime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// A part of a multipart form, with its detected type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Upload {
+    /// Name of the form field.
+    pub name: Option<String>,
+    /// File name the client gave, as is. Don't use it as a path without
+    /// sanitizing it.
+    pub file_name: Option<String>,
+    /// Type the client declared, which may not match the content.
+    pub declared: Option<String>,
+    /// Type detected from the content.
+    pub mime: DetectedMime,
+    /// Content of the part.
+    pub data: Vec<u8>,
+}
+
+impl Upload {
+    /// Detects the type of a part read by other means than the extractors
+    /// of this module.
+    pub fn new(
+        name: Option<&str>,
+        file_name: Option<&str>,
+        declared: Option<&str>,
+        data: Vec<u8>,
+    ) -> Self {
+        Upload {
+            name: name.map(ToString::to_string),
+            file_name: file_name.map(ToString::to_string),
+            declared: declared.map(ToString::to_string),
+            mime: DetectedMime(database().from_u8(&data)),
+            data,
+        }
+    }
+}
+
+/// Which uploads are taken.
+///
+/// A new policy allows no type at all, and parts of any length.
+///
+/// # Examples
+/// ```rust
+/// use tree_magic_mini::web::UploadPolicy;
+///
+/// let policy = UploadPolicy::new()
+///     .allow("image/png")
+///     .allow("video/*")
+///     .allow_subclasses("text/plain");
+///
+/// assert!(policy.allows("image/png"));
+/// assert!(!policy.allows("image/gif"));
+/// assert!(policy.allows("video/mp4"));
+/// // Source code is text
+/// assert!(policy.allows("text/x-csrc"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UploadPolicy {
+    /// Patterns, and whether they take subclasses too
+    allowed: Vec<(String, bool)>,
+    max_len: Option<usize>,
+}
+
+impl UploadPolicy {
+    /// A policy allowing no type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A policy allowing every type.
+    pub fn any() -> Self {
+        Self::new().allow("*/*")
+    }
+
+    /// Allows a type, or all types under a top-level type with a pattern
+    /// such as `image/*`. Aliases are resolved.
+    ///
+    /// Subclasses of the type aren't allowed with it, as they may be handled
+    /// differently, as an SVG image (`image/svg+xml`) is by a browser asked
+    /// for XML.
+    pub fn allow(mut self, pattern: &str) -> Self {
+        self.allowed.push((pattern.to_ascii_lowercase(), false));
+        self
+    }
+
+    /// Allows a type along with all its subclasses, such as every
+    /// ZIP-based format with `application/zip`.
+    pub fn allow_subclasses(mut self, mimetype: &str) -> Self {
+        self.allowed.push((mimetype.to_ascii_lowercase(), true));
+        self
+    }
+
+    /// Sets the length in bytes a part may have at most.
+    pub fn max_len(mut self, bytes: usize) -> Self {
+        self.max_len = Some(bytes);
+        self
+    }
+
+    /// The length in bytes a part may have at most, if limited.
+    pub fn len_limit(&self) -> Option<usize> {
+        self.max_len
+    }
+
+    /// Whether the policy allows the type.
+    pub fn allows(&self, mimetype: &str) -> bool {
+        let db = database();
+        self.allowed
+            .iter()
+            .any(|(pattern, subclasses)| match pattern.strip_suffix("/*") {
+                Some("*") => true,
+                Some(top) => mimetype.split('/').next() == Some(top),
+                None if *subclasses => db.is_a(mimetype, pattern),
+                None => db.get_alias(mimetype) == db.get_alias(pattern),
+            })
+    }
+
+    /// Checks an upload against the policy.
+    pub fn check(&self, upload: &Upload) -> Result<(), UploadError> {
+        if self.max_len.is_some_and(|max| upload.data.len() > max) {
+            return Err(UploadError::TooLarge {
+                field: upload.name.clone(),
+            });
+        }
+        if !self.allows(upload.mime.0) {
+            return Err(UploadError::NotAllowed {
+                field: upload.name.clone(),
+                mime: upload.mime.0,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Every part of a multipart body, with their detected types, in the order
+/// they came. Parts are held in memory whole.
+///
+/// Extracted with the `axum` or `actix` feature. Fails if any part goes
+/// against the policy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Uploads(pub Vec<Upload>);
+
+/// Error returned when an upload can't be read or isn't taken.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadError {
+    /// The body isn't a multipart form, or reading it failed.
+    Read(String),
+    /// A part is longer than the policy allows.
+    TooLarge {
+        /// Name of the form field.
+        field: Option<String>,
+    },
+    /// A part is of a type the policy doesn't allow.
+    NotAllowed {
+        /// Name of the form field.
+        field: Option<String>,
+        /// The detected type.
+        mime: MIME,
+    },
+}
+
+impl fmt::Display for UploadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = |field: &Option<String>| match field {
+            Some(x) => alloc::format!(" in field {}", x),
+            None => String::new(),
+        };
+        match self {
+            UploadError::Read(e) => write!(f, "invalid multipart body: {}", e),
+            UploadError::TooLarge { field: name } => {
+                write!(f, "upload too large{}", field(name))
+            }
+            UploadError::NotAllowed { field: name, mime } => {
+                write!(f, "type {} not allowed{}", mime, field(name))
+            }
+        }
+    }
+}
+
+impl std::error::Error for UploadError {}
@@ -0,0 +1,38 @@
+//! Take uploads as form fields in Rocket
+use super::{Upload, UploadError, UploadPolicy};
+use alloc::boxed::Box;
+use alloc::string::ToString;
+use rocket::data::{ByteUnit, Limits};
+use rocket::form::{self, DataField, FromFormField};
+
+#[rocket::async_trait]
+impl<'r> FromFormField<'r> for Upload {
+    /// Reads the part up to the length the policy allows, or else the
+    /// `file` limit of Rocket.
+    async fn from_data(field: DataField<'r, '_>) -> form::Result<'r, Self> {
+        let any = UploadPolicy::any();
+        let policy = field
+            .request
+            .rocket()
+            .state::<UploadPolicy>()
+            .unwrap_or(&any);
+        let name = field.name.source().as_str();
+        let limit = match policy.len_limit() {
+            Some(max) => ByteUnit::from(max),
+            None => field.request.limits().get("file").unwrap_or(Limits::FILE),
+        };
+
+        let data = field.data.open(limit).into_bytes().await?;
+        if !data.is_complete() {
+            let e = UploadError::TooLarge {
+                field: Some(name.to_string()),
+            };
+            return Err(form::Error::custom(e).into());
+        }
+        let file_name = field.file_name.and_then(|x| x.as_str());
+        let declared = field.content_type.to_string();
+        let upload = Upload::new(Some(name), file_name, Some(&declared), data.into_inner());
+        policy.check(&upload).map_err(form::Error::custom)?;
+        Ok(upload)
+    }
+}
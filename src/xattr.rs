@@ -0,0 +1,94 @@
+//! Read and write the type a file is tagged with in its `user.mime_type`
+//! extended attribute, as the shared MIME-info spec suggests for types given
+//! by other means than the content
+use crate::TypeDatabase;
+use std::ffi::CString;
+use std::fs::File;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::string::String;
+
+const NAME: &[u8] = b"user.mime_type\0";
+
+/// Longest value read. Longer ones aren't type names.
+const MAX_LEN: usize = 256;
+
+/// The type in the attribute, lowercased, if the file has one. Symbolic
+/// links are followed.
+pub(crate) fn mime_type(path: &Path) -> Option<String> {
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut buf = [0u8; MAX_LEN];
+    // Safety: both names are valid C strings, and the size is that of `buf`
+    #[cfg(target_os = "linux")]
+    let len = unsafe {
+        libc::getxattr(
+            path.as_ptr(),
+            NAME.as_ptr().cast(),
+            buf.as_mut_ptr().cast(),
+            buf.len(),
+        )
+    };
+    #[cfg(target_os = "macos")]
+    let len = unsafe {
+        libc::getxattr(
+            path.as_ptr(),
+            NAME.as_ptr().cast(),
+            buf.as_mut_ptr().cast(),
+            buf.len(),
+            0,
+            0,
+        )
+    };
+    if len <= 0 {
+        return None;
+    }
+    // Some tools store the value with a trailing null byte
+    let value = std::str::from_utf8(&buf[..len as usize]).ok()?;
+    let value = value.trim_end_matches('\0').trim();
+    Some(value.to_ascii_lowercase())
+}
+
+/// Sets the attribute of an open file, replacing any value it had
+fn set_mime_type(file: &File, mime: &str) -> io::Result<()> {
+    let fd = file.as_raw_fd();
+    // Safety: the name is a valid C string, and the size is that of `mime`
+    #[cfg(target_os = "linux")]
+    let ret = unsafe {
+        libc::fsetxattr(
+            fd,
+            NAME.as_ptr().cast(),
+            mime.as_ptr().cast(),
+            mime.len(),
+            0,
+        )
+    };
+    #[cfg(target_os = "macos")]
+    let ret = unsafe {
+        libc::fsetxattr(
+            fd,
+            NAME.as_ptr().cast(),
+            mime.as_ptr().cast(),
+            mime.len(),
+            0,
+            0,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Detects the type of the file from its content and tags it with it. Both
+/// go through the same open handle, so they're of the same file even if the
+/// path is replaced in the meantime.
+pub(crate) fn tag_filepath<'a>(db: &'a TypeDatabase, filepath: &Path) -> io::Result<&'a str> {
+    let file = File::open(filepath)?;
+    let mime = db
+        .from_file(&file)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no type found"))?;
+    set_mime_type(&file, mime)?;
+    Ok(mime)
+}
@@ -0,0 +1,30 @@
+//! Locations of the shared MIME-info database, following the XDG base
+//! directory spec
+use alloc::vec::Vec;
+use std::env;
+use std::path::PathBuf;
+
+/// System-wide data directories, least important first
+const SYSTEM_DIRS: &[&str; 2] = &["/usr/share/mime", "/usr/local/share/mime"];
+
+/// The per-user MIME directory: `$XDG_DATA_HOME/mime`, or
+/// `~/.local/share/mime` if that's unset
+pub(crate) fn user_mime_dir() -> Option<PathBuf> {
+    match env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        Some(data) => Some(PathBuf::from(data).join("mime")),
+        None => env::var_os("HOME")
+            .filter(|d| !d.is_empty())
+            .map(|home| PathBuf::from(home).join(".local/share/mime")),
+    }
+}
+
+/// Every path of a database file, least important first, so the user's
+/// copy comes last and wins wherever later files override earlier ones
+pub(crate) fn search_paths(file: &str) -> Vec<PathBuf> {
+    SYSTEM_DIRS
+        .iter()
+        .map(PathBuf::from)
+        .chain(user_mime_dir())
+        .map(|dir| dir.join(file))
+        .collect()
+}
@@ -0,0 +1,105 @@
+//! Member names of ZIP archives, for checkers of formats packaged as ZIP
+use crate::source::source_len;
+use crate::DataSource;
+use alloc::vec::Vec;
+
+const ZIP_LOCAL_HEADER: &[u8; 4] = b"PK\x03\x04";
+const ZIP_CENTRAL_HEADER: &[u8; 4] = b"PK\x01\x02";
+const ZIP_END: &[u8; 4] = b"PK\x05\x06";
+/// The end record, and the longest comment that can follow it
+pub(crate) const ZIP_END_SEARCH_LEN: u64 = 22 + 0xffff;
+/// Members looked at
+const MAX_MEMBERS: usize = 64;
+/// Most of the central directory read
+pub(crate) const MAX_CENTRAL_DIRECTORY_LEN: usize = 64 * 1024;
+
+fn le_u16(b: &[u8], at: usize) -> Option<usize> {
+    let b = b.get(at..at + 2)?;
+    Some(u16::from_le_bytes([b[0], b[1]]) as usize)
+}
+
+fn le_u32(b: &[u8], at: usize) -> Option<u32> {
+    let b = b.get(at..at + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+pub(crate) fn is_zip(source: &dyn DataSource) -> bool {
+    match source.read_at(0, ZIP_LOCAL_HEADER.len()) {
+        Ok(b) => b[..] == ZIP_LOCAL_HEADER[..],
+        Err(_) => false,
+    }
+}
+
+/// Names of the members, from the central directory if the length of the
+/// data is known, or else from the local headers as far as they can be
+/// followed
+pub(crate) fn zip_names(source: &dyn DataSource) -> Vec<Vec<u8>> {
+    let mut names = Vec::new();
+    if let Some(len) = source_len(source) {
+        let start = len.saturating_sub(ZIP_END_SEARCH_LEN);
+        let tail = match source.read_at(start, (len - start) as usize) {
+            Ok(b) => b,
+            Err(_) => return names,
+        };
+        let end = match tail.windows(4).rposition(|w| w == ZIP_END) {
+            Some(n) => &tail[n..],
+            None => return names,
+        };
+        let (size, offset) = match (le_u32(end, 12), le_u32(end, 16)) {
+            (Some(size), Some(offset)) => (size as usize, offset as u64),
+            _ => return names,
+        };
+        let dir = match source.read_at(offset, size.min(MAX_CENTRAL_DIRECTORY_LEN)) {
+            Ok(b) => b,
+            Err(_) => return names,
+        };
+        let mut at = 0;
+        while names.len() < MAX_MEMBERS
+            && dir
+                .get(at..)
+                .is_some_and(|d| d.starts_with(ZIP_CENTRAL_HEADER))
+        {
+            let (name_len, extra_len, comment_len) = match (
+                le_u16(&dir, at + 28),
+                le_u16(&dir, at + 30),
+                le_u16(&dir, at + 32),
+            ) {
+                (Some(n), Some(e), Some(c)) => (n, e, c),
+                _ => break,
+            };
+            match dir.get(at + 46..at + 46 + name_len) {
+                Some(name) => names.push(name.to_vec()),
+                None => break,
+            }
+            at += 46 + name_len + extra_len + comment_len;
+        }
+        return names;
+    }
+
+    let mut at = 0u64;
+    while names.len() < MAX_MEMBERS {
+        let header = match source.read_at(at, 30) {
+            Ok(b) if b.starts_with(ZIP_LOCAL_HEADER) && b.len() == 30 => b,
+            _ => break,
+        };
+        let (flags, size, name_len, extra_len) = match (
+            le_u16(&header, 6),
+            le_u32(&header, 18),
+            le_u16(&header, 26),
+            le_u16(&header, 28),
+        ) {
+            (Some(f), Some(s), Some(n), Some(e)) => (f, s, n, e),
+            _ => break,
+        };
+        match source.read_at(at + 30, name_len) {
+            Ok(name) => names.push(name.into_owned()),
+            Err(_) => break,
+        }
+        // Sizes given after the data leave the next header unknown
+        if flags & 0x08 != 0 {
+            break;
+        }
+        at += (30 + name_len + extra_len) as u64 + size as u64;
+    }
+    names
+}
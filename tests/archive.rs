@@ -0,0 +1,59 @@
+#![cfg(feature = "archive")]
+
+mod archive {
+    use tree_magic_mini as tree_magic;
+
+    const ZIP: &[u8] = include_bytes!("application/zip-members");
+    const TAR: &[u8] = include_bytes!("application/x-tar-members");
+
+    /// ZIP tests
+    #[test]
+    fn zip_stored() {
+        assert_eq!(
+            tree_magic::from_archive_member(ZIP, "stored/cat.gif"),
+            Some("image/gif")
+        );
+    }
+    #[test]
+    fn zip_deflated() {
+        assert_eq!(
+            tree_magic::from_archive_member(ZIP, "pics/dog.png"),
+            Some("image/png")
+        );
+        assert_eq!(
+            tree_magic::from_archive_member(ZIP, "docs/readme.txt"),
+            Some("text/plain")
+        );
+    }
+    #[test]
+    fn zip_missing() {
+        assert_eq!(tree_magic::from_archive_member(ZIP, "pics/cat.png"), None);
+    }
+
+    /// TAR tests
+    #[test]
+    fn tar_plain() {
+        assert_eq!(
+            tree_magic::from_archive_member(TAR, "./readme.txt"),
+            Some("text/plain")
+        );
+    }
+    #[test]
+    fn tar_long_name() {
+        let name = format!("pics/{}/cat.gif", "very_long_directory_name_".repeat(5));
+        assert_eq!(
+            tree_magic::from_archive_member(TAR, &name),
+            Some("image/gif")
+        );
+    }
+    #[test]
+    fn tar_missing() {
+        assert_eq!(tree_magic::from_archive_member(TAR, "cat.gif"), None);
+    }
+
+    #[test]
+    fn not_an_archive() {
+        let gif: &[u8] = include_bytes!("image/gif");
+        assert_eq!(tree_magic::from_archive_member(gif, "cat.gif"), None);
+    }
+}
@@ -0,0 +1,116 @@
+#![cfg(all(unix, feature = "std"))]
+
+mod cache {
+    use std::fs::{self, File};
+    use std::path::PathBuf;
+    use tree_magic::{DetectionCache, TypeDatabase};
+    use tree_magic_mini as tree_magic;
+
+    /// A fresh directory for one test
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("tree_magic_cache_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn unchanged_file_not_read() {
+        let db = TypeDatabase::builder().build();
+        let cache = DetectionCache::new();
+        let path = scratch("unchanged").join("file");
+        fs::write(&path, b"GIF89a\x01\x00\x01\x00").unwrap();
+        assert_eq!(cache.detect_filepath(&db, &path), Some("image/gif"));
+
+        // Same size and modification time, so the old type stands
+        let mtime = fs::metadata(&path).unwrap().modified().unwrap();
+        fs::write(&path, b"%PDF-1.4\n\n").unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+        assert_eq!(cache.detect_filepath(&db, &path), Some("image/gif"));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn changed_file_read_again() {
+        let db = TypeDatabase::builder().build();
+        let cache = DetectionCache::new();
+        let path = scratch("changed").join("file");
+        fs::write(&path, b"GIF89a\x01\x00\x01\x00").unwrap();
+        assert_eq!(cache.detect_filepath(&db, &path), Some("image/gif"));
+
+        fs::write(&path, b"%PDF-1.4\n").unwrap();
+        assert_eq!(cache.detect_filepath(&db, &path), Some("application/pdf"));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn directories_not_kept() {
+        let db = TypeDatabase::builder().build();
+        let cache = DetectionCache::new();
+        let dir = scratch("directory");
+        assert_eq!(cache.detect_filepath(&db, &dir), Some("inode/directory"));
+        assert!(cache.is_empty());
+        assert_eq!(cache.detect_filepath(&db, &dir.join("missing")), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn type_unknown_to_database() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[90:application/x-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .build();
+        let cache = DetectionCache::new();
+        let path = scratch("unknown").join("file");
+        fs::write(&path, b"FOObar").unwrap();
+        assert_eq!(cache.detect_filepath(&db, &path), Some("application/x-foo"));
+
+        let other = TypeDatabase::builder().build();
+        assert_eq!(cache.detect_filepath(&other, &path), Some("text/plain"));
+    }
+
+    /// Persistence tests
+    #[test]
+    fn save_and_load() {
+        let db = TypeDatabase::builder().build();
+        let cache = DetectionCache::new();
+        for name in ["image/gif", "application/zip", "text/plain"] {
+            let path = PathBuf::from("tests").join(name);
+            cache.detect_filepath(&db, &path).unwrap();
+        }
+        let mut saved = Vec::new();
+        cache.save(&mut saved).unwrap();
+
+        let loaded = DetectionCache::load(&saved[..]).unwrap();
+        assert_eq!(loaded.len(), 3);
+        let mut again = Vec::new();
+        loaded.save(&mut again).unwrap();
+        let mut saved: Vec<&[u8]> = saved.split(|&c| c == b'\n').collect();
+        let mut again: Vec<&[u8]> = again.split(|&c| c == b'\n').collect();
+        saved.sort();
+        again.sort();
+        assert_eq!(saved, again);
+    }
+
+    #[test]
+    fn load_invalid() {
+        for text in [
+            "",
+            "something else\n",
+            "tree_magic_mini cache 1\n1 2 3 4 5\n",
+            "tree_magic_mini cache 1\n1 2 3 4 x image/gif\n",
+            "tree_magic_mini cache 1\n1 2 3 4 5 image/gif extra\n",
+        ] {
+            let err = DetectionCache::load(text.as_bytes()).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData, "{:?}", text);
+        }
+        let empty = DetectionCache::load(&b"tree_magic_mini cache 1\n"[..]).unwrap();
+        assert!(empty.is_empty());
+    }
+}
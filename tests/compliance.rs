@@ -0,0 +1,18 @@
+mod compliance {
+    use tree_magic::{compliance, SpecFeature};
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn supported_features() {
+        let features = compliance();
+        assert!(features.contains(&SpecFeature::Magic));
+        assert!(features.contains(&SpecFeature::Aliases));
+        assert!(features.contains(&SpecFeature::Subclassing));
+        // Both read files
+        let std = cfg!(feature = "std");
+        assert_eq!(features.contains(&SpecFeature::Globs), std);
+        assert_eq!(features.contains(&SpecFeature::TreeMagic), std);
+        assert!(!features.contains(&SpecFeature::XmlRoot));
+        assert!(!features.contains(&SpecFeature::Icons));
+    }
+}
@@ -0,0 +1,596 @@
+mod database {
+    use tree_magic::{DatabaseError, TypeDatabase};
+    use tree_magic_mini as tree_magic;
+
+    const ZIP: &[u8] = include_bytes!("application/zip");
+
+    /// Custom magic tests
+    #[test]
+    fn custom_type() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .build();
+        assert_eq!(db.from_u8(b"FOObar"), "application/x-foo");
+        assert!(db.match_u8("application/x-foo", b"FOObar"));
+        assert!(!db.match_u8("application/x-foo", b"BARfoo"));
+    }
+    #[test]
+    fn custom_type_loose_text_rule() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:text/x-foo]\n>0=\0\x01\n\n")
+            .unwrap()
+            .build();
+        assert_eq!(db.from_u8(b"\nfoo"), "text/x-foo");
+        // Blank text is plain text, though the rule matches
+        assert_eq!(db.from_u8(b"\n\n"), "text/plain");
+        assert_eq!(db.from_u8(b"\xff\xfe\n\x00"), "text/plain");
+    }
+    #[test]
+    fn custom_type_runtime_name() {
+        let dbs: Vec<TypeDatabase> = (0..3)
+            .map(|i| {
+                let magic = format!("MIME-Magic\0\n[50:application/x-foo{}]\n>0=\0\x03FOO\n", i);
+                TypeDatabase::builder()
+                    .magic(magic.as_bytes())
+                    .unwrap()
+                    .build()
+            })
+            .collect();
+        for (i, db) in dbs.iter().enumerate() {
+            assert_eq!(db.from_u8(b"FOObar"), format!("application/x-foo{}", i));
+        }
+    }
+    #[test]
+    fn custom_type_owned() {
+        let owned: Vec<String> = (0..3)
+            .map(|i| {
+                let magic = format!("MIME-Magic\0\n[50:application/x-foo{}]\n>0=\0\x03FOO\n", i);
+                let db = TypeDatabase::builder()
+                    .magic(magic.as_bytes())
+                    .unwrap()
+                    .build();
+                assert_eq!(
+                    db.from_source_owned(&&b"FOObar"[..]).as_deref(),
+                    Some(db.from_u8(b"FOObar"))
+                );
+                db.from_u8_owned(b"FOObar")
+            })
+            .collect();
+        assert_eq!(
+            owned,
+            [
+                "application/x-foo0",
+                "application/x-foo1",
+                "application/x-foo2"
+            ]
+        );
+    }
+    #[test]
+    fn custom_negated_subrule() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n!1>3=\0\x01!\n")
+            .unwrap()
+            .build();
+        assert!(db.match_u8("application/x-foo", b"FOObar"));
+        assert!(!db.match_u8("application/x-foo", b"FOO!bar"));
+    }
+    #[test]
+    fn custom_negated_toplevel() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/zip]\n!>30=\0\x08mimetype\n")
+            .unwrap()
+            .build();
+        assert!(db.match_u8("application/zip", ZIP));
+
+        let mut odf = ZIP.to_vec();
+        odf[30..38].copy_from_slice(b"mimetype");
+        assert!(!db.match_u8("application/zip", &odf));
+        assert!(tree_magic::match_u8("application/zip", &odf));
+    }
+    #[test]
+    fn custom_invalid() {
+        assert!(TypeDatabase::builder().magic(b"not magic").is_err());
+    }
+
+    /// Precedence tests
+    const GIF: &[u8] = include_bytes!("image/gif");
+    const FOO_GIF: &[u8] = b"MIME-Magic\0\n[50:image/gif]\n>0=\0\x03FOO\n";
+
+    #[test]
+    fn merge_keeps_system_rules() {
+        let db = TypeDatabase::builder().magic(FOO_GIF).unwrap().build();
+        assert!(db.match_u8("image/gif", GIF));
+        assert!(db.match_u8("image/gif", b"FOObar"));
+    }
+    #[test]
+    fn override_replaces_system_rules() {
+        let db = TypeDatabase::builder()
+            .override_magic(FOO_GIF)
+            .unwrap()
+            .build();
+        assert!(!db.match_u8("image/gif", GIF));
+        assert!(db.match_u8("image/gif", b"FOObar"));
+        // Types the override doesn't define are untouched
+        assert!(db.match_u8("application/zip", ZIP));
+    }
+    #[test]
+    fn override_replaces_earlier_layers() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .override_magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03BAR\n")
+            .unwrap()
+            .build();
+        assert!(!db.match_u8("application/x-foo", b"FOObar"));
+        assert!(db.match_u8("application/x-foo", b"BARfoo"));
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn mime_dir() {
+        let dir = std::env::temp_dir().join(format!("tree_magic_mime_dir_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Without a magic file, nothing changes
+        let db = TypeDatabase::builder().mime_dir(&dir).unwrap().build();
+        assert!(db.match_u8("image/gif", GIF));
+
+        std::fs::write(dir.join("magic"), FOO_GIF).unwrap();
+        let db = TypeDatabase::builder().mime_dir(&dir).unwrap().build();
+        assert!(!db.match_u8("image/gif", GIF));
+        assert!(db.match_u8("image/gif", b"FOObar"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Exclusion tests
+    #[test]
+    fn exclude() {
+        let db = TypeDatabase::builder()
+            .exclude("application/zip", 0, b"PK\x03\x04")
+            .build();
+        assert!(!db.match_u8("application/zip", ZIP));
+        assert_ne!(db.from_u8(ZIP), "application/zip");
+    }
+    #[test]
+    fn exclude_alias() {
+        let db = TypeDatabase::builder()
+            .exclude("application/x-zip-compressed", 0, b"PK\x03\x04")
+            .build();
+        assert!(!db.match_u8("application/zip", ZIP));
+    }
+    /// Disabling tests
+    #[test]
+    fn disable_type() {
+        let gif: &[u8] = include_bytes!("image/gif");
+        let db = TypeDatabase::builder().disable_type("image/gif").build();
+        assert_eq!(db.from_u8(gif), "application/octet-stream");
+        assert!(!db.match_u8("image/gif", gif));
+        assert_eq!(db.from_u8(ZIP), "application/zip");
+    }
+    #[test]
+    fn disable_type_keeps_subclasses() {
+        let dng: &[u8] = include_bytes!("image/x-adobe-dng");
+        let db = TypeDatabase::builder().disable_type("image/tiff").build();
+        assert!(!db.match_u8("image/tiff", include_bytes!("image/tiff")));
+        assert_eq!(db.from_u8(dng), "image/x-adobe-dng");
+    }
+    #[test]
+    fn disable_rule() {
+        let magic =
+            b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n1>3=\0\x01!\n>0=\0\x03BAR\n";
+        let db = TypeDatabase::builder()
+            .magic(magic)
+            .unwrap()
+            .disable_rule("application/x-foo", 0)
+            .build();
+        assert!(!db.match_u8("application/x-foo", b"FOO!"));
+        assert!(db.match_u8("application/x-foo", b"BAR"));
+
+        let db = TypeDatabase::builder()
+            .magic(magic)
+            .unwrap()
+            .disable_rule("application/x-foo", 1)
+            .disable_rule("application/x-foo", 5)
+            .build();
+        assert!(db.match_u8("application/x-foo", b"FOO!"));
+        assert!(!db.match_u8("application/x-foo", b"BAR"));
+    }
+
+    /// Alias tests
+    #[test]
+    fn alias() {
+        let db = TypeDatabase::builder()
+            .alias("application/x-mycorp-doc", "application/zip")
+            .build();
+        assert!(db.match_u8("application/x-mycorp-doc", ZIP));
+        assert!(db.match_u8("Application/X-MyCorp-Doc", ZIP));
+        assert_eq!(db.from_u8(ZIP), "application/zip");
+        assert!(!tree_magic::match_u8("application/x-mycorp-doc", ZIP));
+    }
+    #[test]
+    fn alias_of_alias() {
+        let db = TypeDatabase::builder()
+            .alias("application/x-mycorp-doc", "application/x-zip-compressed")
+            .build();
+        assert!(db.match_u8("application/x-mycorp-doc", ZIP));
+    }
+    #[test]
+    fn alias_known_type_ignored() {
+        let db = TypeDatabase::builder()
+            .alias("image/gif", "application/zip")
+            .build();
+        assert!(!db.match_u8("image/gif", ZIP));
+    }
+    #[test]
+    fn alias_custom_magic() {
+        let db = TypeDatabase::builder()
+            .alias("application/x-legacy-foo", "application/x-foo")
+            .magic(b"MIME-Magic\0\n[50:application/x-legacy-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .build();
+        assert_eq!(db.from_u8(b"FOObar"), "application/x-foo");
+        assert!(db.match_u8("application/x-legacy-foo", b"FOObar"));
+    }
+
+    #[test]
+    fn exclude_other_checker() {
+        let db = TypeDatabase::builder()
+            .exclude("application/msword", 0, b"\xD0\xCF\x11\xE0")
+            .build();
+        assert!(!db.match_u8("application/msword", include_bytes!("application/msword")));
+        assert!(db.match_u8(
+            "application/x-ole-storage",
+            include_bytes!("application/msword")
+        ));
+    }
+    #[test]
+    fn exclude_mismatch() {
+        let db = TypeDatabase::builder()
+            .exclude("application/zip", 4, b"PLAIN")
+            .build();
+        assert!(db.match_u8("application/zip", ZIP));
+    }
+
+    /// Retain tests
+    #[test]
+    fn retain_types() {
+        let db = TypeDatabase::builder()
+            .retain_types(&["image/*", "application/pdf"])
+            .build();
+        assert_eq!(db.from_u8(include_bytes!("image/gif")), "image/gif");
+        let sevenzip = include_bytes!("application/x-7z-compressed");
+        assert_eq!(db.from_u8(sevenzip), "application/octet-stream");
+        assert!(db.match_u8("image/png", include_bytes!("image/png")));
+        assert!(!db.match_u8("application/x-7z-compressed", sevenzip));
+
+        let system = TypeDatabase::default();
+        let diff = system.diff(&db);
+        assert!(diff.only_in_other.is_empty());
+        assert!(diff
+            .only_in_self
+            .types
+            .contains(&"application/x-7z-compressed"));
+        assert!(!diff.only_in_self.types.contains(&"image/gif"));
+    }
+    #[test]
+    fn retain_types_parents() {
+        let db = TypeDatabase::builder()
+            .retain_types(&["application/vnd.oasis.opendocument.text"])
+            .build();
+        assert!(db.match_u8("application/zip", ZIP));
+        assert_eq!(
+            db.from_u8(include_bytes!("image/gif")),
+            "application/octet-stream"
+        );
+    }
+
+    /// Memory limit tests
+    #[test]
+    fn memory_footprint_grows_with_checks() {
+        let db = TypeDatabase::default();
+        let before = db.memory_footprint();
+        assert!(before > 0);
+        assert_eq!(db.from_u8(include_bytes!("image/gif")), "image/gif");
+        assert!(db.memory_footprint() > before);
+    }
+    #[test]
+    fn memory_limit_keeps_results() {
+        let db = TypeDatabase::builder().memory_limit(0).build();
+        let before = db.memory_footprint();
+        assert_eq!(db.from_u8(include_bytes!("image/gif")), "image/gif");
+        assert_eq!(db.from_u8(ZIP), "application/zip");
+        assert!(db.match_u8("image/png", include_bytes!("image/png")));
+        let png = db.matcher("image/png").unwrap();
+        assert!(png.matches_u8(include_bytes!("image/png")));
+        assert!(!png.matches_u8(ZIP));
+        // Nothing fits, so no rule trees are kept
+        assert_eq!(db.memory_footprint(), before);
+        assert_eq!(db.validate(), TypeDatabase::default().validate());
+    }
+    #[test]
+    fn memory_limit_caps_footprint() {
+        let limit = TypeDatabase::default().memory_footprint() + 4096;
+        let db = TypeDatabase::builder().memory_limit(limit).build();
+        db.from_u8(include_bytes!("image/gif"));
+        db.from_u8(ZIP);
+        assert!(db.memory_footprint() <= limit);
+    }
+
+    /// Strict PDF tests
+    #[test]
+    fn strict_pdf() {
+        let db = TypeDatabase::builder().strict_pdf(true).build();
+        assert!(db.match_u8("application/pdf", b"%PDF-1.7\n"));
+        assert!(!db.match_u8("application/pdf", b"\r\n%PDF-1.7\n"));
+        assert_ne!(db.from_u8(b"\x00\x00junk\r\n%PDF-1.7\n"), "application/pdf");
+    }
+
+    /// Text heuristics tests
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn text_heuristics_off() {
+        let db = TypeDatabase::builder().text_heuristics(false).build();
+        assert_eq!(db.from_u8(b"name: demo\nversion: 1\n"), "text/plain");
+        assert_eq!(db.from_u8(b"a = 1\nb = true\n"), "text/plain");
+        assert_eq!(db.from_u8(b"a,b\n1,2\n3,4\n"), "text/plain");
+        // The directive is magic, not a guess
+        assert!(db.match_u8("application/yaml", b"%YAML 1.2\n---\nname: demo\n"));
+        assert_eq!(db.from_u8(include_bytes!("model/obj")), "text/plain");
+    }
+
+    /// Source heuristics tests
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn source_heuristics() {
+        let db = TypeDatabase::builder().source_heuristics(true).build();
+        for (mime, input) in [
+            ("text/x-python", &include_bytes!("text/x-python")[..]),
+            ("text/rust", include_bytes!("text/rust")),
+            ("text/x-go", include_bytes!("text/x-go")),
+            ("text/x-java", include_bytes!("text/x-java")),
+            ("text/x-c++src", include_bytes!("text/x-c++src")),
+        ] {
+            assert_eq!(db.from_u8(input), mime);
+        }
+        // Subclasses of C, as in the shared MIME-info database
+        assert!(db.match_u8("text/x-csrc", include_bytes!("text/x-java")));
+        assert!(!db.match_u8("text/x-csrc", include_bytes!("text/rust")));
+    }
+    #[test]
+    fn source_heuristics_off() {
+        let db = TypeDatabase::default();
+        assert_eq!(db.from_u8(include_bytes!("text/x-python")), "text/plain");
+        assert!(!db.match_u8("text/rust", include_bytes!("text/rust")));
+    }
+    #[test]
+    fn source_heuristics_prose() {
+        let db = TypeDatabase::builder().source_heuristics(true).build();
+        let input = b"Notes from the meeting\n\nWe agreed to package the release,\nand to import the old data first.\n";
+        assert_eq!(db.from_u8(input), "text/plain");
+    }
+
+    /// Delimited text tests
+    #[test]
+    fn delimited_heuristics() {
+        let db = TypeDatabase::builder().delimited_heuristics(true).build();
+        assert_eq!(db.from_u8(include_bytes!("text/csv")), "text/csv");
+        assert_eq!(
+            db.from_u8(include_bytes!("text/tab-separated-values")),
+            "text/tab-separated-values"
+        );
+        assert_eq!(
+            db.from_u8(b"name,qty,price\napple,3,0.50\npear,5,0.80\nplum,8,0.30\n"),
+            "text/csv"
+        );
+        // Two fields take more lines
+        let pairs = b"key,value\na,1\nb,2\nc,3\nd,4\ne,5\nf,6\ng,7\n";
+        assert_eq!(db.from_u8(pairs), "text/csv");
+        assert_eq!(db.from_u8(&pairs[..22]), "text/plain");
+    }
+    #[test]
+    fn delimited_heuristics_off() {
+        let db = TypeDatabase::default();
+        assert_eq!(db.from_u8(include_bytes!("text/csv")), "text/plain");
+        assert!(!db.match_u8("text/csv", include_bytes!("text/csv")));
+    }
+    #[test]
+    fn delimited_heuristics_prose() {
+        let db = TypeDatabase::builder().delimited_heuristics(true).build();
+        for input in [
+            &b"Dear John,\nThanks for lunch, it was great.\nSee you soon, Mary\n"[..],
+            b"Hi Bob,\nThanks, really.\nSee you soon, Mary\nPS: the keys, too\n",
+            b"Buy milk; eggs\nCall mum; dad\nPay rent; bills\n",
+            b"red | green\nblue | yellow\nblack | white\n",
+            // Too few lines, and then too few commas on one
+            b"Hello, world\nFoo, bar\n",
+            b"x,y\n1,2\n3\n4,5\n",
+            // Long clauses rather than column names
+            b"We met at noon, as planned\nThe plan was good, mostly\nIt rained all day, sadly\nWe left early, then\n",
+        ] {
+            assert_eq!(db.from_u8(input), "text/plain");
+        }
+    }
+
+    /// Game format tests
+    #[test]
+    fn game_formats() {
+        let db = TypeDatabase::builder().game_formats(true).build();
+        for (mime, input) in [
+            (
+                "application/x-nes-rom",
+                &include_bytes!("application/x-nes-rom")[..],
+            ),
+            (
+                "application/vnd.nintendo.snes.rom",
+                include_bytes!("application/vnd.nintendo.snes.rom"),
+            ),
+            (
+                "application/x-gameboy-rom",
+                include_bytes!("application/x-gameboy-rom"),
+            ),
+            (
+                "application/x-gameboy-color-rom",
+                include_bytes!("application/x-gameboy-color-rom"),
+            ),
+            (
+                "application/x-gba-rom",
+                include_bytes!("application/x-gba-rom"),
+            ),
+            (
+                "application/x-playstation-rom",
+                include_bytes!("application/x-playstation-rom"),
+            ),
+            (
+                "application/x-unity3d-bundle",
+                include_bytes!("application/x-unity3d-bundle"),
+            ),
+            (
+                "application/x-unreal-pak",
+                include_bytes!("application/x-unreal-pak"),
+            ),
+        ] {
+            assert_eq!(db.from_u8(input), mime);
+        }
+        // A copier header in front moves the SNES header along with the rest
+        let mut snes = vec![0; 512];
+        snes.extend_from_slice(include_bytes!("application/vnd.nintendo.snes.rom"));
+        assert!(db.match_u8("application/x-snes-rom", &snes));
+        assert!(!db.match_u8("application/x-snes-rom", &snes[1..]));
+    }
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn game_formats_off() {
+        let db = TypeDatabase::default();
+        assert!(!db.match_u8(
+            "application/x-nes-rom",
+            include_bytes!("application/x-nes-rom")
+        ));
+        assert_eq!(
+            db.from_u8(include_bytes!("application/x-playstation-rom")),
+            "application/x-cd-image"
+        );
+        // Magic rules of the database still apply
+        assert_eq!(
+            db.from_u8(include_bytes!("application/x-gameboy-rom")),
+            "application/x-gameboy-rom"
+        );
+    }
+    #[test]
+    fn game_formats_checksums() {
+        let db = TypeDatabase::builder().game_formats(true).build();
+        let mut gba = include_bytes!("application/x-gba-rom").to_vec();
+        gba[0xa0] ^= 1;
+        assert!(!db.match_u8("application/x-gba-rom", &gba));
+        // A Game Boy Color game isn't taken for an original Game Boy one
+        assert!(!db.match_u8(
+            "application/x-gameboy-color-rom",
+            include_bytes!("application/x-gameboy-rom")
+        ));
+    }
+
+    /// Needed length tests
+    #[test]
+    fn needed_len() {
+        let db = TypeDatabase::default();
+        let gif: &[u8] = include_bytes!("image/gif");
+        let len = db.needed_len("image/gif").unwrap();
+        assert!(db.match_u8("image/gif", &gif[..len.min(gif.len())]));
+        assert_eq!(db.needed_len("IMAGE/GIF"), Some(len));
+        assert_eq!(db.needed_len("text/plain"), Some(2048));
+        assert_eq!(db.needed_len("application/msword"), None);
+        // The PDF header may come after up to 1024 bytes of junk
+        assert_eq!(db.needed_len("application/pdf"), Some(1032));
+        assert_eq!(db.needed_len("application/x-unknown"), None);
+    }
+    #[test]
+    fn needed_len_exclusion() {
+        let db = TypeDatabase::builder()
+            .exclude("application/x-tar", 600, b"PLAIN")
+            .build();
+        assert_eq!(db.needed_len("application/x-tar"), Some(605));
+    }
+
+    /// Diff tests
+    #[test]
+    fn diff_same() {
+        let a = TypeDatabase::default();
+        let b = TypeDatabase::default();
+        assert!(a.diff(&b).is_empty());
+    }
+    #[test]
+    fn diff_rules() {
+        let a = TypeDatabase::default();
+        let b = TypeDatabase::builder()
+            .exclude("application/zip", 4, b"PLAIN\x00")
+            .build();
+
+        let diff = a.diff(&b);
+        assert!(diff.only_in_self.is_empty());
+        assert!(diff.only_in_other.types.is_empty());
+        assert_eq!(
+            diff.only_in_other.rules,
+            vec![("application/zip", "!>4=PLAIN\\x00".to_string())]
+        );
+
+        let diff = b.diff(&a);
+        assert!(diff.only_in_other.is_empty());
+        assert_eq!(diff.only_in_self.rules.len(), 1);
+    }
+
+    /// Cycle tests
+    #[test]
+    fn subclass_cycle_broken() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .magic(b"MIME-Magic\0\n[50:application/x-bar]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .subclass("application/x-foo", "application/octet-stream")
+            .subclass("application/x-bar", "application/x-foo")
+            .subclass("application/x-foo", "application/x-bar")
+            .build();
+        assert_eq!(db.from_u8(b"FOObar"), "application/x-bar");
+        assert!(db.warnings().contains(&DatabaseError::SubclassCycle {
+            parent: "application/x-bar".to_string(),
+            child: "application/x-foo".to_string(),
+        }));
+    }
+    #[test]
+    fn subclass_cycle_unreachable() {
+        // Neither type has another parent, so both end up under the root
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .magic(b"MIME-Magic\0\n[50:application/x-bar]\n>0=\0\x03BAR\n")
+            .unwrap()
+            .subclass("application/x-bar", "application/x-foo")
+            .subclass("application/x-foo", "application/x-bar")
+            .build();
+        let cycles = db
+            .warnings()
+            .iter()
+            .filter(|w| matches!(w, DatabaseError::SubclassCycle { .. }));
+        assert_eq!(cycles.count(), 1);
+        assert_eq!(db.from_u8(b"BARfoo"), "application/x-bar");
+    }
+    #[test]
+    fn alias_cycle_warned() {
+        let db = TypeDatabase::builder()
+            .alias("application/x-foo", "application/x-bar")
+            .alias("application/x-bar", "application/x-foo")
+            .build();
+        assert!(db
+            .warnings()
+            .iter()
+            .any(|w| matches!(w, DatabaseError::AliasCycle(a) if a.contains(&"application/x-bar".to_string()))));
+    }
+    #[test]
+    fn system_subclasses_acyclic() {
+        let db = TypeDatabase::default();
+        assert!(!db
+            .warnings()
+            .iter()
+            .any(|w| matches!(w, DatabaseError::SubclassCycle { .. })));
+    }
+}
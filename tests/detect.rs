@@ -0,0 +1,208 @@
+mod detect {
+    #[cfg(feature = "std")]
+    use std::path::Path;
+    #[cfg(feature = "std")]
+    use std::time::Duration;
+    use tree_magic::{DetectOptions, TypeDatabase};
+    use tree_magic_mini as tree_magic;
+
+    const BINARY: &[u8] = b"\x00\x01\x02\x03";
+
+    #[test]
+    fn default_options() {
+        let opts = DetectOptions::new();
+        assert_eq!(
+            tree_magic::detect_u8(BINARY, &opts),
+            Some("application/octet-stream")
+        );
+        assert_eq!(
+            tree_magic::detect_u8(include_bytes!("text/plain"), &opts),
+            Some("text/plain")
+        );
+    }
+
+    /// Fallback tests
+    #[test]
+    fn binary_fallback_none() {
+        let opts = DetectOptions::new().binary_fallback(None);
+        assert_eq!(tree_magic::detect_u8(BINARY, &opts), None);
+        assert_eq!(
+            tree_magic::detect_u8(include_bytes!("text/plain"), &opts),
+            Some("text/plain")
+        );
+    }
+    #[test]
+    fn binary_fallback_custom() {
+        let opts = DetectOptions::new().binary_fallback(Some("application/x-unknown"));
+        assert_eq!(
+            tree_magic::detect_u8(BINARY, &opts),
+            Some("application/x-unknown")
+        );
+    }
+    #[test]
+    fn text_fallback_none() {
+        let opts = DetectOptions::new().text_fallback(None);
+        assert_eq!(
+            tree_magic::detect_u8(include_bytes!("text/plain"), &opts),
+            None
+        );
+        assert_eq!(
+            tree_magic::detect_u8(BINARY, &opts),
+            Some("application/octet-stream")
+        );
+    }
+    #[test]
+    fn fallback_keeps_specific_types() {
+        let opts = DetectOptions::new()
+            .binary_fallback(None)
+            .text_fallback(None);
+        assert_eq!(
+            tree_magic::detect_u8(include_bytes!("image/gif"), &opts),
+            Some("image/gif")
+        );
+        #[cfg(feature = "std")]
+        assert_eq!(
+            tree_magic::detect_filepath(Path::new("tests/application/zip"), &opts),
+            Some("application/zip")
+        );
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn fallback_filepath() {
+        let opts = DetectOptions::new().text_fallback(None);
+        assert_eq!(
+            tree_magic::detect_filepath(Path::new("tests/text/plain"), &opts),
+            None
+        );
+    }
+
+    #[test]
+    fn empty_fallback() {
+        let opts = DetectOptions::new().empty_fallback(None);
+        assert_eq!(
+            tree_magic::detect_u8(b"", &opts),
+            Some("application/x-zerosize")
+        );
+
+        let db = TypeDatabase::builder().retain_types(&["image/*"]).build();
+        assert_eq!(db.detect(&&b""[..], &opts), None);
+        assert_eq!(
+            db.detect(&&b""[..], &DetectOptions::new()),
+            Some("text/plain")
+        );
+        let opts = DetectOptions::new().empty_fallback(Some("application/octet-stream"));
+        assert_eq!(
+            db.detect(&&b""[..], &opts),
+            Some("application/octet-stream")
+        );
+    }
+
+    /// Limit tests
+    #[test]
+    fn max_depth() {
+        let gif: &[u8] = include_bytes!("image/gif");
+        let opts = DetectOptions::new().max_depth(2);
+        assert_eq!(
+            tree_magic::detect_u8(gif, &opts),
+            Some("application/octet-stream")
+        );
+        let opts = DetectOptions::new().max_depth(3);
+        assert_eq!(tree_magic::detect_u8(gif, &opts), Some("image/gif"));
+        let opts = DetectOptions::new().max_depth(0);
+        assert_eq!(tree_magic::detect_u8(gif, &opts), None);
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn time_budget() {
+        let gif: &[u8] = include_bytes!("image/gif");
+        let opts = DetectOptions::new().time_budget(Duration::from_secs(60));
+        assert_eq!(tree_magic::detect_u8(gif, &opts), Some("image/gif"));
+        let opts = DetectOptions::new().time_budget(Duration::ZERO);
+        assert_eq!(tree_magic::detect_u8(gif, &opts), None);
+    }
+
+    /// Counts the reads made of a byte stream
+    struct Reads<'a> {
+        bytes: &'a [u8],
+        reads: std::cell::Cell<usize>,
+        read: std::cell::Cell<usize>,
+    }
+    impl tree_magic::DataSource for Reads<'_> {
+        fn read_at(
+            &self,
+            offset: u64,
+            len: usize,
+        ) -> Result<std::borrow::Cow<'_, [u8]>, tree_magic::ReadError> {
+            let out = self.bytes.read_at(offset, len)?;
+            self.reads.set(self.reads.get() + 1);
+            self.read.set(self.read.get() + out.len());
+            Ok(out)
+        }
+    }
+    fn reads(bytes: &[u8]) -> Reads<'_> {
+        Reads {
+            bytes,
+            reads: Default::default(),
+            read: Default::default(),
+        }
+    }
+    #[test]
+    fn max_io_bytes() {
+        let gif: &[u8] = include_bytes!("image/gif");
+        let db = TypeDatabase::default();
+
+        let source = reads(gif);
+        let opts = DetectOptions::new().max_io_bytes(1 << 20);
+        assert_eq!(db.detect(&source, &opts), Some("image/gif"));
+
+        for limit in [0, 16, 256, 4096] {
+            let source = reads(gif);
+            let opts = DetectOptions::new().max_io_bytes(limit);
+            assert!(db.detect(&source, &opts).is_some());
+            assert!(source.read.get() <= limit);
+        }
+    }
+    #[test]
+    fn max_reads() {
+        let tar: &[u8] = include_bytes!("application/x-tar");
+        let db = TypeDatabase::default();
+        for limit in [0, 1, 5] {
+            let source = reads(tar);
+            let opts = DetectOptions::new().max_reads(limit);
+            assert!(db.detect(&source, &opts).is_some());
+            assert!(source.reads.get() <= limit);
+        }
+        let opts = DetectOptions::new().max_reads(1000);
+        assert_eq!(db.detect(&reads(tar), &opts), Some("application/x-tar"));
+    }
+
+    #[test]
+    fn family_preference() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .magic(b"MIME-Magic\0\n[50:image/x-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .magic(b"MIME-Magic\0\n[50:video/x-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .build();
+        let foo: &[u8] = b"FOO";
+
+        let opts = DetectOptions::new();
+        assert_eq!(db.detect(&foo, &opts), Some("application/x-foo"));
+        let opts = DetectOptions::new().family_preference(&["video", "image"]);
+        assert_eq!(db.detect(&foo, &opts), Some("video/x-foo"));
+        let opts = DetectOptions::new().family_preference(&["Image"]);
+        assert_eq!(db.detect(&foo, &opts), Some("image/x-foo"));
+        // Preferring a media type nothing matches changes nothing
+        let opts = DetectOptions::new().family_preference(&["audio"]);
+        assert_eq!(db.detect(&foo, &opts), Some("application/x-foo"));
+    }
+    #[test]
+    fn family_preference_others_checked() {
+        // Types of media types left out are checked after the preferred ones
+        let gif: &[u8] = include_bytes!("image/gif");
+        let opts = DetectOptions::new().family_preference(&["text", "application"]);
+        assert_eq!(tree_magic::detect_u8(gif, &opts), Some("image/gif"));
+    }
+}
@@ -0,0 +1,162 @@
+#![cfg(feature = "std")]
+
+mod detect_and_hash {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::Hasher;
+    use std::io::{self, Read, Write};
+    use tree_magic::detect_and_hash;
+    use tree_magic_mini as tree_magic;
+
+    /// A hasher of the standard library as a writer
+    #[derive(Default)]
+    struct HashWriter(DefaultHasher);
+
+    impl Write for HashWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.write(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A reader giving out a few bytes at a time, and being interrupted in
+    /// between
+    struct Trickle<'a> {
+        data: &'a [u8],
+        interrupt: bool,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.interrupt = !self.interrupt;
+            if self.interrupt {
+                return Err(io::ErrorKind::Interrupted.into());
+            }
+            let n = buf.len().min(self.data.len()).min(1000);
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn same_as_from_u8() {
+        for dir in [
+            "tests/application",
+            "tests/image",
+            "tests/text",
+            "tests/audio",
+        ] {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                let data = std::fs::read(&path).unwrap();
+                let mut copy = Vec::new();
+                let found = detect_and_hash(&data[..], &mut copy).unwrap();
+                assert_eq!(
+                    found,
+                    Some(tree_magic::from_u8(&data)),
+                    "{}",
+                    path.display()
+                );
+                assert_eq!(copy, data);
+            }
+        }
+    }
+
+    #[test]
+    fn hash_of_whole_stream() {
+        let mut data = include_bytes!("image/png").to_vec();
+        data.extend((0..1 << 20).map(|i| i as u8));
+
+        let mut hasher = HashWriter::default();
+        let found = detect_and_hash(&data[..], &mut hasher).unwrap();
+        assert_eq!(found, Some("image/png"));
+
+        let mut expected = DefaultHasher::new();
+        expected.write(&data);
+        assert_eq!(hasher.0.finish(), expected.finish());
+    }
+
+    #[test]
+    fn short_reads() {
+        let mut data = include_bytes!("image/gif").to_vec();
+        data.resize(300_000, 0);
+        let reader = Trickle {
+            data: &data,
+            interrupt: false,
+        };
+        let mut copy = Vec::new();
+        assert_eq!(
+            detect_and_hash(reader, &mut copy).unwrap(),
+            Some("image/gif")
+        );
+        assert_eq!(copy, data);
+    }
+
+    /// A ZIP archive of stored members
+    fn stored_zip(members: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut dir = Vec::new();
+        for (name, data) in members {
+            let offset = out.len() as u32;
+            out.extend_from_slice(b"PK\x03\x04\x0a\0\0\0\0\0\0\0\0\0\0\0");
+            out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&[0, 0]);
+            out.extend_from_slice(name.as_bytes());
+            out.extend_from_slice(data);
+
+            dir.extend_from_slice(b"PK\x01\x02\x14\0\x0a\0\0\0\0\0\0\0\0\0\0\0\0\0");
+            dir.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            dir.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            dir.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            dir.extend_from_slice(&[0; 12]);
+            dir.extend_from_slice(&offset.to_le_bytes());
+            dir.extend_from_slice(name.as_bytes());
+        }
+        let dir_offset = out.len() as u32;
+        out.extend_from_slice(&dir);
+        out.extend_from_slice(b"PK\x05\x06\0\0\0\0");
+        out.extend_from_slice(&(members.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(members.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(dir.len() as u32).to_le_bytes());
+        out.extend_from_slice(&dir_offset.to_le_bytes());
+        out.extend_from_slice(&[0, 0]);
+        out
+    }
+
+    #[test]
+    fn end_of_long_stream() {
+        // Members past the start kept are found in the central directory
+        let padding = vec![0; 1 << 20];
+        let data = stored_zip(&[("padding.bin", &padding), ("3D/3dmodel.model", b"<model/>")]);
+        let mut sink = io::sink();
+        let found = detect_and_hash(&data[..], &mut sink).unwrap();
+        assert_eq!(found, Some("model/3mf"));
+        assert_eq!(found, Some(tree_magic::from_u8(&data)));
+    }
+
+    #[test]
+    fn empty() {
+        let mut copy = Vec::new();
+        let found = detect_and_hash(io::empty(), &mut copy).unwrap();
+        assert_eq!(found, Some("application/x-zerosize"));
+        assert!(copy.is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+        }
+        let e = detect_and_hash(Failing, &mut Vec::new()).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::BrokenPipe);
+    }
+}
@@ -0,0 +1,372 @@
+mod detector {
+    #[cfg(feature = "std")]
+    use std::fs;
+    #[cfg(feature = "std")]
+    use std::path::{Path, PathBuf};
+    use tree_magic_mini::{Detector, Strategy};
+
+    const GIF: &[u8] = include_bytes!("image/gif");
+    const ZIP: &[u8] = include_bytes!("application/zip");
+
+    /// A fresh directory for one test
+    #[cfg(feature = "std")]
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "tree_magic_detector_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Glob tests
+    #[test]
+    fn glob_only() {
+        let detector = Detector::builder()
+            .content(false)
+            .globs("50:application/x-foo:*.foo")
+            .build();
+        assert_eq!(
+            detector.detect_u8(Some("a.foo"), GIF),
+            Some("application/x-foo")
+        );
+        assert_eq!(
+            detector.detect_u8(Some("A.FOO"), GIF),
+            Some("application/x-foo")
+        );
+        assert_eq!(detector.detect_u8(None, GIF), None);
+    }
+    #[test]
+    fn glob_case_sensitive() {
+        let detector = Detector::builder()
+            .globs("50:application/x-foo:*.Foo:cs")
+            .build();
+        assert_eq!(detector.from_filename("a.Foo"), vec!["application/x-foo"]);
+        assert!(detector.from_filename("a.foo").is_empty());
+    }
+    #[test]
+    fn glob_pattern() {
+        let detector = Detector::builder()
+            .globs("50:application/x-foo:foo[0-9]?.bin")
+            .build();
+        assert_eq!(
+            detector.from_filename("foo3a.bin"),
+            vec!["application/x-foo"]
+        );
+        assert!(detector.from_filename("fooa3.bin").is_empty());
+    }
+    #[test]
+    fn glob_weight() {
+        let detector = Detector::builder()
+            .globs("40:application/x-foo:*.foo\n60:application/x-bar:a.*")
+            .build();
+        assert_eq!(detector.from_filename("a.foo"), vec!["application/x-bar"]);
+    }
+    #[test]
+    fn glob_noglobs() {
+        let detector = Detector::builder()
+            .globs("50:image/gif:__NOGLOBS__")
+            .build();
+        assert!(!detector.from_filename("cat.gif").contains(&"image/gif"));
+    }
+    #[test]
+    fn glob_disabled() {
+        let detector = Detector::builder()
+            .glob(false)
+            .globs("50:application/x-foo:*.foo")
+            .build();
+        assert!(detector.from_filename("a.foo").is_empty());
+        assert_eq!(detector.detect_u8(Some("a.foo"), GIF), Some("image/gif"));
+    }
+
+    /// Strategy tests
+    #[test]
+    fn xdg_single_glob() {
+        let detector = Detector::builder()
+            .order(Strategy::XdgRecommended)
+            .globs("90:application/x-foo:cat.gif")
+            .build();
+        assert_eq!(
+            detector.detect_u8(Some("cat.gif"), GIF),
+            Some("application/x-foo")
+        );
+    }
+    #[test]
+    fn xdg_conflicting_globs() {
+        let detector = Detector::builder()
+            .globs("90:image/png:cat.pic\n90:image/gif:cat.pic")
+            .build();
+        assert_eq!(detector.detect_u8(Some("cat.pic"), GIF), Some("image/gif"));
+    }
+    #[test]
+    fn content_first() {
+        let detector = Detector::builder()
+            .order(Strategy::ContentFirst)
+            .globs("90:application/x-foo:cat.gif")
+            .build();
+        assert_eq!(detector.detect_u8(Some("cat.gif"), GIF), Some("image/gif"));
+        assert_eq!(
+            detector.detect_u8(Some("cat.gif"), b"\x00\x01"),
+            Some("application/x-foo")
+        );
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn content_first_subclass() {
+        let detector = Detector::builder().order(Strategy::ContentFirst).build();
+        assert_eq!(
+            detector.detect_u8(Some("letter.odt"), ZIP),
+            Some("application/vnd.oasis.opendocument.text")
+        );
+        assert_eq!(
+            detector.detect_u8(Some("letter.zip"), ZIP),
+            Some("application/zip")
+        );
+    }
+
+    /// Filepath tests
+    #[test]
+    #[cfg(feature = "std")]
+    fn filepath() {
+        let detector = Detector::builder().build();
+        assert_eq!(
+            detector.detect_filepath(Path::new("tests/image/gif")),
+            Some("image/gif")
+        );
+        assert_eq!(detector.detect_filepath(Path::new("tests/missing")), None);
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn directory() {
+        let dir = scratch("directory");
+        fs::create_dir(dir.join("DCIM")).unwrap();
+        fs::write(dir.join("DCIM").join("cat.gif"), GIF).unwrap();
+
+        let plain = Detector::builder().build();
+        assert_eq!(plain.detect_filepath(&dir), Some("inode/directory"));
+
+        let detector = Detector::builder().treemagic(true).build();
+        assert_eq!(detector.detect_filepath(&dir), Some("x-content/image-dcf"));
+        assert_eq!(
+            detector.detect_filepath(&dir.join("DCIM")),
+            Some("inode/directory")
+        );
+    }
+
+    /// Result hook tests
+    fn archive<'a>(bytes: &[u8], mime: &'a str) -> &'a str {
+        match mime {
+            "application/zip" if bytes.starts_with(b"PK") => "application/x-archive",
+            _ => mime,
+        }
+    }
+    fn internal<'a>(_: &[u8], mime: &'a str) -> &'a str {
+        match mime {
+            "application/x-archive" => "application/x-internal",
+            _ => mime,
+        }
+    }
+    #[test]
+    fn hooks_in_order() {
+        let detector = Detector::builder()
+            .on_result(archive)
+            .on_result(internal)
+            .build();
+        assert_eq!(
+            detector.detect_u8(None, ZIP),
+            Some("application/x-internal")
+        );
+        assert_eq!(detector.detect_u8(None, GIF), Some("image/gif"));
+
+        let reversed = Detector::builder()
+            .on_result(internal)
+            .on_result(archive)
+            .build();
+        assert_eq!(reversed.detect_u8(None, ZIP), Some("application/x-archive"));
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn hooks_filepath() {
+        let dir = scratch("hooks_filepath");
+        let path = dir.join("data.bin");
+        fs::write(&path, ZIP).unwrap();
+
+        let detector = Detector::builder().on_result(archive).build();
+        assert_eq!(
+            detector.detect_filepath(&path),
+            Some("application/x-archive")
+        );
+        assert_eq!(detector.detect_filepath(&dir), Some("inode/directory"));
+    }
+
+    /// GIO tests
+    #[test]
+    fn gio_single_glob() {
+        let detector = Detector::builder()
+            .globs("50:application/x-foo:*.zzfoo")
+            .build();
+        assert_eq!(
+            detector.guess_content_type(Some("dir/a.zzfoo"), Some(GIF)),
+            ("application/x-foo", false)
+        );
+        assert_eq!(
+            detector.guess_content_type(Some("a.zzfoo"), None),
+            ("application/x-foo", false)
+        );
+        // Only the last component is matched
+        assert_eq!(
+            detector.guess_content_type(Some("a.zzfoo/b"), Some(GIF)),
+            ("image/gif", false)
+        );
+    }
+    #[test]
+    fn gio_conflicting_globs() {
+        let detector = Detector::builder()
+            .globs("50:application/x-bar:*.zzbar\n50:image/gif:*.zzbar")
+            .build();
+        assert_eq!(
+            detector.guess_content_type(Some("a.zzbar"), Some(GIF)),
+            ("image/gif", false)
+        );
+        assert_eq!(
+            detector.guess_content_type(Some("a.zzbar"), Some(ZIP)),
+            ("application/x-bar", true)
+        );
+        assert_eq!(
+            detector.guess_content_type(Some("a.zzbar"), None),
+            ("application/x-bar", true)
+        );
+    }
+    #[test]
+    fn gio_decisive_magic() {
+        let globs = "50:application/x-aaa:*.zzq\n50:application/x-bbb:*.zzq";
+        // A priority of 80 or more outweighs conflicting globs
+        let detector = Detector::builder()
+            .database(foo_types(&[(80, "application/x-zzz")]))
+            .globs(globs)
+            .build();
+        assert_eq!(
+            detector.guess_content_type(Some("a.zzq"), Some(b"FOObar")),
+            ("application/x-zzz", false)
+        );
+        // Below that, the first glob is guessed
+        let detector = Detector::builder()
+            .database(foo_types(&[(79, "application/x-zzz")]))
+            .globs(globs)
+            .build();
+        assert_eq!(
+            detector.guess_content_type(Some("a.zzq"), Some(b"FOObar")),
+            ("application/x-aaa", true)
+        );
+    }
+    #[test]
+    fn gio_content_only() {
+        let detector = Detector::builder().build();
+        assert_eq!(
+            detector.guess_content_type(None, Some(GIF)),
+            ("image/gif", false)
+        );
+        assert_eq!(
+            detector.guess_content_type(Some("a.zzunknown"), Some(b"\x00\x01\x02\xff")),
+            ("application/octet-stream", true)
+        );
+        assert_eq!(
+            detector.guess_content_type(None, None),
+            ("application/octet-stream", true)
+        );
+        assert_eq!(
+            detector.guess_content_type(Some("photos/"), Some(GIF)),
+            ("inode/directory", false)
+        );
+    }
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn gio_desktop_entry() {
+        let detector = Detector::builder().build();
+        let entry: &[u8] = b"[Desktop Entry]\nType=Application\nExec=true\n";
+        assert_eq!(
+            detector.guess_content_type(None, Some(entry)),
+            ("application/x-desktop", false)
+        );
+        assert_eq!(
+            detector.guess_content_type(Some("notes.zzunknown"), Some(entry)),
+            ("text/plain", false)
+        );
+    }
+
+    /// Qt tests
+    fn foo_types(priorities: &[(u32, &str)]) -> tree_magic_mini::TypeDatabase {
+        let mut db = tree_magic_mini::TypeDatabase::builder();
+        for (priority, mime) in priorities {
+            let mut magic = format!("MIME-Magic\0\n[{}:{}]\n", priority, mime).into_bytes();
+            magic.extend_from_slice(b">0=\0\x03FOO\n");
+            db = db.magic(&magic).unwrap();
+        }
+        db.build()
+    }
+    #[test]
+    fn qt_magic_priority() {
+        let db = foo_types(&[(40, "application/x-aaa"), (80, "application/x-zzz")]);
+        let detector = Detector::builder()
+            .order(Strategy::QtCompatible)
+            .database(db)
+            .build();
+        assert_eq!(
+            detector.detect_u8(None, b"FOObar"),
+            Some("application/x-zzz")
+        );
+
+        let db = foo_types(&[(40, "application/x-aaa"), (80, "application/x-zzz")]);
+        let detector = Detector::builder().database(db).build();
+        assert_eq!(
+            detector.detect_u8(None, b"FOObar"),
+            Some("application/x-aaa")
+        );
+    }
+    #[test]
+    fn qt_magic_same_priority() {
+        let db = foo_types(&[(50, "application/x-bbb"), (50, "application/x-aaa")]);
+        let detector = Detector::builder()
+            .order(Strategy::QtCompatible)
+            .database(db)
+            .build();
+        assert_eq!(
+            detector.detect_u8(None, b"FOObar"),
+            Some("application/x-aaa")
+        );
+    }
+    #[test]
+    fn qt_conflicting_globs() {
+        let detector = Detector::builder()
+            .order(Strategy::QtCompatible)
+            .globs("50:image/gif:*.zzq\n50:application/x-bar:*.zzq")
+            .build();
+        assert_eq!(detector.detect_u8(Some("a.zzq"), GIF), Some("image/gif"));
+        // Settled by name when the content is no help
+        assert_eq!(
+            detector.detect_u8(Some("a.zzq"), ZIP),
+            Some("application/x-bar")
+        );
+    }
+    #[test]
+    fn qt_weaker_glob() {
+        let globs = "60:application/x-foo:*.zzw\n40:image/gif:*.zzw";
+        let detector = Detector::builder()
+            .order(Strategy::QtCompatible)
+            .globs(globs)
+            .build();
+        assert_eq!(detector.detect_u8(Some("a.zzw"), GIF), Some("image/gif"));
+        assert_eq!(
+            detector.detect_u8(Some("a.zzw"), b"\x00\x01\x02\xff"),
+            Some("application/x-foo")
+        );
+
+        let detector = Detector::builder().globs(globs).build();
+        assert_eq!(
+            detector.detect_u8(Some("a.zzw"), GIF),
+            Some("application/x-foo")
+        );
+    }
+}
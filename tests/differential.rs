@@ -0,0 +1,43 @@
+//! Compares our results against `file` and `xdg-mime`.
+//!
+//! Runs on the files in `tests/` by default. Point `TREE_MAGIC_CORPUS` at a
+//! directory (e.g. one filled by `scripts/fetch-corpus.sh`) to use a larger
+//! corpus. References that aren't installed are skipped.
+#![cfg(feature = "testing")]
+
+mod differential {
+    use tree_magic_mini::testing::{compare, corpus, Reference};
+
+    fn run(reference: Reference) {
+        let result = match compare(corpus(), reference) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("skipping {:?}: {}", reference, e);
+                return;
+            }
+        };
+
+        eprintln!(
+            "{:?}: {}/{} agree ({:.1}% mismatch), {} errors",
+            reference,
+            result.matched,
+            result.total,
+            result.mismatch_rate() * 100.0,
+            result.errors.len()
+        );
+        for ((ours, theirs), count) in result.mismatch_counts() {
+            eprintln!("  {:>4}  ours: {:?}, theirs: {}", count, ours, theirs);
+        }
+        assert_eq!(result.total, result.matched + result.mismatches.len());
+    }
+
+    #[test]
+    fn file() {
+        run(Reference::File);
+    }
+
+    #[test]
+    fn xdg_mime() {
+        run(Reference::XdgMime);
+    }
+}
@@ -0,0 +1,76 @@
+#![cfg(all(feature = "unix-io", unix))]
+
+mod dirfd {
+    use std::ffi::OsStr;
+    use std::fs::{self, File};
+    use std::os::fd::AsFd;
+    use std::path::PathBuf;
+    use tree_magic_mini as tree_magic;
+
+    /// A fresh directory for one test
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("tree_magic_dirfd_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn same_as_filepath() {
+        let dir = File::open("tests").unwrap();
+        for name in [
+            "image/gif",
+            "application/zip",
+            "text/plain",
+            "image",
+            "application/x-zerosize",
+        ] {
+            assert_eq!(
+                tree_magic::from_dirfd(dir.as_fd(), OsStr::new(name)),
+                tree_magic::from_filepath(&PathBuf::from("tests").join(name)),
+                "{}",
+                name
+            );
+        }
+    }
+    #[test]
+    fn missing() {
+        let dir = File::open("tests").unwrap();
+        assert_eq!(
+            tree_magic::from_dirfd(dir.as_fd(), OsStr::new("missing")),
+            None
+        );
+        assert_eq!(
+            tree_magic::from_dirfd(dir.as_fd(), OsStr::new("a\0b")),
+            None
+        );
+    }
+    #[test]
+    fn parent_renamed() {
+        let root = scratch("parent_renamed");
+        fs::create_dir(root.join("before")).unwrap();
+        fs::write(root.join("before/cat.gif"), include_bytes!("image/gif")).unwrap();
+
+        let dir = File::open(root.join("before")).unwrap();
+        fs::rename(root.join("before"), root.join("after")).unwrap();
+        assert_eq!(
+            tree_magic::from_dirfd(dir.as_fd(), OsStr::new("cat.gif")),
+            Some("image/gif")
+        );
+    }
+    #[test]
+    fn fifo_does_not_block() {
+        let root = scratch("fifo");
+        let fifo = root.join("fifo");
+        let status = std::process::Command::new("mkfifo").arg(&fifo).status();
+        if !status.is_ok_and(|s| s.success()) {
+            return;
+        }
+        let dir = File::open(&root).unwrap();
+        assert_eq!(
+            tree_magic::from_dirfd(dir.as_fd(), OsStr::new("fifo")),
+            tree_magic::from_filepath(&fifo)
+        );
+    }
+}
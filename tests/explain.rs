@@ -0,0 +1,311 @@
+mod explain {
+    use tree_magic_mini as tree_magic;
+
+    /// Archive tests
+    #[test]
+    fn application_x_tar_ustar() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-tar"));
+        assert_eq!(result.mime, "application/x-tar");
+        assert_eq!(result.variant, Some("ustar"));
+    }
+    #[test]
+    fn application_x_tar_v7() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-tar-v7"));
+        assert_eq!(result.mime, "application/x-tar");
+        assert_eq!(result.variant, Some("v7"));
+    }
+
+    /// Image tests
+    #[test]
+    fn image_webp_lossless() {
+        let result = tree_magic::explain_u8(include_bytes!("image/webp"));
+        assert_eq!(result.mime, "image/webp");
+        assert_eq!(result.variant, Some("lossless"));
+    }
+    #[test]
+    fn image_webp_extended() {
+        let result = tree_magic::explain_u8(include_bytes!("image/webp-extended"));
+        assert_eq!(result.mime, "image/webp");
+        assert_eq!(result.variant, Some("extended"));
+    }
+
+    /// Font tests
+    #[test]
+    fn font_woff_truetype() {
+        let result = tree_magic::explain_u8(include_bytes!("font/woff"));
+        assert_eq!(result.mime, "font/woff");
+        assert_eq!(result.variant, Some("truetype"));
+    }
+    #[test]
+    fn font_woff2_cff() {
+        let result = tree_magic::explain_u8(include_bytes!("font/woff2"));
+        assert_eq!(result.mime, "font/woff2");
+        assert_eq!(result.variant, Some("cff"));
+    }
+
+    /// Executable tests
+    #[test]
+    fn application_x_mach_binary_universal() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-mach-binary"));
+        assert_eq!(result.mime, "application/x-mach-binary");
+        assert_eq!(result.variant, Some("universal-arm64"));
+    }
+    #[test]
+    fn application_x_mach_binary_arm64() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-mach-binary-arm64"));
+        assert_eq!(result.mime, "application/x-mach-binary");
+        assert_eq!(result.variant, Some("arm64"));
+    }
+    #[test]
+    fn application_x_sharedlib_arm64() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-sharedlib"));
+        assert_eq!(result.mime, "application/x-sharedlib");
+        assert_eq!(result.variant, Some("arm64"));
+    }
+    #[test]
+    fn application_x_core_ppc64() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-core"));
+        assert_eq!(result.mime, "application/x-core");
+        assert_eq!(result.variant, Some("ppc64"));
+    }
+    #[test]
+    fn application_x_msdownload_i386() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-msdownload"));
+        assert_eq!(result.mime, "application/x-msdownload");
+        assert_eq!(result.variant, Some("i386"));
+    }
+    #[test]
+    fn application_x_cd_image_iso9660() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-cd-image"));
+        assert_eq!(result.mime, "application/x-cd-image");
+        assert_eq!(result.variant, Some("iso9660"));
+    }
+    #[test]
+    fn application_x_cd_image_udf() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-cd-image-udf"));
+        assert_eq!(result.mime, "application/x-cd-image");
+        assert_eq!(result.variant, Some("udf"));
+    }
+    #[test]
+    fn application_x_raw_disk_image_mbr() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-raw-disk-image"));
+        assert_eq!(result.mime, "application/x-raw-disk-image");
+        assert_eq!(result.variant, Some("mbr"));
+    }
+    #[test]
+    fn application_x_raw_disk_image_gpt() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-raw-disk-image-gpt"));
+        assert_eq!(result.mime, "application/x-raw-disk-image");
+        assert_eq!(result.variant, Some("gpt"));
+    }
+    #[test]
+    fn application_x_qemu_disk_v3() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-qemu-disk"));
+        assert_eq!(result.mime, "application/x-qemu-disk");
+        assert_eq!(result.variant, Some("3"));
+    }
+    #[test]
+    fn application_x_vhd_disk_fixed() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-vhd-disk"));
+        assert_eq!(result.mime, "application/x-vhd-disk");
+        assert_eq!(result.variant, Some("fixed"));
+    }
+    #[test]
+    fn application_x_fat_fat16() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-fat"));
+        assert_eq!(result.mime, "application/x-fat");
+        assert_eq!(result.variant, Some("fat16"));
+    }
+    #[test]
+    fn audio_mpeg_layer2() {
+        let result = tree_magic::explain_u8(include_bytes!("audio/mpeg-layer2"));
+        assert_eq!(result.mime, "audio/mpeg");
+        assert_eq!(result.variant, Some("mp2"));
+    }
+
+    /// Document tests
+    #[test]
+    fn application_pdf_version() {
+        let result = tree_magic::explain_u8(b"junk\n%PDF-1.7\n1 0 obj\n<< /Type /Catalog >>\n");
+        assert_eq!(result.mime, "application/pdf");
+        assert_eq!(result.variant, Some("1.7"));
+    }
+    #[test]
+    fn application_pdf_linearized() {
+        let result = tree_magic::explain_u8(b"%PDF-1.5\n1 0 obj\n<< /Linearized 1 /L 1234 >>\n");
+        assert_eq!(result.mime, "application/pdf");
+        assert_eq!(result.variant, Some("1.5-linearized"));
+    }
+
+    /// Certificate and key tests
+    #[test]
+    fn pem_label() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-pem-key"));
+        assert_eq!(result.mime, "application/x-pem-key");
+        assert_eq!(result.variant, Some("EC PRIVATE KEY"));
+    }
+    #[test]
+    fn pem_label_generic() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-pem-file"));
+        assert_eq!(result.mime, "application/x-pem-file");
+        assert_eq!(result.variant, Some("PUBLIC KEY"));
+    }
+    #[test]
+    fn der_no_label() {
+        let result = tree_magic::explain_u8(include_bytes!("application/pkix-cert"));
+        assert_eq!(result.mime, "application/pkix-cert");
+        assert_eq!(result.variant, None);
+    }
+
+    /// 3D model tests
+    #[test]
+    fn model_stl_binary() {
+        let input = include_bytes!("model/stl");
+        // Exporters often start the header of binary STL with `solid` too
+        assert!(input.starts_with(b"solid"));
+        let result = tree_magic::explain_u8(input);
+        assert_eq!(result.mime, "model/stl");
+        assert_eq!(result.variant, Some("binary"));
+    }
+    #[test]
+    fn model_stl_ascii() {
+        let result = tree_magic::explain_u8(include_bytes!("model/stl-ascii"));
+        assert_eq!(result.mime, "model/stl");
+        assert_eq!(result.variant, Some("ascii"));
+    }
+    #[test]
+    fn model_ply_format() {
+        let result = tree_magic::explain_u8(include_bytes!("model/x-ply-binary"));
+        assert_eq!(result.mime, "model/x-ply");
+        assert_eq!(result.variant, Some("binary_little_endian"));
+    }
+
+    /// Geospatial tests
+    #[test]
+    fn geojson_type() {
+        let result = tree_magic::explain_u8(include_bytes!("application/geo+json"));
+        assert_eq!(result.mime, "application/geo+json");
+        assert_eq!(result.variant, Some("FeatureCollection"));
+    }
+    #[test]
+    fn shapefile_shape_type() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-shapefile"));
+        assert_eq!(result.mime, "application/x-shapefile");
+        assert_eq!(result.variant, Some("point"));
+    }
+
+    /// Scientific data tests
+    #[test]
+    fn hdf5_after_user_block() {
+        let input = include_bytes!("application/x-hdf");
+        assert!(!input.starts_with(b"\x89HDF"));
+        let result = tree_magic::explain_u8(input);
+        assert_eq!(result.mime, "application/x-hdf");
+        assert_eq!(result.variant, Some("hdf5"));
+    }
+    #[test]
+    fn netcdf_format() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-netcdf"));
+        assert_eq!(result.mime, "application/x-netcdf");
+        assert_eq!(result.variant, Some("classic"));
+    }
+    #[test]
+    fn nifti_single_file() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-nifti"));
+        assert_eq!(result.mime, "application/x-nifti");
+        assert_eq!(result.variant, Some("nifti1-single"));
+    }
+
+    /// Game format tests
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn gameboy_color() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-gameboy-color-rom"));
+        assert_eq!(result.mime, "application/x-gameboy-color-rom");
+        assert_eq!(result.variant, Some("cgb"));
+    }
+
+    /// eBook tests
+    #[test]
+    fn kfx_container() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-kfx-ebook"));
+        assert_eq!(result.mime, "application/x-kfx-ebook");
+        assert_eq!(result.variant, Some("container"));
+    }
+    #[test]
+    fn azw_topaz() {
+        let result = tree_magic::explain_u8(b"TPZ0\x0e\x00\x00\x00");
+        assert_eq!(result.mime, "application/vnd.amazon.ebook");
+        assert_eq!(result.variant, Some("topaz"));
+    }
+
+    /// Archive format tests
+    #[test]
+    fn rar_versions() {
+        let result = tree_magic::explain_u8(include_bytes!("application/vnd.rar"));
+        assert_eq!(result.mime, "application/vnd.rar");
+        assert_eq!(result.variant, Some("rar5"));
+        let result = tree_magic::explain_u8(include_bytes!("application/vnd.rar-rar4"));
+        assert_eq!(result.variant, Some("rar4"));
+    }
+    #[test]
+    fn cpio_formats() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-cpio"));
+        assert_eq!(result.variant, Some("newc"));
+        let result = tree_magic::explain_u8(include_bytes!("application/x-cpio-odc"));
+        assert_eq!(result.variant, Some("odc"));
+    }
+    #[test]
+    fn lz4_frame() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-lz4"));
+        assert_eq!(result.variant, Some("frame"));
+    }
+
+    /// Package tests
+    #[test]
+    fn alpine_package_versions() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-alpine-package-v3"));
+        assert_eq!(result.mime, "application/x-alpine-package");
+        assert_eq!(result.variant, Some("v3"));
+    }
+    #[cfg(feature = "archive")]
+    #[test]
+    fn alpine_package_v2() {
+        let result = tree_magic::explain_u8(include_bytes!("application/x-alpine-package"));
+        assert_eq!(result.variant, Some("v2"));
+    }
+
+    /// Text tests
+    #[test]
+    fn text_x_ssa_ass() {
+        let result = tree_magic::explain_u8(include_bytes!("text/x-ssa"));
+        assert_eq!(result.mime, "text/x-ssa");
+        assert_eq!(result.variant, Some("ass"));
+    }
+
+    /// Evidence tests
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn image_png_evidence() {
+        let input = include_bytes!("image/png");
+        let result = tree_magic::explain_u8(input);
+        assert_eq!(result.mime, "image/png");
+        assert!(!result.evidence.is_empty());
+        assert_eq!(&input[result.evidence[0].clone()], b"\x89PNG");
+    }
+    #[test]
+    fn application_zip_evidence() {
+        let input = include_bytes!("application/zip");
+        let result = tree_magic::explain_u8(input);
+        assert_eq!(result.mime, "application/zip");
+        for range in result.evidence {
+            assert!(range.end <= input.len());
+        }
+    }
+    #[test]
+    fn text_plain_no_evidence() {
+        let result = tree_magic::explain_u8(include_bytes!("text/plain"));
+        assert_eq!(result.mime, "text/plain");
+        assert!(result.evidence.is_empty());
+    }
+}
@@ -0,0 +1,24 @@
+#![cfg(feature = "std")]
+
+mod explain_delimited {
+    use std::sync::Arc;
+    use tree_magic::TypeDatabase;
+    use tree_magic_mini as tree_magic;
+
+    // One test, as explain_u8 goes by the database shared by every test in
+    // the process, and CSV and TSV are only found with a database that
+    // looks for them
+    #[test]
+    fn text_delimiter() {
+        let db = TypeDatabase::builder().delimited_heuristics(true).build();
+        tree_magic::set_global_database(Arc::new(db));
+
+        let result = tree_magic::explain_u8(include_bytes!("text/csv"));
+        assert_eq!(result.mime, "text/csv");
+        assert_eq!(result.variant, Some(";"));
+
+        let result = tree_magic::explain_u8(include_bytes!("text/tab-separated-values"));
+        assert_eq!(result.mime, "text/tab-separated-values");
+        assert_eq!(result.variant, Some("\t"));
+    }
+}
@@ -0,0 +1,19 @@
+mod export_graph {
+    use tree_magic_mini::{export_graph, GraphFormat};
+
+    #[test]
+    fn dot() {
+        let dot = export_graph(GraphFormat::Dot);
+        assert!(dot.starts_with("digraph mime {\n"));
+        assert!(dot.contains("\"application/x-ole-storage\" -> \"application/msword\";"));
+        assert!(dot.contains("\"all/all\" [label=\"all/all\\n0 rules\"];"));
+    }
+
+    #[test]
+    fn json() {
+        let json = export_graph(GraphFormat::Json);
+        assert!(json.starts_with("{\"nodes\":[{\"mime\":"));
+        assert!(json.contains("[\"application/x-ole-storage\",\"application/msword\"]"));
+        assert!(json.ends_with("]}"));
+    }
+}
@@ -0,0 +1,72 @@
+mod family {
+    use tree_magic_mini as tree_magic;
+
+    ///Image tests
+    #[test]
+    fn image_gif() {
+        assert!(tree_magic::is_image_u8(include_bytes!("image/gif")));
+    }
+    #[test]
+    fn image_png() {
+        assert!(tree_magic::is_image_u8(include_bytes!("image/png")));
+    }
+    #[test]
+    fn image_tiff() {
+        assert!(tree_magic::is_image_u8(include_bytes!("image/tiff")));
+    }
+    #[test]
+    fn image_not_audio() {
+        assert!(!tree_magic::is_audio_u8(include_bytes!("image/png")));
+        assert!(!tree_magic::is_video_u8(include_bytes!("image/png")));
+        assert!(!tree_magic::is_archive_u8(include_bytes!("image/png")));
+    }
+
+    // Audio tests
+    #[test]
+    fn audio_flac() {
+        assert!(tree_magic::is_audio_u8(include_bytes!("audio/flac")));
+    }
+    #[test]
+    fn audio_mpeg() {
+        assert!(tree_magic::is_audio_u8(include_bytes!("audio/mpeg")));
+    }
+    #[test]
+    fn audio_wav() {
+        assert!(tree_magic::is_audio_u8(include_bytes!("audio/wav")));
+        assert!(!tree_magic::is_image_u8(include_bytes!("audio/wav")));
+    }
+
+    /// Archive tests
+    #[test]
+    fn application_zip() {
+        assert!(tree_magic::is_archive_u8(include_bytes!("application/zip")));
+    }
+    #[test]
+    fn application_x_tar() {
+        assert!(tree_magic::is_archive_u8(include_bytes!(
+            "application/x-tar"
+        )));
+    }
+    #[test]
+    fn application_x_7z() {
+        assert!(tree_magic::is_archive_u8(include_bytes!(
+            "application/x-7z-compressed"
+        )));
+    }
+    #[test]
+    fn application_msword_not_archive() {
+        assert!(!tree_magic::is_archive_u8(include_bytes!(
+            "application/msword"
+        )));
+    }
+
+    /// Text tests
+    #[test]
+    fn text_plain() {
+        let input = include_bytes!("text/plain");
+        assert!(!tree_magic::is_image_u8(input));
+        assert!(!tree_magic::is_video_u8(input));
+        assert!(!tree_magic::is_audio_u8(input));
+        assert!(!tree_magic::is_archive_u8(input));
+    }
+}
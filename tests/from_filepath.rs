@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 mod from_filepath {
     use std::path::Path;
     use tree_magic_mini as tree_magic;
@@ -9,4 +11,289 @@ mod from_filepath {
             None
         );
     }
+
+    #[test]
+    fn directory() {
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/image")),
+            Some("inode/directory")
+        );
+    }
+
+    #[test]
+    fn trailer_read_from_file() {
+        // The length of the file tells where the trailer is
+        for mime in ["application/vnd.apache.orc", "application/bson"] {
+            let path = Path::new("tests").join(mime);
+            assert_eq!(tree_magic::from_filepath(&path), Some(mime));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn mount_point() {
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("/")),
+            Some("inode/mount-point")
+        );
+        assert!(tree_magic::match_filepath(
+            "inode/directory",
+            Path::new("/")
+        ));
+        assert!(!tree_magic::match_filepath(
+            "inode/mount-point",
+            Path::new("tests")
+        ));
+    }
+
+    #[test]
+    fn image_gif() {
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/image/gif")),
+            Some("image/gif")
+        );
+    }
+
+    #[test]
+    fn application_x_zerosize() {
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/application/x-zerosize")),
+            Some("application/x-zerosize")
+        );
+        assert!(!tree_magic::match_filepath(
+            "application/x-zerosize",
+            Path::new("tests/text/plain")
+        ));
+    }
+
+    // Directory sector lies past the initially loaded prefix
+    #[test]
+    fn application_msword_deep() {
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/application/msword-deep")),
+            Some("application/msword")
+        );
+    }
+
+    // Volume descriptors lie past the initially loaded prefix
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn application_x_cd_image() {
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/application/x-cd-image")),
+            Some("application/x-cd-image")
+        );
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/application/x-cd-image-udf")),
+            Some("application/x-cd-image")
+        );
+        assert!(tree_magic::match_filepath(
+            "application/x-iso9660-image",
+            Path::new("tests/application/x-cd-image")
+        ));
+    }
+
+    // Footer of a fixed VHD is found from the length of the file
+    #[test]
+    fn application_x_vhd_disk_fixed() {
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/application/x-vhd-disk")),
+            Some("application/x-vhd-disk")
+        );
+    }
+
+    // Superblock lies 64 KiB in
+    #[test]
+    fn application_x_btrfs() {
+        assert_eq!(
+            tree_magic::from_filepath(Path::new("tests/application/x-btrfs")),
+            Some("application/x-btrfs")
+        );
+    }
+
+    /// Path/bytes parity tests
+    const TINY: &[(&str, &[u8])] = &[
+        ("gif", b"GIF89a\x01\x00\x01\x00\x00\x00\x00"),
+        ("png", b"\x89PNG\r\n\x1a\n"),
+        ("script", b"#!/bin/sh\necho tiny script\n"),
+        ("pdf", b"%PDF-1.4\n"),
+        ("char", b"a"),
+        ("binary", b"\x00\x01\x02"),
+    ];
+
+    /// Detecting from the path gives what detecting from the bytes does
+    fn assert_parity(path: &Path) {
+        let bytes = std::fs::read(path).unwrap();
+        let expected = tree_magic::from_u8(&bytes);
+        assert_eq!(
+            tree_magic::from_filepath(path),
+            Some(expected),
+            "{:?}",
+            path
+        );
+        assert!(tree_magic::match_filepath(expected, path), "{:?}", path);
+    }
+
+    #[test]
+    fn tiny_files_parity() {
+        let dir = std::env::temp_dir().join(format!("tree_magic_tiny_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, bytes) in TINY {
+            let path = dir.join(name);
+            std::fs::write(&path, bytes).unwrap();
+            assert_parity(&path);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(tree_magic::from_u8(TINY[0].1), "image/gif");
+        assert_eq!(tree_magic::from_u8(TINY[2].1), "application/x-shellscript");
+    }
+    #[test]
+    fn fixtures_parity() {
+        for top in std::fs::read_dir("tests").unwrap().flatten() {
+            if !top.path().is_dir() {
+                continue;
+            }
+            for entry in std::fs::read_dir(top.path()).unwrap().flatten() {
+                if entry.path().is_file() {
+                    assert_parity(&entry.path());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn try_match_filepath() {
+        let gif = Path::new("tests/image/gif");
+        assert!(tree_magic::try_match_filepath("image/gif", gif).unwrap());
+        assert!(!tree_magic::try_match_filepath("image/png", gif).unwrap());
+
+        let missing = Path::new("this/file/does/not/exist");
+        assert!(!tree_magic::match_filepath("image/gif", missing));
+        assert_eq!(
+            tree_magic::try_match_filepath("image/gif", missing)
+                .unwrap_err()
+                .kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+    #[cfg(unix)]
+    #[test]
+    fn try_match_filepath_unreadable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path =
+            std::env::temp_dir().join(format!("tree_magic_unreadable_{}", std::process::id()));
+        std::fs::write(&path, include_bytes!("image/gif")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o000)).unwrap();
+        // Permissions don't apply to root
+        if std::fs::File::open(&path).is_err() {
+            assert!(!tree_magic::match_filepath("image/gif", &path));
+            assert_eq!(
+                tree_magic::try_match_filepath("image/gif", &path)
+                    .unwrap_err()
+                    .kind(),
+                std::io::ErrorKind::PermissionDenied
+            );
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn match_filepath_header_value() {
+        assert!(tree_magic::match_filepath(
+            "Image/GIF; charset=binary",
+            Path::new("tests/image/gif")
+        ));
+    }
+
+    #[test]
+    fn from_filepath_buf_reuse() {
+        let mut buf = Vec::new();
+        assert_eq!(
+            tree_magic::from_filepath_buf(Path::new("tests/image/gif"), &mut buf),
+            Some("image/gif")
+        );
+        assert_eq!(&buf[..4], b"GIF8");
+        let capacity = buf.capacity();
+
+        assert_eq!(
+            tree_magic::from_filepath_buf(Path::new("tests/image/png"), &mut buf),
+            Some("image/png")
+        );
+        assert_eq!(&buf[1..4], b"PNG");
+        assert_eq!(buf.capacity(), capacity);
+    }
+    #[test]
+    fn from_filepath_buf_missing_keeps_buffer() {
+        let mut buf = b"old".to_vec();
+        assert_eq!(
+            tree_magic::from_filepath_buf(Path::new("this/file/does/not/exist"), &mut buf),
+            None
+        );
+        assert_eq!(buf, b"old");
+    }
+
+    #[test]
+    fn with_meta() {
+        for path in [
+            "tests/image/gif",
+            "tests/image",
+            "tests/application/x-zerosize",
+        ] {
+            let path = Path::new(path);
+            let meta = std::fs::metadata(path).unwrap();
+            assert_eq!(
+                tree_magic::from_filepath_with_meta(path, &meta),
+                tree_magic::from_filepath(path)
+            );
+        }
+    }
+    #[test]
+    fn with_meta_not_looked_up_again() {
+        // The metadata given is trusted, so an empty file's size keeps the
+        // GIF from being opened
+        let meta = std::fs::metadata("tests/application/x-zerosize").unwrap();
+        assert_eq!(
+            tree_magic::from_filepath_with_meta(Path::new("tests/image/gif"), &meta),
+            Some("application/x-zerosize")
+        );
+    }
+
+    #[test]
+    fn from_file() {
+        for path in [
+            "tests/image/gif",
+            "tests/application/x-tar",
+            "tests/text/plain",
+            "tests/application/x-zerosize",
+        ] {
+            let file = std::fs::File::open(path).unwrap();
+            assert_eq!(
+                tree_magic::from_file(&file),
+                tree_magic::from_filepath(Path::new(path))
+            );
+        }
+    }
+    #[test]
+    fn from_file_keeps_position() {
+        use std::io::{Read, Seek, SeekFrom};
+
+        let mut file = std::fs::File::open("tests/image/png").unwrap();
+        file.seek(SeekFrom::Start(1)).unwrap();
+        assert_eq!(tree_magic::from_file(&file), Some("image/png"));
+
+        let mut rest = [0; 3];
+        file.read_exact(&mut rest).unwrap();
+        assert_eq!(&rest, b"PNG");
+    }
+    #[test]
+    fn from_file_write_only() {
+        let path =
+            std::env::temp_dir().join(format!("tree_magic_from_file_{}", std::process::id()));
+        std::fs::write(&path, include_bytes!("image/gif")).unwrap();
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        assert_eq!(tree_magic::from_file(&file), None);
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -38,6 +38,48 @@ mod from_u8 {
         );
     }
     #[test]
+    fn image_x_canon_cr2() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/x-canon-cr2")),
+            convmime!("image/x-canon-cr2")
+        );
+    }
+    #[test]
+    fn image_x_nikon_nef() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/x-nikon-nef")),
+            convmime!("image/x-nikon-nef")
+        );
+    }
+    #[test]
+    fn image_x_sony_arw() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/x-sony-arw")),
+            convmime!("image/x-sony-arw")
+        );
+    }
+    #[test]
+    fn image_x_adobe_dng() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/x-adobe-dng")),
+            convmime!("image/x-adobe-dng")
+        );
+    }
+    #[test]
+    fn image_webp() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/webp")),
+            convmime!("image/webp")
+        );
+    }
+    #[test]
+    fn image_webp_extended() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/webp-extended")),
+            convmime!("image/webp")
+        );
+    }
+    #[test]
     fn image_x_portable_bitmap() {
         assert_eq!(
             tree_magic::from_u8(include_bytes!("image/x-portable-bitmap")),
@@ -45,6 +87,7 @@ mod from_u8 {
         );
     }
     #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
     fn image_x_pcx() {
         assert_eq!(
             tree_magic::from_u8(include_bytes!("image/x-pcx")),
@@ -52,6 +95,7 @@ mod from_u8 {
         );
     }
     #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
     fn image_x_tga() {
         assert_eq!(
             tree_magic::from_u8(include_bytes!("image/x-tga")),
@@ -68,6 +112,175 @@ mod from_u8 {
         );
     }
     #[test]
+    fn application_gzip() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/gzip")),
+            convmime!("application/gzip")
+        );
+    }
+    #[test]
+    fn application_x_compressed_tar() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-compressed-tar-named")),
+            convmime!("application/x-compressed-tar")
+        );
+    }
+    // Without a file name, only inflating the start tells it's a tar archive
+    #[cfg(feature = "archive")]
+    #[test]
+    fn application_x_compressed_tar_unnamed() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-compressed-tar")),
+            convmime!("application/x-compressed-tar")
+        );
+    }
+    #[cfg(not(feature = "archive"))]
+    #[test]
+    fn application_x_compressed_tar_unnamed() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-compressed-tar")),
+            convmime!("application/gzip")
+        );
+    }
+    // SQLite databases and the files kept next to them
+    #[test]
+    fn application_vnd_sqlite3() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.sqlite3")),
+            convmime!("application/vnd.sqlite3")
+        );
+    }
+    #[test]
+    fn application_x_sqlite3_wal() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-sqlite3-wal")),
+            convmime!("application/x-sqlite3-wal")
+        );
+    }
+    #[test]
+    fn application_x_sqlite3_shm() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-sqlite3-shm")),
+            convmime!("application/x-sqlite3-shm")
+        );
+    }
+    #[test]
+    fn application_x_sqlite3_journal() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-sqlite3-journal")),
+            convmime!("application/x-sqlite3-journal")
+        );
+    }
+    // Data serialization formats
+    #[test]
+    fn application_vnd_apache_parquet() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.apache.parquet")),
+            convmime!("application/vnd.apache.parquet")
+        );
+    }
+    #[test]
+    fn application_vnd_apache_avro() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.apache.avro")),
+            convmime!("application/vnd.apache.avro")
+        );
+    }
+    #[test]
+    fn application_vnd_apache_orc() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.apache.orc")),
+            convmime!("application/vnd.apache.orc")
+        );
+    }
+    #[test]
+    fn application_cbor() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/cbor")),
+            convmime!("application/cbor")
+        );
+    }
+    #[test]
+    fn application_bson() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/bson")),
+            convmime!("application/bson")
+        );
+    }
+    #[test]
+    fn application_vnd_msgpack() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.msgpack")),
+            convmime!("application/vnd.msgpack")
+        );
+    }
+    #[test]
+    fn application_json() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/json")),
+            convmime!("application/json")
+        );
+    }
+    #[test]
+    fn application_json5() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/json5")),
+            convmime!("application/json5")
+        );
+    }
+    #[test]
+    fn application_x_ndjson() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-ndjson")),
+            convmime!("application/x-ndjson")
+        );
+    }
+    #[test]
+    fn application_x_yaml() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-yaml")),
+            convmime!("application/x-yaml")
+        );
+    }
+    #[test]
+    fn application_toml() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/toml")),
+            convmime!("application/toml")
+        );
+    }
+    // Container image documents
+    #[test]
+    fn application_vnd_oci_image_manifest() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.oci.image.manifest.v1+json")),
+            convmime!("application/vnd.oci.image.manifest.v1+json")
+        );
+    }
+    #[test]
+    fn application_vnd_oci_image_index() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.oci.image.index.v1+json")),
+            convmime!("application/vnd.oci.image.index.v1+json")
+        );
+    }
+    #[test]
+    fn application_vnd_oci_image_config() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.oci.image.config.v1+json")),
+            convmime!("application/vnd.oci.image.config.v1+json")
+        );
+    }
+    #[test]
+    fn application_vnd_docker_distribution_manifest() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!(
+                "application/vnd.docker.distribution.manifest.v2+json"
+            )),
+            convmime!("application/vnd.docker.distribution.manifest.v2+json")
+        );
+    }
+    #[test]
     fn application_x_7z() {
         assert_eq!(
             tree_magic::from_u8(include_bytes!("application/x-7z-compressed")),
@@ -82,6 +295,233 @@ mod from_u8 {
         );
     }
 
+    /// Office tests
+    #[test]
+    fn application_msword() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/msword")),
+            convmime!("application/msword")
+        );
+    }
+    #[test]
+    fn application_vnd_ms_excel() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.ms-excel")),
+            convmime!("application/vnd.ms-excel")
+        );
+    }
+    #[test]
+    fn application_vnd_ms_powerpoint() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.ms-powerpoint")),
+            convmime!("application/vnd.ms-powerpoint")
+        );
+    }
+    #[test]
+    fn application_x_msi() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-msi")),
+            convmime!("application/x-msi")
+        );
+    }
+    // PDF headers may come after junk, but only within the first 1024 bytes
+    #[test]
+    fn application_pdf_leading_junk() {
+        assert_eq!(
+            tree_magic::from_u8(b"\x00\x00junk header\r\n%PDF-1.7\n%\xe2\xe3\xcf\xd3\n"),
+            convmime!("application/pdf")
+        );
+        assert_eq!(
+            tree_magic::from_u8(b"From: someone\n\n%PDF-1.4\n"),
+            convmime!("application/pdf")
+        );
+    }
+    #[test]
+    fn application_pdf_junk_too_long() {
+        let mut input = vec![0u8; 1025];
+        input.extend_from_slice(b"%PDF-1.4\n");
+        assert_ne!(tree_magic::from_u8(&input), convmime!("application/pdf"));
+    }
+    #[test]
+    fn application_pdf_no_version() {
+        assert_eq!(
+            tree_magic::from_u8(b"see %PDF-x for details\n"),
+            convmime!("text/plain")
+        );
+    }
+
+    /// Executable tests
+    #[test]
+    fn application_wasm() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/wasm")),
+            convmime!("application/wasm")
+        );
+    }
+    #[test]
+    fn application_vnd_android_dex() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.android.dex")),
+            convmime!("application/vnd.android.dex")
+        );
+    }
+    #[test]
+    fn application_x_java() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-java")),
+            convmime!("application/x-java")
+        );
+    }
+    #[test]
+    fn application_x_mach_binary_fat() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-mach-binary")),
+            convmime!("application/x-mach-binary")
+        );
+    }
+    #[test]
+    fn application_x_mach_binary_thin() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-mach-binary-arm64")),
+            convmime!("application/x-mach-binary")
+        );
+    }
+    #[test]
+    fn application_x_executable() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-executable")),
+            convmime!("application/x-executable")
+        );
+    }
+    #[test]
+    fn application_x_pie_executable() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-pie-executable")),
+            convmime!("application/x-pie-executable")
+        );
+    }
+    #[test]
+    fn application_x_sharedlib() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-sharedlib")),
+            convmime!("application/x-sharedlib")
+        );
+    }
+    #[test]
+    fn application_x_core() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-core")),
+            convmime!("application/x-core")
+        );
+    }
+    #[test]
+    fn application_vnd_microsoft_portable_executable() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!(
+                "application/vnd.microsoft.portable-executable"
+            )),
+            convmime!("application/vnd.microsoft.portable-executable")
+        );
+    }
+    #[test]
+    fn application_x_msdownload() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-msdownload")),
+            convmime!("application/x-msdownload")
+        );
+    }
+    #[test]
+    fn application_x_cd_image() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-cd-image")),
+            convmime!("application/x-cd-image")
+        );
+    }
+    #[test]
+    fn application_x_raw_disk_image() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-raw-disk-image")),
+            convmime!("application/x-raw-disk-image")
+        );
+    }
+    #[test]
+    fn application_x_raw_disk_image_gpt() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-raw-disk-image-gpt")),
+            convmime!("application/x-raw-disk-image")
+        );
+    }
+    #[test]
+    fn application_x_qemu_disk() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-qemu-disk")),
+            convmime!("application/x-qemu-disk")
+        );
+    }
+    #[test]
+    fn application_x_vmdk_disk() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-vmdk-disk")),
+            convmime!("application/x-vmdk-disk")
+        );
+    }
+    #[test]
+    fn application_x_vhd_disk() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-vhd-disk")),
+            convmime!("application/x-vhd-disk")
+        );
+    }
+    #[test]
+    fn application_x_vhdx_disk() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-vhdx-disk")),
+            convmime!("application/x-vhdx-disk")
+        );
+    }
+    #[test]
+    fn application_x_ext2() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-ext2")),
+            convmime!("application/x-ext2")
+        );
+    }
+    #[test]
+    fn application_x_ext4() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-ext4")),
+            convmime!("application/x-ext4")
+        );
+    }
+    #[test]
+    fn application_x_btrfs() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-btrfs")),
+            convmime!("application/x-btrfs")
+        );
+    }
+    #[test]
+    fn application_x_xfs() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-xfs")),
+            convmime!("application/x-xfs")
+        );
+    }
+    #[test]
+    fn application_x_fat() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-fat")),
+            convmime!("application/x-fat")
+        );
+    }
+    #[test]
+    fn application_x_ntfs() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-ntfs")),
+            convmime!("application/x-ntfs")
+        );
+    }
+
     /// Text tests
     #[test]
     fn text_plain() {
@@ -90,21 +530,704 @@ mod from_u8 {
             convmime!("text/plain")
         );
     }
-
-    // Audio tests
+    // Subtitles and playlists
     #[test]
-    fn audio_flac() {
+    fn application_x_subrip() {
         assert_eq!(
-            tree_magic::from_u8(include_bytes!("audio/flac")),
-            convmime!("audio/flac")
+            tree_magic::from_u8(include_bytes!("application/x-subrip")),
+            convmime!("application/x-subrip")
         );
     }
-
     #[test]
-    fn audio_mpeg() {
+    fn text_vtt() {
         assert_eq!(
-            tree_magic::from_u8(include_bytes!("audio/mpeg")),
-            convmime!("audio/mpeg")
+            tree_magic::from_u8(include_bytes!("text/vtt")),
+            convmime!("text/vtt")
+        );
+    }
+    #[test]
+    fn text_x_ssa() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("text/x-ssa")),
+            convmime!("text/x-ssa")
+        );
+    }
+    #[test]
+    fn audio_x_mpegurl() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/x-mpegurl")),
+            convmime!("audio/x-mpegurl")
+        );
+    }
+    #[test]
+    fn application_vnd_apple_mpegurl() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.apple.mpegurl")),
+            convmime!("application/vnd.apple.mpegurl")
+        );
+    }
+    #[test]
+    fn audio_x_scpls() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/x-scpls")),
+            convmime!("audio/x-scpls")
+        );
+    }
+    // Certificates and keys
+    #[test]
+    fn application_pkix_cert_pem() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/pkix-cert+pem")),
+            convmime!("application/pkix-cert+pem")
+        );
+    }
+    #[test]
+    fn application_pkix_cert() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/pkix-cert")),
+            convmime!("application/pkix-cert")
+        );
+    }
+    #[test]
+    fn application_pkcs12() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/pkcs12")),
+            convmime!("application/pkcs12")
+        );
+    }
+    #[test]
+    fn application_pkcs8_pem() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/pkcs8+pem")),
+            convmime!("application/pkcs8+pem")
+        );
+    }
+    #[test]
+    fn application_x_pem_key() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-pem-key")),
+            convmime!("application/x-pem-key")
+        );
+    }
+    #[test]
+    fn application_x_pem_file() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-pem-file")),
+            convmime!("application/x-pem-file")
+        );
+    }
+    #[test]
+    fn application_pgp_keys() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/pgp-keys")),
+            convmime!("application/pgp-keys")
+        );
+    }
+    #[test]
+    fn application_pgp_keys_binary() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/pgp-keys-binary")),
+            convmime!("application/pgp-keys")
+        );
+    }
+    // Mail, contacts and calendars
+    #[test]
+    fn message_rfc822() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("message/rfc822")),
+            convmime!("message/rfc822")
+        );
+    }
+    #[test]
+    fn application_mbox() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/mbox")),
+            convmime!("application/mbox")
+        );
+    }
+    #[test]
+    fn text_vcard() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("text/vcard")),
+            convmime!("text/vcard")
+        );
+    }
+    #[test]
+    fn text_calendar() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("text/calendar")),
+            convmime!("text/calendar")
+        );
+    }
+    #[test]
+    fn text_vcard_leading_space() {
+        assert_eq!(
+            tree_magic::from_u8(b"\xEF\xBB\xBF\r\n  begin:vcard\rVERSION:3.0\rEND:VCARD\r"),
+            convmime!("text/vcard")
+        );
+    }
+    // Nothing but a byte order mark and whitespace
+    #[test]
+    fn text_plain_whitespace() {
+        assert_eq!(
+            tree_magic::from_u8(b" \t\r\n\x0b\x0c"),
+            convmime!("text/plain")
+        );
+    }
+    #[test]
+    fn text_plain_bom_only() {
+        for bom in [
+            &b"\xef\xbb\xbf"[..],
+            b"\xff\xfe",
+            b"\xfe\xff",
+            b"\xff\xfe\x00\x00",
+            b"\x00\x00\xfe\xff",
+        ] {
+            assert_eq!(
+                tree_magic::from_u8(bom),
+                convmime!("text/plain"),
+                "{:x?}",
+                bom
+            );
+        }
+    }
+    #[test]
+    fn text_plain_bom_whitespace() {
+        for blank in [
+            &b"\xef\xbb\xbf \r\n"[..],
+            b"\xff\xfe \x00\r\x00\n\x00",
+            b"\xfe\xff\x00 \x00\r\x00\n",
+            b"\xff\xfe\x00\x00\n\x00\x00\x00",
+            b"\x00\x00\xfe\xff\x00\x00\x00\t",
+        ] {
+            assert_eq!(
+                tree_magic::from_u8(blank),
+                convmime!("text/plain"),
+                "{:x?}",
+                blank
+            );
+        }
+    }
+    #[test]
+    fn bom_with_null_not_blank() {
+        // Neither UTF-32 with a stray half unit, nor UTF-16 starting with a
+        // null character
+        assert_ne!(
+            tree_magic::from_u8(b"\xff\xfe\x00\x00 \x00"),
+            convmime!("text/plain")
+        );
+    }
+    #[test]
+    fn application_x_zerosize() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-zerosize")),
+            convmime!("application/x-zerosize")
+        );
+    }
+
+    // Lineage tests
+    #[test]
+    fn lineage_gif() {
+        assert_eq!(
+            tree_magic::from_u8_with_lineage(include_bytes!("image/gif")),
+            ["image/gif", "application/octet-stream"]
+        );
+    }
+    #[test]
+    fn lineage_vcard() {
+        assert_eq!(
+            tree_magic::from_u8_with_lineage(include_bytes!("text/vcard")),
+            ["text/vcard", "text/plain", "application/octet-stream"]
+        );
+    }
+    #[test]
+    fn lineage_blank_text() {
+        assert_eq!(
+            tree_magic::from_u8_with_lineage(b"\xff\xfe\n\x00"),
+            ["text/plain", "application/octet-stream"]
+        );
+    }
+    #[test]
+    fn lineage_zerosize() {
+        assert_eq!(
+            tree_magic::from_u8_with_lineage(b""),
+            ["application/x-zerosize"]
+        );
+    }
+
+    // Audio tests
+    #[test]
+    fn audio_flac() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/flac")),
+            convmime!("audio/flac")
+        );
+    }
+
+    #[test]
+    fn audio_mpeg() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/mpeg")),
+            convmime!("audio/mpeg")
+        );
+    }
+
+    #[test]
+    fn audio_wav() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/wav")),
+            convmime!("audio/x-wav")
+        );
+    }
+    #[test]
+    fn audio_mpeg_layer2() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/mpeg-layer2")),
+            convmime!("audio/mpeg")
+        );
+    }
+    #[test]
+    fn audio_aac() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/aac")),
+            convmime!("audio/aac")
+        );
+    }
+    #[test]
+    fn audio_vorbis() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/ogg")),
+            convmime!("audio/x-vorbis+ogg")
+        );
+    }
+    #[test]
+    fn audio_opus() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/opus")),
+            convmime!("audio/x-opus+ogg")
+        );
+    }
+
+    // Video tests
+    #[test]
+    fn video_x_msvideo() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("video/x-msvideo")),
+            convmime!("video/x-msvideo")
+        );
+    }
+    #[test]
+    fn video_x_theora_ogg() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("video/x-theora+ogg")),
+            convmime!("video/x-theora+ogg")
+        );
+    }
+
+    // Font tests
+    #[test]
+    fn font_ttf() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("font/ttf")),
+            convmime!("font/ttf")
+        );
+    }
+    #[test]
+    fn font_otf() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("font/otf")),
+            convmime!("font/otf")
+        );
+    }
+    #[test]
+    fn font_collection() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("font/collection")),
+            convmime!("font/collection")
+        );
+    }
+    #[test]
+    fn font_woff() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("font/woff")),
+            convmime!("font/woff")
+        );
+    }
+    #[test]
+    fn font_woff2() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("font/woff2")),
+            convmime!("font/woff2")
+        );
+    }
+
+    // 3D model tests
+    #[test]
+    fn model_gltf_binary() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("model/gltf-binary")),
+            convmime!("model/gltf-binary")
+        );
+    }
+    #[test]
+    fn model_gltf_json() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("model/gltf+json")),
+            convmime!("model/gltf+json")
+        );
+    }
+    #[test]
+    fn model_stl() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("model/stl")),
+            convmime!("model/stl")
+        );
+    }
+    #[test]
+    fn model_stl_ascii() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("model/stl-ascii")),
+            convmime!("model/stl")
+        );
+    }
+    #[test]
+    fn model_obj() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("model/obj")),
+            convmime!("model/obj")
+        );
+    }
+    #[test]
+    fn model_x_ply() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("model/x-ply")),
+            convmime!("model/x-ply")
+        );
+    }
+    #[test]
+    fn model_step() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("model/step")),
+            convmime!("model/step")
+        );
+    }
+    #[test]
+    fn model_3mf() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("model/3mf")),
+            convmime!("model/3mf")
+        );
+    }
+
+    // Geospatial tests
+    #[test]
+    fn application_geo_json() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/geo+json")),
+            convmime!("application/geo+json")
+        );
+    }
+    #[test]
+    fn application_x_shapefile() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-shapefile")),
+            convmime!("application/x-shapefile")
+        );
+    }
+    #[test]
+    fn application_gpx_xml() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/gpx+xml")),
+            convmime!("application/gpx+xml")
+        );
+    }
+    #[test]
+    fn application_kml_xml() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.google-earth.kml+xml")),
+            convmime!("application/vnd.google-earth.kml+xml")
+        );
+    }
+    #[test]
+    fn application_vnd_flatgeobuf() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.flatgeobuf")),
+            convmime!("application/vnd.flatgeobuf")
+        );
+    }
+    #[test]
+    fn image_x_geotiff() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/x-geotiff")),
+            convmime!("image/x-geotiff")
+        );
+    }
+
+    // Scientific data tests
+    #[test]
+    fn application_x_hdf() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-hdf")),
+            convmime!("application/x-hdf")
+        );
+    }
+    #[test]
+    fn application_x_netcdf() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-netcdf")),
+            convmime!("application/x-netcdf")
+        );
+    }
+    #[test]
+    fn application_fits() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/fits")),
+            convmime!("application/fits")
+        );
+    }
+    #[test]
+    fn application_dicom() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/dicom")),
+            convmime!("application/dicom")
+        );
+    }
+    #[test]
+    fn application_dicom_raw() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/dicom-raw")),
+            convmime!("application/dicom")
+        );
+    }
+    #[test]
+    fn application_x_nifti() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-nifti")),
+            convmime!("application/x-nifti")
+        );
+    }
+
+    // eBook tests
+    #[test]
+    fn application_epub_zip() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/epub+zip")),
+            convmime!("application/epub+zip")
+        );
+    }
+    #[test]
+    fn zip_directory_past_end() {
+        // A central directory record whose extra field runs far past the
+        // directory, as in a truncated or fuzzed archive
+        let mut zip = b"PK\x03\x04".to_vec();
+        zip.resize(30, 0);
+        let mut record = b"PK\x01\x02".to_vec();
+        record.resize(46, 0);
+        record[28] = 1;
+        record[30..32].copy_from_slice(&[0xff, 0xff]);
+        record.push(b'a');
+        zip.extend_from_slice(&record);
+        zip.extend_from_slice(b"PK\x05\x06");
+        zip.extend_from_slice(&[0; 8]);
+        zip.extend_from_slice(&(record.len() as u32).to_le_bytes());
+        zip.extend_from_slice(&30u32.to_le_bytes());
+        zip.extend_from_slice(&[0; 2]);
+
+        assert_eq!(tree_magic::from_u8(&zip), "application/zip");
+    }
+    #[test]
+    fn application_x_mobipocket_ebook() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-mobipocket-ebook")),
+            convmime!("application/x-mobipocket-ebook")
+        );
+    }
+    #[test]
+    fn application_vnd_amazon_mobi8_ebook() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.amazon.mobi8-ebook")),
+            convmime!("application/vnd.amazon.mobi8-ebook")
+        );
+    }
+    #[test]
+    fn application_vnd_amazon_ebook() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.amazon.ebook")),
+            convmime!("application/vnd.amazon.ebook")
+        );
+    }
+    #[test]
+    fn application_x_kfx_ebook() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-kfx-ebook")),
+            convmime!("application/x-kfx-ebook")
+        );
+    }
+    #[test]
+    fn application_x_aportisdoc() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-aportisdoc")),
+            convmime!("application/x-aportisdoc")
+        );
+    }
+
+    // Archive format tests
+    #[test]
+    fn application_vnd_rar() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.rar")),
+            convmime!("application/vnd.rar")
+        );
+    }
+    #[test]
+    fn application_vnd_rar_rar4() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.rar-rar4")),
+            convmime!("application/vnd.rar")
+        );
+    }
+    #[test]
+    fn application_zstd() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/zstd")),
+            convmime!("application/zstd")
+        );
+    }
+    #[test]
+    fn application_x_lz4() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-lz4")),
+            convmime!("application/x-lz4")
+        );
+    }
+    #[test]
+    fn application_x_lzip() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-lzip")),
+            convmime!("application/x-lzip")
+        );
+    }
+    #[test]
+    fn application_x_lrzip() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-lrzip")),
+            convmime!("application/x-lrzip")
+        );
+    }
+    #[test]
+    fn application_x_arj() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-arj")),
+            convmime!("application/x-arj")
+        );
+    }
+    #[test]
+    fn application_vnd_ms_cab_compressed() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.ms-cab-compressed")),
+            convmime!("application/vnd.ms-cab-compressed")
+        );
+    }
+    #[test]
+    fn application_x_xar() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-xar")),
+            convmime!("application/x-xar")
+        );
+    }
+    #[test]
+    fn application_x_cpio() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-cpio")),
+            convmime!("application/x-cpio")
+        );
+    }
+    #[test]
+    fn application_x_cpio_odc() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-cpio-odc")),
+            convmime!("application/x-cpio")
+        );
+    }
+    #[test]
+    fn application_x_archive() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-archive")),
+            convmime!("application/x-archive")
+        );
+    }
+    #[test]
+    fn application_vnd_debian_binary_package() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.debian.binary-package")),
+            convmime!("application/vnd.debian.binary-package")
+        );
+    }
+    // Package tests
+    #[test]
+    fn application_x_rpm() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-rpm")),
+            convmime!("application/x-rpm")
+        );
+    }
+    #[test]
+    fn application_x_source_rpm() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-source-rpm")),
+            convmime!("application/x-source-rpm")
+        );
+    }
+    // The second version is a gzipped tar archive, told by its first member
+    #[cfg(feature = "archive")]
+    #[test]
+    fn application_x_alpine_package() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-alpine-package")),
+            convmime!("application/x-alpine-package")
+        );
+    }
+    #[cfg(not(feature = "archive"))]
+    #[test]
+    fn application_x_alpine_package() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-alpine-package")),
+            convmime!("application/gzip")
+        );
+    }
+    #[test]
+    fn application_x_alpine_package_v3() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-alpine-package-v3")),
+            convmime!("application/x-alpine-package")
+        );
+    }
+    #[test]
+    fn application_vnd_android_package_archive() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.android.package-archive")),
+            convmime!("application/vnd.android.package-archive")
+        );
+    }
+    #[test]
+    fn application_vnd_snap() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.snap")),
+            convmime!("application/vnd.snap")
+        );
+    }
+    #[test]
+    fn application_vnd_squashfs() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.squashfs")),
+            convmime!("application/vnd.squashfs")
+        );
+    }
+    #[test]
+    fn application_vnd_flatpak() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/vnd.flatpak")),
+            convmime!("application/vnd.flatpak")
         );
     }
 }
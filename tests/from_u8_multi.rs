@@ -0,0 +1,69 @@
+mod from_u8_multi {
+    use tree_magic_mini as tree_magic;
+
+    /// Splits `bytes` into parts of `size` bytes
+    fn chunks(bytes: &[u8], size: usize) -> Vec<&[u8]> {
+        bytes.chunks(size).collect()
+    }
+
+    ///Image tests
+    #[test]
+    fn image_gif() {
+        let input = include_bytes!("image/gif");
+        let (head, tail) = input.split_at(2);
+        assert_eq!(tree_magic::from_u8_multi(&[head, tail]), "image/gif");
+    }
+    #[test]
+    fn image_png_bytewise() {
+        let input = include_bytes!("image/png");
+        assert_eq!(tree_magic::from_u8_multi(&chunks(input, 1)), "image/png");
+    }
+
+    /// Archive tests
+    #[test]
+    fn application_zip() {
+        let input = include_bytes!("application/zip");
+        assert_eq!(
+            tree_magic::from_u8_multi(&chunks(input, 3)),
+            "application/zip"
+        );
+    }
+    #[test]
+    fn application_x_tar() {
+        // Header fields straddle the part boundaries
+        let input = include_bytes!("application/x-tar");
+        assert_eq!(
+            tree_magic::from_u8_multi(&chunks(input, 7)),
+            "application/x-tar"
+        );
+    }
+    #[test]
+    fn application_msword() {
+        let input = include_bytes!("application/msword");
+        assert_eq!(
+            tree_magic::from_u8_multi(&chunks(input, 100)),
+            "application/msword"
+        );
+    }
+    #[test]
+    fn application_zip_match() {
+        let input = include_bytes!("application/zip");
+        let (head, tail) = input.split_at(1);
+        assert!(tree_magic::match_u8_multi(
+            "application/zip",
+            &[head, &[], tail]
+        ));
+        assert!(!tree_magic::match_u8_multi("image/gif", &[head, tail]));
+    }
+
+    /// Text tests
+    #[test]
+    fn text_plain() {
+        let input = include_bytes!("text/plain");
+        assert_eq!(tree_magic::from_u8_multi(&chunks(input, 5)), "text/plain");
+    }
+    #[test]
+    fn empty() {
+        assert_eq!(tree_magic::from_u8_multi(&[]), tree_magic::from_u8(&[]));
+    }
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "std")]
+
+mod global {
+    use std::sync::Arc;
+    use std::thread;
+    use tree_magic::TypeDatabase;
+    use tree_magic_mini as tree_magic;
+
+    fn foo_database() -> TypeDatabase {
+        TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .build()
+    }
+
+    // One test, as the database is shared by every test in the process
+    #[test]
+    fn set_and_reset() {
+        assert_eq!(tree_magic::from_u8(b"FOObar"), "text/plain");
+
+        let foo = Arc::new(foo_database());
+        tree_magic::set_global_database(Arc::clone(&foo));
+        let mime = tree_magic::from_u8(b"FOObar");
+        assert_eq!(mime, "application/x-foo");
+        assert!(tree_magic::match_u8("application/x-foo", b"FOObar"));
+        let seen = thread::spawn(|| tree_magic::from_u8(b"FOObar"))
+            .join()
+            .unwrap();
+        assert_eq!(seen, "application/x-foo");
+
+        // Names from a replaced database stay valid
+        tree_magic::set_global_database(Arc::new(TypeDatabase::default()));
+        assert_eq!(tree_magic::from_u8(b"FOObar"), "text/plain");
+        assert_eq!(mime, "application/x-foo");
+
+        // Switching back to a database set before
+        tree_magic::set_global_database(foo);
+        assert_eq!(tree_magic::from_u8(b"FOObar"), "application/x-foo");
+
+        tree_magic::reset_global_database();
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/gif")),
+            "image/gif"
+        );
+    }
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "std")]
+
+mod info {
+    use std::path::Path;
+    use tree_magic_mini as tree_magic;
+
+    /// The XML files come from the system, so there's nothing to check
+    /// without shared-mime-info installed
+    fn installed() -> bool {
+        Path::new("/usr/share/mime/application/pdf.xml").exists()
+    }
+
+    #[test]
+    fn acronym() {
+        if !installed() {
+            return;
+        }
+        assert_eq!(
+            tree_magic::acronym("application/pdf").as_deref(),
+            Some("PDF")
+        );
+        assert_eq!(tree_magic::acronym("image/gif").as_deref(), Some("GIF"));
+    }
+    #[test]
+    fn expanded_acronym() {
+        if !installed() {
+            return;
+        }
+        assert_eq!(
+            tree_magic::expanded_acronym("application/pdf").as_deref(),
+            Some("Portable Document Format")
+        );
+        assert_eq!(
+            tree_magic::expanded_acronym("image/gif").as_deref(),
+            Some("Graphics Interchange Format")
+        );
+    }
+    #[test]
+    fn alias_and_case() {
+        if !installed() {
+            return;
+        }
+        assert_eq!(
+            tree_magic::acronym("application/x-pdf").as_deref(),
+            Some("PDF")
+        );
+        assert_eq!(
+            tree_magic::acronym("Application/PDF; q=0.8").as_deref(),
+            Some("PDF")
+        );
+    }
+    #[test]
+    fn description_fallback() {
+        if !installed() {
+            return;
+        }
+        let pdf = |locale| tree_magic::description("application/pdf", locale);
+        assert_eq!(pdf("C").as_deref(), Some("PDF document"));
+        assert_eq!(pdf("").as_deref(), Some("PDF document"));
+        assert_eq!(pdf("pt_BR").as_deref(), Some("Documento PDF"));
+        assert_eq!(pdf("pt-BR").as_deref(), Some("Documento PDF"));
+        assert_eq!(pdf("pt_PT.UTF-8").as_deref(), Some("documento PDF"));
+        assert_eq!(pdf("de_CH@euro").as_deref(), Some("PDF-Dokument"));
+        assert_eq!(pdf("x-klingon").as_deref(), Some("PDF document"));
+    }
+    #[test]
+    fn description_locales() {
+        if !installed() {
+            return;
+        }
+        let locales = tree_magic::description_locales("application/x-pdf");
+        assert!(locales.iter().any(|l| l == "pt_BR"));
+        assert!(locales.iter().any(|l| l == "pt"));
+        assert!(!locales.iter().any(|l| l.is_empty()));
+    }
+    #[test]
+    fn missing() {
+        assert_eq!(tree_magic::acronym("text/plain"), None);
+        assert_eq!(tree_magic::acronym("application/x-unknown"), None);
+        assert_eq!(tree_magic::acronym("../../etc/passwd"), None);
+        assert_eq!(tree_magic::expanded_acronym("nonsense"), None);
+        assert_eq!(tree_magic::description("application/x-unknown", "C"), None);
+        assert!(tree_magic::description_locales("nonsense").is_empty());
+    }
+}
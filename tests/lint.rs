@@ -0,0 +1,82 @@
+mod lint {
+    use tree_magic::{Lint, TypeDatabase};
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn empty_pattern() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n1>4=\0\0\n")
+            .unwrap()
+            .build();
+        let lints = db.validate();
+        assert!(lints.contains(&Lint::EmptyPattern {
+            mimetype: "application/x-foo",
+            rule: "1>4=".to_string(),
+        }));
+    }
+
+    #[test]
+    fn duplicate_rule() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .magic(b"MIME-Magic\0\n[50:application/x-bar]\n>0=\0\x03FOO\n")
+            .unwrap()
+            .build();
+        assert!(db.validate().contains(&Lint::DuplicateRule {
+            mimetypes: vec!["application/x-bar", "application/x-foo"],
+            rule: ">0=FOO".to_string(),
+        }));
+    }
+
+    #[test]
+    fn nested_rules_tell_apart() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x03FOO\n1>3=\0\x01a\n")
+            .unwrap()
+            .magic(b"MIME-Magic\0\n[50:application/x-bar]\n>0=\0\x03FOO\n1>3=\0\x01b\n")
+            .unwrap()
+            .build();
+        assert!(!db.validate().iter().any(|l| matches!(
+            l,
+            Lint::DuplicateRule { mimetypes, .. } if mimetypes.contains(&"application/x-foo")
+        )));
+    }
+
+    #[test]
+    fn unreachable_type() {
+        let db = TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n!>0=\0\x03FOO\n")
+            .unwrap()
+            .build();
+        assert!(db.validate().contains(&Lint::UnreachableType {
+            mimetype: "application/x-foo"
+        }));
+    }
+
+    #[test]
+    fn alias_cycle() {
+        let db = TypeDatabase::builder()
+            .alias("application/x-foo", "application/x-bar")
+            .alias("application/x-bar", "application/x-foo")
+            .build();
+        let cycles: Vec<_> = db
+            .validate()
+            .into_iter()
+            .filter(|l| match l {
+                Lint::AliasCycle { aliases } => aliases.contains(&"application/x-bar"),
+                _ => false,
+            })
+            .collect();
+        assert_eq!(cycles.len(), 1);
+    }
+
+    #[test]
+    fn system_database() {
+        // Real data shares rules between related types, and has an alias
+        // cycle or two, but every rule is usable
+        assert!(!tree_magic::validate_database()
+            .iter()
+            .any(|l| matches!(l, Lint::EmptyPattern { .. } | Lint::UnreachableType { .. })));
+    }
+}
@@ -0,0 +1,214 @@
+mod magic {
+    use tree_magic::TypeDatabase;
+    use tree_magic_mini as tree_magic;
+
+    const SYSTEM_MAGIC: &[u8] = include_bytes!("../magic_db/src/magic");
+
+    /// A database with a single type `application/x-foo`, given by the
+    /// rules of a magic file
+    fn foo(rules: &[u8]) -> TypeDatabase {
+        let mut data = b"MIME-Magic\0\n[50:application/x-foo]\n".to_vec();
+        data.extend_from_slice(rules);
+        TypeDatabase::builder().magic(&data).unwrap().build()
+    }
+
+    /// Value tests
+    #[test]
+    fn value_with_nul_newline_and_bracket() {
+        let db = foo(b">0=\0\x06\0\n[x]\0\n");
+        assert!(db.match_u8("application/x-foo", b"\0\n[x]\0rest"));
+        assert!(!db.match_u8("application/x-foo", b"\0\n[y]\0rest"));
+    }
+    #[test]
+    fn value_after_value_with_newline() {
+        // The second rule still belongs to the type after a value ending in a
+        // newline, as values are read by their length
+        let db = foo(b">0=\0\x02A\n\n>4=\0\x02B\n\n");
+        assert!(db.match_u8("application/x-foo", b"xxxxB\n"));
+    }
+    #[test]
+    fn long_offsets_and_indents() {
+        let db = foo(b">65536=\0\x02OK\n12>65538=\0\x01!\n");
+        let mut input = vec![0; 65540];
+        input[65536..65539].copy_from_slice(b"OK!");
+        assert!(db.match_u8("application/x-foo", &input));
+    }
+
+    /// Mask tests
+    #[test]
+    fn mask_ignores_value_bits_outside_it() {
+        // As in the rule for BMP, with the file size masked out
+        let db = foo(b">0=\0\x04BMxx&\xff\xff\0\0\n");
+        assert!(db.match_u8("application/x-foo", b"BM\x12\x34"));
+        assert!(!db.match_u8("application/x-foo", b"BN\x12\x34"));
+    }
+    #[test]
+    fn mask_with_range() {
+        let db = foo(b">0=\0\x02A\xff&\xff\x0f+4\n");
+        assert!(db.match_u8("application/x-foo", b"..A\x1f"));
+        assert!(!db.match_u8("application/x-foo", b"..A\x10"));
+    }
+
+    /// Word size tests
+    #[test]
+    fn word_size_swaps_on_little_endian() {
+        let db = foo(b">0=\0\x04\x01\x02\x03\x04~2\n");
+        let native: &[u8] = if cfg!(target_endian = "little") {
+            b"\x02\x01\x04\x03"
+        } else {
+            b"\x01\x02\x03\x04"
+        };
+        assert!(db.match_u8("application/x-foo", native));
+
+        let db = foo(b">0=\0\x04\x01\x02\x03\x04~4\n");
+        let native: &[u8] = if cfg!(target_endian = "little") {
+            b"\x04\x03\x02\x01"
+        } else {
+            b"\x01\x02\x03\x04"
+        };
+        assert!(db.match_u8("application/x-foo", native));
+    }
+    #[test]
+    fn word_size_one_is_bytes() {
+        let db = foo(b">0=\0\x02AB~1\n");
+        assert!(db.match_u8("application/x-foo", b"AB"));
+    }
+
+    /// Range tests
+    #[test]
+    fn range_is_count_of_offsets() {
+        let db = foo(b">1=\0\x01X+2\n");
+        assert!(db.match_u8("application/x-foo", b".X.."));
+        assert!(db.match_u8("application/x-foo", b"..X."));
+        assert!(!db.match_u8("application/x-foo", b"...X"));
+    }
+    #[test]
+    fn range_matches_short_data() {
+        // The range reaches past the end, but the value fits where it is
+        let db = foo(b">0=\0\x05<html+256\n");
+        assert!(db.match_u8("application/x-foo", b"  <html>"));
+        assert!(!db.match_u8("application/x-foo", b"  <htm"));
+    }
+
+    /// Tests against the compiled shared MIME-info database
+    struct Rule<'a> {
+        indent: u32,
+        offset: usize,
+        value: &'a [u8],
+        mask: Option<&'a [u8]>,
+        word: usize,
+    }
+
+    fn number(b: &[u8], pos: &mut usize) -> Option<usize> {
+        let start = *pos;
+        while b[*pos].is_ascii_digit() {
+            *pos += 1;
+        }
+        std::str::from_utf8(&b[start..*pos]).unwrap().parse().ok()
+    }
+
+    /// Reads the compiled file independently of the crate, as the names of
+    /// the types, their rules as written and as read
+    fn compiled_rules(b: &[u8]) -> Vec<(&str, &[u8], Vec<Rule<'_>>)> {
+        assert!(b.starts_with(b"MIME-Magic\0\n"));
+        let mut types: Vec<(&str, &[u8], Vec<Rule>)> = Vec::new();
+        let mut start = 0;
+        let mut pos = 12;
+        while pos < b.len() {
+            if b[pos] == b'[' {
+                if let Some(last) = types.last_mut() {
+                    last.1 = &b[start..pos];
+                }
+                let end = pos + b[pos..].iter().position(|&c| c == b'\n').unwrap();
+                let header = std::str::from_utf8(&b[pos + 1..end - 1]).unwrap();
+                types.push((header.split_once(':').unwrap().1, &[], Vec::new()));
+                pos = end + 1;
+                start = pos;
+                continue;
+            }
+            let indent = number(b, &mut pos).unwrap_or(0) as u32;
+            assert_eq!(b[pos], b'>');
+            pos += 1;
+            let offset = number(b, &mut pos).unwrap();
+            assert_eq!(b[pos], b'=');
+            let len = u16::from_be_bytes([b[pos + 1], b[pos + 2]]) as usize;
+            pos += 3;
+            let value = &b[pos..pos + len];
+            pos += len;
+            let mut mask = None;
+            if b[pos] == b'&' {
+                mask = Some(&b[pos + 1..pos + 1 + len]);
+                pos += 1 + len;
+            }
+            let mut word = 1;
+            if b[pos] == b'~' {
+                pos += 1;
+                word = number(b, &mut pos).unwrap();
+            }
+            if b[pos] == b'+' {
+                pos += 1;
+                number(b, &mut pos);
+            }
+            assert_eq!(b[pos], b'\n', "unknown field in rule at {}", pos);
+            pos += 1;
+            let rule = Rule {
+                indent,
+                offset,
+                value,
+                mask,
+                word,
+            };
+            types.last_mut().unwrap().2.push(rule);
+        }
+        if let Some(last) = types.last_mut() {
+            last.1 = &b[start..];
+        }
+        types
+    }
+
+    /// Data satisfying a single rule, with its value at its offset in the
+    /// byte order of the host
+    fn sample(rule: &Rule) -> Vec<u8> {
+        let mut data = vec![0; rule.offset + rule.value.len()];
+        let value = &mut data[rule.offset..];
+        value.copy_from_slice(rule.value);
+        if let Some(mask) = rule.mask {
+            value.iter_mut().zip(mask).for_each(|(v, m)| *v &= m);
+        }
+        if cfg!(target_endian = "little") && rule.word > 1 {
+            value.chunks_mut(rule.word).for_each(|w| w.reverse());
+        }
+        data
+    }
+
+    #[test]
+    fn compiled_database_every_type() {
+        let types = compiled_rules(SYSTEM_MAGIC);
+        assert!(types.len() > 400);
+
+        // Renamed, so no checker or system rule of the type gets involved
+        let mut data = b"MIME-Magic\0\n".to_vec();
+        let mut expected = Vec::new();
+        for (i, (mime, rules_text, rules)) in types.iter().enumerate() {
+            let name = format!("application/x-test-{}", i);
+            data.extend_from_slice(format!("[50:{}]\n", name).as_bytes());
+            data.extend_from_slice(rules_text);
+
+            // The first rule tree, when it's a single rule
+            if rules.len() == 1 || rules[1].indent == 0 {
+                expected.push((name, mime, sample(&rules[0])));
+            }
+        }
+
+        let db = TypeDatabase::builder().magic(&data).unwrap().build();
+        assert!(expected.len() > 300);
+        for (name, mime, sample) in &expected {
+            assert!(
+                db.match_u8(name, sample),
+                "{} ({}) doesn't match its own rule",
+                mime,
+                name
+            );
+        }
+    }
+}
@@ -10,6 +10,24 @@ mod match_u8 {
         ));
     }
     #[test]
+    fn image_gif_uppercase() {
+        assert!(tree_magic::match_u8(
+            "IMAGE/GIF",
+            include_bytes!("image/gif")
+        ));
+    }
+    #[test]
+    fn image_gif_parameters() {
+        assert!(tree_magic::match_u8(
+            "image/gif; q=0.8",
+            include_bytes!("image/gif")
+        ));
+        assert!(tree_magic::match_u8(
+            " Image/Gif ;charset=binary",
+            include_bytes!("image/gif")
+        ));
+    }
+    #[test]
     fn image_png() {
         assert!(tree_magic::match_u8(
             "image/png",
@@ -39,6 +57,7 @@ mod match_u8 {
         ));
     }
     #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
     fn image_x_pcx() {
         assert!(tree_magic::match_u8(
             "image/x-pcx",
@@ -46,6 +65,7 @@ mod match_u8 {
         ));
     }
     #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
     fn image_x_tga() {
         assert!(tree_magic::match_u8(
             "image/x-tga",
@@ -62,6 +82,34 @@ mod match_u8 {
         ));
     }
     #[test]
+    fn application_x_tar_v7() {
+        assert!(tree_magic::match_u8(
+            "application/x-tar",
+            include_bytes!("application/x-tar-v7")
+        ));
+    }
+    #[test]
+    fn application_x_tar_bad_checksum() {
+        let mut bytes = [b'A'; 512];
+        bytes[257..265].copy_from_slice(b"ustar\x0000");
+        assert!(!tree_magic::match_u8("application/x-tar", &bytes));
+    }
+    #[test]
+    fn application_x_compressed_tar() {
+        assert!(tree_magic::match_u8(
+            "application/x-compressed-tar",
+            include_bytes!("application/x-compressed-tar-named")
+        ));
+        assert!(tree_magic::match_u8(
+            "application/gzip",
+            include_bytes!("application/x-compressed-tar-named")
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/x-compressed-tar",
+            include_bytes!("application/gzip")
+        ));
+    }
+    #[test]
     fn application_x_7z() {
         assert!(tree_magic::match_u8(
             "application/x-7z-compressed",
@@ -84,6 +132,70 @@ mod match_u8 {
             include_bytes!("text/plain")
         ));
     }
+    #[test]
+    fn application_x_zerosize() {
+        assert!(tree_magic::match_u8("application/x-zerosize", b""));
+        assert!(!tree_magic::match_u8("application/x-zerosize", b" "));
+    }
+
+    // Subtitle and playlist tests
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn subrip_dot_separator() {
+        let input = b"1\n00:00:01.000 --> 00:00:02.500\nHello\n";
+        assert!(tree_magic::match_u8("application/x-srt", input));
+    }
+    #[test]
+    fn subrip_needs_times() {
+        let input = b"1\nHello\n\n2\nWorld\n";
+        assert!(!tree_magic::match_u8("application/x-subrip", input));
+    }
+    #[test]
+    fn vtt_header_word() {
+        assert!(tree_magic::match_u8("text/vtt", b"\xef\xbb\xbfWEBVTT\n\n"));
+        assert!(tree_magic::match_u8("text/vtt", b"\n\nwebvtt\tcaptions\n"));
+    }
+    #[test]
+    fn m3u_hls_is_m3u() {
+        let input = include_bytes!("application/vnd.apple.mpegurl");
+        assert!(tree_magic::match_u8("audio/x-mpegurl", input));
+        assert!(!tree_magic::match_u8(
+            "application/x-mpegurl",
+            include_bytes!("audio/x-mpegurl")
+        ));
+    }
+
+    // Certificate and key tests
+    #[test]
+    fn pem_after_text() {
+        let input = include_bytes!("application/pkix-cert+pem");
+        assert!(!input.starts_with(b"-----"));
+        assert!(tree_magic::match_u8("application/x-pem-file", input));
+        assert!(!tree_magic::match_u8("application/pkix-cert", input));
+    }
+    #[test]
+    fn pem_needs_contents() {
+        let input = b"-----BEGIN CERTIFICATE-----\nnot base64!\n";
+        assert!(!tree_magic::match_u8("application/x-pem-file", input));
+    }
+    #[test]
+    fn pgp_armor_not_pem() {
+        assert!(!tree_magic::match_u8(
+            "application/x-pem-file",
+            include_bytes!("application/pgp-keys")
+        ));
+    }
+    #[test]
+    fn der_cert_alias() {
+        assert!(tree_magic::match_u8(
+            "application/x-x509-cert",
+            include_bytes!("application/pkix-cert")
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/pkix-cert",
+            include_bytes!("application/pkcs12")
+        ));
+    }
 
     // Audio tests
     #[test]
@@ -108,10 +220,733 @@ mod match_u8 {
         ));
     }
     #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
     fn audio_wav() {
         assert!(tree_magic::match_u8(
             "audio/wav",
             include_bytes!("audio/wav")
         ));
     }
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn audio_wav_rf64() {
+        let mut input = include_bytes!("audio/wav").to_vec();
+        input[..4].copy_from_slice(b"RF64");
+        assert!(tree_magic::match_u8("audio/wav", &input));
+    }
+    #[test]
+    fn audio_wav_not_webp() {
+        assert!(!tree_magic::match_u8(
+            "image/webp",
+            include_bytes!("audio/wav")
+        ));
+    }
+
+    #[test]
+    fn audio_mpeg_frames_disagree() {
+        // A Layer II frame of 626 bytes, then a Layer I header
+        let mut input = include_bytes!("audio/mpeg-layer2")[..626].to_vec();
+        input.extend_from_slice(b"\xff\xff\xa0\xc4");
+        input.resize(2 * 626, 0);
+        assert!(!tree_magic::match_u8("audio/mpeg", &input));
+
+        input[627] = 0xfd;
+        assert!(tree_magic::match_u8("audio/mpeg", &input));
+    }
+    #[test]
+    fn audio_aac_not_mpeg() {
+        assert!(!tree_magic::match_u8(
+            "audio/mpeg",
+            include_bytes!("audio/aac")
+        ));
+    }
+    #[test]
+    fn audio_flac_after_id3() {
+        let mut input = b"ID3\x03\x00\x00\x00\x00\x00\x04\x00\x00\x00\x00".to_vec();
+        input.extend_from_slice(&include_bytes!("audio/flac")[..64]);
+        assert!(tree_magic::match_u8("audio/flac", &input));
+        assert_eq!(tree_magic::from_u8(&input), "audio/flac");
+    }
+    #[test]
+    fn audio_opus_after_skeleton() {
+        let opus = include_bytes!("audio/opus");
+        let mut input = b"OggS\x00\x02".to_vec();
+        input.resize(26, 0);
+        input.extend_from_slice(b"\x01\x10fishead\x00\x00\x00\x00\x00\x00\x00\x00\x00");
+        input.extend_from_slice(&opus[..]);
+        assert!(tree_magic::match_u8("audio/opus", &input));
+        assert!(!tree_magic::match_u8("video/ogg", &input));
+    }
+
+    // Video tests
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn video_avi() {
+        assert!(tree_magic::match_u8(
+            "video/avi",
+            include_bytes!("video/x-msvideo")
+        ));
+    }
+
+    // Font tests
+    #[test]
+    fn font_ttf_alias() {
+        assert!(tree_magic::match_u8(
+            "application/x-font-ttf",
+            include_bytes!("font/ttf")
+        ));
+    }
+    #[test]
+    fn font_otf_is_ttf() {
+        assert!(tree_magic::match_u8("font/ttf", include_bytes!("font/otf")));
+        assert!(!tree_magic::match_u8(
+            "font/otf",
+            include_bytes!("font/ttf")
+        ));
+    }
+    #[test]
+    fn font_ttf_bad_directory() {
+        let mut input = include_bytes!("font/ttf").to_vec();
+        // Table offset pointing into the directory
+        input[12 + 8..12 + 12].copy_from_slice(&[0, 0, 0, 4]);
+        assert!(!tree_magic::match_u8("font/ttf", &input));
+    }
+
+    // Executable tests
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn application_java_class_alias() {
+        assert!(tree_magic::match_u8(
+            "application/java-vm",
+            include_bytes!("application/x-java")
+        ));
+    }
+    #[test]
+    fn application_fat_mach_o_not_java() {
+        assert!(!tree_magic::match_u8(
+            "application/x-java",
+            include_bytes!("application/x-mach-binary")
+        ));
+    }
+
+    // Mail tests
+    #[test]
+    fn message_rfc822_line_endings() {
+        let input = include_bytes!("message/rfc822");
+        let lf: Vec<u8> = input.iter().copied().filter(|&c| c != b'\r').collect();
+        let cr: Vec<u8> = lf
+            .iter()
+            .map(|&c| if c == b'\n' { b'\r' } else { c })
+            .collect();
+        for input in [&input[..], &lf, &cr] {
+            assert!(tree_magic::match_u8("message/rfc822", input));
+        }
+    }
+    #[test]
+    fn message_rfc822_leading_space() {
+        assert!(tree_magic::match_u8(
+            "message/rfc822",
+            b"\n\n  Received: from example.org\n\tby example.com\nX-Spam: no\n\nHi\n"
+        ));
+    }
+    #[test]
+    fn message_rfc822_not_prose() {
+        assert!(!tree_magic::match_u8(
+            "message/rfc822",
+            b"Note: this is not a message.\nIt only starts like one.\n"
+        ));
+        assert!(!tree_magic::match_u8(
+            "message/rfc822",
+            b"Key: value\nOther: value\n\n"
+        ));
+    }
+    #[test]
+    fn application_mbox_not_message() {
+        assert!(!tree_magic::match_u8(
+            "message/rfc822",
+            include_bytes!("application/mbox")
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/mbox",
+            include_bytes!("message/rfc822")
+        ));
+    }
+    #[test]
+    fn text_calendar_alias() {
+        assert!(tree_magic::match_u8(
+            "application/ics",
+            include_bytes!("text/calendar")
+        ));
+        assert!(!tree_magic::match_u8(
+            "text/calendar",
+            include_bytes!("text/vcard")
+        ));
+    }
+
+    // SQLite tests
+    #[test]
+    fn application_x_sqlite3_alias() {
+        assert!(tree_magic::match_u8(
+            "application/x-sqlite3",
+            include_bytes!("application/vnd.sqlite3")
+        ));
+    }
+    #[test]
+    fn application_x_sqlite3_wal_bad_checksum() {
+        let mut input = include_bytes!("application/x-sqlite3-wal").to_vec();
+        // Salt, which the header checksum covers
+        input[16] ^= 1;
+        assert!(!tree_magic::match_u8("application/x-sqlite3-wal", &input));
+    }
+    #[test]
+    fn application_x_sqlite3_shm_copies_differ() {
+        let mut input = include_bytes!("application/x-sqlite3-shm").to_vec();
+        input[48 + 16] ^= 1;
+        assert!(!tree_magic::match_u8("application/x-sqlite3-shm", &input));
+    }
+    #[test]
+    fn application_x_sqlite3_siblings_distinct() {
+        let inputs: [(&str, &[u8]); 4] = [
+            (
+                "application/vnd.sqlite3",
+                include_bytes!("application/vnd.sqlite3"),
+            ),
+            (
+                "application/x-sqlite3-wal",
+                include_bytes!("application/x-sqlite3-wal"),
+            ),
+            (
+                "application/x-sqlite3-shm",
+                include_bytes!("application/x-sqlite3-shm"),
+            ),
+            (
+                "application/x-sqlite3-journal",
+                include_bytes!("application/x-sqlite3-journal"),
+            ),
+        ];
+        for (mime, _) in inputs {
+            for (other, input) in inputs {
+                assert_eq!(tree_magic::match_u8(mime, input), mime == other);
+            }
+        }
+    }
+
+    // Data serialization format tests
+    #[test]
+    fn application_vnd_apache_orc_needs_postscript() {
+        let input = include_bytes!("application/vnd.apache.orc");
+        assert!(!tree_magic::match_u8(
+            "application/vnd.apache.orc",
+            &input[..input.len() - 1]
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/vnd.apache.orc",
+            b"ORCHESTRA"
+        ));
+    }
+    #[test]
+    fn application_bson_length_mismatch() {
+        let mut input = include_bytes!("application/bson").to_vec();
+        assert!(tree_magic::match_u8("application/bson", &input));
+        input.push(0);
+        assert!(!tree_magic::match_u8("application/bson", &input));
+    }
+    #[test]
+    fn application_vnd_msgpack_whole_input() {
+        let mut input = include_bytes!("application/vnd.msgpack").to_vec();
+        assert!(tree_magic::match_u8("application/x-msgpack", &input));
+        input.push(0xc0);
+        assert!(!tree_magic::match_u8("application/vnd.msgpack", &input));
+        // Keys must be strings
+        assert!(!tree_magic::match_u8(
+            "application/vnd.msgpack",
+            b"\x81\x01\x02"
+        ));
+    }
+    #[test]
+    fn data_formats_distinct() {
+        let inputs: [(&str, &[u8]); 6] = [
+            (
+                "application/vnd.apache.parquet",
+                include_bytes!("application/vnd.apache.parquet"),
+            ),
+            (
+                "application/vnd.apache.avro",
+                include_bytes!("application/vnd.apache.avro"),
+            ),
+            (
+                "application/vnd.apache.orc",
+                include_bytes!("application/vnd.apache.orc"),
+            ),
+            ("application/cbor", include_bytes!("application/cbor")),
+            ("application/bson", include_bytes!("application/bson")),
+            (
+                "application/vnd.msgpack",
+                include_bytes!("application/vnd.msgpack"),
+            ),
+        ];
+        for (mime, _) in inputs {
+            for (other, input) in inputs {
+                assert_eq!(tree_magic::match_u8(mime, input), mime == other);
+            }
+        }
+    }
+
+    // JSON tests
+    #[test]
+    fn json_is_json5() {
+        assert!(tree_magic::match_u8(
+            "application/json5",
+            include_bytes!("application/json")
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/json",
+            include_bytes!("application/json5")
+        ));
+    }
+    #[test]
+    fn json_cut_off() {
+        // Valid as far as it goes, but only when there's more to come
+        let mut input = b"[".to_vec();
+        while input.len() < 8192 {
+            input.extend_from_slice(b"{\"id\": 1, \"tags\": [\"a\", \"b\"]}, ");
+        }
+        assert!(tree_magic::match_u8("application/json", &input));
+        assert!(!tree_magic::match_u8("application/json", b"[{\"id\": 1}, "));
+    }
+    #[test]
+    fn json_scalar() {
+        assert!(!tree_magic::match_u8(
+            "application/json",
+            b"\"just a string\""
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/json",
+            b"{\"a\": 1} trailing"
+        ));
+    }
+    #[test]
+    fn ndjson_single_line() {
+        assert!(!tree_magic::match_u8(
+            "application/x-ndjson",
+            b"{\"a\": 1}\n"
+        ));
+        assert!(tree_magic::match_u8(
+            "application/jsonl",
+            b"{\"a\": 1}\n[2]\n"
+        ));
+    }
+
+    // YAML and TOML tests
+    #[test]
+    fn yaml_registered_name() {
+        assert!(tree_magic::match_u8(
+            "application/yaml",
+            include_bytes!("application/x-yaml")
+        ));
+    }
+    #[test]
+    fn yaml_markdown() {
+        // A list, and front matter followed by prose
+        assert!(!tree_magic::match_u8(
+            "application/yaml",
+            b"- milk\n- eggs\n"
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/yaml",
+            b"---\ntitle: Post\n---\n# Heading\n\nSome text.\n"
+        ));
+    }
+    #[test]
+    fn toml_ini() {
+        // Section names with spaces, and unquoted strings
+        assert!(!tree_magic::match_u8(
+            "application/toml",
+            b"[Desktop Entry]\nName=Foo\n"
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/toml",
+            b"[section]\nname=John\n"
+        ));
+    }
+
+    // Container image tests
+    #[test]
+    fn oci_media_type_wins() {
+        // A descriptor list nested under other keys doesn't count
+        let input =
+            br#"{"schemaVersion": 2, "mediaType": "application/vnd.oci.image.index.v1+json",
+            "manifests": [{"mediaType": "application/vnd.oci.image.manifest.v1+json"}]}"#;
+        assert!(tree_magic::match_u8(
+            "application/vnd.oci.image.index.v1+json",
+            input
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/vnd.oci.image.manifest.v1+json",
+            input
+        ));
+    }
+    #[test]
+    fn oci_unknown_media_type() {
+        let input = br#"{"schemaVersion": 2, "mediaType": "application/x-foo",
+            "config": {}, "layers": []}"#;
+        assert_eq!(tree_magic::from_u8(input), "application/json");
+    }
+    #[test]
+    fn oci_config_alias() {
+        assert!(tree_magic::match_u8(
+            "application/vnd.docker.container.image.v1+json",
+            include_bytes!("application/vnd.oci.image.config.v1+json")
+        ));
+    }
+    #[test]
+    fn oci_layer_alias() {
+        assert!(tree_magic::match_u8(
+            "application/vnd.oci.image.layer.v1.tar",
+            include_bytes!("application/x-tar")
+        ));
+    }
+
+    // Executable tests
+    #[test]
+    fn executable_dll_is_pe() {
+        let input = include_bytes!("application/x-msdownload");
+        assert!(tree_magic::match_u8(
+            "application/vnd.microsoft.portable-executable",
+            input
+        ));
+        assert!(tree_magic::match_u8(
+            "application/x-msdos-executable",
+            input
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/x-msdownload",
+            include_bytes!("application/vnd.microsoft.portable-executable")
+        ));
+    }
+    #[test]
+    fn executable_pie_not_sharedlib() {
+        let input = include_bytes!("application/x-pie-executable");
+        assert!(tree_magic::match_u8("application/x-executable", input));
+        assert!(!tree_magic::match_u8("application/x-sharedlib", input));
+        assert!(!tree_magic::match_u8(
+            "application/x-pie-executable",
+            include_bytes!("application/x-sharedlib")
+        ));
+    }
+
+    // Disk image tests
+    #[test]
+    fn disk_image_boot_sector_not_partitioned() {
+        // Boot code runs through where the partition table would be
+        let mut input = [0x90u8; 512];
+        input[510] = 0x55;
+        input[511] = 0xaa;
+        assert!(!tree_magic::match_u8(
+            "application/x-raw-disk-image",
+            &input
+        ));
+    }
+    #[test]
+    fn disk_image_fixed_vhd_not_raw() {
+        let input = include_bytes!("application/x-vhd-disk");
+        assert!(!tree_magic::match_u8("application/x-raw-disk-image", input));
+        assert!(tree_magic::match_u8("application/x-virtualbox-vhd", input));
+    }
+    #[test]
+    fn disk_image_cd_image_is_raw() {
+        assert!(tree_magic::match_u8(
+            "application/x-raw-disk-image",
+            include_bytes!("application/x-cd-image")
+        ));
+    }
+
+    // Filesystem tests
+    #[test]
+    fn filesystem_ext3_has_journal() {
+        let mut input = include_bytes!("application/x-ext2").to_vec();
+        // The has_journal compatible feature
+        input[1024 + 0x5c] |= 0x4;
+        assert!(tree_magic::match_u8("application/x-ext3", &input));
+        assert!(!tree_magic::match_u8("application/x-ext2", &input));
+        assert!(!tree_magic::match_u8("application/x-ext4", &input));
+    }
+    #[test]
+    fn filesystem_ntfs_not_fat() {
+        let input = include_bytes!("application/x-ntfs");
+        assert!(!tree_magic::match_u8("application/x-fat", input));
+        assert!(tree_magic::match_u8("application/x-raw-disk-image", input));
+    }
+    #[test]
+    fn filesystem_mbr_not_fat() {
+        assert!(!tree_magic::match_u8(
+            "application/x-vfat",
+            include_bytes!("application/x-raw-disk-image")
+        ));
+    }
+
+    // 3D model tests
+    #[test]
+    fn model_stl_alias() {
+        assert!(tree_magic::match_u8(
+            "application/sla",
+            include_bytes!("model/stl-ascii")
+        ));
+    }
+    #[test]
+    fn model_obj_needs_vertices() {
+        assert!(!tree_magic::match_u8("model/obj", b"o Empty\ng group\n"));
+        assert!(!tree_magic::match_u8(
+            "model/obj",
+            b"v 0 0 0\nv 1 0 0\nvery 0 1 0\n"
+        ));
+    }
+    #[test]
+    fn model_ply_needs_format() {
+        assert!(!tree_magic::match_u8(
+            "model/x-ply",
+            b"ply\nelement vertex 1\n"
+        ));
+    }
+    #[test]
+    fn model_gltf_needs_asset() {
+        assert!(!tree_magic::match_u8(
+            "model/gltf+json",
+            b"{\"scenes\": [], \"version\": \"2.0\"}"
+        ));
+    }
+    #[test]
+    fn model_3mf_not_zip_only() {
+        assert!(!tree_magic::match_u8(
+            "model/3mf",
+            include_bytes!("application/zip")
+        ));
+    }
+
+    // Geospatial tests
+    #[test]
+    fn geojson_geometry_needs_coordinates() {
+        let input = br#"{"type": "Point", "coordinates": [4.35, 50.85]}"#;
+        assert!(tree_magic::match_u8("application/geo+json", input));
+        let input = br#"{"type": "Point", "label": "Start"}"#;
+        assert!(!tree_magic::match_u8("application/geo+json", input));
+    }
+    #[test]
+    fn geojson_nested_type() {
+        let input = br#"{"data": {"type": "Feature"}}"#;
+        assert!(!tree_magic::match_u8("application/geo+json", input));
+    }
+    #[test]
+    fn shapefile_length_mismatch() {
+        let input = include_bytes!("application/x-shapefile");
+        assert!(!tree_magic::match_u8(
+            "application/x-shapefile",
+            &input[..input.len() - 1]
+        ));
+    }
+    #[test]
+    fn kml_prefixed_root() {
+        let input =
+            b"<?xml version=\"1.0\"?>\n<kml:kml xmlns:kml=\"http://www.opengis.net/kml/2.2\"/>";
+        assert!(tree_magic::match_u8(
+            "application/vnd.google-earth.kml+xml",
+            input
+        ));
+        assert!(!tree_magic::match_u8("application/gpx+xml", input));
+    }
+    #[test]
+    fn geotiff_is_tiff() {
+        assert!(tree_magic::match_u8(
+            "image/tiff",
+            include_bytes!("image/x-geotiff")
+        ));
+        assert!(!tree_magic::match_u8(
+            "image/x-geotiff",
+            include_bytes!("image/tiff")
+        ));
+    }
+
+    // Scientific data tests
+    #[test]
+    fn netcdf4_is_hdf5() {
+        let mut input = include_bytes!("application/x-hdf").to_vec();
+        input.extend_from_slice(b"\x01\x00_NCProperties\x00version=2");
+        assert!(tree_magic::match_u8("application/x-netcdf", &input));
+        assert!(!tree_magic::match_u8(
+            "application/x-netcdf",
+            include_bytes!("application/x-hdf")
+        ));
+    }
+    #[test]
+    fn fits_aliases() {
+        let input = include_bytes!("application/fits");
+        assert!(tree_magic::match_u8("image/fits", input));
+        assert!(tree_magic::match_u8("image/x-fits", input));
+    }
+    #[test]
+    fn dicom_needs_ordered_elements() {
+        let mut input = include_bytes!("application/dicom-raw").to_vec();
+        // The second element, given the tag of the first
+        input[36] = 0x16;
+        assert!(!tree_magic::match_u8("application/dicom", &input));
+    }
+    #[test]
+    fn nifti_big_endian_pair() {
+        let mut input = include_bytes!("application/x-nifti").to_vec();
+        input[..4].copy_from_slice(&348u32.to_be_bytes());
+        input[344..348].copy_from_slice(b"ni1\0");
+        assert!(tree_magic::match_u8("application/x-nifti", &input));
+        // ANALYZE 7.5 has the same size of header, and no magic
+        input[344..348].copy_from_slice(&[0; 4]);
+        assert!(!tree_magic::match_u8("application/x-nifti", &input));
+    }
+
+    // eBook tests
+    #[test]
+    fn ebook_kindle_is_mobipocket() {
+        for input in [
+            &include_bytes!("application/vnd.amazon.mobi8-ebook")[..],
+            include_bytes!("application/vnd.amazon.ebook"),
+        ] {
+            assert!(tree_magic::match_u8(
+                "application/x-mobipocket-ebook",
+                input
+            ));
+        }
+        assert!(!tree_magic::match_u8(
+            "application/vnd.amazon.ebook",
+            include_bytes!("application/x-mobipocket-ebook")
+        ));
+    }
+    #[test]
+    fn ebook_palmdoc_not_mobipocket() {
+        assert!(!tree_magic::match_u8(
+            "application/x-mobipocket-ebook",
+            include_bytes!("application/x-aportisdoc")
+        ));
+    }
+    #[test]
+    fn ebook_zip_not_epub() {
+        assert!(!tree_magic::match_u8(
+            "application/epub+zip",
+            include_bytes!("application/zip")
+        ));
+    }
+
+    // Archive format tests
+    #[test]
+    fn arj_needs_main_header() {
+        assert!(!tree_magic::match_u8(
+            "application/x-arj",
+            b"\x60\xea\x00\x00"
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/x-arj",
+            b"\x60\xeaHello, world! This is just text."
+        ));
+    }
+    #[test]
+    fn zstd_after_skippable_frame() {
+        let mut input = b"\x5e\x2a\x4d\x18\x04\x00\x00\x00meta".to_vec();
+        input.extend_from_slice(include_bytes!("application/zstd"));
+        assert!(tree_magic::match_u8("application/zstd", &input));
+        assert!(tree_magic::match_u8("application/x-zstd", &input));
+    }
+    #[test]
+    fn ar_not_deb() {
+        let input = include_bytes!("application/x-archive");
+        assert!(!tree_magic::match_u8(
+            "application/vnd.debian.binary-package",
+            input
+        ));
+        assert!(tree_magic::match_u8(
+            "application/x-archive",
+            include_bytes!("application/vnd.debian.binary-package")
+        ));
+    }
+    #[test]
+    fn ar_thin() {
+        assert!(tree_magic::match_u8("application/x-archive", b"!<thin>\n"));
+    }
+
+    // Package tests
+    #[test]
+    fn rpm_needs_signature_header() {
+        let mut input = include_bytes!("application/x-rpm").to_vec();
+        input[96] = 0;
+        assert!(!tree_magic::match_u8("application/x-rpm", &input));
+        assert!(tree_magic::match_u8(
+            "application/x-rpm",
+            include_bytes!("application/x-source-rpm")
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/x-source-rpm",
+            include_bytes!("application/x-rpm")
+        ));
+    }
+    #[test]
+    fn android_package_needs_manifest() {
+        assert!(!tree_magic::match_u8(
+            "application/vnd.android.package-archive",
+            include_bytes!("application/zip")
+        ));
+        assert!(tree_magic::match_u8(
+            "application/zip",
+            include_bytes!("application/vnd.android.package-archive")
+        ));
+    }
+    #[test]
+    fn snap_is_squashfs() {
+        assert!(tree_magic::match_u8(
+            "application/vnd.squashfs",
+            include_bytes!("application/vnd.snap")
+        ));
+        assert!(!tree_magic::match_u8(
+            "application/vnd.snap",
+            include_bytes!("application/vnd.squashfs")
+        ));
+    }
+
+    // Matchers
+    #[test]
+    fn matcher_same_as_match_u8() {
+        let inputs: &[&[u8]] = &[
+            include_bytes!("image/gif"),
+            include_bytes!("application/x-tar"),
+            include_bytes!("application/msword"),
+            include_bytes!("font/ttf"),
+            b"Hello, world!",
+        ];
+        for mime in [
+            "image/gif",
+            "application/x-tar",
+            "application/msword",
+            "font/ttf",
+        ] {
+            let m = tree_magic::matcher(mime).unwrap();
+            for input in inputs {
+                assert_eq!(m.matches_u8(input), tree_magic::match_u8(mime, input));
+            }
+        }
+    }
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn matcher_alias() {
+        let m = tree_magic::matcher("Application/Java-VM; q=0.5").unwrap();
+        assert_eq!(m.mimetype(), "application/x-java");
+        assert!(m.matches_u8(include_bytes!("application/x-java")));
+    }
+    #[test]
+    fn matcher_unknown() {
+        assert!(tree_magic::matcher("application/x-no-such-type").is_none());
+    }
+    #[test]
+    fn matcher_exclusion() {
+        let db = tree_magic::TypeDatabase::builder()
+            .exclude("application/zip", 4, b"PLAIN")
+            .build();
+        let m = db.matcher("application/zip").unwrap();
+        assert!(!m.matches_u8(b"PK\x03\x04PLAIN"));
+        assert!(m.matches_u8(include_bytes!("application/zip")));
+    }
 }
@@ -0,0 +1,80 @@
+#![cfg(all(feature = "builtin-minimal", not(feature = "with-gpl-data")))]
+
+mod minimal {
+    use tree_magic_mini as tree_magic;
+
+    /// Magic tests
+    #[test]
+    fn images() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/png")),
+            "image/png"
+        );
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/gif")),
+            "image/gif"
+        );
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("image/bmp")),
+            "image/bmp"
+        );
+    }
+    #[test]
+    fn archives() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/zip")),
+            "application/zip"
+        );
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/gzip")),
+            "application/gzip"
+        );
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-7z-compressed")),
+            "application/x-7z-compressed"
+        );
+    }
+    #[test]
+    fn executables() {
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("application/x-msdownload")),
+            "application/x-msdownload"
+        );
+    }
+    #[test]
+    fn html_is_text() {
+        assert_eq!(
+            tree_magic::from_u8(b"<!DOCTYPE html>\n<html></html>\n"),
+            "text/html"
+        );
+        assert!(tree_magic::match_u8("text/plain", b"<html></html>\n"));
+    }
+
+    /// Checker tests
+    #[test]
+    fn checkers_fall_back_to_magic() {
+        // Ogg streams without a recognized codec
+        assert!(tree_magic::match_u8(
+            "application/ogg",
+            include_bytes!("audio/ogg")
+        ));
+        assert_eq!(
+            tree_magic::from_u8(include_bytes!("audio/opus")),
+            "audio/x-opus+ogg"
+        );
+    }
+
+    /// Alias tests
+    #[test]
+    fn aliases() {
+        assert!(tree_magic::match_u8(
+            "image/x-ms-bmp",
+            include_bytes!("image/bmp")
+        ));
+    }
+
+    #[test]
+    fn no_lints() {
+        assert_eq!(tree_magic::validate_database(), []);
+    }
+}
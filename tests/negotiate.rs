@@ -0,0 +1,114 @@
+mod negotiate {
+    use tree_magic::negotiate;
+    use tree_magic_mini as tree_magic;
+
+    /// Range tests
+    #[test]
+    fn exact_type() {
+        assert_eq!(negotiate("image/png", "image/png"), Some("image/png"));
+        assert_eq!(negotiate("image/gif", "image/png"), None);
+    }
+    #[test]
+    fn wildcards() {
+        assert_eq!(negotiate("image/*", "image/png"), Some("image/png"));
+        assert_eq!(negotiate("*/*", "image/png"), Some("image/png"));
+        assert_eq!(negotiate("audio/*", "image/png"), None);
+    }
+    #[test]
+    fn case_and_whitespace() {
+        assert_eq!(
+            negotiate(" IMAGE/PNG ; Q=0.5 , text/html", "image/png"),
+            Some("image/png")
+        );
+    }
+    #[test]
+    fn malformed_ranges_are_skipped() {
+        assert_eq!(
+            negotiate("*/png, image, image/png;q=2, image/*;q=0.5", "image/png"),
+            Some("image/png")
+        );
+        assert_eq!(negotiate("*/png, image/png;q=2", "image/png"), None);
+    }
+    #[test]
+    fn empty_header() {
+        assert_eq!(negotiate("", "image/png"), Some("image/png"));
+    }
+
+    /// Quality tests
+    #[test]
+    fn zero_quality_refuses() {
+        assert_eq!(
+            negotiate("*/*, image/png;q=0", "image/png"),
+            Some("application/octet-stream")
+        );
+        assert_eq!(negotiate("image/*;q=0", "image/png"), None);
+    }
+    #[test]
+    fn most_specific_range_decides() {
+        // The exact range outranks the wildcard, despite its lower quality
+        assert_eq!(
+            negotiate(
+                "image/*, image/png;q=0.1, application/octet-stream;q=0.5",
+                "image/png"
+            ),
+            Some("application/octet-stream")
+        );
+    }
+    #[test]
+    fn detected_type_wins_ties() {
+        assert_eq!(
+            negotiate("application/octet-stream, image/png", "image/png"),
+            Some("image/png")
+        );
+    }
+
+    /// Subclass tests
+    #[test]
+    fn parent_type() {
+        assert_eq!(negotiate("text/plain", "text/x-csrc"), Some("text/plain"));
+        assert_eq!(
+            negotiate("text/html, application/xml;q=0.9", "image/svg+xml"),
+            Some("application/xml")
+        );
+    }
+    #[test]
+    fn nearest_parent_first() {
+        // SVG is XML, which is text
+        assert_eq!(
+            negotiate("text/plain, application/xml", "image/svg+xml"),
+            Some("application/xml")
+        );
+    }
+    #[test]
+    fn higher_quality_parent() {
+        assert_eq!(
+            negotiate("image/*;q=0.2, text/*", "image/svg+xml"),
+            Some("text/plain")
+        );
+    }
+
+    /// Alias tests
+    #[test]
+    fn detected_alias() {
+        assert_eq!(
+            negotiate("application/zip", "application/x-zip-compressed"),
+            Some("application/zip")
+        );
+    }
+    #[test]
+    fn accepted_alias() {
+        // Sent under the name the client knows
+        assert_eq!(
+            negotiate("application/x-zip-compressed", "application/zip"),
+            Some("application/x-zip-compressed")
+        );
+    }
+
+    /// Unknown type tests
+    #[test]
+    fn unknown_type() {
+        let mime = "application/x-tree-magic-none";
+        assert_eq!(negotiate("application/*", mime), Some(mime));
+        assert_eq!(negotiate("text/*", mime), None);
+    }
+}
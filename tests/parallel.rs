@@ -0,0 +1,41 @@
+#![cfg(feature = "rayon")]
+
+mod parallel {
+    use tree_magic_mini as tree_magic;
+
+    /// Pads the data past the size checked in parallel
+    fn large(head: &[u8], fill: u8) -> Vec<u8> {
+        let mut out = head.to_vec();
+        out.resize(256 * 1024, fill);
+        out
+    }
+
+    /// Slices are checked in parallel, `MultiSlice` sequentially
+    fn assert_same(bytes: &[u8]) {
+        assert_eq!(
+            tree_magic::from_u8(bytes),
+            tree_magic::from_u8_multi(&[bytes])
+        );
+    }
+
+    #[test]
+    fn text() {
+        assert_same(&large(b"Hello, world!\n", b' '));
+        assert_same(&large(b"#!/bin/sh\necho hi\n", b'\n'));
+        assert_same(&large(b"<?xml version=\"1.0\"?>\n<svg", b' '));
+        assert_eq!(
+            tree_magic::from_u8(&large(b"#!/bin/sh\n", b'\n')),
+            "application/x-shellscript"
+        );
+    }
+    #[test]
+    fn binary() {
+        assert_same(&large(include_bytes!("image/gif"), 0));
+        assert_same(&large(include_bytes!("application/zip"), 0));
+        assert_same(&large(b"\x00\x01\x02", 0));
+        assert_eq!(
+            tree_magic::from_u8(&large(include_bytes!("image/png"), 0)),
+            "image/png"
+        );
+    }
+}
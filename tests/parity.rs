@@ -0,0 +1,22 @@
+//! Checks that detecting from a path gives what detecting from the bytes
+//! does, on the files in `tests/` or `TREE_MAGIC_CORPUS`.
+#![cfg(feature = "testing")]
+
+mod parity {
+    use tree_magic_mini::testing::{corpus, parity};
+
+    #[test]
+    fn corpus_parity() {
+        let files = corpus();
+        assert!(!files.is_empty());
+        assert_eq!(parity(&files).unwrap(), vec![]);
+    }
+    #[test]
+    fn skips_directories() {
+        assert_eq!(parity(["tests", "tests/image"]).unwrap(), vec![]);
+    }
+    #[test]
+    fn missing_file() {
+        assert!(parity(["tests/missing"]).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+mod polyglot {
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn image_png() {
+        assert_eq!(
+            tree_magic::detect_polyglot(include_bytes!("image/png")),
+            vec!["image/png"]
+        );
+    }
+    #[test]
+    fn application_zip() {
+        assert_eq!(
+            tree_magic::detect_polyglot(include_bytes!("application/zip")),
+            vec!["application/zip"]
+        );
+    }
+    #[test]
+    fn gif_and_pdf() {
+        // PDF readers look for the header anywhere in the first kilobyte
+        let mut input = include_bytes!("image/gif").to_vec();
+        input.extend_from_slice(b"%PDF-1.4\n");
+        input.resize(2048, b' ');
+        let found = tree_magic::detect_polyglot(&input);
+        assert_eq!(found, vec!["application/pdf", "image/gif"]);
+    }
+}
@@ -0,0 +1,32 @@
+#![cfg(all(feature = "registry", windows))]
+
+mod registry {
+    use tree_magic::registry::{from_extension, register_extension, to_extension};
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn system_types() {
+        // Registered by every Windows install
+        assert_eq!(from_extension("txt"), Some("text/plain"));
+        assert_eq!(from_extension(".txt"), Some("text/plain"));
+        assert_eq!(to_extension("image/png").as_deref(), Some(".png"));
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(from_extension("tree-magic-none"), None);
+        assert_eq!(to_extension("application/x-tree-magic-none"), None);
+    }
+
+    #[test]
+    fn registered() {
+        register_extension("tree-magic-test", "application/x-zip-compressed").unwrap();
+        assert_eq!(from_extension("tree-magic-test"), Some("application/zip"));
+
+        let classes = windows_registry::CURRENT_USER
+            .create(r"Software\Classes")
+            .unwrap();
+        classes.remove_tree(".tree-magic-test").unwrap();
+        assert_eq!(from_extension("tree-magic-test"), None);
+    }
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "remote")]
+
+mod remote {
+    use std::io;
+    use std::ops::Range;
+    use std::sync::Mutex;
+    use tree_magic::remote::{self, RangeRead};
+    use tree_magic_mini as tree_magic;
+
+    /// An object in memory, remembering the ranges asked for
+    struct Object {
+        data: Vec<u8>,
+        reads: Mutex<Vec<Range<u64>>>,
+    }
+
+    impl Object {
+        fn new(data: Vec<u8>) -> Self {
+            Object {
+                data,
+                reads: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn bytes_read(&self) -> u64 {
+            self.reads
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|r| r.end - r.start)
+                .sum()
+        }
+    }
+
+    impl RangeRead for Object {
+        async fn read_range(&self, range: Range<u64>) -> io::Result<Vec<u8>> {
+            self.reads.lock().unwrap().push(range.clone());
+            let end = (range.end as usize).min(self.data.len());
+            let start = (range.start as usize).min(end);
+            Ok(self.data[start..end].to_vec())
+        }
+    }
+
+    /// Storage that can't be reached
+    struct Offline;
+
+    impl RangeRead for Offline {
+        async fn read_range(&self, _range: Range<u64>) -> io::Result<Vec<u8>> {
+            Err(io::Error::new(io::ErrorKind::ConnectionRefused, "offline"))
+        }
+    }
+
+    fn sample_files() -> Vec<std::path::PathBuf> {
+        let mut files = Vec::new();
+        for dir in [
+            "tests/application",
+            "tests/image",
+            "tests/text",
+            "tests/audio",
+        ] {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_file() {
+                    files.push(path);
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn same_as_from_u8() {
+        for path in sample_files() {
+            let data = std::fs::read(&path).unwrap();
+            let expected = tree_magic::from_u8(&data);
+            let object = Object::new(data);
+            let size = object.data.len() as u64;
+            let found = remote::from_reader(&object, Some(size)).await.unwrap();
+            assert_eq!(found, Some(expected), "{}", path.display());
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_size() {
+        let object = Object::new(include_bytes!("image/gif").to_vec());
+        let found = remote::from_reader(&object, None).await.unwrap();
+        assert_eq!(found, Some("image/gif"));
+    }
+
+    #[tokio::test]
+    async fn reads_only_ranges_needed() {
+        let mut data = include_bytes!("image/png").to_vec();
+        data.resize(16 << 20, 0);
+        let object = Object::new(data);
+        let found = remote::from_reader(&object, Some(16 << 20)).await.unwrap();
+        assert_eq!(found, Some("image/png"));
+        assert!(object.bytes_read() < 64 << 10, "{}", object.bytes_read());
+    }
+
+    #[tokio::test]
+    async fn nothing_read_past_the_end() {
+        let object = Object::new(b"hello".to_vec());
+        let found = remote::from_reader(&object, Some(5)).await.unwrap();
+        assert_eq!(found, Some("text/plain"));
+        assert!(object.reads.lock().unwrap().iter().all(|r| r.end <= 5));
+
+        let empty = Object::new(Vec::new());
+        let found = remote::from_reader(&empty, Some(0)).await.unwrap();
+        assert_eq!(found, Some("application/x-zerosize"));
+        assert!(empty.reads.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn storage_errors() {
+        let e = remote::from_reader(&Offline, None).await.unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::ConnectionRefused);
+    }
+
+    #[test]
+    fn futures_are_send() {
+        fn send<T: Send>(_: T) {}
+        let object = Object::new(Vec::new());
+        send(remote::from_reader(&object, None));
+    }
+
+    /// object_store tests
+    #[cfg(feature = "object-store")]
+    #[tokio::test]
+    async fn object_store() {
+        use object_store::{memory::InMemory, path::Path, ObjectStore};
+
+        let store = InMemory::new();
+        let location = Path::from("images/avatar");
+        let gif = include_bytes!("image/gif").to_vec();
+        store.put(&location, gif.into()).await.unwrap();
+
+        let found = remote::from_object_store(&store, &location).await.unwrap();
+        assert_eq!(found, Some("image/gif"));
+
+        let missing = Path::from("images/missing");
+        let e = remote::from_object_store(&store, &missing)
+            .await
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::NotFound);
+    }
+}
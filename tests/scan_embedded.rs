@@ -0,0 +1,70 @@
+mod scan_embedded {
+    use tree_magic::ScanOptions;
+    use tree_magic_mini as tree_magic;
+
+    /// `padding` filler bytes followed by `file`
+    fn embed(padding: usize, file: &[u8]) -> Vec<u8> {
+        let mut out = vec![0xAA; padding];
+        out.extend_from_slice(file);
+        out
+    }
+
+    #[test]
+    fn image_png() {
+        let blob = embed(37, include_bytes!("image/png"));
+        let found = tree_magic::scan_embedded(&blob, &ScanOptions::new());
+        assert_eq!(found.first(), Some(&(37, "image/png")));
+    }
+    #[test]
+    fn zip_after_image() {
+        let mut blob = embed(0, include_bytes!("image/gif"));
+        blob.extend_from_slice(include_bytes!("application/zip"));
+        let offset = include_bytes!("image/gif").len();
+
+        let found = tree_magic::scan_embedded(&blob, &ScanOptions::new());
+        assert!(found.contains(&(offset, "application/zip")));
+    }
+    #[test]
+    fn skips_start() {
+        let found = tree_magic::scan_embedded(include_bytes!("image/png"), &ScanOptions::new());
+        assert!(found.iter().all(|&(offset, _)| offset > 0));
+    }
+    #[test]
+    fn max_offset() {
+        let blob = embed(200, include_bytes!("image/png"));
+        let opts = ScanOptions::new().max_offset(199);
+        assert!(tree_magic::scan_embedded(&blob, &opts).is_empty());
+        let opts = ScanOptions::new().max_offset(200);
+        assert_eq!(
+            tree_magic::scan_embedded(&blob, &opts),
+            vec![(200, "image/png")]
+        );
+    }
+    #[test]
+    fn max_results() {
+        let mut blob = embed(10, include_bytes!("image/png"));
+        blob.extend_from_slice(include_bytes!("image/png"));
+        let opts = ScanOptions::new().max_results(1);
+        assert_eq!(tree_magic::scan_embedded(&blob, &opts).len(), 1);
+    }
+    #[test]
+    fn min_signature_len() {
+        let blob = embed(10, include_bytes!("image/png"));
+        let opts = ScanOptions::new().min_signature_len(100);
+        assert!(tree_magic::scan_embedded(&blob, &opts).is_empty());
+    }
+    #[test]
+    fn several_signatures() {
+        // Both signatures of the type are looked for, not only the first
+        let db = tree_magic::TypeDatabase::builder()
+            .magic(b"MIME-Magic\0\n[50:application/x-foo]\n>0=\0\x04FOO1\n>0=\0\x04FOO2\n")
+            .unwrap()
+            .build();
+        let found = db.scan_embedded(&embed(10, b"FOO2 and more"), &ScanOptions::new());
+        assert_eq!(found, vec![(10, "application/x-foo")]);
+    }
+    #[test]
+    fn empty() {
+        assert!(tree_magic::scan_embedded(&[], &ScanOptions::new()).is_empty());
+    }
+}
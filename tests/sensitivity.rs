@@ -0,0 +1,40 @@
+mod sensitivity {
+    use tree_magic_mini as tree_magic;
+
+    fn covers(ranges: &[(std::ops::Range<usize>, &str)], offset: usize) -> bool {
+        ranges.iter().any(|(r, _)| r.contains(&offset))
+    }
+
+    #[test]
+    #[cfg(not(feature = "builtin-minimal"))]
+    fn image_gif() {
+        let input = include_bytes!("image/gif");
+        let ranges = tree_magic::sensitivity(input);
+        assert_eq!(ranges, vec![(0..4, "application/octet-stream")]);
+
+        // The signature the magic rules matched is among the ranges
+        for range in tree_magic::explain_u8(input).evidence {
+            assert!(range.clone().all(|i| covers(&ranges, i)));
+        }
+    }
+    #[test]
+    fn application_x_tar_checksum() {
+        let ranges = tree_magic::sensitivity(include_bytes!("application/x-tar"));
+        // Every byte counts towards the header checksum
+        assert!(covers(&ranges, 0));
+        assert!(covers(&ranges, 148));
+        assert!(covers(&ranges, 511));
+        assert!(!covers(&ranges, 512));
+    }
+    #[test]
+    fn text_plain() {
+        assert!(tree_magic::sensitivity(include_bytes!("text/plain")).is_empty());
+    }
+    #[test]
+    fn merged_by_type() {
+        let ranges = tree_magic::sensitivity(include_bytes!("image/png"));
+        for pair in ranges.windows(2) {
+            assert!(pair[0].0.end < pair[1].0.start || pair[0].1 != pair[1].1);
+        }
+    }
+}
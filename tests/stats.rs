@@ -0,0 +1,63 @@
+mod stats {
+    use std::sync::Mutex;
+    use tree_magic_mini as tree_magic;
+    use tree_magic_mini::{DetectOptions, Stats, StatsHook};
+
+    const GIF: &[u8] = include_bytes!("image/gif");
+
+    #[test]
+    fn counts() {
+        let stats = Stats::new();
+        let opts = DetectOptions::new();
+        assert_eq!(
+            tree_magic::detect_with_stats(&GIF, &opts, &stats),
+            tree_magic::detect_u8(GIF, &opts)
+        );
+        assert_eq!(stats.calls(), 1);
+        // all/allfiles, application/octet-stream, image/gif
+        assert_eq!(stats.rule_hits(), 3);
+        assert_eq!(
+            stats.nodes_visited(),
+            stats.rule_hits() + stats.rule_misses()
+        );
+        assert!(stats.bytes_read() >= 6);
+    }
+    #[test]
+    fn aggregate() {
+        let stats = Stats::new();
+        let opts = DetectOptions::new();
+        tree_magic::detect_with_stats(&GIF, &opts, &stats);
+        let visited = stats.nodes_visited();
+        tree_magic::detect_with_stats(&GIF, &opts, &stats);
+        assert_eq!(stats.calls(), 2);
+        assert_eq!(stats.nodes_visited(), 2 * visited);
+
+        stats.reset();
+        assert_eq!(stats.calls(), 0);
+        assert_eq!(stats.nodes_visited(), 0);
+        assert_eq!(stats.bytes_read(), 0);
+    }
+
+    /// Remembers the types that matched
+    #[derive(Default)]
+    struct Matched(Mutex<Vec<String>>);
+
+    impl StatsHook for Matched {
+        fn type_checked(&self, mimetype: &str, matched: bool) {
+            if matched {
+                self.0.lock().unwrap().push(mimetype.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn custom_hook() {
+        let hook = Matched::default();
+        let result = tree_magic::detect_with_stats(&GIF, &DetectOptions::new(), &hook);
+        assert_eq!(result, Some("image/gif"));
+        assert_eq!(
+            *hook.0.lock().unwrap(),
+            ["all/allfiles", "application/octet-stream", "image/gif"]
+        );
+    }
+}
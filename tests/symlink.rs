@@ -0,0 +1,74 @@
+#![cfg(all(unix, feature = "std"))]
+
+mod symlink {
+    use std::fs;
+    use std::os::unix::fs::symlink;
+    use std::path::{Path, PathBuf};
+    use tree_magic_mini as tree_magic;
+
+    /// A fresh directory for the links of one test
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "tree_magic_symlink_{}_{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn not_a_link() {
+        let result = tree_magic::from_filepath_following(Path::new("tests/image/gif"), 8).unwrap();
+        assert_eq!(result.link, None);
+        assert_eq!(result.target, Some("image/gif"));
+        assert!(result.chain.is_empty());
+    }
+    #[test]
+    fn chain() {
+        let dir = scratch("chain");
+        let gif = fs::canonicalize("tests/image/gif").unwrap();
+        symlink(&gif, dir.join("a")).unwrap();
+        symlink("a", dir.join("b")).unwrap();
+
+        let result = tree_magic::from_filepath_following(&dir.join("b"), 8).unwrap();
+        assert_eq!(result.link, Some("inode/symlink"));
+        assert_eq!(result.target, Some("image/gif"));
+        assert_eq!(result.target_path, gif);
+        assert_eq!(result.chain, vec![dir.join("b"), dir.join("a")]);
+    }
+    #[test]
+    fn max_depth() {
+        let dir = scratch("max_depth");
+        symlink(fs::canonicalize("tests/image/gif").unwrap(), dir.join("a")).unwrap();
+        symlink("a", dir.join("b")).unwrap();
+
+        assert!(tree_magic::from_filepath_following(&dir.join("b"), 1).is_err());
+        assert!(tree_magic::from_filepath_following(&dir.join("b"), 2).is_ok());
+    }
+    #[test]
+    fn cycle() {
+        let dir = scratch("cycle");
+        symlink("b", dir.join("a")).unwrap();
+        symlink("a", dir.join("b")).unwrap();
+
+        assert!(tree_magic::from_filepath_following(&dir.join("a"), 100).is_err());
+    }
+    #[test]
+    fn dangling() {
+        let dir = scratch("dangling");
+        symlink("missing", dir.join("a")).unwrap();
+
+        let result = tree_magic::from_filepath_following(&dir.join("a"), 8).unwrap();
+        assert_eq!(result.link, Some("inode/symlink"));
+        assert_eq!(result.target, None);
+        assert_eq!(result.target_path, dir.join("missing"));
+    }
+    #[test]
+    fn missing() {
+        assert!(
+            tree_magic::from_filepath_following(Path::new("this/file/does/not/exist"), 8).is_err()
+        );
+    }
+}
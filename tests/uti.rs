@@ -0,0 +1,43 @@
+#![cfg(feature = "uti")]
+
+mod uti {
+    use tree_magic::uti::{from_uti, to_uti};
+    use tree_magic_mini as tree_magic;
+
+    #[test]
+    fn detected_types() {
+        for (path, uti) in [
+            ("tests/image/gif", "com.compuserve.gif"),
+            ("tests/image/png", "public.png"),
+            ("tests/application/zip", "public.zip-archive"),
+            ("tests/text/plain", "public.plain-text"),
+        ] {
+            let mime = tree_magic::from_u8(&std::fs::read(path).unwrap());
+            assert_eq!(to_uti(mime), Some(uti), "{}", path);
+            assert_eq!(from_uti(uti), Some(mime));
+        }
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(
+            to_uti("application/x-zip-compressed"),
+            Some("public.zip-archive")
+        );
+    }
+
+    #[test]
+    fn closest_parent() {
+        assert_eq!(to_uti("text/x-rust"), Some("public.plain-text"));
+        assert_eq!(to_uti("application/vnd.sqlite3"), Some("public.data"));
+        assert_eq!(to_uti("application/x-not-a-type"), None);
+    }
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(from_uti("PUBLIC.PNG"), Some("image/png"));
+        assert_eq!(from_uti("public.data"), Some("application/octet-stream"));
+        // Conformed to by others, but not a type of its own
+        assert_eq!(from_uti("public.image"), None);
+    }
+}
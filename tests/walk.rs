@@ -0,0 +1,82 @@
+#![cfg(feature = "walk")]
+
+mod walk {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::PathBuf;
+    use tree_magic::walk::{self, detect_entry, ignore::WalkBuilder, walkdir::WalkDir};
+    use tree_magic_mini as tree_magic;
+
+    /// A fresh directory for one test
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("tree_magic_walk_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn walkdir_entries() {
+        for entry in WalkDir::new("tests/image").into_iter() {
+            let entry = entry.unwrap();
+            assert_eq!(
+                detect_entry(&entry),
+                tree_magic::from_filepath(entry.path()),
+                "{}",
+                entry.path().display()
+            );
+        }
+    }
+
+    #[test]
+    fn same_as_filepath() {
+        let found: HashMap<PathBuf, _> = walk::walk("tests").collect();
+        let mut count = 0;
+        for entry in WalkDir::new("tests").into_iter() {
+            let path = entry.unwrap().into_path();
+            assert_eq!(
+                found[&path],
+                tree_magic::from_filepath(&path),
+                "{}",
+                path.display()
+            );
+            count += 1;
+        }
+        assert_eq!(found.len(), count);
+    }
+
+    #[test]
+    fn builder_filters() {
+        let dir = scratch("filters");
+        fs::write(dir.join(".hidden"), b"GIF89a").unwrap();
+        fs::copy("tests/image/gif", dir.join("gif")).unwrap();
+
+        let found: HashMap<PathBuf, _> = walk::walk_with(&WalkBuilder::new(&dir)).collect();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[&dir], Some("inode/directory"));
+        assert_eq!(found[&dir.join("gif")], Some("image/gif"));
+
+        let found: HashMap<PathBuf, _> = walk::walk(&dir).collect();
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[&dir.join(".hidden")], Some("image/gif"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_followed() {
+        let dir = scratch("symlinks");
+        let target = fs::canonicalize("tests/image/gif").unwrap();
+        std::os::unix::fs::symlink(&target, dir.join("link")).unwrap();
+        for entry in WalkDir::new(&dir).min_depth(1) {
+            assert_eq!(detect_entry(&entry.unwrap()), Some("image/gif"));
+        }
+    }
+
+    #[test]
+    fn stops_when_dropped() {
+        let mut found = walk::walk("tests");
+        assert!(found.next().is_some());
+        drop(found);
+    }
+}
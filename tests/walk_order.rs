@@ -0,0 +1,67 @@
+mod walk_order {
+    use std::sync::Mutex;
+    use tree_magic_mini::{DetectOptions, StatsHook};
+
+    /// Types checked before their siblings, in order
+    const PREFERRED: &[&str] = &[
+        "image/png",
+        "image/jpeg",
+        "image/gif",
+        "application/zip",
+        "application/x-msdos-executable",
+        "application/pdf",
+    ];
+
+    /// Records the types checked, in order
+    #[derive(Default)]
+    struct Checked(Mutex<Vec<String>>);
+
+    impl StatsHook for Checked {
+        fn type_checked(&self, mimetype: &str, _matched: bool) {
+            self.0.lock().unwrap().push(mimetype.to_string());
+        }
+    }
+
+    /// Subclasses of `application/octet-stream` in the order the walk checks
+    /// them, for data none of them match
+    fn octet_stream_children() -> Vec<String> {
+        let checked = Checked::default();
+        let bytes: &[u8] = b"\x00\x01\x02\x03";
+        let found = tree_magic_mini::detect_with_stats(&bytes, &DetectOptions::new(), &checked);
+        assert_eq!(found, Some("application/octet-stream"));
+
+        let checked = checked.0.into_inner().unwrap();
+        let start = checked
+            .iter()
+            .position(|m| m == "application/octet-stream")
+            .unwrap();
+        checked[start + 1..].to_vec()
+    }
+
+    #[test]
+    fn preferred_first() {
+        let order = octet_stream_children();
+        let present: Vec<&str> = PREFERRED
+            .iter()
+            .copied()
+            .filter(|m| order.iter().any(|o| o == m))
+            .collect();
+        assert!(present.len() >= 3);
+        assert_eq!(&order[..present.len()], &present[..]);
+    }
+    #[test]
+    fn others_by_name() {
+        let order = octet_stream_children();
+        let others: Vec<&String> = order
+            .iter()
+            .filter(|m| !PREFERRED.contains(&m.as_str()))
+            .collect();
+        assert!(others.len() > 10);
+        assert!(others.windows(2).all(|w| w[0] < w[1]));
+    }
+    #[test]
+    #[cfg(feature = "bench")]
+    fn unknown_type() {
+        assert!(tree_magic_mini::bench::walk_order("application/x-unknown").is_empty());
+    }
+}
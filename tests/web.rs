@@ -0,0 +1,218 @@
+#![cfg(feature = "web")]
+
+mod web {
+    use tree_magic::web::{DetectedMime, Upload, UploadError, UploadPolicy};
+    use tree_magic_mini as tree_magic;
+
+    const GIF: &[u8] = include_bytes!("image/gif");
+    #[cfg(any(feature = "axum", feature = "actix", feature = "rocket"))]
+    const BOUNDARY: &str = "tree-magic-boundary";
+
+    #[cfg(any(feature = "axum", feature = "actix", feature = "rocket"))]
+    /// A multipart form with a part of each name, file name and content
+    fn form(parts: &[(&str, &str, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (name, file_name, data) in parts {
+            body.extend_from_slice(
+                format!(
+                    "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                     Content-Type: text/plain\r\n\r\n",
+                    BOUNDARY, name, file_name
+                )
+                .as_bytes(),
+            );
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+        body
+    }
+
+    #[cfg(any(feature = "axum", feature = "actix", feature = "rocket"))]
+    fn content_type() -> String {
+        format!("multipart/form-data; boundary={}", BOUNDARY)
+    }
+
+    /// Policy tests
+    #[test]
+    fn detected_not_declared() {
+        let upload = Upload::new(Some("a"), Some("a.txt"), Some("text/plain"), GIF.to_vec());
+        assert_eq!(upload.mime, DetectedMime("image/gif"));
+        assert_eq!(upload.declared.as_deref(), Some("text/plain"));
+    }
+    #[test]
+    fn new_policy_allows_nothing() {
+        assert!(!UploadPolicy::new().allows("image/gif"));
+        assert!(UploadPolicy::any().allows("image/gif"));
+    }
+    #[test]
+    fn patterns() {
+        let policy = UploadPolicy::new()
+            .allow("image/*")
+            .allow("Application/PDF");
+        assert!(policy.allows("image/gif"));
+        assert!(policy.allows("application/pdf"));
+        assert!(!policy.allows("application/zip"));
+    }
+    #[test]
+    fn aliases() {
+        let policy = UploadPolicy::new().allow("application/x-zip-compressed");
+        assert!(policy.allows("application/zip"));
+    }
+    #[test]
+    fn subclasses() {
+        let policy = UploadPolicy::new().allow("application/xml");
+        assert!(!policy.allows("image/svg+xml"));
+        let policy = UploadPolicy::new().allow_subclasses("application/xml");
+        assert!(policy.allows("image/svg+xml"));
+        assert!(policy.allows("application/xml"));
+    }
+    #[test]
+    fn check() {
+        let upload = Upload::new(Some("a"), None, None, GIF.to_vec());
+        let policy = UploadPolicy::new().allow("image/gif");
+        assert_eq!(policy.check(&upload), Ok(()));
+        assert_eq!(
+            policy.clone().max_len(10).check(&upload),
+            Err(UploadError::TooLarge {
+                field: Some("a".to_string())
+            })
+        );
+        let e = UploadPolicy::new().check(&upload).unwrap_err();
+        assert_eq!(e.to_string(), "type image/gif not allowed in field a");
+    }
+
+    /// axum tests
+    #[cfg(feature = "axum")]
+    mod with_axum {
+        use super::*;
+        use axum::body::Body;
+        use axum::extract::{FromRequest, Request};
+        use axum::http::{header, StatusCode};
+        use axum::response::IntoResponse;
+        use tree_magic::web::Uploads;
+
+        fn request(body: Vec<u8>, policy: Option<UploadPolicy>) -> Request {
+            let mut req = Request::builder()
+                .header(header::CONTENT_TYPE, content_type())
+                .body(Body::from(body))
+                .unwrap();
+            if let Some(policy) = policy {
+                req.extensions_mut().insert(policy);
+            }
+            req
+        }
+
+        #[tokio::test]
+        async fn uploads() {
+            let body = form(&[("a", "a.txt", GIF), ("b", "b.gif", b"plain text")]);
+            let Uploads(uploads) = Uploads::from_request(request(body, None), &())
+                .await
+                .unwrap();
+            assert_eq!(uploads.len(), 2);
+            assert_eq!(uploads[0].name.as_deref(), Some("a"));
+            assert_eq!(uploads[0].file_name.as_deref(), Some("a.txt"));
+            assert_eq!(uploads[0].mime.0, "image/gif");
+            assert_eq!(uploads[0].data, GIF);
+            assert_eq!(uploads[1].mime.0, "text/plain");
+        }
+
+        #[tokio::test]
+        async fn rejected() {
+            let policy = UploadPolicy::new().allow("image/*");
+            let body = form(&[("a", "a.gif", b"plain text")]);
+            let e = Uploads::from_request(request(body, Some(policy.clone())), &())
+                .await
+                .unwrap_err();
+            assert_eq!(
+                e.into_response().status(),
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            );
+
+            let body = form(&[("a", "a.gif", GIF)]);
+            let e = Uploads::from_request(request(body, Some(policy.max_len(10))), &())
+                .await
+                .unwrap_err();
+            assert_eq!(e.into_response().status(), StatusCode::PAYLOAD_TOO_LARGE);
+        }
+    }
+
+    /// Actix Web tests
+    #[cfg(feature = "actix")]
+    mod with_actix {
+        use super::*;
+        use actix_web::http::{header, StatusCode};
+        use actix_web::test::TestRequest;
+        use actix_web::{FromRequest, ResponseError};
+        use tree_magic::web::Uploads;
+
+        #[tokio::test]
+        async fn uploads() {
+            let body = form(&[("a", "a.txt", GIF)]);
+            let (req, mut payload) = TestRequest::default()
+                .insert_header((header::CONTENT_TYPE, content_type()))
+                .set_payload(body)
+                .to_http_parts();
+            let Uploads(uploads) = Uploads::from_request(&req, &mut payload).await.unwrap();
+            assert_eq!(uploads.len(), 1);
+            assert_eq!(uploads[0].name.as_deref(), Some("a"));
+            assert_eq!(uploads[0].file_name.as_deref(), Some("a.txt"));
+            assert_eq!(uploads[0].mime.0, "image/gif");
+        }
+
+        #[tokio::test]
+        async fn rejected() {
+            let body = form(&[("a", "a.gif", b"plain text")]);
+            let (req, mut payload) = TestRequest::default()
+                .insert_header((header::CONTENT_TYPE, content_type()))
+                .app_data(UploadPolicy::new().allow("image/*"))
+                .set_payload(body)
+                .to_http_parts();
+            let e = Uploads::from_request(&req, &mut payload).await.unwrap_err();
+            assert_eq!(e.status_code(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        }
+    }
+
+    /// Rocket tests
+    #[cfg(feature = "rocket")]
+    mod with_rocket {
+        use super::*;
+        use rocket::form::Form;
+        use rocket::http::{ContentType, Status};
+        use rocket::local::blocking::Client;
+
+        #[rocket::post("/", data = "<upload>")]
+        fn upload(upload: Form<Upload>) -> String {
+            upload.mime.to_string()
+        }
+
+        fn client(policy: UploadPolicy) -> Client {
+            let rocket = rocket::build()
+                .mount("/", rocket::routes![upload])
+                .manage(policy);
+            Client::untracked(rocket).unwrap()
+        }
+
+        #[test]
+        fn uploads() {
+            let client = client(UploadPolicy::any());
+            let res = client
+                .post("/")
+                .header(ContentType::parse_flexible(&content_type()).unwrap())
+                .body(form(&[("upload", "a.txt", GIF)]))
+                .dispatch();
+            assert_eq!(res.into_string().as_deref(), Some("image/gif"));
+        }
+
+        #[test]
+        fn rejected() {
+            let client = client(UploadPolicy::new().allow("image/*"));
+            let res = client
+                .post("/")
+                .header(ContentType::parse_flexible(&content_type()).unwrap())
+                .body(form(&[("upload", "a.gif", b"plain text")]))
+                .dispatch();
+            assert_eq!(res.status(), Status::UnprocessableEntity);
+        }
+    }
+}
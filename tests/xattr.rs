@@ -0,0 +1,112 @@
+#![cfg(all(feature = "unix-io", target_os = "linux"))]
+
+mod xattr {
+    use std::ffi::CString;
+    use std::fs;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+    use tree_magic::Detector;
+    use tree_magic_mini as tree_magic;
+
+    /// A copy of a test file in a fresh place, so it can be tagged
+    fn scratch(name: &str, from: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("tree_magic_xattr_{}_{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::copy(from, &path).unwrap();
+        path
+    }
+
+    /// Tags the file, or returns false if its file system doesn't allow it
+    fn tag(path: &Path, value: &[u8]) -> bool {
+        let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        let name = b"user.mime_type\0";
+        // Safety: both names are valid C strings, and the size is the value's
+        let ret = unsafe {
+            libc::setxattr(
+                path.as_ptr(),
+                name.as_ptr().cast(),
+                value.as_ptr().cast(),
+                value.len(),
+                0,
+            )
+        };
+        ret == 0
+    }
+
+    #[test]
+    fn tagged_type_first() {
+        let path = scratch("tagged", "tests/image/gif");
+        if !tag(&path, b"image/png") {
+            return;
+        }
+        let detector = Detector::builder().xattr(true).build();
+        assert_eq!(detector.detect_filepath(&path), Some("image/png"));
+
+        // Off by default
+        let detector = Detector::builder().build();
+        assert_eq!(detector.detect_filepath(&path), Some("image/gif"));
+    }
+
+    #[test]
+    fn alias_and_case() {
+        let path = scratch("alias", "tests/text/plain");
+        if !tag(&path, b"Application/X-PDF\0") {
+            return;
+        }
+        let detector = Detector::builder().xattr(true).build();
+        assert_eq!(detector.detect_filepath(&path), Some("application/pdf"));
+    }
+
+    #[test]
+    fn unknown_falls_back() {
+        let path = scratch("unknown", "tests/image/gif");
+        if !tag(&path, b"application/x-not-a-type") {
+            return;
+        }
+        let detector = Detector::builder().xattr(true).build();
+        assert_eq!(detector.detect_filepath(&path), Some("image/gif"));
+    }
+
+    #[test]
+    fn untagged_falls_back() {
+        let path = scratch("untagged", "tests/image/gif");
+        let detector = Detector::builder().xattr(true).build();
+        assert_eq!(detector.detect_filepath(&path), Some("image/gif"));
+    }
+
+    /// Tagging tests
+    #[test]
+    fn tag_then_detect() {
+        let path = scratch("roundtrip", "tests/image/gif");
+        match tree_magic::tag_filepath(&path) {
+            Ok(mime) => assert_eq!(mime, "image/gif"),
+            // No user attributes on this file system
+            Err(_) => return,
+        }
+        // Renamed, so only the tag can tell it's a GIF without reading it
+        let moved = path.with_file_name("renamed.png");
+        fs::rename(&path, &moved).unwrap();
+        let detector = Detector::builder().content(false).xattr(true).build();
+        assert_eq!(detector.detect_filepath(&moved), Some("image/gif"));
+    }
+
+    #[test]
+    fn tag_replaces_old_tag() {
+        let path = scratch("retag", "tests/image/gif");
+        if !tag(&path, b"image/png") {
+            return;
+        }
+        assert_eq!(tree_magic::tag_filepath(&path).unwrap(), "image/gif");
+        let detector = Detector::builder().xattr(true).build();
+        assert_eq!(detector.detect_filepath(&path), Some("image/gif"));
+    }
+
+    #[test]
+    fn tag_missing_file() {
+        let err = tree_magic::tag_filepath(Path::new("tests/missing")).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+}